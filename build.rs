@@ -22,6 +22,30 @@ fn main() {
     // Set the PROJECT_VERSION environment variable for the application to use
     println!("cargo:rustc-env=PROJECT_VERSION={}", version);
 
+    // Short commit hash for diagnostics (e.g. the get_server_info tool)
+    let git_revision = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GIT_REVISION={}", git_revision);
+
+    // The full GeoNames US/Canada table when `task postal-db:fetch` has
+    // downloaded it, otherwise the sample of major cities
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let data = std::path::Path::new(&manifest_dir).join("data");
+    let full = data.join("geonames_postal_codes.txt");
+    let postal_data = if full.exists() {
+        full
+    } else {
+        data.join("postal_codes.tsv")
+    };
+    println!("cargo:rustc-env=POSTAL_DATA={}", postal_data.display());
+    println!("cargo:rerun-if-changed=data");
+
     // Ensure build.rs reruns if git HEAD changes (branch switch, commit)
     println!("cargo:rerun-if-changed=.git/HEAD");
     // Also rerun if tags change (heuristic, checking refs/tags might be better but HEAD is usually sufficient for simple cases)
@@ -0,0 +1,427 @@
+use crate::cli::ToolArgs;
+use crate::config::Settings;
+use crate::error::AppError;
+#[cfg(feature = "notify")]
+use crate::notifications::{EmailChannel, WebhookChannel};
+use crate::source::search_animals;
+use crate::store::{self, load_json};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct AlertsFile {
+    #[serde(default)]
+    pub searches: Vec<SavedSearch>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SavedSearch {
+    pub name: String,
+    pub args: ToolArgs,
+    /// Animal IDs already reported for this search
+    #[serde(default)]
+    pub seen_ids: Vec<String>,
+    /// Unix timestamp of the last poll; `None` until the baseline is taken
+    #[serde(default)]
+    pub last_checked: Option<u64>,
+}
+
+/// A "new match" notification for a saved search.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Alert {
+    pub search_name: String,
+    pub animal: Value,
+}
+
+/// A destination for alerts. Implementations are selected by name through
+/// the `channels` list in the `[alerts]` config section.
+pub trait NotificationChannel: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn notify<'a>(&'a self, alerts: &'a [Alert]) -> BoxFuture<'a, Result<(), AppError>>;
+
+    /// Called after every check, even without new matches, so batching
+    /// channels (e.g. email digests) can send once their period is due.
+    fn flush(&self) -> BoxFuture<'_, Result<(), AppError>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+pub struct LogChannel;
+
+impl NotificationChannel for LogChannel {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn notify<'a>(&'a self, alerts: &'a [Alert]) -> BoxFuture<'a, Result<(), AppError>> {
+        Box::pin(async move {
+            for alert in alerts {
+                info!(
+                    search = %alert.search_name,
+                    animal_id = alert.animal["id"].as_str().unwrap_or(""),
+                    "New match: {} ({})",
+                    alert.animal["name"].as_str().unwrap_or("Unknown"),
+                    alert.animal["url"].as_str().unwrap_or("")
+                );
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Builds the configured channels. The SSE channel is only available when
+/// running the HTTP server, which passes it in as `sse`.
+pub fn build_channels(
+    settings: &Settings,
+    sse: Option<Arc<dyn NotificationChannel>>,
+) -> Vec<Arc<dyn NotificationChannel>> {
+    let mut channels: Vec<Arc<dyn NotificationChannel>> = Vec::new();
+    for name in &settings.alerts.channels {
+        match (name.as_str(), &sse) {
+            ("log", _) => channels.push(Arc::new(LogChannel)),
+            ("sse", Some(sse)) => channels.push(sse.clone()),
+            ("sse", None) => {
+                warn!("Alert channel 'sse' is only available in HTTP mode; skipping")
+            }
+            #[cfg(feature = "notify")]
+            ("webhook", _) => {
+                if settings.notifications.webhooks.is_empty() {
+                    warn!("Alert channel 'webhook' has no [[notifications.webhooks]] configured");
+                }
+                for webhook in &settings.notifications.webhooks {
+                    channels.push(Arc::new(WebhookChannel::new(webhook.clone())));
+                }
+            }
+            #[cfg(feature = "notify")]
+            ("email", _) => match &settings.notifications.email {
+                Some(email) => channels.push(Arc::new(EmailChannel::new(
+                    email.clone(),
+                    settings.data_dir.join("email_digest.json"),
+                ))),
+                None => warn!("Alert channel 'email' has no [notifications.email] configured"),
+            },
+            #[cfg(not(feature = "notify"))]
+            ("webhook" | "email", _) => {
+                warn!(
+                    "Alert channel '{}' needs a build with the notify feature; skipping",
+                    name
+                )
+            }
+            (other, _) => warn!("Unknown alert channel '{}'; skipping", other),
+        }
+    }
+    channels
+}
+
+fn alerts_path(settings: &Settings) -> PathBuf {
+    settings.data_dir.join("alerts.json")
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Reduces an animal record to the fields needed in a notification.
+pub fn summarize_animal(animal: &Value) -> Value {
+    let attrs = &animal["attributes"];
+    json!({
+        "id": animal["id"],
+        "name": attrs["name"].as_str().unwrap_or("Unknown"),
+        "breed": attrs["breedString"].as_str().unwrap_or("Mix"),
+        "age": attrs["ageGroup"],
+        "sex": attrs["sex"],
+        "url": attrs["url"].as_str().unwrap_or(""),
+        "picture": attrs["orgsAnimalsPictures"]
+            .as_array()
+            .and_then(|p| p.first())
+            .and_then(|p| p["urlSecureFullsize"].as_str()),
+    })
+}
+
+/// Saves (or replaces) a named search. Replacing resets its baseline.
+pub async fn save_search(settings: &Settings, name: &str, args: ToolArgs) -> Result<(), AppError> {
+    store::update(&alerts_path(settings), |file: &mut AlertsFile| {
+        file.searches.retain(|s| s.name != name);
+        file.searches.push(SavedSearch {
+            name: name.to_string(),
+            args,
+            seen_ids: Vec::new(),
+            last_checked: None,
+        });
+        Ok(())
+    })
+    .await
+}
+
+pub async fn list_saved_searches(settings: &Settings) -> Result<Vec<SavedSearch>, AppError> {
+    let file: AlertsFile = load_json(&alerts_path(settings))?;
+    Ok(file.searches)
+}
+
+pub async fn delete_saved_search(settings: &Settings, name: &str) -> Result<(), AppError> {
+    store::update(&alerts_path(settings), |file: &mut AlertsFile| {
+        let before = file.searches.len();
+        file.searches.retain(|s| s.name != name);
+        if file.searches.len() == before {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Runs every saved search once and returns the animals not seen before.
+/// The first poll of a search only records a baseline, so saving a search
+/// doesn't flood the channels with its existing matches.
+pub async fn check_saved_searches(settings: &Settings) -> Result<Vec<Alert>, AppError> {
+    let path = alerts_path(settings);
+    let file: AlertsFile = load_json(&path)?;
+    // Search before taking the store lock, so tool calls don't wait on the API
+    let mut results = HashMap::new();
+    for search in file.searches {
+        match search_animals(settings, search.args).await {
+            Ok(data) => {
+                let animals = data["data"].as_array().cloned().unwrap_or_default();
+                results.insert(search.name, animals);
+            }
+            Err(e) => warn!("Saved search '{}' failed: {}", search.name, e),
+        }
+    }
+
+    store::update(&path, |file: &mut AlertsFile| {
+        let mut alerts = Vec::new();
+        for search in file.searches.iter_mut() {
+            let Some(animals) = results.get(&search.name) else {
+                continue;
+            };
+            let mut seen: HashSet<String> = search.seen_ids.iter().cloned().collect();
+
+            for animal in animals {
+                let Some(id) = animal["id"].as_str() else {
+                    continue;
+                };
+                if seen.insert(id.to_string()) && search.last_checked.is_some() {
+                    alerts.push(Alert {
+                        search_name: search.name.clone(),
+                        animal: summarize_animal(animal),
+                    });
+                }
+            }
+
+            let mut seen_ids: Vec<String> = seen.into_iter().collect();
+            seen_ids.sort();
+            search.seen_ids = seen_ids;
+            search.last_checked = Some(now_secs());
+        }
+        Ok(alerts)
+    })
+    .await
+}
+
+pub async fn deliver(alerts: &[Alert], channels: &[Arc<dyn NotificationChannel>]) {
+    for channel in channels {
+        if !alerts.is_empty() {
+            if let Err(e) = channel.notify(alerts).await {
+                warn!("Alert channel '{}' failed: {}", channel.name(), e);
+            }
+        }
+        if let Err(e) = channel.flush().await {
+            warn!("Alert channel '{}' failed to flush: {}", channel.name(), e);
+        }
+    }
+}
+
+/// Starts the background poller if `[alerts] enabled = true`.
+pub fn spawn_alert_scheduler(
+    settings: Settings,
+    channels: Vec<Arc<dyn NotificationChannel>>,
+) -> Option<JoinHandle<()>> {
+    if !settings.alerts.enabled {
+        return None;
+    }
+
+    let interval = Duration::from_secs(settings.alerts.interval_minutes.max(1) * 60);
+    info!(
+        "Alert scheduler started (every {} minutes)",
+        interval.as_secs() / 60
+    );
+
+    // Polling shouldn't slow down tool calls sharing the rate limit
+    let settings = settings.background();
+    Some(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match check_saved_searches(&settings).await {
+                Ok(alerts) => deliver(&alerts, &channels).await,
+                Err(e) => warn!("Alert check failed: {}", e),
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn get_test_settings(url: String, name: &str) -> Settings {
+        let data_dir = std::env::temp_dir().join(format!("rescue-groups-mcp-alerts-{}", name));
+        let _ = std::fs::remove_dir_all(&data_dir);
+        Settings::for_test(url, data_dir)
+    }
+
+    struct CountingChannel(AtomicUsize);
+
+    impl NotificationChannel for CountingChannel {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        fn notify<'a>(&'a self, alerts: &'a [Alert]) -> BoxFuture<'a, Result<(), AppError>> {
+            Box::pin(async move {
+                self.0.fetch_add(alerts.len(), Ordering::SeqCst);
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_list_delete_search() {
+        let settings = get_test_settings("http://localhost".to_string(), "crud");
+        let args = ToolArgs {
+            species: Some("cats".to_string()),
+            ..Default::default()
+        };
+
+        save_search(&settings, "cats", args.clone()).await.unwrap();
+        save_search(&settings, "cats", args).await.unwrap();
+        let searches = list_saved_searches(&settings).await.unwrap();
+        assert_eq!(searches.len(), 1);
+        assert_eq!(searches[0].args.species.as_deref(), Some("cats"));
+
+        delete_saved_search(&settings, "cats").await.unwrap();
+        assert!(list_saved_searches(&settings).await.unwrap().is_empty());
+        assert!(matches!(
+            delete_saved_search(&settings, "cats").await,
+            Err(AppError::NotFound)
+        ));
+        std::fs::remove_dir_all(&settings.data_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_check_saved_searches_diffs_results() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url(), "diff");
+
+        save_search(&settings, "dogs", ToolArgs::default())
+            .await
+            .unwrap();
+
+        let first = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Buddy"}}]}"#)
+            .create_async()
+            .await;
+
+        // Baseline run reports nothing
+        let alerts = check_saved_searches(&settings).await.unwrap();
+        assert!(alerts.is_empty());
+        first.remove_async().await;
+        settings.cache.invalidate_all();
+
+        let _second = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "1", "attributes": {"name": "Buddy"}}, {"id": "2", "attributes": {"name": "Lucy", "url": "http://lucy"}}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let alerts = check_saved_searches(&settings).await.unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].search_name, "dogs");
+        assert_eq!(alerts[0].animal["name"], "Lucy");
+
+        let counter = Arc::new(CountingChannel(AtomicUsize::new(0)));
+        let channels: Vec<Arc<dyn NotificationChannel>> =
+            vec![Arc::new(LogChannel), counter.clone()];
+        deliver(&alerts, &channels).await;
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        std::fs::remove_dir_all(&settings.data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_build_channels() {
+        let mut settings = get_test_settings("http://localhost".to_string(), "channels");
+        settings.alerts.channels = vec![
+            "log".to_string(),
+            "sse".to_string(),
+            "webhook".to_string(),
+            "bogus".to_string(),
+        ];
+        assert_eq!(build_channels(&settings, None).len(), 1);
+
+        settings
+            .notifications
+            .webhooks
+            .push(crate::config::WebhookConfig {
+                url: "http://localhost/hook".to_string(),
+                format: Default::default(),
+                secret: None,
+                max_retries: 0,
+            });
+        let sse: Arc<dyn NotificationChannel> = Arc::new(CountingChannel(AtomicUsize::new(0)));
+        let channels = build_channels(&settings, Some(sse));
+        let names: Vec<&str> = channels.iter().map(|c| c.name()).collect();
+        if cfg!(feature = "notify") {
+            assert_eq!(names, vec!["log", "counting", "webhook"]);
+        } else {
+            assert_eq!(names, vec!["log", "counting"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_alert_scheduler_disabled() {
+        let settings = get_test_settings("http://localhost".to_string(), "disabled");
+        assert!(spawn_alert_scheduler(settings, Vec::new()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_spawn_alert_scheduler_enabled() {
+        let mut settings = get_test_settings("http://localhost".to_string(), "enabled");
+        settings.alerts.enabled = true;
+        let handle = spawn_alert_scheduler(settings, Vec::new()).unwrap();
+        handle.abort();
+    }
+
+    #[test]
+    fn test_summarize_animal() {
+        let animal = json!({
+            "id": "7",
+            "attributes": {
+                "name": "Rex",
+                "breedString": "Boxer",
+                "url": "http://rex",
+                "orgsAnimalsPictures": [{ "urlSecureFullsize": "http://rex.jpg" }]
+            }
+        });
+        let summary = summarize_animal(&animal);
+        assert_eq!(summary["id"], "7");
+        assert_eq!(summary["breed"], "Boxer");
+        assert_eq!(summary["picture"], "http://rex.jpg");
+    }
+}
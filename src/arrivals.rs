@@ -0,0 +1,106 @@
+use crate::alerts::now_secs;
+use crate::cli::{NewArrivalsArgs, OrgIdArgs, ToolArgs};
+use crate::client::{fan_out, get_organization_details};
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::fmt::{epoch_days, extract_single_item};
+use crate::petfinder::ID_PREFIX;
+use crate::source::search_animals;
+use crate::validate::{self, MAX_ARRIVAL_DAYS};
+use serde_json::{json, Value};
+
+const DEFAULT_DAYS: u32 = 7;
+
+/// Pets listed nearby in the last `days` days, newest first, grouped by the
+/// organization listing them.
+pub async fn list_new_arrivals(
+    settings: &Settings,
+    args: NewArrivalsArgs,
+) -> Result<Value, AppError> {
+    let days = args.days.unwrap_or(DEFAULT_DAYS);
+    if !(1..=MAX_ARRIVAL_DAYS).contains(&days) {
+        return Err(AppError::invalid_argument(
+            "days",
+            format!(
+                "days {} is out of range: use 1 to {}",
+                days, MAX_ARRIVAL_DAYS
+            ),
+        ));
+    }
+    validate::miles(args.miles)?;
+
+    let mut data = search_animals(
+        settings,
+        ToolArgs {
+            postal_code: args.postal_code,
+            species: args.species,
+            miles: args.miles,
+            sort_by: Some("Newest".to_string()),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let cutoff = (now_secs() / 86_400) as i64 - i64::from(days);
+    let recent: Vec<Value> = data["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|a| {
+            a["attributes"]["createdDate"]
+                .as_str()
+                .and_then(epoch_days)
+                .is_some_and(|listed| listed >= cutoff)
+        })
+        .cloned()
+        .collect();
+
+    data["groups"] = json!(group_by_org(settings, &recent).await);
+    data["data"] = json!(recent);
+    data["days"] = json!(days);
+    Ok(data)
+}
+
+/// Animals grouped by organization, in the order the organizations first
+/// appear. RescueGroups organizations are named from their (cached)
+/// details; the others by ID only.
+async fn group_by_org(settings: &Settings, animals: &[Value]) -> Vec<Value> {
+    let mut groups: Vec<(String, Vec<Value>)> = Vec::new();
+    for animal in animals {
+        let org_id = animal["relationships"]["orgs"]["data"][0]["id"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        match groups.iter_mut().find(|(id, _)| *id == org_id) {
+            Some((_, members)) => members.push(animal.clone()),
+            None => groups.push((org_id, vec![animal.clone()])),
+        }
+    }
+
+    let names = fan_out(
+        settings,
+        groups.iter().map(|(org_id, _)| async move {
+            if org_id.is_empty() || org_id.starts_with(ID_PREFIX) {
+                return None;
+            }
+            let org = get_organization_details(
+                settings,
+                OrgIdArgs {
+                    org_id: org_id.clone(),
+                },
+            )
+            .await
+            .ok()?;
+            let item = org.get("data").and_then(extract_single_item)?;
+            item["attributes"]["name"].as_str().map(str::to_string)
+        }),
+    )
+    .await;
+
+    groups
+        .into_iter()
+        .zip(names)
+        .map(|((org_id, animals), name)| {
+            json!({ "org_id": org_id, "org_name": name, "animals": animals })
+        })
+        .collect()
+}
@@ -0,0 +1,251 @@
+use crate::cli::{BreedRescueArgs, OrgIdArgs, OrgSearchArgs, ToolArgs};
+use crate::client::{fan_out, get_organization_details};
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::fmt::extract_single_item;
+use crate::petfinder::ID_PREFIX;
+use crate::source::{search_animals, search_orgs};
+use crate::validate;
+use serde_json::{json, Value};
+
+/// Nearby listings of a breed that mark an org as a likely breed rescue
+/// even when its name and description don't mention the breed.
+const MIN_BREED_LISTINGS: usize = 2;
+
+/// A candidate rescue and why it was picked.
+struct Candidate {
+    org: Value,
+    matched_name: bool,
+    matched_description: bool,
+    breed_listings: usize,
+}
+
+/// Organizations near a location that specialize in `breed`: ones naming it
+/// in their name or description, and ones currently listing several pets
+/// of it. Orgs matching by name or description come first, then by how many
+/// of the breed they list.
+pub async fn find_breed_rescues(
+    settings: &Settings,
+    args: BreedRescueArgs,
+) -> Result<Value, AppError> {
+    let breed = args.breed.trim().to_string();
+    if breed.is_empty() {
+        return Err(AppError::invalid_argument(
+            "breed",
+            "breed must not be empty".to_string(),
+        ));
+    }
+    validate::miles(args.miles)?;
+
+    let org_search = |query: Option<String>| OrgSearchArgs {
+        postal_code: args.postal_code.clone(),
+        miles: args.miles,
+        query,
+        species: None,
+    };
+    // Named matches come from the API; descriptions are only searchable in
+    // the page of nearby orgs
+    let (named, nearby, animals) = tokio::join!(
+        search_orgs(settings, org_search(Some(breed.clone()))),
+        search_orgs(settings, org_search(None)),
+        search_animals(
+            settings,
+            ToolArgs {
+                postal_code: args.postal_code.clone(),
+                miles: args.miles,
+                species: args.species.clone(),
+                breeds: Some(breed.clone()),
+                ..Default::default()
+            },
+        ),
+    );
+    let (named, nearby, animals) = (named?, nearby?, animals?);
+
+    let needle = breed.to_lowercase();
+    let mentions = |org: &Value, key: &str| {
+        org["attributes"][key]
+            .as_str()
+            .is_some_and(|text| text.to_lowercase().contains(&needle))
+    };
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for org in named["data"]
+        .as_array()
+        .into_iter()
+        .chain(nearby["data"].as_array())
+        .flatten()
+    {
+        let (matched_name, matched_description) = (mentions(org, "name"), mentions(org, "about"));
+        if (matched_name || matched_description)
+            && !candidates.iter().any(|c| c.org["id"] == org["id"])
+        {
+            candidates.push(Candidate {
+                org: org.clone(),
+                matched_name,
+                matched_description,
+                breed_listings: 0,
+            });
+        }
+    }
+
+    let mut listing_counts: Vec<(String, usize)> = Vec::new();
+    for animal in animals["data"].as_array().into_iter().flatten() {
+        let Some(org_id) = animal["relationships"]["orgs"]["data"][0]["id"].as_str() else {
+            continue;
+        };
+        match listing_counts.iter_mut().find(|(id, _)| id == org_id) {
+            Some((_, count)) => *count += 1,
+            None => listing_counts.push((org_id.to_string(), 1)),
+        }
+    }
+    let mut unknown = Vec::new();
+    for (org_id, count) in listing_counts {
+        match candidates
+            .iter_mut()
+            .find(|c| c.org["id"] == org_id.as_str())
+        {
+            Some(candidate) => candidate.breed_listings = count,
+            None if count >= MIN_BREED_LISTINGS => unknown.push((org_id, count)),
+            None => {}
+        }
+    }
+    let details = fan_out(
+        settings,
+        unknown.iter().map(|(org_id, _)| async move {
+            if org_id.starts_with(ID_PREFIX) {
+                return json!({ "id": org_id });
+            }
+            get_organization_details(
+                settings,
+                OrgIdArgs {
+                    org_id: org_id.clone(),
+                },
+            )
+            .await
+            .ok()
+            .and_then(|org| org.get("data").and_then(extract_single_item).cloned())
+            .unwrap_or_else(|| json!({ "id": org_id }))
+        }),
+    )
+    .await;
+    for (org, (_, count)) in details.into_iter().zip(unknown) {
+        candidates.push(Candidate {
+            org,
+            matched_name: false,
+            matched_description: false,
+            breed_listings: count,
+        });
+    }
+
+    candidates.sort_by_key(|c| {
+        (
+            !(c.matched_name || c.matched_description),
+            std::cmp::Reverse(c.breed_listings),
+        )
+    });
+    let data: Vec<Value> = candidates
+        .into_iter()
+        .map(|c| {
+            let attrs = &c.org["attributes"];
+            json!({
+                "org_id": c.org["id"],
+                "name": attrs["name"],
+                "city": attrs["city"],
+                "state": attrs["state"],
+                "email": attrs["email"],
+                "phone": attrs["phone"],
+                "url": attrs["url"],
+                "matched_name": c.matched_name,
+                "matched_description": c.matched_description,
+                "breed_listings": c.breed_listings,
+            })
+        })
+        .collect();
+    Ok(json!({ "breed": breed, "data": data }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use moka::future::Cache;
+
+    use std::sync::Arc;
+
+    fn get_test_settings(url: String) -> Settings {
+        Settings {
+            cache: Arc::new(Cache::new(0)),
+            ..Settings::for_test(
+                url,
+                std::env::temp_dir().join("rescue-groups-mcp-breed-rescues"),
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_breed_rescues() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _named = server
+            .mock("POST", "/public/orgs/search")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "data": {"filters": [{"fieldName": "orgs.name", "criteria": "Beagle"}]}
+            })))
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Beagle Freedom"}}]}"#)
+            .create_async()
+            .await;
+        let _nearby = server
+            .mock("POST", "/public/orgs/search")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [
+                    {"id": "1", "attributes": {"name": "Beagle Freedom"}},
+                    {"id": "2", "attributes": {"name": "Hound Haven", "about": "We rescue beagles and bassets."}},
+                    {"id": "3", "attributes": {"name": "City Shelter"}}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+        let _animals = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .with_status(200)
+            .with_body(
+                json!({"data": [
+                    {"id": "10", "relationships": {"orgs": {"data": [{"id": "3"}]}}},
+                    {"id": "11", "relationships": {"orgs": {"data": [{"id": "3"}]}}},
+                    {"id": "12", "relationships": {"orgs": {"data": [{"id": "3"}]}}},
+                    {"id": "13", "relationships": {"orgs": {"data": [{"id": "2"}]}}},
+                    {"id": "14", "relationships": {"orgs": {"data": [{"id": "4"}]}}}
+                ]})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let _org = server
+            .mock("GET", "/public/orgs/3")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "3", "attributes": {"name": "City Shelter", "city": "Austin"}}]}"#)
+            .create_async()
+            .await;
+
+        let args = BreedRescueArgs {
+            breed: " Beagle ".to_string(),
+            species: None,
+            postal_code: None,
+            miles: None,
+        };
+        let result = find_breed_rescues(&settings, args).await.unwrap();
+        assert_eq!(result["breed"], "Beagle");
+        let orgs = result["data"].as_array().unwrap();
+        let ids: Vec<&str> = orgs.iter().map(|o| o["org_id"].as_str().unwrap()).collect();
+        // Specialists first, then a general shelter listing several; a
+        // single listing elsewhere doesn't count
+        assert_eq!(ids, ["2", "1", "3"]);
+        assert_eq!(orgs[0]["matched_description"], true);
+        assert_eq!(orgs[0]["breed_listings"], 1);
+        assert_eq!(orgs[1]["matched_name"], true);
+        assert_eq!(orgs[2]["city"], "Austin");
+        assert_eq!(orgs[2]["breed_listings"], 3);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::trace;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests upstream and save every exchange
+    Record,
+    /// Serve saved exchanges and never contact the upstream API
+    Replay,
+}
+
+/// A directory of recorded upstream exchanges, one JSON file per request.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    pub mode: CassetteMode,
+    pub dir: PathBuf,
+}
+
+/// One recorded request/response pair. Credentials are never stored.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Interaction {
+    pub method: String,
+    /// Path and query, without scheme and host, so a cassette replays
+    /// against any base URL
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    pub status: u16,
+    pub response: Value,
+}
+
+/// Strips scheme and host from `url`.
+fn request_path(url: &str) -> &str {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    rest.find('/').map(|i| &rest[i..]).unwrap_or("/")
+}
+
+impl Cassette {
+    fn file_name(method: &str, path: &str, body: Option<&Value>) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(method.as_bytes());
+        hasher.update(path.as_bytes());
+        if let Some(body) = body {
+            hasher.update(body.to_string().as_bytes());
+        }
+        let digest = hex::encode(hasher.finalize());
+        format!("{}-{}.json", method.to_lowercase(), &digest[..16])
+    }
+
+    fn load(&self, method: &str, url: &str, body: Option<&Value>) -> Result<Interaction, AppError> {
+        let path = request_path(url);
+        let file = self.dir.join(Self::file_name(method, path, body));
+        let content = fs::read_to_string(&file).map_err(|_| {
+            AppError::ApiError(format!(
+                "No recorded response for {} {} in cassette {}",
+                method,
+                path,
+                self.dir.display()
+            ))
+        })?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn save(&self, interaction: &Interaction) -> Result<(), AppError> {
+        fs::create_dir_all(&self.dir)?;
+        let file = self.dir.join(Self::file_name(
+            &interaction.method,
+            &interaction.path,
+            interaction.body.as_ref(),
+        ));
+        fs::write(file, serde_json::to_string_pretty(interaction)?)?;
+        Ok(())
+    }
+}
+
+/// Performs an upstream request through the configured cassette: `send` is
+/// only called when not replaying, and its result is saved when recording.
+/// Returns the status and, for successful responses, the JSON body.
+pub async fn exchange<F, Fut>(
+    settings: &Settings,
+    method: &str,
+    url: &str,
+    body: Option<&Value>,
+    send: F,
+) -> Result<(StatusCode, Value), AppError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<reqwest::Response, AppError>>,
+{
+    if let Some(cassette) = settings
+        .cassette
+        .as_ref()
+        .filter(|c| c.mode == CassetteMode::Replay)
+    {
+        let interaction = cassette.load(method, url, body)?;
+        let status = StatusCode::from_u16(interaction.status)
+            .map_err(|e| AppError::Internal(format!("Invalid recorded status: {}", e)))?;
+        trace::upstream(
+            settings,
+            method,
+            url,
+            body,
+            Some(status),
+            &interaction.response,
+        );
+        return Ok((status, interaction.response));
+    }
+
+    let response = send().await?;
+    let status = response.status();
+    let bytes = read_body(settings, url, response).await?;
+    let data: Value = if status.is_success() {
+        serde_json::from_slice(&bytes)?
+    } else {
+        // Error bodies are kept when they are JSON, for their details
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null)
+    };
+    trace::upstream(settings, method, url, body, Some(status), &data);
+
+    if let Some(cassette) = settings
+        .cassette
+        .as_ref()
+        .filter(|c| c.mode == CassetteMode::Record)
+    {
+        cassette.save(&Interaction {
+            method: method.to_string(),
+            path: request_path(url).to_string(),
+            body: body.cloned(),
+            status: status.as_u16(),
+            response: data.clone(),
+        })?;
+    }
+    Ok((status, data))
+}
+
+/// Reads a response body, giving up as soon as it exceeds
+/// `max_response_bytes` rather than holding all of it in memory.
+async fn read_body(
+    settings: &Settings,
+    url: &str,
+    mut response: reqwest::Response,
+) -> Result<Vec<u8>, AppError> {
+    let too_large = |limit: usize| {
+        AppError::ApiError(format!(
+            "Response from {} is larger than the {} KB limit (max_response_kb)",
+            request_path(url),
+            limit / 1024
+        ))
+    };
+    let limit = settings.max_response_bytes;
+    if let (Some(limit), Some(length)) = (limit, response.content_length()) {
+        if length > limit as u64 {
+            return Err(too_large(limit));
+        }
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if let Some(limit) = limit.filter(|&limit| body.len() > limit) {
+            return Err(too_large(limit));
+        }
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{BreedIdArgs, SpeciesArgs};
+    use crate::client::{get_breed_details, list_breeds, list_species};
+
+    use moka::future::Cache;
+
+    use std::sync::Arc;
+
+    fn get_test_settings(url: String, cassette: Cassette) -> Settings {
+        Settings {
+            cassette: Some(cassette),
+            cache: Arc::new(Cache::new(0)),
+            ..Settings::for_test(url, std::env::temp_dir().join("rescue-groups-mcp-test"))
+        }
+    }
+
+    #[test]
+    fn test_request_path() {
+        assert_eq!(
+            request_path("https://api.rescuegroups.org/v5/public/animals?limit=1"),
+            "/v5/public/animals?limit=1"
+        );
+        assert_eq!(request_path("http://127.0.0.1:1234"), "/");
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let dir = std::env::temp_dir().join("rescue-groups-mcp-cassette");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut server = mockito::Server::new_async().await;
+        let _species = server
+            .mock("GET", "/public/animals/species")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "8", "attributes": {"singular": "Dog", "plural": "Dogs"}}]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let _breeds = server
+            .mock("GET", "/public/animals/species/8/breeds")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let recorder = get_test_settings(
+            server.url(),
+            Cassette {
+                mode: CassetteMode::Record,
+                dir: dir.clone(),
+            },
+        );
+        list_species(&recorder).await.unwrap();
+        let args = SpeciesArgs {
+            species: "8".to_string(),
+        };
+        assert!(list_breeds(&recorder, args.clone()).await.is_err());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        // Replaying never reaches a server, and recorded errors are reproduced
+        let player = get_test_settings(
+            "http://127.0.0.1:9".to_string(),
+            Cassette {
+                mode: CassetteMode::Replay,
+                dir: dir.clone(),
+            },
+        );
+        let species = list_species(&player).await.unwrap();
+        assert_eq!(species["data"][0]["attributes"]["plural"], "Dogs");
+        assert!(matches!(
+            list_breeds(&player, args).await,
+            Err(AppError::NotFound)
+        ));
+        let missing = get_breed_details(
+            &player,
+            BreedIdArgs {
+                breed_id: "1".to_string(),
+            },
+        )
+        .await;
+        assert!(
+            matches!(missing, Err(AppError::ApiError(msg)) if msg.contains("No recorded response"))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,6 +1,8 @@
+use crate::export::{ExportFormat, ResultsFormat};
 use clap::{Args, Parser, Subcommand};
 use clap_complete::Shell;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(author, version = env!("PROJECT_VERSION"), about)]
@@ -10,10 +12,55 @@ pub struct Cli {
     #[arg(long, default_value = "config.toml")]
     pub config: String,
 
+    /// Only read the --config file, skipping the system-wide and user
+    /// configuration files
+    #[arg(long, env = "RESCUE_GROUPS_NO_SYSTEM_CONFIG")]
+    pub no_system_config: bool,
+
     /// Output raw JSON instead of formatted text
     #[arg(long, global = true)]
     pub json: bool,
 
+    /// Save every upstream request/response pair to DIR (for bug reports and
+    /// regression tests)
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Serve upstream responses from a directory written by --record,
+    /// without contacting the API
+    #[arg(long, global = true, value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+
+    /// Log every upstream request URL and body with a summary of its
+    /// response (credentials redacted)
+    #[arg(long, global = true)]
+    pub trace_api: bool,
+
+    /// Print the upstream requests a command would make (credentials
+    /// redacted) instead of sending them
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Give up on a command after SECS seconds, overriding `timeout_seconds`
+    /// and `call_timeout_seconds` from the config
+    #[arg(long, global = true, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout: Option<u64>,
+
+    /// Send API requests to URL, overriding `base_url` from the config and
+    /// the selected profile
+    #[arg(long, global = true, value_name = "URL")]
+    pub base_url: Option<String>,
+
+    /// Apply the settings of a named profile: `production`, `mock` (a local
+    /// fixture server) or one defined under `[profiles.<name>]`
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Make output reproducible: random order replaced by ID order, ties
+    /// broken by ID and ages and timestamps worked out at a fixed time
+    #[arg(long, global = true)]
+    pub deterministic: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -23,6 +70,7 @@ pub enum Commands {
     /// Start the MCP server (default)
     Server,
     /// Start the MCP server in HTTP mode
+    #[cfg(feature = "http")]
     Http(HttpArgs),
     /// Search for adoptable pets
     Search(ToolArgs),
@@ -32,14 +80,32 @@ pub enum Commands {
     GetAnimal(AnimalIdArgs),
     /// Get contact information for a specific animal
     GetContact(AnimalIdArgs),
+    /// List an animal's photos with their captions and available sizes
+    Photos(AnimalIdArgs),
     /// Compare multiple animals side-by-side
     Compare(CompareArgs),
+    /// Compare animals and save the result under a shareable token
+    SaveComparison(CompareArgs),
+    /// Show a saved comparison by its token
+    GetComparison(ComparisonTokenArgs),
     /// Search for rescue organizations
     SearchOrgs(OrgSearchArgs),
+    /// Find rescues near a location that specialize in a breed
+    FindBreedRescues(BreedRescueArgs),
     /// Get details for a specific organization
     GetOrg(OrgIdArgs),
+    /// Get full contact details for a specific organization
+    GetOrgContact(OrgIdArgs),
     /// List animals at a specific organization
-    ListOrgAnimals(OrgIdArgs),
+    ListOrgAnimals(OrgAnimalsArgs),
+    /// Find an organization's animal by the reference number it assigned
+    FindByReference(ReferenceArgs),
+    /// List the sites of a multi-site organization
+    ListOrgLocations(OrgIdArgs),
+    /// Show how to adopt from an organization, or a specific animal
+    AdoptionProcess(OrgOrAnimalArgs),
+    /// Show how to sponsor an animal or donate to an organization
+    Sponsorship(OrgOrAnimalArgs),
     /// Get a random adoptable pet
     RandomPet {
         #[arg(long)]
@@ -49,16 +115,130 @@ pub enum Commands {
     ListAdopted(AdoptedAnimalsArgs),
     /// List available breeds for a species
     ListBreeds(SpeciesArgs),
+    /// Suggest breeds matching a partial or misspelled name
+    SuggestBreeds(BreedSuggestArgs),
     /// Get details for a specific breed
     GetBreed(BreedIdArgs),
     /// List metadata values (colors, patterns, etc.)
     ListMetadata(MetadataArgs),
     /// List available metadata types
     ListMetadataTypes,
+    /// Explain the filter fields searches are built from
+    ExplainFilters(FilterReferenceArgs),
+    /// Look up a postal code or place name in the offline postal database
+    LookupLocation(LocationArgs),
     /// Generate shell completions or man pages
     Generate(GenerateArgs),
+    /// Run animal IDs or search queries read from stdin (one per line)
+    Batch(BatchArgs),
+    /// Save a named search to be polled for new matches
+    SaveSearch(SaveSearchArgs),
+    /// List saved searches
+    ListSavedSearches,
+    /// Delete a saved search
+    DeleteSavedSearch(SavedSearchNameArgs),
+    /// Run all saved searches once and report new matches
+    CheckAlerts,
+    /// Add an animal to your favorites
+    SaveFavorite(FavoriteArgs),
+    /// List your favorite animals
+    ListFavorites,
+    /// Remove an animal from your favorites
+    RemoveFavorite(AnimalIdArgs),
+    /// Share your favorites
+    Favorites {
+        #[command(subcommand)]
+        action: FavoritesAction,
+    },
+    /// Report which animals are no longer available or changed since they
+    /// were last checked
+    CheckUpdates(CheckUpdatesArgs),
+    /// Add a note to an animal (shown with its details)
+    AddNote(NoteArgs),
+    /// Show your notes on an animal
+    GetNotes(AnimalIdArgs),
+    /// Rank adoptable pets by how well their descriptions match a free-text query
+    SemanticSearch(SemanticSearchArgs),
+    /// List pets listed nearby in the last few days, grouped by organization
+    NewArrivals(NewArrivalsArgs),
+    /// Compare how many matching pets are near each of several postal codes
+    CompareLocations(CompareLocationsArgs),
+    /// Find pets that fit your lifestyle, with an explained match score
+    MatchMeAPet(MatchArgs),
+    /// Export every animal an organization has published (CSV, JSON or XLSX)
+    ExportOrg(ExportOrgArgs),
+    /// Save search results to a file (CSV, JSON or Markdown) in an allowed
+    /// directory (see `[export]`)
+    ExportResults(ExportResultsArgs),
+    /// Render an organization's adoptable animals to a static HTML site
+    ExportSite(ExportSiteArgs),
+    /// Report what's new since the last run for the configured digest searches
+    Digest(DigestArgs),
+    /// Convert a free-text request into search arguments
+    ParseQuery(ParseQueryArgs),
+    /// Show the effective configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Show or clear the local tool usage statistics (see `[metrics]`)
+    Stats {
+        #[command(subcommand)]
+        action: StatsAction,
+    },
+    /// Shelter-staff commands for your own organization (see `[private_api]`)
+    Private {
+        #[command(subcommand)]
+        action: PrivateAction,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum PrivateAction {
+    /// Every animal of your organization, including ones not yet public
+    ListAnimals,
+    /// Get any animal of your organization, public or not
+    GetAnimal(AnimalIdArgs),
+    /// Show the organization your account belongs to
+    Org,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum FavoritesAction {
+    /// Write your favorites, with photos, key attributes, organization
+    /// contacts and your notes, to a file to share or bring to a visit
+    Export(FavoritesExportArgs),
 }
 
+#[derive(Args, Clone, Debug)]
+pub struct FavoritesExportArgs {
+    #[arg(long, value_enum, default_value = "md")]
+    pub format: ResultsFormat,
+
+    /// File to write (default: favorites.<format>)
+    #[arg(long)]
+    pub output: Option<String>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigAction {
+    /// Print the values the configuration layers add up to
+    Show {
+        /// Also show the file, variable or flag each value came from
+        #[arg(long)]
+        origin: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum StatsAction {
+    /// Per-tool call counts, argument usage, error rates and latencies
+    Report,
+    /// Delete the recorded statistics
+    Reset,
+}
+
+#[cfg(feature = "http")]
 #[derive(Args, Clone, Debug)]
 pub struct HttpArgs {
     /// Host to bind to
@@ -72,6 +252,18 @@ pub struct HttpArgs {
     /// Optional authentication token (Bearer token)
     #[arg(long, env = "MCP_AUTH_TOKEN")]
     pub auth_token: Option<String>,
+
+    /// Detach from the terminal and run in the background (unix only)
+    #[arg(long)]
+    pub daemonize: bool,
+
+    /// Write the server's process ID to this file
+    #[arg(long)]
+    pub pid_file: Option<String>,
+
+    /// Write logs to this file instead of stderr (reopened on SIGHUP)
+    #[arg(long)]
+    pub log_file: Option<String>,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -83,9 +275,198 @@ pub struct GenerateArgs {
     /// Generate man pages to the specified directory
     #[arg(short, long)]
     pub man: Option<String>,
+
+    /// Write completion files to this directory instead of stdout
+    /// (all shells unless --shell is given)
+    #[arg(long)]
+    pub completions_dir: Option<String>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct ExportSiteArgs {
+    #[arg(long)]
+    pub org_id: String,
+
+    /// Directory to write the site to (created if missing)
+    #[arg(long, default_value = "site")]
+    pub out_dir: String,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct ExportOrgArgs {
+    #[arg(long)]
+    pub org_id: String,
+
+    #[arg(long, value_enum, default_value = "csv")]
+    pub format: ExportFormat,
+
+    /// File to write (default: org-<id>.<format>)
+    #[arg(long)]
+    pub output: Option<String>,
 }
 
 #[derive(Args, Deserialize, Clone, Debug)]
+pub struct ExportResultsArgs {
+    /// File to write; must be inside one of the `[export]` allowed_dirs
+    #[arg(long)]
+    pub path: String,
+
+    /// Output format (default: from the file extension, else CSV)
+    #[arg(long, value_enum)]
+    pub format: Option<ResultsFormat>,
+
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub search: ToolArgs,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct DigestArgs {
+    /// Emit HTML instead of Markdown
+    #[arg(long)]
+    pub html: bool,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct BatchArgs {
+    /// Maximum number of requests to run at the same time
+    #[arg(long, default_value = "4")]
+    pub concurrency: usize,
+}
+
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct SaveSearchArgs {
+    /// Name used to identify the saved search in alerts
+    #[arg(long)]
+    pub name: String,
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub search: ToolArgs,
+}
+
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct SavedSearchNameArgs {
+    #[arg(long)]
+    pub name: String,
+}
+
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct SemanticSearchArgs {
+    /// Free-text description of the pet you're looking for
+    #[arg(long)]
+    pub query: String,
+    /// Number of ranked results to return (default 5)
+    #[arg(long)]
+    pub limit: Option<usize>,
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub search: ToolArgs,
+}
+
+#[derive(Args, Deserialize, Clone, Debug, Default)]
+pub struct NewArrivalsArgs {
+    #[arg(long)]
+    pub postal_code: Option<String>,
+    #[arg(long)]
+    pub species: Option<String>,
+    #[arg(long)]
+    pub miles: Option<u32>,
+    /// How many days back to look (default 7)
+    #[arg(long)]
+    pub days: Option<u32>,
+}
+
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct CompareLocationsArgs {
+    /// Two to five postal codes to search around
+    #[arg(long, value_delimiter = ',')]
+    pub postal_codes: Vec<String>,
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub search: ToolArgs,
+}
+
+/// Lifestyle quiz answers for `match-me-a-pet`. All answers are optional.
+#[derive(Args, Deserialize, Clone, Debug, Default)]
+pub struct MatchArgs {
+    /// apartment, house or farm
+    #[arg(long)]
+    pub home_type: Option<String>,
+    #[arg(long)]
+    pub has_yard: Option<bool>,
+    /// Hours the pet would be alone on a typical day
+    #[arg(long)]
+    pub hours_away: Option<u32>,
+    #[arg(long)]
+    pub has_kids: Option<bool>,
+    /// Whether there are dogs already in the home
+    #[arg(long)]
+    pub has_dogs: Option<bool>,
+    /// Whether there are cats already in the home
+    #[arg(long)]
+    pub has_cats: Option<bool>,
+    /// Your activity level: low, moderate or high
+    #[arg(long)]
+    pub activity_level: Option<String>,
+    /// How much grooming you're willing to do: low, moderate or high
+    #[arg(long)]
+    pub grooming_tolerance: Option<String>,
+    #[arg(long)]
+    pub species: Option<String>,
+    #[arg(long)]
+    pub postal_code: Option<String>,
+    #[arg(long)]
+    pub miles: Option<u32>,
+    /// Number of ranked results to return (default 5)
+    #[arg(long)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct LocationArgs {
+    /// A postal code (78704, K1P 1J1) or place name ("Austin, TX")
+    #[arg(long)]
+    pub query: String,
+}
+
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct ParseQueryArgs {
+    /// e.g. "young small dog good with cats within 30 miles of 78704"
+    #[arg(long)]
+    pub query: String,
+}
+
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct FavoriteArgs {
+    #[arg(long)]
+    pub animal_id: String,
+    /// Optional note to keep with the favorite
+    #[arg(long)]
+    pub note: Option<String>,
+}
+
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct CheckUpdatesArgs {
+    /// Comma-separated list of animal IDs seen before
+    #[arg(long, value_delimiter = ',')]
+    #[serde(default)]
+    pub animal_ids: Vec<String>,
+    /// Also check your favorites (the default when no IDs are given)
+    #[arg(long)]
+    #[serde(default)]
+    pub favorites: bool,
+}
+
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct NoteArgs {
+    #[arg(long)]
+    pub animal_id: String,
+    /// e.g. "emailed 3/4, meet-and-greet Saturday"
+    #[arg(long)]
+    pub note: String,
+}
+
+#[derive(Args, Deserialize, Serialize, Clone, Debug, Default)]
 pub struct ToolArgs {
     #[arg(long)]
     pub postal_code: Option<String>,
@@ -113,12 +494,54 @@ pub struct ToolArgs {
     pub special_needs: Option<bool>,
     #[arg(long)]
     pub needs_foster: Option<bool>,
+    /// Set to false to hide courtesy listings posted on behalf of another caretaker
+    #[arg(long)]
+    pub courtesy_listings: Option<bool>,
+    /// Free-text match against the listing's color description
     #[arg(long)]
     pub color: Option<String>,
+    /// Exact color group, one of `list-metadata --metadata-type colors`
+    #[arg(long)]
+    pub color_group: Option<String>,
     #[arg(long)]
     pub pattern: Option<String>,
     #[arg(long)]
     pub sort_by: Option<String>,
+    /// Name of a search preset defined in the config file
+    #[arg(long)]
+    pub preset: Option<String>,
+    /// Page of results to fetch, from 1
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub page: Option<u32>,
+}
+
+impl ToolArgs {
+    /// Fills every unset field from `base`, so explicitly passed arguments
+    /// always win over a preset.
+    pub fn with_defaults_from(self, base: &ToolArgs) -> ToolArgs {
+        ToolArgs {
+            postal_code: self.postal_code.or_else(|| base.postal_code.clone()),
+            miles: self.miles.or(base.miles),
+            species: self.species.or_else(|| base.species.clone()),
+            breeds: self.breeds.or_else(|| base.breeds.clone()),
+            sex: self.sex.or_else(|| base.sex.clone()),
+            age: self.age.or_else(|| base.age.clone()),
+            size: self.size.or_else(|| base.size.clone()),
+            good_with_children: self.good_with_children.or(base.good_with_children),
+            good_with_dogs: self.good_with_dogs.or(base.good_with_dogs),
+            good_with_cats: self.good_with_cats.or(base.good_with_cats),
+            house_trained: self.house_trained.or(base.house_trained),
+            special_needs: self.special_needs.or(base.special_needs),
+            needs_foster: self.needs_foster.or(base.needs_foster),
+            courtesy_listings: self.courtesy_listings.or(base.courtesy_listings),
+            color: self.color.or_else(|| base.color.clone()),
+            color_group: self.color_group.or_else(|| base.color_group.clone()),
+            pattern: self.pattern.or_else(|| base.pattern.clone()),
+            sort_by: self.sort_by.or_else(|| base.sort_by.clone()),
+            preset: self.preset,
+            page: self.page.or(base.page),
+        }
+    }
 }
 
 #[derive(Args, Deserialize, Clone, Debug)]
@@ -140,12 +563,32 @@ pub struct CompareArgs {
     pub animal_ids: Vec<String>,
 }
 
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct ComparisonTokenArgs {
+    /// Token returned when the comparison was saved
+    #[arg(long)]
+    pub token: String,
+}
+
 #[derive(Args, Deserialize, Clone, Debug)]
 pub struct SpeciesArgs {
     #[arg(long)]
     pub species: String,
 }
 
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct BreedSuggestArgs {
+    /// A partial or misspelled breed name
+    #[arg(long)]
+    pub query: String,
+    /// Species whose breeds to match (default: the configured species)
+    #[arg(long)]
+    pub species: Option<String>,
+    /// Number of candidates to return (default 5)
+    #[arg(long)]
+    pub limit: Option<usize>,
+}
+
 #[derive(Args, Deserialize, Clone, Debug)]
 pub struct OrgSearchArgs {
     #[arg(long)]
@@ -154,6 +597,22 @@ pub struct OrgSearchArgs {
     pub miles: Option<u32>,
     #[arg(long)]
     pub query: Option<String>,
+    /// Only organizations currently listing this species nearby
+    #[arg(long)]
+    pub species: Option<String>,
+}
+
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct BreedRescueArgs {
+    /// Breed to find specialist rescues for (e.g. "Beagle")
+    #[arg(long)]
+    pub breed: String,
+    #[arg(long)]
+    pub species: Option<String>,
+    #[arg(long)]
+    pub postal_code: Option<String>,
+    #[arg(long)]
+    pub miles: Option<u32>,
 }
 
 #[derive(Args, Deserialize, Clone, Debug)]
@@ -162,6 +621,34 @@ pub struct OrgIdArgs {
     pub org_id: String,
 }
 
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct OrgAnimalsArgs {
+    #[arg(long)]
+    pub org_id: String,
+    /// Only animals of this species (e.g. cats)
+    #[arg(long)]
+    pub species: Option<String>,
+}
+
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct ReferenceArgs {
+    #[arg(long)]
+    pub org_id: String,
+    /// The organization's own reference number for the animal
+    #[arg(long)]
+    pub reference: String,
+}
+
+/// Names an organization directly, or through one of its animals.
+#[derive(Args, Deserialize, Clone, Debug, Default)]
+#[group(required = true, multiple = false)]
+pub struct OrgOrAnimalArgs {
+    #[arg(long)]
+    pub org_id: Option<String>,
+    #[arg(long)]
+    pub animal_id: Option<String>,
+}
+
 #[derive(Args, Deserialize, Clone, Debug)]
 pub struct AdoptedAnimalsArgs {
     #[arg(long)]
@@ -180,6 +667,17 @@ pub struct MetadataArgs {
     pub species: Option<String>,
 }
 
+#[derive(Args, Deserialize, Clone, Debug)]
+pub struct FilterReferenceArgs {
+    /// Species whose breeds, colors and patterns to list (default: the
+    /// configured species)
+    #[arg(long)]
+    pub species: Option<String>,
+    /// Only this filter field (e.g. "animals.ageGroup") or tool argument
+    #[arg(long)]
+    pub field: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,6 +695,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_search_preset_arg() {
+        let args = vec!["prog", "search", "--preset", "senior-cats"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Some(Commands::Search(search_args)) => {
+                assert_eq!(search_args.preset, Some("senior-cats".to_string()));
+            }
+            _ => panic!("Expected Search command"),
+        }
+    }
+
+    #[test]
+    fn test_tool_args_with_defaults_from() {
+        let preset = ToolArgs {
+            species: Some("cats".to_string()),
+            age: Some("Senior".to_string()),
+            ..Default::default()
+        };
+        let args = ToolArgs {
+            age: Some("Adult".to_string()),
+            postal_code: Some("12345".to_string()),
+            ..Default::default()
+        };
+        let merged = args.with_defaults_from(&preset);
+        assert_eq!(merged.species.as_deref(), Some("cats"));
+        assert_eq!(merged.age.as_deref(), Some("Adult"));
+        assert_eq!(merged.postal_code.as_deref(), Some("12345"));
+    }
+
     #[test]
     fn test_server_command() {
         let args = vec!["prog", "server"];
@@ -204,6 +732,7 @@ mod tests {
         assert!(matches!(cli.command, Some(Commands::Server)));
     }
 
+    #[cfg(feature = "http")]
     #[test]
     fn test_http_command() {
         let args = vec!["prog", "http", "--port", "8080", "--host", "127.0.0.1"];
@@ -212,6 +741,30 @@ mod tests {
             Some(Commands::Http(http_args)) => {
                 assert_eq!(http_args.port, 8080);
                 assert_eq!(http_args.host, "127.0.0.1");
+                assert!(!http_args.daemonize);
+            }
+            _ => panic!("Expected Http command"),
+        }
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_http_daemon_args() {
+        let args = vec![
+            "prog",
+            "http",
+            "--daemonize",
+            "--pid-file",
+            "/tmp/rg.pid",
+            "--log-file",
+            "/tmp/rg.log",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Some(Commands::Http(http_args)) => {
+                assert!(http_args.daemonize);
+                assert_eq!(http_args.pid_file.as_deref(), Some("/tmp/rg.pid"));
+                assert_eq!(http_args.log_file.as_deref(), Some("/tmp/rg.log"));
             }
             _ => panic!("Expected Http command"),
         }
@@ -280,11 +833,201 @@ mod tests {
         match cli.command {
             Some(Commands::Generate(gen_args)) => {
                 assert!(gen_args.shell.is_some());
+                assert!(gen_args.completions_dir.is_none());
             }
             _ => panic!("Expected Generate command"),
         }
     }
 
+    #[test]
+    fn test_batch_command() {
+        let args = vec!["prog", "batch", "--concurrency", "8"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Some(Commands::Batch(batch_args)) => {
+                assert_eq!(batch_args.concurrency, 8);
+            }
+            _ => panic!("Expected Batch command"),
+        }
+    }
+
+    #[test]
+    fn test_save_search_command() {
+        let args = vec!["prog", "save-search", "--name", "cats", "--species", "cats"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Some(Commands::SaveSearch(save_args)) => {
+                assert_eq!(save_args.name, "cats");
+                assert_eq!(save_args.search.species.as_deref(), Some("cats"));
+            }
+            _ => panic!("Expected SaveSearch command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_semantic_search() {
+        let args = vec![
+            "prog",
+            "semantic-search",
+            "--query",
+            "calm lap cat",
+            "--species",
+            "cats",
+            "--limit",
+            "3",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Some(Commands::SemanticSearch(search_args)) => {
+                assert_eq!(search_args.query, "calm lap cat");
+                assert_eq!(search_args.limit, Some(3));
+                assert_eq!(search_args.search.species.as_deref(), Some("cats"));
+            }
+            _ => panic!("Expected SemanticSearch command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_match_me_a_pet() {
+        let args = vec![
+            "prog",
+            "match-me-a-pet",
+            "--home-type",
+            "apartment",
+            "--has-kids",
+            "true",
+            "--hours-away",
+            "9",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Some(Commands::MatchMeAPet(match_args)) => {
+                assert_eq!(match_args.home_type.as_deref(), Some("apartment"));
+                assert_eq!(match_args.has_kids, Some(true));
+                assert_eq!(match_args.hours_away, Some(9));
+            }
+            _ => panic!("Expected MatchMeAPet command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_save_favorite() {
+        let args = vec![
+            "prog",
+            "save-favorite",
+            "--animal-id",
+            "42",
+            "--note",
+            "Loves kids",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Some(Commands::SaveFavorite(fav_args)) => {
+                assert_eq!(fav_args.animal_id, "42");
+                assert_eq!(fav_args.note.as_deref(), Some("Loves kids"));
+            }
+            _ => panic!("Expected SaveFavorite command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_favorites_export() {
+        let args = vec!["prog", "favorites", "export", "--format", "md"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Some(Commands::Favorites {
+                action: FavoritesAction::Export(export_args),
+            }) => {
+                assert_eq!(export_args.format, ResultsFormat::Markdown);
+                assert!(export_args.output.is_none());
+            }
+            _ => panic!("Expected Favorites Export command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_export_site() {
+        let args = vec!["prog", "export-site", "--org-id", "866"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Some(Commands::ExportSite(site_args)) => {
+                assert_eq!(site_args.org_id, "866");
+                assert_eq!(site_args.out_dir, "site");
+            }
+            _ => panic!("Expected ExportSite command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_export_org() {
+        let args = vec!["prog", "export-org", "--org-id", "9", "--format", "xlsx"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Some(Commands::ExportOrg(export_args)) => {
+                assert_eq!(export_args.org_id, "9");
+                assert_eq!(export_args.format, ExportFormat::Xlsx);
+                assert!(export_args.output.is_none());
+            }
+            _ => panic!("Expected ExportOrg command"),
+        }
+        assert!(Cli::try_parse_from(vec![
+            "prog",
+            "export-org",
+            "--org-id",
+            "9",
+            "--format",
+            "pdf"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_cli_digest() {
+        let cli = Cli::try_parse_from(vec!["prog", "digest", "--html"]).unwrap();
+        match cli.command {
+            Some(Commands::Digest(digest_args)) => assert!(digest_args.html),
+            _ => panic!("Expected Digest command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_query() {
+        let args = vec!["prog", "parse-query", "--query", "small dog near 78704"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Some(Commands::ParseQuery(query_args)) => {
+                assert_eq!(query_args.query, "small dog near 78704")
+            }
+            _ => panic!("Expected ParseQuery command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_record_replay_conflict() {
+        let cli = Cli::try_parse_from(vec!["prog", "list-species", "--record", "out"]).unwrap();
+        assert_eq!(cli.record, Some(PathBuf::from("out")));
+        assert!(Cli::try_parse_from(vec!["prog", "--record", "a", "--replay", "b"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_timeout() {
+        let cli = Cli::try_parse_from(vec!["prog", "list-species", "--timeout", "5"]).unwrap();
+        assert_eq!(cli.timeout, Some(5));
+        assert!(Cli::try_parse_from(vec!["prog", "--timeout", "0", "list-species"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_stats_report() {
+        let cli = Cli::try_parse_from(vec!["prog", "stats", "report"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Stats {
+                action: StatsAction::Report
+            })
+        ));
+        assert!(Cli::try_parse_from(vec!["prog", "stats"]).is_err());
+    }
+
     #[test]
     fn test_search_events_removed() {
         let args = vec!["prog", "search-events"];
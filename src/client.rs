@@ -1,12 +1,51 @@
+use crate::cassette::exchange;
 use crate::cli::{
-    AdoptedAnimalsArgs, AnimalIdArgs, BreedIdArgs, CompareArgs, MetadataArgs, OrgIdArgs,
-    OrgSearchArgs, SpeciesArgs, ToolArgs,
+    AdoptedAnimalsArgs, AnimalIdArgs, BreedIdArgs, BreedSuggestArgs, CompareArgs, MetadataArgs,
+    OrgAnimalsArgs, OrgIdArgs, OrgOrAnimalArgs, OrgSearchArgs, ReferenceArgs, SpeciesArgs,
+    ToolArgs,
 };
 use crate::config::Settings;
+use crate::drift;
 use crate::error::AppError;
 use crate::fmt::extract_single_item;
+use crate::metrics;
+use crate::models::{parse_list, parse_single, Animal, Breed, Organization, Species};
+use crate::postal::resolve_location;
+use crate::shared_cache;
+use crate::source::{get_animal, get_contact};
+use crate::synonyms::{ranked, species_synonym, suggest};
+use crate::trace;
+use crate::validate;
+use futures::stream::{self, StreamExt};
+use moka::future::Cache;
 use serde_json::{json, Value};
-use tokio::task::JoinSet;
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tracing::warn;
+
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/nicholaswilde/rescue-groups-mcp/releases/latest";
+
+// Release checks are cached for a day so get_server_info never hammers GitHub
+static RELEASE_CACHE: LazyLock<Cache<String, String>> = LazyLock::new(|| {
+    Cache::builder()
+        .max_capacity(4)
+        .time_to_live(Duration::from_secs(24 * 60 * 60))
+        .build()
+});
+
+// Failed checks are remembered for an hour, so an unreachable GitHub doesn't
+// hold up every get_server_info call
+static RELEASE_FAILURES: LazyLock<Cache<String, String>> = LazyLock::new(|| {
+    Cache::builder()
+        .max_capacity(4)
+        .time_to_live(Duration::from_secs(60 * 60))
+        .build()
+});
+
+/// Upper bound on a release check, however long API calls may take
+const RELEASE_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 
 async fn fetch_with_cache(
     settings: &Settings,
@@ -14,62 +53,369 @@ async fn fetch_with_cache(
     method: &str,
     body: Option<Value>,
 ) -> Result<Value, AppError> {
-    let cache_key = format!(
+    let cache_key = cache_key(url, method, body.as_ref());
+
+    let mut data = match shared_cache::cached(settings, &cache_key).await {
+        Some(cached) => {
+            trace::upstream(settings, method, url, body.as_ref(), None, &cached);
+            cached
+        }
+        None => {
+            let data = fetch_public(settings, url, method, body.as_ref()).await?;
+            drift::inspect(&data);
+            shared_cache::store(settings, cache_key, &data).await;
+            data
+        }
+    };
+    if settings.deterministic {
+        break_ties_by_id(&mut data, url);
+    }
+    Ok(data)
+}
+
+/// Compares two resources by ID, numerically when both IDs are numbers.
+fn compare_ids(a: &Value, b: &Value) -> std::cmp::Ordering {
+    let id = |item: &Value| match &item["id"] {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        _ => String::new(),
+    };
+    let (a, b) = (id(a), id(b));
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(&b),
+    }
+}
+
+/// Sorts the resources in a response's `data` list by ID. Resources without
+/// an ID keep their place relative to each other.
+pub(crate) fn sort_by_id(response: &mut Value) {
+    if let Some(items) = response.get_mut("data").and_then(Value::as_array_mut) {
+        items.sort_by(compare_ids);
+    }
+}
+
+/// Orders resources that tie on the attribute `url` asked the API to sort
+/// by (e.g. `sort=-animals.createdDate`) by ID, keeping the API's order
+/// otherwise. Listings without a `sort` are left as they are.
+fn break_ties_by_id(response: &mut Value, url: &str) {
+    let Some(field) = url
+        .split_once('?')
+        .and_then(|(_, query)| query.split('&').find_map(|p| p.strip_prefix("sort=")))
+        .and_then(|sort| sort.trim_start_matches('-').rsplit('.').next())
+    else {
+        return;
+    };
+    let Some(items) = response.get_mut("data").and_then(Value::as_array_mut) else {
+        return;
+    };
+    let tied = |a: &Value, b: &Value| {
+        let key = &a["attributes"][field];
+        !key.is_null() && *key == b["attributes"][field]
+    };
+    for run in items.chunk_by_mut(tied) {
+        run.sort_by(compare_ids);
+    }
+}
+
+/// Flags a response holding one page of a longer listing with
+/// `truncated` and the `total` count from its `meta`.
+pub(crate) fn mark_truncation(mut response: Value) -> Value {
+    let shown = response["data"].as_array().map_or(0, Vec::len) as u64;
+    let total = response["meta"]["count"].as_u64();
+    let pages = response["meta"]["pages"].as_u64().unwrap_or(1);
+    if pages > 1 || total.is_some_and(|total| total > shown) {
+        response["truncated"] = json!(true);
+        response["total"] = json!(total);
+    }
+    response
+}
+
+fn cache_key(url: &str, method: &str, body: Option<&Value>) -> String {
+    format!(
         "{}:{}:{}",
         method,
         url,
-        body.as_ref().map(|b| b.to_string()).unwrap_or_default()
-    );
+        body.map(|b| b.to_string()).unwrap_or_default()
+    )
+}
+
+/// Sends one rate-limited request with the given `Authorization` header,
+/// bypassing the response cache.
+pub(crate) async fn fetch_upstream(
+    settings: &Settings,
+    url: &str,
+    method: &str,
+    body: Option<&Value>,
+    authorization: &str,
+) -> Result<Value, AppError> {
+    // Wait until a spot is available, across replicas if shared
+    let ready = shared_cache::until_ready(settings, &settings.limiter);
+    let (status, data) = send_upstream(settings, url, method, body, authorization, ready).await?;
+    check_status(status, data)
+}
+
+/// Sends a request with the API key, rotating through the configured keys
+/// and failing over to another one when a key is rate-limited or refused.
+async fn fetch_public(
+    settings: &Settings,
+    url: &str,
+    method: &str,
+    body: Option<&Value>,
+) -> Result<Value, AppError> {
+    // A dry run doesn't take a turn with any of the keys
+    let Some(pool) = settings.key_pool.as_ref().filter(|_| !settings.dry_run) else {
+        return fetch_upstream(settings, url, method, body, &settings.api_key).await;
+    };
+
+    let session = settings.session.as_deref().unwrap_or_default();
+    let mut attempts = pool.len();
+    loop {
+        let index = settings
+            .queue
+            .run(session, settings.priority, || pool.acquire())
+            .await?;
+        let ready = async {
+            shared_cache::until_shared_ready(settings).await;
+            Ok(())
+        };
+        let (status, data) =
+            send_upstream(settings, url, method, body, pool.key(index), ready).await?;
+        attempts -= 1;
+        if !pool.report(index, status) || attempts == 0 {
+            return check_status(status, data);
+        }
+    }
+}
 
-    if let Some(cached) = settings.cache.get(&cache_key).await {
-        return Ok(cached);
+async fn send_upstream(
+    settings: &Settings,
+    url: &str,
+    method: &str,
+    body: Option<&Value>,
+    authorization: &str,
+    ready: impl Future<Output = Result<(), AppError>>,
+) -> Result<(reqwest::StatusCode, Value), AppError> {
+    if settings.dry_run {
+        let headers = [
+            ("Authorization", authorization),
+            ("Content-Type", "application/vnd.api+json"),
+        ];
+        trace::planned(settings, method, url, &headers, body);
+        return Err(AppError::DryRun);
     }
+    // Set once the rate limit lets the request go, so its latency doesn't
+    // include the wait
+    let sent_at = std::sync::OnceLock::new();
+    let result = exchange(settings, method, url, body, || async {
+        // Check rate limit before making the request, but not past the deadline
+        match settings.deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), ready)
+                .await
+                .map_err(|_| AppError::DeadlineExceeded)??,
+            None => ready.await?,
+        }
+        let _ = sent_at.set(std::time::Instant::now());
 
-    // Check rate limit before making the request
-    // Wait until a spot is available
-    settings.limiter.until_ready().await;
+        let client = reqwest::Client::builder()
+            .timeout(settings.request_timeout()?)
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to build client: {}", e)))?;
 
-    let client = reqwest::Client::builder()
-        .timeout(settings.timeout)
-        .build()
-        .map_err(|e| AppError::Internal(format!("Failed to build client: {}", e)))?;
+        let mut request = match method {
+            "POST" => client.post(url),
+            _ => client.get(url),
+        };
 
-    let mut request = match method {
-        "POST" => client.post(url),
-        _ => client.get(url),
-    };
+        request = request
+            .header("Authorization", authorization)
+            .header("Content-Type", "application/vnd.api+json");
 
-    request = request
-        .header("Authorization", &settings.api_key)
-        .header("Content-Type", "application/vnd.api+json");
+        if let Some(b) = body {
+            request = request.json(b);
+        }
 
-    if let Some(b) = body {
-        request = request.json(&b);
+        request.send().await.map_err(|e| settings.deadline_error(e))
+    })
+    .await;
+    if let Some(sent_at) = sent_at.get() {
+        let ok = matches!(&result, Ok((status, _)) if status.is_success());
+        metrics::record_upstream(settings, method, url, sent_at.elapsed(), ok).await;
     }
+    result
+}
 
-    let response = request.send().await?;
-
-    if !response.status().is_success() {
-        if response.status() == reqwest::StatusCode::NOT_FOUND {
+fn check_status(status: reqwest::StatusCode, data: Value) -> Result<Value, AppError> {
+    if !status.is_success() {
+        if status == reqwest::StatusCode::NOT_FOUND {
             return Err(AppError::NotFound);
         }
-        return Err(AppError::ApiError(format!(
-            "API Error: {}",
-            response.status()
+        let details = error_details(&data);
+        let message = if details.is_empty() {
+            format!("API Error: {}", status)
+        } else {
+            format!("API Error: {}: {}", status, details.join("; "))
+        };
+        return Err(AppError::Upstream {
+            status: status.as_u16(),
+            message,
+        });
+    }
+    Ok(data)
+}
+
+/// The `detail` (or failing that, `title`) of each error in a JSON:API
+/// error document.
+fn error_details(data: &Value) -> Vec<String> {
+    data["errors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e["detail"].as_str().or_else(|| e["title"].as_str()))
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Makes one small, uncached request with `api_key` to check that the API
+/// accepts it. A refused key is a `ConfigError`; other failures are passed
+/// through as they are.
+pub async fn check_api_key(settings: &Settings) -> Result<(), AppError> {
+    let ready = async {
+        settings.limiter.until_ready().await;
+        Ok(())
+    };
+    verify_api_key(settings, ready).await
+}
+
+/// `check_api_key` for a caller that already took a rate limit slot.
+#[cfg(feature = "http")]
+pub(crate) async fn check_api_key_now(settings: &Settings) -> Result<(), AppError> {
+    verify_api_key(settings, async { Ok(()) }).await
+}
+
+async fn verify_api_key(
+    settings: &Settings,
+    ready: impl Future<Output = Result<(), AppError>>,
+) -> Result<(), AppError> {
+    let url = format!("{}/public/animals/species?limit=1", settings.base_url);
+    let (status, data) =
+        send_upstream(settings, &url, "GET", None, &settings.api_key, ready).await?;
+    if matches!(
+        status,
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN
+    ) {
+        return Err(AppError::ConfigError(format!(
+            "The RescueGroups API rejected the API key ({}). Check RESCUE_GROUPS_API_KEY or api_key in config.toml",
+            status
         )));
     }
+    check_status(status, data).map(|_| ())
+}
 
-    let data: Value = response.json().await?;
-    settings.cache.insert(cache_key, data.clone()).await;
-    Ok(data)
+/// Error for a species the API doesn't know, suggesting close names.
+fn unknown_species(species: &str, data: &[Value]) -> AppError {
+    let mut names: Vec<String> = data
+        .iter()
+        .filter_map(|s| s["attributes"]["plural"].as_str())
+        .map(str::to_lowercase)
+        .collect();
+    names.sort();
+
+    let suggestions = suggest(species, &names);
+    let hint = if suggestions.is_empty() {
+        format!("Available species: {}", names.join(", "))
+    } else {
+        format!("Did you mean: {}?", suggestions.join(", "))
+    };
+    AppError::invalid_argument(
+        "species",
+        format!("Unknown species '{}'. {}", species, hint),
+    )
+}
+
+/// The canonical name of the color group `group` (any case) for a species.
+/// Unknown groups are an error naming the closest valid ones, since an
+/// exact filter on a misspelled group would silently match nothing.
+async fn resolve_color_group(
+    settings: &Settings,
+    species: &str,
+    group: &str,
+) -> Result<String, AppError> {
+    let data = list_metadata(
+        settings,
+        MetadataArgs {
+            metadata_type: "colors".to_string(),
+            species: Some(species.to_string()),
+        },
+    )
+    .await?;
+    let mut names: Vec<String> = data["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|c| c["attributes"]["name"].as_str())
+        .map(str::to_string)
+        .collect();
+    if let Some(name) = names.iter().find(|name| name.eq_ignore_ascii_case(group)) {
+        return Ok(name.clone());
+    }
+
+    names.sort();
+    names.dedup();
+    let suggestions = suggest(group, &names);
+    let hint = if suggestions.is_empty() {
+        format!("Available color groups: {}", names.join(", "))
+    } else {
+        format!("Did you mean: {}?", suggestions.join(", "))
+    };
+    Err(AppError::invalid_argument(
+        "color_group",
+        format!(
+        "Unknown color group '{}'. {} Use 'color' for a partial match on the color description.",
+        group, hint
+    ),
+    ))
 }
 
 async fn resolve_species_id(settings: &Settings, species: &str) -> Result<String, AppError> {
     if species.chars().all(char::is_numeric) {
         return Ok(species.to_string());
     }
+    // "puppy", "bunny" etc. name a species too
+    let species = species_synonym(species).map_or(species, |(plural, _)| plural);
+
+    let target = species.to_lowercase();
+    let url = format!("{}/public/animals/species", settings.base_url);
+    let key = cache_key(&url, "GET", None);
+    let (mut species_list, from_cache) = match shared_cache::cached(settings, &key).await {
+        Some(list) => (list, true),
+        None => (list_species(settings).await?, false),
+    };
+    let mut found = find_species(&species_list, &target)?;
+    // Marks a refresh for the lifetime of a cache entry, so made-up species
+    // don't refetch the list on every call
+    let refreshed_key = format!("refreshed:{}", key);
+    if found.is_none() && from_cache && !settings.cache.contains_key(&refreshed_key) {
+        // A stale or truncated cached list; check the live one before giving up
+        settings.cache.insert(refreshed_key, Value::Null).await;
+        shared_cache::invalidate(settings, &key).await;
+        species_list = list_species(settings).await?;
+        found = find_species(&species_list, &target)?;
+    }
+
+    match found {
+        Some(id) => Ok(id),
+        None => Err(unknown_species(
+            species,
+            species_list["data"].as_array().map_or(&[], Vec::as_slice),
+        )),
+    }
+}
 
-    let species_list = list_species(settings).await?;
+/// The ID of the species named `target` (lowercase, singular or plural).
+fn find_species(species_list: &Value, target: &str) -> Result<Option<String>, AppError> {
     let data = species_list
         .get("data")
         .and_then(|d| d.as_array())
@@ -77,19 +423,15 @@ async fn resolve_species_id(settings: &Settings, species: &str) -> Result<String
             "Failed to fetch species list for resolution".to_string(),
         ))?;
 
-    let target = species.to_lowercase();
-    let found = data.iter().find(|s| {
-        let attrs = &s["attributes"];
-        let singular = attrs["singular"].as_str().unwrap_or("").to_lowercase();
-        let plural = attrs["plural"].as_str().unwrap_or("").to_lowercase();
-        singular == target || plural == target
-    });
-
-    if let Some(s) = found {
-        Ok(s["id"].as_str().unwrap_or("").to_string())
-    } else {
-        Err(AppError::NotFound)
-    }
+    Ok(data
+        .iter()
+        .find(|s| {
+            let attrs = &s["attributes"];
+            let singular = attrs["singular"].as_str().unwrap_or("").to_lowercase();
+            let plural = attrs["plural"].as_str().unwrap_or("").to_lowercase();
+            singular == target || plural == target
+        })
+        .map(|s| s["id"].as_str().unwrap_or("").to_string()))
 }
 
 pub async fn list_breeds(settings: &Settings, args: SpeciesArgs) -> Result<Value, AppError> {
@@ -102,6 +444,49 @@ pub async fn list_breeds(settings: &Settings, args: SpeciesArgs) -> Result<Value
     fetch_with_cache(settings, &url, "GET", None).await
 }
 
+/// Breeds of a species whose names are close to a partial or misspelled
+/// one, closest first, with their IDs. Matched against the (cached) breed
+/// list.
+pub async fn suggest_breeds(
+    settings: &Settings,
+    args: BreedSuggestArgs,
+) -> Result<Value, AppError> {
+    let species = args
+        .species
+        .unwrap_or_else(|| settings.default_species.clone());
+    let data = list_breeds(
+        settings,
+        SpeciesArgs {
+            species: species.clone(),
+        },
+    )
+    .await?;
+    let breeds: Vec<(String, Value)> = data["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|b| {
+            Some((
+                b["attributes"]["name"].as_str()?.to_string(),
+                b["id"].clone(),
+            ))
+        })
+        .collect();
+    let names: Vec<String> = breeds.iter().map(|(name, _)| name.clone()).collect();
+
+    let suggestions: Vec<Value> = ranked(&args.query, &names)
+        .into_iter()
+        .take(args.limit.unwrap_or(DEFAULT_SUGGESTIONS))
+        .map(|(distance, name)| {
+            let id = breeds.iter().find(|(n, _)| n == name).map(|(_, id)| id);
+            json!({ "id": id, "name": name, "distance": distance })
+        })
+        .collect();
+    Ok(json!({ "query": args.query, "species": species, "data": suggestions }))
+}
+
+const DEFAULT_SUGGESTIONS: usize = 5;
+
 pub async fn list_species(settings: &Settings) -> Result<Value, AppError> {
     let url = format!("{}/public/animals/species", settings.base_url);
     fetch_with_cache(settings, &url, "GET", None).await
@@ -145,7 +530,9 @@ pub async fn list_metadata_types() -> Result<Value, AppError> {
 
 pub async fn list_animals(settings: &Settings) -> Result<Value, AppError> {
     let url = format!("{}/public/animals", settings.base_url);
-    fetch_with_cache(settings, &url, "GET", None).await
+    fetch_with_cache(settings, &url, "GET", None)
+        .await
+        .map(mark_truncation)
 }
 
 pub async fn get_animal_details(
@@ -158,57 +545,90 @@ pub async fn get_animal_details(
 
 pub async fn get_contact_info(settings: &Settings, args: AnimalIdArgs) -> Result<Value, AppError> {
     let url = format!(
-        "{}/public/animals/{}?include=orgs",
+        "{}/public/animals/{}?include=orgs,locations",
         settings.base_url, args.animal_id
     );
     fetch_with_cache(settings, &url, "GET", None).await
 }
 
+/// Runs `tasks` at most `settings.concurrency` at a time, so a fan-out
+/// doesn't use up the rate limit in one go. Results keep the order of
+/// `tasks`.
+pub(crate) fn fan_out<F: Future>(
+    settings: &Settings,
+    tasks: impl IntoIterator<Item = F>,
+) -> impl Future<Output = Vec<F::Output>> {
+    // Collected first, so the returned future doesn't hold the iterator
+    let tasks: Vec<F> = tasks.into_iter().collect();
+    stream::iter(tasks)
+        .buffered(settings.concurrency.max(1))
+        .collect()
+}
+
 pub async fn compare_animals(settings: &Settings, args: CompareArgs) -> Result<Value, AppError> {
-    let mut set = JoinSet::new();
-    // Deduplicate and limit
+    validate::compare_ids(&args.animal_ids)?;
+    // Deduplicate, keeping the requested order
     let mut ids = args.animal_ids.clone();
-    ids.sort();
-    ids.dedup();
-
-    for id in ids.iter().take(5) {
-        let settings = settings.clone();
-        let id = id.clone();
-        set.spawn(
-            async move { get_animal_details(&settings, AnimalIdArgs { animal_id: id }).await },
-        );
-    }
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(id.clone()));
+
+    let results = fan_out(
+        settings,
+        ids.into_iter().map(|id| async move {
+            let result = get_animal(
+                settings,
+                AnimalIdArgs {
+                    animal_id: id.clone(),
+                },
+            )
+            .await;
+            (id, result)
+        }),
+    )
+    .await;
 
+    let requested = results.len();
     let mut valid_animals = Vec::new();
     let mut errors = Vec::new();
-
-    while let Some(res) = set.join_next().await {
-        match res {
-            Ok(Ok(val)) => {
-                if let Some(data) = val.get("data") {
-                    if let Some(animal) = extract_single_item(data) {
-                        valid_animals.push(animal.clone());
-                    }
-                }
+    for (id, result) in results {
+        let error = match result.map(|val| val.get("data").and_then(extract_single_item).cloned()) {
+            Ok(Some(animal)) => {
+                valid_animals.push(animal);
+                continue;
             }
-            Ok(Err(e)) => errors.push(e.to_string()),
-            Err(e) => errors.push(format!("Task join error: {}", e)),
-        }
+            Ok(None) => AppError::NotFound,
+            Err(e) => e,
+        };
+        errors.push(json!({
+            "animal_id": id,
+            "error": error.to_string(),
+            "kind": error.kind(),
+            "retryable": error.retryable(),
+        }));
     }
 
-    Ok(json!({ "data": valid_animals, "errors": errors }))
+    // The animals that could be fetched are still compared
+    Ok(json!({
+        "data": valid_animals,
+        "errors": errors,
+        "requested": requested,
+        "partial": !errors.is_empty(),
+    }))
 }
 
 pub async fn search_organizations(
     settings: &Settings,
     args: OrgSearchArgs,
 ) -> Result<Value, AppError> {
+    validate::miles(args.miles)?;
     let url = format!("{}/public/orgs/search", settings.base_url);
     let miles = args.miles.unwrap_or(settings.default_miles);
-    let postal_code = args
-        .postal_code
-        .as_deref()
-        .unwrap_or(&settings.default_postal_code);
+    let postal_code = resolve_location(
+        settings,
+        args.postal_code
+            .as_deref()
+            .unwrap_or(&settings.default_postal_code),
+    )?;
 
     let body = if let Some(q) = &args.query {
         json!({
@@ -237,7 +657,9 @@ pub async fn search_organizations(
         })
     };
 
-    fetch_with_cache(settings, &url, "POST", Some(body)).await
+    fetch_with_cache(settings, &url, "POST", Some(body))
+        .await
+        .map(mark_truncation)
 }
 
 pub async fn get_organization_details(
@@ -248,12 +670,314 @@ pub async fn get_organization_details(
     fetch_with_cache(settings, &url, "GET", None).await
 }
 
-pub async fn list_org_animals(settings: &Settings, args: OrgIdArgs) -> Result<Value, AppError> {
+/// An organization's contact details as one flat block: email, phone,
+/// address, website, adoption page and process, meet-and-greet policy and
+/// social links. Fields the organization hasn't filled in are null.
+pub async fn get_org_contact(settings: &Settings, args: OrgIdArgs) -> Result<Value, AppError> {
+    validate::numeric_id("org_id", &args.org_id)?;
+    let data = get_organization_details(settings, args).await?;
+    let org = data
+        .get("data")
+        .and_then(extract_single_item)
+        .ok_or(AppError::NotFound)?;
+    Ok(org_contact(org))
+}
+
+/// The organization named by `args`, and the animal too when named through
+/// one. The organization is null when the animal's listing doesn't include
+/// it (as with Petfinder animals).
+async fn org_and_animal(
+    settings: &Settings,
+    args: OrgOrAnimalArgs,
+) -> Result<(Value, Option<Value>), AppError> {
+    match (args.org_id, args.animal_id) {
+        (Some(org_id), None) => {
+            validate::numeric_id("org_id", &org_id)?;
+            let data = get_organization_details(settings, OrgIdArgs { org_id }).await?;
+            let org = data
+                .get("data")
+                .and_then(extract_single_item)
+                .ok_or(AppError::NotFound)?;
+            Ok((org.clone(), None))
+        }
+        (None, Some(animal_id)) => {
+            let data = get_contact(settings, AnimalIdArgs { animal_id }).await?;
+            let animal = data
+                .get("data")
+                .and_then(extract_single_item)
+                .ok_or(AppError::NotFound)?;
+            let org = data["included"]
+                .as_array()
+                .and_then(|inc| inc.iter().find(|item| item["type"] == "orgs"))
+                .cloned()
+                .unwrap_or_default();
+            Ok((org, Some(animal.clone())))
+        }
+        _ => Err(AppError::validation(
+            "pass either org_id or animal_id, not both".to_string(),
+        )),
+    }
+}
+
+/// A string attribute of a resource, if set to something other than
+/// whitespace.
+fn attribute(resource: &Value, key: &str) -> Option<String> {
+    resource["attributes"][key]
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// What adopting from an organization involves: its adoption page and
+/// process, fee policy, service area and where to apply. Named through an
+/// animal, the animal's own fee and listing are included too.
+pub async fn get_adoption_process(
+    settings: &Settings,
+    args: OrgOrAnimalArgs,
+) -> Result<Value, AppError> {
+    let (org, animal) = org_and_animal(settings, args).await?;
+    let animal = animal.unwrap_or_default();
+    Ok(json!({
+        "org_id": org.get("id"),
+        "org_name": attribute(&org, "name"),
+        "animal_id": animal.get("id"),
+        "animal_name": attribute(&animal, "name"),
+        "service_area": attribute(&org, "serveAreas"),
+        "adoption_process": attribute(&org, "adoptionProcess"),
+        "fee": attribute(&animal, "adoptionFeeString").or_else(|| attribute(&org, "adoptionFee")),
+        "adoption_url": attribute(&org, "adoptionUrl"),
+        "application_url": attribute(&animal, "url"),
+        "email": attribute(&org, "email"),
+        "phone": attribute(&org, "phone"),
+    }))
+}
+
+/// Ways to help without adopting: sponsoring the animal (with its minimum),
+/// fostering it, or donating to the organization.
+pub async fn get_sponsorship_info(
+    settings: &Settings,
+    args: OrgOrAnimalArgs,
+) -> Result<Value, AppError> {
+    let (org, animal) = org_and_animal(settings, args).await?;
+    let animal = animal.unwrap_or_default();
+    let yes = |key: &str| attribute(&animal, key).map(|v| v.eq_ignore_ascii_case("yes"));
+    Ok(json!({
+        "org_id": org.get("id"),
+        "org_name": attribute(&org, "name"),
+        "animal_id": animal.get("id"),
+        "animal_name": attribute(&animal, "name"),
+        "sponsorable": yes("isSponsorable"),
+        "sponsorship_minimum": attribute(&animal, "sponsorshipMinimum"),
+        "sponsorship_description": attribute(&animal, "sponsorshipDescription"),
+        "needs_foster": yes("isNeedingFoster"),
+        "sponsorship_url": attribute(&org, "sponsorshipUrl"),
+        "donation_url": attribute(&org, "donationUrl"),
+    }))
+}
+
+/// Social profiles RescueGroups may list for an organization.
+const SOCIAL_LINKS: &[(&str, &str)] = &[
+    ("facebook", "facebookUrl"),
+    ("twitter", "twitterUrl"),
+    ("instagram", "instagramUrl"),
+    ("youtube", "youtubeUrl"),
+];
+
+fn org_contact(org: &Value) -> Value {
+    let field = |key: &str| attribute(org, key);
+    let social: serde_json::Map<String, Value> = SOCIAL_LINKS
+        .iter()
+        .filter_map(|(name, key)| Some((name.to_string(), json!(field(key)?))))
+        .collect();
+
+    json!({
+        "org_id": org["id"],
+        "name": field("name"),
+        "email": field("email"),
+        "phone": field("phone"),
+        "address": {
+            "street": field("street"),
+            "city": field("city"),
+            "state": field("state"),
+            "postal_code": field("postalcode"),
+            "country": field("country"),
+        },
+        "website": field("url"),
+        "adoption_url": field("adoptionUrl"),
+        "adoption_process": field("adoptionProcess"),
+        "meet_and_greet": field("meetPets"),
+        "social": social,
+    })
+}
+
+/// The sites of a multi-site organization, with their addresses and
+/// opening hours.
+pub async fn list_org_locations(settings: &Settings, args: OrgIdArgs) -> Result<Value, AppError> {
+    validate::numeric_id("org_id", &args.org_id)?;
     let url = format!(
+        "{}/public/orgs/{}/locations",
+        settings.base_url, args.org_id
+    );
+    fetch_with_cache(settings, &url, "GET", None)
+        .await
+        .map(mark_truncation)
+}
+
+/// Every available animal at an organization, or those of one species,
+/// following pagination, with the organization and species names in
+/// `included`.
+pub async fn list_org_animals(
+    settings: &Settings,
+    args: OrgAnimalsArgs,
+) -> Result<Value, AppError> {
+    let mut url = format!(
         "{}/public/orgs/{}/animals/search/available",
         settings.base_url, args.org_id
     );
-    fetch_with_cache(settings, &url, "GET", None).await
+    if let Some(species) = &args.species {
+        let species = species.trim().to_lowercase();
+        let species = species_synonym(&species).map_or(species.as_str(), |(plural, _)| plural);
+        if species.is_empty() || !species.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(AppError::invalid_argument(
+                "species",
+                format!(
+                    "species '{}' is malformed: expected a name such as cats",
+                    species
+                ),
+            ));
+        }
+        url = format!("{}/{}", url, species);
+    }
+    fetch_all_pages(settings, &format!("{}?include=orgs,species", url), None).await
+}
+
+/// An organization's animals carrying its own reference number (the
+/// `rescueId` staff quote), whatever their adoption status. Usually one,
+/// but organizations sometimes reuse numbers.
+pub async fn find_by_reference(
+    settings: &Settings,
+    args: ReferenceArgs,
+) -> Result<Value, AppError> {
+    validate::numeric_id("org_id", &args.org_id)?;
+    let reference = args.reference.trim();
+    if reference.is_empty() {
+        return Err(AppError::invalid_argument(
+            "reference",
+            "reference is empty: pass the number the organization assigned".to_string(),
+        ));
+    }
+    let url = format!(
+        "{}/public/orgs/{}/animals/search",
+        settings.base_url, args.org_id
+    );
+    let mut filters = Vec::new();
+    add_filter(&mut filters, "animals.rescueId", "equal", reference);
+    let body = json!({ "data": { "filters": filters } });
+    fetch_with_cache(settings, &url, "POST", Some(body))
+        .await
+        .map(mark_truncation)
+}
+
+// Largest page size the API accepts
+const MAX_PAGE_SIZE: u32 = 250;
+// Guards against looping forever on a misbehaving `meta.pages`
+const MAX_PAGES: u64 = 40;
+
+/// Fetches every page of a listing endpoint, up to `MAX_PAGES`, collecting
+/// `data` and de-duplicated `included` items. With an `authorization`
+/// override the pages are fetched uncached. The API's `meta.count` of all
+/// matches is kept. If the call runs out of time part way, or there are more
+/// pages, the pages so far are returned with a `note` saying so.
+pub(crate) async fn fetch_all_pages(
+    settings: &Settings,
+    url: &str,
+    authorization: Option<&str>,
+) -> Result<Value, AppError> {
+    let mut items = Vec::new();
+    let mut included: Vec<Value> = Vec::new();
+    let separator = if url.contains('?') { '&' } else { '?' };
+    let mut page = 1;
+    let mut pages = 1;
+    let mut count = None;
+    let mut note = None;
+    loop {
+        let page_url = format!("{}{}limit={}&page={}", url, separator, MAX_PAGE_SIZE, page);
+        let fetched = match authorization {
+            Some(auth) => fetch_upstream(settings, &page_url, "GET", None, auth).await,
+            None => fetch_with_cache(settings, &page_url, "GET", None).await,
+        };
+        let response = match fetched {
+            Ok(response) => response,
+            // Out of time: keep the pages already fetched
+            Err(AppError::DeadlineExceeded) if page > 1 => {
+                warn!(
+                    "Stopped paging {} after {} of {} pages",
+                    url,
+                    page - 1,
+                    pages
+                );
+                note = Some(format!(
+                    "Only {} of {} pages were fetched before the time allowed for this call ran out",
+                    page - 1,
+                    pages
+                ));
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        let page_items = response["data"].as_array().cloned().unwrap_or_default();
+        for item in response["included"].as_array().into_iter().flatten() {
+            if !included
+                .iter()
+                .any(|i| i["type"] == item["type"] && i["id"] == item["id"])
+            {
+                included.push(item.clone());
+            }
+        }
+        count = count.or(response["meta"]["count"].as_u64());
+        let reported = response["meta"]["pages"].as_u64().unwrap_or(1);
+        pages = reported.min(MAX_PAGES);
+        let done = page_items.is_empty() || page >= pages;
+        if done && page < reported && !page_items.is_empty() {
+            note = Some(format!(
+                "Only the first {} of {} pages were fetched",
+                page, reported
+            ));
+        }
+        items.extend(page_items);
+        if done {
+            break;
+        }
+        page += 1;
+    }
+    let mut result = json!({ "data": items, "included": included });
+    if let Some(count) = count {
+        result["meta"] = json!({ "count": count });
+    }
+    if let Some(note) = note {
+        result["note"] = json!(note);
+    }
+    Ok(result)
+}
+
+/// Every available animal at an organization, following pagination.
+pub async fn list_all_org_animals(settings: &Settings, org_id: &str) -> Result<Value, AppError> {
+    let url = format!(
+        "{}/public/orgs/{}/animals/search/available",
+        settings.base_url, org_id
+    );
+    fetch_all_pages(settings, &url, None).await
+}
+
+/// Every animal an organization has published, whatever its status, with
+/// the status and species names in `included`.
+pub async fn list_org_inventory(settings: &Settings, org_id: &str) -> Result<Value, AppError> {
+    let url = format!(
+        "{}/public/orgs/{}/animals?include=statuses,species",
+        settings.base_url, org_id
+    );
+    fetch_all_pages(settings, &url, None).await
 }
 
 fn build_search_body(miles: u32, postal_code: &str, filters: Vec<Value>) -> Value {
@@ -284,29 +1008,96 @@ fn add_filter(
     }));
 }
 
+/// Resolves `args.preset` (if any) against the configured presets, with
+/// explicitly passed arguments taking precedence.
+pub fn apply_preset(settings: &Settings, args: ToolArgs) -> Result<ToolArgs, AppError> {
+    let Some(name) = args.preset.clone() else {
+        return Ok(args);
+    };
+
+    match settings.presets.get(&name) {
+        Some(preset) => Ok(args.with_defaults_from(&preset.args)),
+        None => {
+            let mut available: Vec<&str> = settings.presets.keys().map(|k| k.as_str()).collect();
+            available.sort();
+            Err(AppError::ConfigError(format!(
+                "Unknown preset '{}'. Available presets: {}",
+                name,
+                if available.is_empty() {
+                    "none".to_string()
+                } else {
+                    available.join(", ")
+                }
+            )))
+        }
+    }
+}
+
+/// `apply_preset`, then the configured `[default_filters]` for whatever is
+/// still unset. Fails for a species the server excludes.
+pub fn apply_search_defaults(settings: &Settings, args: ToolArgs) -> Result<ToolArgs, AppError> {
+    let defaults = &settings.default_filters;
+    let args = apply_preset(settings, args)?.with_defaults_from(&defaults.args);
+    let species = args
+        .species
+        .as_deref()
+        .unwrap_or(&settings.default_species)
+        .trim()
+        .to_lowercase();
+    let species = species_synonym(&species).map_or(species.as_str(), |(plural, _)| plural);
+    let excluded = defaults.exclude_species.iter().any(|excluded| {
+        let excluded = excluded.trim().to_lowercase();
+        species_synonym(&excluded).map_or(excluded.as_str(), |(plural, _)| plural) == species
+    });
+    if excluded {
+        return Err(AppError::invalid_argument(
+            "species",
+            format!("Searching for {} is disabled on this server", species),
+        ));
+    }
+    Ok(args)
+}
+
 pub async fn fetch_pets(settings: &Settings, args: ToolArgs) -> Result<Value, AppError> {
+    let args = validate::search_args(apply_search_defaults(settings, args)?)?;
+
     // Merge Tool Args with Server Defaults
     // This is the "Dynamic Lookup" logic:
     // 1. If AI sends a postal_code, use it.
     // 2. If AI sends null/nothing, use settings.default_postal_code.
     let miles = args.miles.unwrap_or(settings.default_miles);
-    let species = args.species.as_deref().unwrap_or(&settings.default_species);
-    let postal_code = args
-        .postal_code
-        .as_deref()
-        .unwrap_or(&settings.default_postal_code);
-
+    let mut species = args.species.as_deref().unwrap_or(&settings.default_species);
+    // Everyday words: "kitten" is a cat in the Baby age group
+    let mut age = args.age.clone();
+    if let Some((plural, implied_age)) = species_synonym(species) {
+        species = plural;
+        age = age.or(implied_age.map(str::to_string));
+    }
+    // Place names like "Austin, TX" are mapped to a postal code locally
+    let postal_code = resolve_location(
+        settings,
+        args.postal_code
+            .as_deref()
+            .unwrap_or(&settings.default_postal_code),
+    )?;
+
+    let random = args.sort_by.as_deref() == Some("Random");
     let sort_param = match args.sort_by.as_deref() {
         Some("Newest") => "?sort=-animals.createdDate",
         Some("Distance") => "?sort=distance",
-        Some("Random") => "?sort=random",
+        // Deterministic output lists the matches by ID instead
+        Some("Random") if !settings.deterministic => "?sort=random",
         _ => "",
     };
 
-    let url = format!(
+    let mut url = format!(
         "{}/public/animals/search/available/{}/haspic{}",
         settings.base_url, species, sort_param
     );
+    if let Some(page) = args.page.filter(|&page| page > 1) {
+        let separator = if sort_param.is_empty() { '?' } else { '&' };
+        url = format!("{}{}page={}", url, separator, page);
+    }
 
     let mut filters = Vec::new();
 
@@ -318,7 +1109,7 @@ pub async fn fetch_pets(settings: &Settings, args: ToolArgs) -> Result<Value, Ap
         add_filter(&mut filters, "animals.sex", "equal", sex);
     }
 
-    if let Some(age) = &args.age {
+    if let Some(age) = &age {
         add_filter(&mut filters, "animals.ageGroup", "equal", age);
     }
 
@@ -382,16 +1173,54 @@ pub async fn fetch_pets(settings: &Settings, args: ToolArgs) -> Result<Value, Ap
         );
     }
 
+    // Courtesy listings are included by default; only an explicit false
+    // narrows the search to animals the listing org actually cares for
+    if args.courtesy_listings == Some(false) {
+        add_filter(&mut filters, "animals.isCourtesyListing", "equal", "No");
+    }
+
     if let Some(color) = &args.color {
         add_filter(&mut filters, "animals.colorDetails", "contains", color);
     }
 
+    if let Some(group) = &args.color_group {
+        let group = resolve_color_group(settings, species, group).await?;
+        add_filter(&mut filters, "colors.name", "equal", group);
+    }
+
     if let Some(pattern) = &args.pattern {
         add_filter(&mut filters, "animals.patternDetails", "contains", pattern);
     }
 
-    let body = build_search_body(miles, postal_code, filters);
-    fetch_with_cache(settings, &url, "POST", Some(body)).await
+    let body = build_search_body(miles, &postal_code, filters);
+    match fetch_with_cache(settings, &url, "POST", Some(body)).await {
+        // A 404 is usually a misspelled species; suggest the right name
+        Err(AppError::NotFound) => match list_species(settings).await {
+            Ok(list) => {
+                let data = list["data"].as_array().cloned().unwrap_or_default();
+                let known = data.iter().any(|s| {
+                    ["singular", "plural"].iter().any(|field| {
+                        s["attributes"][field]
+                            .as_str()
+                            .is_some_and(|name| name.eq_ignore_ascii_case(species))
+                    })
+                });
+                Err(if known {
+                    AppError::NotFound
+                } else {
+                    unknown_species(species, &data)
+                })
+            }
+            Err(_) => Err(AppError::NotFound),
+        },
+        Ok(mut data) => {
+            if random && settings.deterministic {
+                sort_by_id(&mut data);
+            }
+            Ok(mark_truncation(data))
+        }
+        result => result,
+    }
 }
 
 pub async fn get_random_pet(
@@ -399,22 +1228,9 @@ pub async fn get_random_pet(
     species: Option<String>,
 ) -> Result<Value, AppError> {
     let args = ToolArgs {
-        postal_code: None,
-        miles: None,
         species,
-        breeds: None,
-        sex: None,
-        age: None,
-        size: None,
-        good_with_children: None,
-        good_with_dogs: None,
-        good_with_cats: None,
-        house_trained: None,
-        special_needs: None,
-        needs_foster: None,
-        color: None,
-        pattern: None,
         sort_by: Some("Random".to_string()),
+        ..Default::default()
     };
     fetch_pets(settings, args).await
 }
@@ -423,6 +1239,7 @@ pub async fn fetch_adopted_pets(
     settings: &Settings,
     args: AdoptedAnimalsArgs,
 ) -> Result<Value, AppError> {
+    validate::miles(args.miles)?;
     let miles = args.miles.unwrap_or(settings.default_miles);
     let species = args.species.as_deref().unwrap_or(&settings.default_species);
     let postal_code = args
@@ -437,55 +1254,260 @@ pub async fn fetch_adopted_pets(
     );
 
     let body = build_search_body(miles, postal_code, Vec::new());
-    fetch_with_cache(settings, &url, "POST", Some(body)).await
+    fetch_with_cache(settings, &url, "POST", Some(body))
+        .await
+        .map(mark_truncation)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::cli::SpeciesArgs;
-    use crate::config::Settings;
-    use governor::{Quota, RateLimiter};
-    use moka::future::Cache;
-    use std::num::NonZeroU32;
-    use std::sync::Arc;
-    use std::time::Duration;
+pub async fn fetch_latest_release(settings: &Settings, url: &str) -> Result<String, AppError> {
+    if let Some(tag) = RELEASE_CACHE.get(url).await {
+        return Ok(tag);
+    }
+    if let Some(error) = RELEASE_FAILURES.get(url).await {
+        return Err(AppError::ApiError(format!(
+            "Release check failed recently: {}",
+            error
+        )));
+    }
 
-    fn get_test_settings(url: String) -> Settings {
-        Settings {
-            api_key: "test_key".to_string(),
-            base_url: url,
-            default_postal_code: "00000".to_string(),
-            default_miles: 50,
-            default_species: "dogs".to_string(),
-            timeout: Duration::from_secs(1),
-            lazy: false,
-            cache: Arc::new(Cache::new(10)),
-            limiter: Arc::new(RateLimiter::direct(Quota::per_second(
-                NonZeroU32::new(100).unwrap(),
-            ))),
+    match request_latest_release(settings, url).await {
+        Ok(tag) => {
+            RELEASE_CACHE.insert(url.to_string(), tag.clone()).await;
+            Ok(tag)
+        }
+        Err(e) => {
+            RELEASE_FAILURES
+                .insert(url.to_string(), e.to_string())
+                .await;
+            Err(e)
         }
     }
+}
 
-    #[tokio::test]
-    async fn test_list_species() {
-        let mut server = mockito::Server::new_async().await;
-        let url = server.url();
-        let settings = get_test_settings(url);
+async fn request_latest_release(settings: &Settings, url: &str) -> Result<String, AppError> {
+    let client = reqwest::Client::builder()
+        .timeout(settings.timeout.min(RELEASE_CHECK_TIMEOUT))
+        .user_agent(concat!("rescue-groups-mcp/", env!("PROJECT_VERSION")))
+        .build()
+        .map_err(|e| AppError::Internal(format!("Failed to build client: {}", e)))?;
 
-        let mock = server
-            .mock("GET", "/public/animals/species")
-            .with_status(200)
-            .with_header("content-type", "application/vnd.api+json")
-            .with_body(r#"{"data": [{"id": "1", "type": "species", "attributes": {"singular": "Dog", "plural": "Dogs"}}]}"#)
-            .create_async()
-            .await;
+    let response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(AppError::Upstream {
+            status: response.status().as_u16(),
+            message: format!("Release check failed: {}", response.status()),
+        });
+    }
+
+    let data: Value = response.json().await?;
+    data["tag_name"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| AppError::ApiError("Release response missing tag_name".to_string()))
+}
+
+/// Extracts the leading `major.minor.patch` from versions like `v0.4.0` or
+/// `v0.4.0-3-gabc1234-dirty` (as produced by `git describe`).
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim().trim_start_matches('v');
+    let core = core.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    Some((
+        parts.next()??,
+        parts.next()??,
+        parts.next().flatten().unwrap_or(0),
+    ))
+}
+
+fn is_newer_version(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(l), Some(c)) => l > c,
+        _ => false,
+    }
+}
+
+pub async fn get_server_info(settings: &Settings) -> Result<Value, AppError> {
+    let current = env!("PROJECT_VERSION");
+    let mut info = json!({
+        "version": current,
+        "git_revision": env!("GIT_REVISION"),
+        "defaults": {
+            "api_key": "[redacted]",
+            "base_url": settings.base_url,
+            "postal_code": settings.default_postal_code,
+            "miles": settings.default_miles,
+            "species": settings.default_species,
+            "timeout_seconds": settings.timeout.as_secs(),
+            "lazy": settings.lazy,
+        },
+        "update_check": settings.update_check,
+    });
+    if let Some(pool) = &settings.key_pool {
+        info["api_keys"] = pool.usage();
+    }
+    let drift = drift::report();
+    if drift.as_object().is_some_and(|d| !d.is_empty()) {
+        info["schema_drift"] = drift;
+    }
+
+    if settings.update_check {
+        match fetch_latest_release(settings, LATEST_RELEASE_URL).await {
+            Ok(latest) => {
+                info["update_available"] = json!(is_newer_version(&latest, current));
+                info["latest_version"] = json!(latest);
+            }
+            Err(e) => {
+                warn!("Update check failed: {}", e);
+                info["update_error"] = json!(e.to_string());
+            }
+        }
+    }
+
+    Ok(info)
+}
+
+/// Typed RescueGroups API client for use as a library.
+///
+/// Shares the caching and rate limiting of the MCP server, but returns
+/// typed models instead of raw JSON.
+#[derive(Clone, Debug)]
+pub struct RescueGroupsClient {
+    settings: Settings,
+}
+
+impl RescueGroupsClient {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self::from_settings(Settings::new(api_key))
+    }
+
+    pub fn from_settings(settings: Settings) -> Self {
+        Self { settings }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.settings.base_url = base_url.into();
+        self
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub async fn search_animals(&self, args: ToolArgs) -> Result<Vec<Animal>, AppError> {
+        parse_list(&fetch_pets(&self.settings, args).await?)
+    }
+
+    pub async fn get_animal(&self, animal_id: &str) -> Result<Animal, AppError> {
+        let args = AnimalIdArgs {
+            animal_id: animal_id.to_string(),
+        };
+        parse_single(&get_animal_details(&self.settings, args).await?)
+    }
+
+    pub async fn search_organizations(
+        &self,
+        args: OrgSearchArgs,
+    ) -> Result<Vec<Organization>, AppError> {
+        parse_list(&search_organizations(&self.settings, args).await?)
+    }
+
+    pub async fn get_organization(&self, org_id: &str) -> Result<Organization, AppError> {
+        let args = OrgIdArgs {
+            org_id: org_id.to_string(),
+        };
+        parse_single(&get_organization_details(&self.settings, args).await?)
+    }
+
+    pub async fn list_org_animals(&self, org_id: &str) -> Result<Vec<Animal>, AppError> {
+        let args = OrgAnimalsArgs {
+            org_id: org_id.to_string(),
+            species: None,
+        };
+        parse_list(&list_org_animals(&self.settings, args).await?)
+    }
+
+    pub async fn list_breeds(&self, species: &str) -> Result<Vec<Breed>, AppError> {
+        let args = SpeciesArgs {
+            species: species.to_string(),
+        };
+        parse_list(&list_breeds(&self.settings, args).await?)
+    }
+
+    pub async fn list_species(&self) -> Result<Vec<Species>, AppError> {
+        parse_list(&list_species(&self.settings).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::SpeciesArgs;
+    use crate::config::Settings;
+    use governor::{Quota, RateLimiter};
+
+    use std::num::NonZeroU32;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn get_test_settings(url: String) -> Settings {
+        Settings::for_test(url, std::env::temp_dir().join("rescue-groups-mcp-test"))
+    }
+
+    #[tokio::test]
+    async fn test_list_species() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+        let settings = get_test_settings(url);
+
+        let mock = server
+            .mock("GET", "/public/animals/species")
+            .with_status(200)
+            .with_header("content-type", "application/vnd.api+json")
+            .with_body(r#"{"data": [{"id": "1", "type": "species", "attributes": {"singular": "Dog", "plural": "Dogs"}}]}"#)
+            .create_async()
+            .await;
 
         let result = list_species(&settings).await.unwrap();
         mock.assert_async().await;
         assert_eq!(result["data"][0]["attributes"]["singular"], "Dog");
     }
 
+    #[tokio::test]
+    async fn test_key_pool_failover() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings(server.url());
+        settings.key_pool = Some(Arc::new(crate::keys::KeyPool::new(
+            vec!["key-a".to_string(), "key-b".to_string()],
+            NonZeroU32::new(100).unwrap(),
+            Duration::from_secs(60),
+        )));
+
+        let limited = server
+            .mock("GET", "/public/animals/species")
+            .match_header("authorization", "key-a")
+            .with_status(429)
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", "/public/animals/species")
+            .match_header("authorization", "key-b")
+            .with_status(200)
+            .with_body(r#"{"data": []}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        // key-a is tried first, gets rate-limited and the request fails over
+        list_species(&settings).await.unwrap();
+        limited.assert_async().await;
+        ok.assert_async().await;
+        assert_eq!(
+            settings.key_pool.as_ref().unwrap().usage()[0]["in_rotation"],
+            false
+        );
+    }
+
     #[tokio::test]
     async fn test_resolve_species_id_numeric() {
         let settings = get_test_settings("http://localhost".to_string());
@@ -514,6 +1536,42 @@ mod tests {
         assert_eq!(id, "1");
     }
 
+    #[tokio::test]
+    async fn test_resolve_species_id_refreshes_stale_cache() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        // Cached before cats were added
+        let url = format!("{}/public/animals/species", server.url());
+        settings
+            .cache
+            .insert(
+                cache_key(&url, "GET", None),
+                json!({"data": [{"id": "1", "attributes": {"singular": "Dog", "plural": "Dogs"}}]}),
+            )
+            .await;
+        let mock = server
+            .mock("GET", "/public/animals/species")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"singular": "Dog", "plural": "Dogs"}}, {"id": "3", "attributes": {"singular": "Cat", "plural": "Cats"}}]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        assert_eq!(resolve_species_id(&settings, "dogs").await.unwrap(), "1");
+        assert_eq!(resolve_species_id(&settings, "cats").await.unwrap(), "3");
+        // The refreshed list is cached again
+        assert_eq!(resolve_species_id(&settings, "cat").await.unwrap(), "3");
+        // Unknown species don't refresh it again until the cache expires
+        for _ in 0..3 {
+            assert!(matches!(
+                resolve_species_id(&settings, "zebra").await,
+                Err(AppError::ValidationError { .. })
+            ));
+        }
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_resolve_species_id_not_found() {
         let mut server = mockito::Server::new_async().await;
@@ -524,12 +1582,22 @@ mod tests {
             .mock("GET", "/public/animals/species")
             .with_status(200)
             .with_header("content-type", "application/vnd.api+json")
-            .with_body(r#"{"data": []}"#)
+            .with_body(
+                r#"{"data": [{"id": "8", "attributes": {"singular": "Dog", "plural": "Dogs"}}]}"#,
+            )
             .create_async()
             .await;
 
-        let result = resolve_species_id(&settings, "cat").await;
-        assert!(matches!(result, Err(AppError::NotFound)));
+        let result = resolve_species_id(&settings, "dgos").await;
+        assert!(
+            matches!(result, Err(AppError::ValidationError { message: msg, .. }) if msg == "Unknown species 'dgos'. Did you mean: dogs?")
+        );
+        let result = resolve_species_id(&settings, "zebra").await;
+        assert!(
+            matches!(result, Err(AppError::ValidationError { message: msg, .. }) if msg.ends_with("Available species: dogs"))
+        );
+        // Synonyms resolve to their species
+        assert_eq!(resolve_species_id(&settings, "doggo").await.unwrap(), "8");
     }
 
     #[tokio::test]
@@ -596,7 +1664,7 @@ mod tests {
         let _mock = server
             .mock(
                 "POST",
-                "/public/animals/search/available/dogs/haspic?sort=-animals.createdDate",
+                "/public/animals/search/available/dogs/haspic?sort=-animals.createdDate&page=2",
             )
             .with_status(200)
             .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Buddy"}}]}"#)
@@ -617,15 +1685,188 @@ mod tests {
             house_trained: Some(true),
             special_needs: Some(false),
             needs_foster: Some(false),
+            courtesy_listings: Some(false),
             color: Some("Black".to_string()),
+            color_group: None,
             pattern: Some("Solid".to_string()),
             sort_by: Some("Newest".to_string()),
+            preset: None,
+            page: Some(2),
         };
 
         let result = fetch_pets(&settings, args).await.unwrap();
         assert_eq!(result["data"][0]["attributes"]["name"], "Buddy");
     }
 
+    #[cfg(feature = "postal-db")]
+    #[tokio::test]
+    async fn test_fetch_pets_resolves_place_name() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let mock = server
+            .mock("POST", "/public/animals/search/available/cats/haspic")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "data": {"filterRadius": {"postalcode": "90210"}}
+            })))
+            .with_status(200)
+            .with_body(r#"{"data": []}"#)
+            .create_async()
+            .await;
+
+        let args = ToolArgs {
+            postal_code: Some("Beverly Hills, CA".to_string()),
+            species: Some("cats".to_string()),
+            ..Default::default()
+        };
+        fetch_pets(&settings, args).await.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pets_default_filters() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings(server.url());
+        settings.default_filters.args.good_with_children = Some(true);
+        settings.default_filters.args.courtesy_listings = Some(false);
+        settings.default_filters.exclude_species = vec!["Bunnies".to_string()];
+
+        let _defaults = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"data": {"filters": [
+                    {"fieldName": "animals.isGoodWithChildren", "operation": "equal", "criteria": "No"},
+                    {"fieldName": "animals.isCourtesyListing", "operation": "equal", "criteria": "No"}
+                ]}}"#
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(r#"{"data": []}"#)
+            .create_async()
+            .await;
+
+        // An explicit argument wins over the default
+        let args = ToolArgs {
+            species: Some("dogs".to_string()),
+            good_with_children: Some(false),
+            ..Default::default()
+        };
+        assert!(fetch_pets(&settings, args).await.is_ok());
+
+        let args = ToolArgs {
+            species: Some("rabbits".to_string()),
+            ..Default::default()
+        };
+        let err = fetch_pets(&settings, args).await.unwrap_err();
+        assert!(
+            matches!(err, AppError::ValidationError { message: msg, .. } if msg.contains("rabbits is disabled"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pets_species_synonym() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let mock = server
+            .mock("POST", "/public/animals/search/available/cats/haspic")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "data": {"filters": [{"fieldName": "animals.ageGroup", "operation": "equal", "criteria": "Baby"}]}
+            })))
+            .with_status(200)
+            .with_body(r#"{"data": []}"#)
+            .create_async()
+            .await;
+
+        let args = ToolArgs {
+            species: Some("kitten".to_string()),
+            ..Default::default()
+        };
+        fetch_pets(&settings, args).await.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pets_color_group() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _species = server
+            .mock("GET", "/public/animals/species")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "3", "attributes": {"singular": "Cat", "plural": "Cats"}}]}"#,
+            )
+            .create_async()
+            .await;
+        let _colors = server
+            .mock("GET", "/public/animals/species/3/colors")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "1", "attributes": {"name": "Black"}}, {"id": "2", "attributes": {"name": "Tortoiseshell"}}]}"#,
+            )
+            .create_async()
+            .await;
+        let mock = server
+            .mock("POST", "/public/animals/search/available/cats/haspic")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "data": {"filters": [
+                    {"fieldName": "animals.colorDetails", "operation": "contains", "criteria": "smoky"},
+                    {"fieldName": "colors.name", "operation": "equal", "criteria": "Tortoiseshell"}
+                ]}
+            })))
+            .with_status(200)
+            .with_body(r#"{"data": []}"#)
+            .create_async()
+            .await;
+
+        let args = ToolArgs {
+            species: Some("cats".to_string()),
+            color: Some("smoky".to_string()),
+            color_group: Some("tortoiseshell".to_string()),
+            ..Default::default()
+        };
+        fetch_pets(&settings, args.clone()).await.unwrap();
+        mock.assert_async().await;
+
+        let args = ToolArgs {
+            color_group: Some("Tortoishell".to_string()),
+            ..args
+        };
+        let err = fetch_pets(&settings, args).await.unwrap_err().to_string();
+        assert!(err.contains("Unknown color group 'Tortoishell'"));
+        assert!(err.contains("Did you mean: Tortoiseshell"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pets_unknown_species_suggests() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _search = server
+            .mock("POST", "/public/animals/search/available/rabits/haspic")
+            .with_status(404)
+            .create_async()
+            .await;
+        let _species = server
+            .mock("GET", "/public/animals/species")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "3", "attributes": {"singular": "Rabbit", "plural": "Rabbits"}}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let args = ToolArgs {
+            species: Some("rabits".to_string()),
+            ..Default::default()
+        };
+        let result = fetch_pets(&settings, args).await;
+        assert!(
+            matches!(result, Err(AppError::ValidationError { message: msg, .. }) if msg.contains("Did you mean: rabbits"))
+        );
+    }
+
     #[tokio::test]
     async fn test_search_organizations() {
         let mut server = mockito::Server::new_async().await;
@@ -642,6 +1883,7 @@ mod tests {
             postal_code: None,
             miles: None,
             query: Some("Rescue".to_string()),
+            species: None,
         };
 
         let result = search_organizations(&settings, args).await.unwrap();
@@ -669,6 +1911,71 @@ mod tests {
         assert_eq!(result["data"][0]["attributes"]["name"], "Buddy");
     }
 
+    #[tokio::test]
+    async fn test_get_random_pet_deterministic() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings(server.url());
+        settings.deterministic = true;
+
+        let _mock = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "10", "attributes": {"name": "Rex"}},
+                             {"id": "9", "attributes": {"name": "Buddy"}},
+                             {"id": "10", "attributes": {"name": "Rex again"}}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let result = get_random_pet(&settings, Some("dogs".to_string()))
+            .await
+            .unwrap();
+        let names: Vec<&str> = result["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["attributes"]["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["Buddy", "Rex", "Rex again"]);
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_keeps_requested_sort() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings(server.url());
+        settings.deterministic = true;
+
+        let _mock = server
+            .mock(
+                "POST",
+                "/public/animals/search/available/dogs/haspic?sort=-animals.createdDate",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "3", "attributes": {"createdDate": "2024-03-01"}},
+                             {"id": "12", "attributes": {"createdDate": "2024-02-01"}},
+                             {"id": "7", "attributes": {"createdDate": "2024-02-01"}},
+                             {"id": "1", "attributes": {"createdDate": "2024-01-01"}}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let args = ToolArgs {
+            species: Some("dogs".to_string()),
+            sort_by: Some("Newest".to_string()),
+            ..Default::default()
+        };
+        let result = fetch_pets(&settings, args).await.unwrap();
+        let ids: Vec<&str> = result["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["3", "7", "12", "1"]);
+    }
+
     #[tokio::test]
     async fn test_fetch_adopted_pets() {
         let mut server = mockito::Server::new_async().await;
@@ -718,7 +2025,7 @@ mod tests {
         let settings = get_test_settings(server.url());
 
         let _mock = server
-            .mock("GET", "/public/animals/123?include=orgs")
+            .mock("GET", "/public/animals/123?include=orgs,locations")
             .with_status(200)
             .with_body(r#"{"data": {"id": "123"}, "included": [{"type": "orgs", "attributes": {"email": "test@example.com"}}]}"#)
             .create_async()
@@ -810,11 +2117,80 @@ mod tests {
             },
         )
         .await;
-        assert!(matches!(result, Err(AppError::ApiError(_))));
+        assert!(matches!(
+            result,
+            Err(AppError::Upstream { status: 500, .. })
+        ));
     }
 
     #[tokio::test]
-    async fn test_list_animals() {
+    async fn test_response_size_limit() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings(server.url());
+        settings.max_response_bytes = Some(1024);
+
+        let names: Vec<Value> = (0..100)
+            .map(|i| json!({"id": i.to_string(), "attributes": {"name": "A rather long species name"}}))
+            .collect();
+        let _mock = server
+            .mock("GET", "/public/animals/species")
+            .with_status(200)
+            .with_body(json!({ "data": names }).to_string())
+            .create_async()
+            .await;
+
+        match list_species(&settings).await {
+            Err(AppError::ApiError(msg)) => assert_eq!(
+                msg,
+                "Response from /public/animals/species is larger than the 1 KB limit (max_response_kb)"
+            ),
+            other => panic!("expected a size error, got {:?}", other),
+        }
+        assert!(settings.cache.iter().next().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_api_error_details() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _mock = server
+            .mock("GET", "/public/animals/12")
+            .with_status(400)
+            .with_header("content-type", "application/vnd.api+json")
+            .with_body(
+                json!({
+                    "errors": [
+                        {"status": "400", "title": "Bad Request", "detail": "Invalid filter field: animals.colour"},
+                        {"status": "400", "title": "Bad Request"}
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let result = get_animal_details(
+            &settings,
+            AnimalIdArgs {
+                animal_id: "12".to_string(),
+            },
+        )
+        .await;
+        match result {
+            Err(AppError::Upstream {
+                status: 400,
+                message,
+            }) => assert_eq!(
+                message,
+                "API Error: 400 Bad Request: Invalid filter field: animals.colour; Bad Request"
+            ),
+            other => panic!("expected an API error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_animals() {
         let mut server = mockito::Server::new_async().await;
         let settings = get_test_settings(server.url());
 
@@ -829,6 +2205,51 @@ mod tests {
         assert!(result["data"].as_array().is_some());
     }
 
+    #[tokio::test]
+    async fn test_suggest_breeds() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _species = server
+            .mock("GET", "/public/animals/species")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "8", "attributes": {"singular": "Dog", "plural": "Dogs"}}]}"#,
+            )
+            .create_async()
+            .await;
+        let _breeds = server
+            .mock("GET", "/public/animals/species/8/breeds")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [
+                    {"id": "1", "attributes": {"name": "Golden Retriever"}},
+                    {"id": "2", "attributes": {"name": "Goldendoodle"}},
+                    {"id": "3", "attributes": {"name": "Beagle"}}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let result = suggest_breeds(
+            &settings,
+            BreedSuggestArgs {
+                query: "golden doodel".to_string(),
+                species: Some("dogs".to_string()),
+                limit: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["data"][0]["id"], "2");
+        assert_eq!(result["data"][0]["name"], "Goldendoodle");
+        assert!(result["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|b| b["name"] != "Beagle"));
+    }
+
     #[tokio::test]
     async fn test_get_organization_details() {
         let mut server = mockito::Server::new_async().await;
@@ -852,13 +2273,298 @@ mod tests {
         assert_eq!(result["data"]["attributes"]["name"], "Test Org");
     }
 
+    #[tokio::test]
+    async fn test_get_org_contact() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _mock = server
+            .mock("GET", "/public/orgs/866")
+            .with_status(200)
+            .with_body(
+                json!({"data": {"id": "866", "attributes": {
+                    "name": "Test Org",
+                    "email": "adopt@test.org",
+                    "phone": " ",
+                    "city": "Austin",
+                    "state": "TX",
+                    "adoptionUrl": "https://test.org/adopt",
+                    "meetPets": "By appointment",
+                    "facebookUrl": "https://facebook.com/testorg"
+                }}})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let contact = get_org_contact(
+            &settings,
+            OrgIdArgs {
+                org_id: "866".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(contact["org_id"], "866");
+        assert_eq!(contact["email"], "adopt@test.org");
+        assert!(contact["phone"].is_null());
+        assert_eq!(contact["address"]["city"], "Austin");
+        assert_eq!(contact["meet_and_greet"], "By appointment");
+        assert_eq!(
+            contact["social"],
+            json!({ "facebook": "https://facebook.com/testorg" })
+        );
+
+        let err = get_org_contact(
+            &settings,
+            OrgIdArgs {
+                org_id: "abc".to_string(),
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, AppError::ValidationError { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_get_adoption_process() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _mock = server
+            .mock("GET", "/public/animals/123?include=orgs,locations")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "data": {"id": "123", "attributes": {"name": "Buddy", "adoptionFeeString": "$150"}},
+                    "included": [{"type": "orgs", "id": "9", "attributes": {
+                        "name": "Rescue", "serveAreas": "Travis County", "adoptionFee": "$100-$250"
+                    }}]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let process = get_adoption_process(
+            &settings,
+            OrgOrAnimalArgs {
+                animal_id: Some("123".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(process["org_id"], "9");
+        assert_eq!(process["animal_name"], "Buddy");
+        assert_eq!(process["service_area"], "Travis County");
+        assert_eq!(process["fee"], "$150");
+        assert!(process["adoption_url"].is_null());
+
+        let err = get_adoption_process(
+            &settings,
+            OrgOrAnimalArgs {
+                org_id: Some("9".to_string()),
+                animal_id: Some("123".to_string()),
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, AppError::ValidationError { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_get_sponsorship_info() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _mock = server
+            .mock("GET", "/public/orgs/9")
+            .with_status(200)
+            .with_body(
+                r#"{"data": {"id": "9", "attributes": {"name": "Rescue", "donationUrl": "https://rescue.org/give"}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let info = get_sponsorship_info(
+            &settings,
+            OrgOrAnimalArgs {
+                org_id: Some("9".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(info["donation_url"], "https://rescue.org/give");
+        assert!(info["animal_id"].is_null());
+        assert!(info["sponsorable"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_find_by_reference() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _mock = server
+            .mock("POST", "/public/orgs/866/animals/search")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "data": { "filters": [{ "fieldName": "animals.rescueId", "operation": "equal", "criteria": "A-17" }] }
+            })))
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "10", "attributes": {"name": "Rex", "rescueId": "A-17"}}]}"#)
+            .create_async()
+            .await;
+
+        let result = find_by_reference(
+            &settings,
+            ReferenceArgs {
+                org_id: "866".to_string(),
+                reference: " A-17 ".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["data"][0]["id"], "10");
+
+        let err = find_by_reference(
+            &settings,
+            ReferenceArgs {
+                org_id: "866".to_string(),
+                reference: "".to_string(),
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, AppError::ValidationError { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_list_org_locations() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _mock = server
+            .mock("GET", "/public/orgs/866/locations")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"type": "locations", "id": "3", "attributes": {"name": "East Campus"}}], "meta": {"count": 1}}"#,
+            )
+            .create_async()
+            .await;
+
+        let result = list_org_locations(
+            &settings,
+            OrgIdArgs {
+                org_id: "866".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["data"][0]["attributes"]["name"], "East Campus");
+        assert!(result.get("truncated").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_all_org_animals_paginates() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _page1 = server
+            .mock(
+                "GET",
+                "/public/orgs/77/animals/search/available?limit=250&page=1",
+            )
+            .with_status(200)
+            .with_body(r#"{"meta": {"pages": 2}, "data": [{"id": "1"}]}"#)
+            .create_async()
+            .await;
+        let _page2 = server
+            .mock(
+                "GET",
+                "/public/orgs/77/animals/search/available?limit=250&page=2",
+            )
+            .with_status(200)
+            .with_body(r#"{"meta": {"pages": 2}, "data": [{"id": "2"}]}"#)
+            .create_async()
+            .await;
+
+        let result = list_all_org_animals(&settings, "77").await.unwrap();
+        let ids: Vec<&str> = result["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["1", "2"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_pages_stops_at_deadline() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings(server.url());
+        settings.deadline = Some(std::time::Instant::now() + Duration::from_millis(300));
+
+        let _page1 = server
+            .mock(
+                "GET",
+                "/public/orgs/78/animals/search/available?limit=250&page=1",
+            )
+            .with_status(200)
+            .with_body(r#"{"meta": {"count": 3, "pages": 3}, "data": [{"id": "1"}]}"#)
+            .create_async()
+            .await;
+        let _page2 = server
+            .mock(
+                "GET",
+                "/public/orgs/78/animals/search/available?limit=250&page=2",
+            )
+            .with_status(200)
+            .with_body_from_request(|_| {
+                std::thread::sleep(Duration::from_millis(800));
+                br#"{"meta": {"pages": 3}, "data": [{"id": "2"}]}"#.to_vec()
+            })
+            .create_async()
+            .await;
+
+        let result = list_all_org_animals(&settings, "78").await.unwrap();
+        assert_eq!(result["data"].as_array().unwrap().len(), 1);
+        assert_eq!(result["meta"]["count"], 3);
+        assert_eq!(
+            result["note"],
+            "Only 1 of 3 pages were fetched before the time allowed for this call ran out"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compare_animals_past_deadline() {
+        let server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings(server.url());
+        settings.deadline = Some(std::time::Instant::now());
+
+        let args = CompareArgs {
+            animal_ids: vec!["1".to_string(), "2".to_string()],
+        };
+        let result = compare_animals(&settings, args).await.unwrap();
+        assert!(result["data"].as_array().unwrap().is_empty());
+        assert_eq!(result["partial"], true);
+        for error in result["errors"].as_array().unwrap() {
+            assert_eq!(error["error"], AppError::DeadlineExceeded.to_string());
+            assert_eq!(error["kind"], "deadline");
+            assert_eq!(error["retryable"], true);
+        }
+    }
+
     #[tokio::test]
     async fn test_list_org_animals() {
         let mut server = mockito::Server::new_async().await;
         let settings = get_test_settings(server.url());
 
         let _mock = server
-            .mock("GET", "/public/orgs/866/animals/search/available")
+            .mock(
+                "GET",
+                "/public/orgs/866/animals/search/available?include=orgs,species&limit=250&page=1",
+            )
             .with_status(200)
             .with_body(r#"{"data": []}"#)
             .create_async()
@@ -866,13 +2572,45 @@ mod tests {
 
         let result = list_org_animals(
             &settings,
-            OrgIdArgs {
+            OrgAnimalsArgs {
                 org_id: "866".to_string(),
+                species: None,
             },
         )
         .await
         .unwrap();
         assert!(result["data"].as_array().is_some());
+
+        let _cats = server
+            .mock(
+                "GET",
+                "/public/orgs/866/animals/search/available/cats?include=orgs,species&limit=250&page=1",
+            )
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1"}]}"#)
+            .create_async()
+            .await;
+        let result = list_org_animals(
+            &settings,
+            OrgAnimalsArgs {
+                org_id: "866".to_string(),
+                species: Some("Kitten".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["data"][0]["id"], "1");
+
+        let err = list_org_animals(
+            &settings,
+            OrgAnimalsArgs {
+                org_id: "866".to_string(),
+                species: Some("cats/../../x".to_string()),
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, AppError::ValidationError { .. }));
     }
 
     #[tokio::test]
@@ -919,18 +2657,19 @@ mod tests {
             .await;
 
         let _mock2 = server
-            .mock("GET", "/public/animals/error")
+            .mock("GET", "/public/animals/2")
             .with_status(500)
             .create_async()
             .await;
 
         let args = CompareArgs {
-            animal_ids: vec!["1".to_string(), "error".to_string()],
+            animal_ids: vec!["1".to_string(), "2".to_string()],
         };
 
         let result = compare_animals(&settings, args).await.unwrap();
         assert_eq!(result["data"].as_array().unwrap().len(), 1);
         assert_eq!(result["errors"].as_array().unwrap().len(), 1);
+        assert_eq!(result["errors"][0]["animal_id"], "2");
     }
 
     #[tokio::test]
@@ -952,4 +2691,150 @@ mod tests {
 
         list_species(&settings).await.unwrap();
     }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("v0.4.0"), Some((0, 4, 0)));
+        assert_eq!(parse_version("0.4.1-3-gabc1234-dirty"), Some((0, 4, 1)));
+        assert_eq!(parse_version("1.2"), Some((1, 2, 0)));
+        assert_eq!(parse_version("abc1234"), None);
+        assert!(is_newer_version("v0.5.0", "v0.4.9-2-gdeadbee"));
+        assert!(!is_newer_version("v0.4.0", "0.4.0"));
+        assert!(!is_newer_version("nightly", "0.4.0"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_latest_release() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let mock = server
+            .mock("GET", "/releases/latest")
+            .with_status(200)
+            .with_body(r#"{"tag_name": "v9.9.9"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let url = format!("{}/releases/latest", server.url());
+        assert_eq!(
+            fetch_latest_release(&settings, &url).await.unwrap(),
+            "v9.9.9"
+        );
+        // Second call is served from the daily cache
+        assert_eq!(
+            fetch_latest_release(&settings, &url).await.unwrap(),
+            "v9.9.9"
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fetch_latest_release_caches_failures() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let mock = server
+            .mock("GET", "/releases/unavailable")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let url = format!("{}/releases/unavailable", server.url());
+        assert!(matches!(
+            fetch_latest_release(&settings, &url).await,
+            Err(AppError::Upstream { status: 503, .. })
+        ));
+        // The failure is remembered instead of retried on every call
+        match fetch_latest_release(&settings, &url).await {
+            Err(AppError::ApiError(msg)) => assert!(msg.contains("failed recently")),
+            other => panic!("Expected a cached failure, got {:?}", other),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_server_info_redacts_key() {
+        let settings = get_test_settings("http://localhost".to_string());
+        let info = get_server_info(&settings).await.unwrap();
+        assert_eq!(info["defaults"]["api_key"], "[redacted]");
+        assert_eq!(info["defaults"]["postal_code"], "00000");
+        assert!(info.get("update_available").is_none());
+        assert!(!info.to_string().contains("test_key"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pets_with_preset() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings(server.url());
+        settings.presets.insert(
+            "senior-cats".to_string(),
+            crate::config::SearchPreset {
+                args: ToolArgs {
+                    species: Some("cats".to_string()),
+                    age: Some("Senior".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let _mock = server
+            .mock("POST", "/public/animals/search/available/cats/haspic")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "data": { "filters": [
+                    { "fieldName": "animals.ageGroup", "operation": "equal", "criteria": "Senior" }
+                ]}
+            })))
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Whiskers"}}]}"#)
+            .create_async()
+            .await;
+
+        let args = ToolArgs {
+            preset: Some("senior-cats".to_string()),
+            ..Default::default()
+        };
+        let result = fetch_pets(&settings, args).await.unwrap();
+        assert_eq!(result["data"][0]["attributes"]["name"], "Whiskers");
+
+        let args = ToolArgs {
+            preset: Some("missing".to_string()),
+            ..Default::default()
+        };
+        let err = fetch_pets(&settings, args).await.unwrap_err();
+        assert!(err.to_string().contains("senior-cats"));
+    }
+
+    #[tokio::test]
+    async fn test_rescue_groups_client_typed() {
+        let mut server = mockito::Server::new_async().await;
+        let _animal = server
+            .mock("GET", "/public/animals/1")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "1", "attributes": {"name": "Buddy", "ageGroup": "Young"}}]}"#,
+            )
+            .create_async()
+            .await;
+        let _species = server
+            .mock("GET", "/public/animals/species")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "8", "attributes": {"singular": "Dog", "plural": "Dogs"}}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = RescueGroupsClient::new("key").with_base_url(server.url());
+        assert_eq!(client.settings().api_key, "key");
+
+        let animal = client.get_animal("1").await.unwrap();
+        assert_eq!(animal.attributes.name.as_deref(), Some("Buddy"));
+        assert_eq!(animal.attributes.age_group.as_deref(), Some("Young"));
+
+        let species = client.list_species().await.unwrap();
+        assert_eq!(species[0].attributes.plural.as_deref(), Some("Dogs"));
+    }
 }
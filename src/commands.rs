@@ -1,76 +1,304 @@
-use crate::cli::{Cli, Commands};
+use crate::alerts::{
+    build_channels, check_saved_searches, delete_saved_search, deliver, list_saved_searches,
+    save_search,
+};
+use crate::arrivals::list_new_arrivals;
+use crate::breed_rescues::find_breed_rescues;
+use crate::cli::{
+    AnimalIdArgs, Cli, Commands, ConfigAction, FavoritesAction, PrivateAction, StatsAction,
+    ToolArgs,
+};
 use crate::client::{
-    compare_animals, fetch_adopted_pets, fetch_pets, get_animal_details, get_breed_details,
-    get_contact_info, get_organization_details, get_random_pet, list_breeds, list_metadata,
-    list_metadata_types, list_org_animals, list_species, search_organizations,
+    compare_animals, fetch_adopted_pets, find_by_reference, get_adoption_process,
+    get_breed_details, get_org_contact, get_organization_details, get_random_pet,
+    get_sponsorship_info, list_metadata, list_metadata_types, list_org_animals, list_org_locations,
+    list_species, suggest_breeds,
 };
-use crate::config::Settings;
+use crate::comparisons::{get_comparison, save_comparison};
+use crate::config::{config_files, effective_values, load_layers, Settings};
+use crate::digest::run_digest;
 use crate::error::AppError;
+use crate::export::{export_favorites, export_org, export_results};
+use crate::favorites::{list_favorites, remove_favorite, save_favorite};
+use crate::filters::explain_filters;
 use crate::fmt::{
-    extract_single_item, format_animal_results, format_breed_details, format_breed_results,
-    format_comparison_table, format_contact_info, format_metadata_results, format_org_results,
-    format_single_animal, format_single_org, format_species_results, print_output,
+    extract_single_item, format_adoption_process, format_alerts, format_animal_photos,
+    format_animal_results, format_animal_with_notes, format_breed_details, format_breed_rescues,
+    format_breed_results, format_breed_suggestions, format_comparison_table, format_config,
+    format_contact_info, format_digest, format_digest_html, format_favorites,
+    format_filter_reference, format_location_comparison, format_location_results,
+    format_match_results, format_metadata_results, format_new_arrivals, format_notes,
+    format_org_animals, format_org_contact, format_org_locations, format_org_results,
+    format_parsed_query, format_planned_requests, format_saved_comparison, format_saved_searches,
+    format_semantic_results, format_single_animal, format_single_org, format_species_results,
+    format_sponsorship_info, format_updates, format_usage_report, json_envelope, print_output,
+    relabel, with_call_to_action,
 };
-use clap::CommandFactory;
-use clap_complete::generate;
-use clap_mangen::Man;
+use crate::images::get_animal_photos;
+use crate::locations::compare_locations;
+use crate::matching::match_pets;
+use crate::metrics::{reset_usage, usage_report};
+use crate::notes::{add_note, attach_notes, get_notes};
+use crate::postal::lookup_location;
+use crate::private_api::{get_private_animal, get_private_org, list_private_animals};
+use crate::query::parse_search_query;
+use crate::semantic::semantic_search;
+use crate::site::export_site;
+use crate::source::{get_animal, get_breeds, get_contact, search_animals, search_orgs};
+use crate::updates::check_updates;
+use clap::{CommandFactory, ValueEnum};
+use clap_complete::{generate, generate_to, Shell};
+use futures::stream::{self, StreamExt};
+use serde_json::{json, Value};
 use std::fs;
-use std::io;
+use std::io::{self, BufRead};
 use std::path::Path;
 use tracing::{info, warn};
 
+/// A single unit of work read from the `batch` input.
+#[derive(Debug)]
+pub enum BatchItem {
+    /// A bare line is treated as an animal ID.
+    AnimalId(String),
+    /// A JSON object line is treated as a search query.
+    Query(Box<ToolArgs>),
+}
+
+/// Parses one input line. Blank lines and `#` comments are skipped.
+fn parse_batch_line(line: &str) -> Option<Result<BatchItem, AppError>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    if line.starts_with('{') {
+        Some(
+            serde_json::from_str::<ToolArgs>(line)
+                .map(|args| BatchItem::Query(Box::new(args)))
+                .map_err(AppError::Serialization),
+        )
+    } else {
+        Some(Ok(BatchItem::AnimalId(line.to_string())))
+    }
+}
+
+/// The outcome of one input line: the line itself, the item parsed from it
+/// (`None` if it couldn't be parsed) and the result of running it.
+pub type BatchResult = (String, Option<BatchItem>, Result<Value, AppError>);
+
+/// Executes every item read from `reader` with at most `concurrency` requests
+/// in flight, returning the results in input order.
+pub async fn run_batch<R: BufRead>(
+    reader: R,
+    settings: &Settings,
+    concurrency: usize,
+) -> Result<Vec<BatchResult>, AppError> {
+    let mut items = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if let Some(item) = parse_batch_line(&line) {
+            items.push((line.trim().to_string(), item));
+        }
+    }
+
+    let results = stream::iter(items)
+        .map(|(line, item)| async move {
+            match item {
+                Ok(BatchItem::AnimalId(id)) => {
+                    let res = get_animal(
+                        settings,
+                        AnimalIdArgs {
+                            animal_id: id.clone(),
+                        },
+                    )
+                    .await;
+                    (line, Some(BatchItem::AnimalId(id)), res)
+                }
+                Ok(BatchItem::Query(args)) => {
+                    let res = search_animals(settings, (*args).clone()).await;
+                    (line, Some(BatchItem::Query(args)), res)
+                }
+                Err(e) => (line, None, Err(e)),
+            }
+        })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await;
+
+    Ok(results)
+}
+
+/// One line of `batch --json` output: the JSON envelope of the result, with
+/// the animal `id` or search `query` it answers (or the raw `input` line if
+/// it couldn't be parsed).
+pub fn batch_json_line(
+    line: &str,
+    item: Option<&BatchItem>,
+    result: Result<Value, &AppError>,
+) -> Value {
+    let mut envelope = json_envelope(result);
+    let (key, input) = match item {
+        Some(BatchItem::AnimalId(id)) => ("id", json!(id)),
+        Some(BatchItem::Query(args)) => ("query", json!(args)),
+        None => ("input", json!(line)),
+    };
+    envelope[key] = input;
+    envelope
+}
+
+/// Runs a `config` command. It works from the configuration layers alone,
+/// so it can show what is missing before settings are built from them.
+pub fn config_command(cli: &Cli, action: ConfigAction) -> Result<(), AppError> {
+    match action {
+        ConfigAction::Show { origin } => {
+            let config = load_layers(cli)?;
+            let files: Vec<Value> = config_files(cli)
+                .into_iter()
+                .map(|(layer, path)| json!({ "layer": layer, "path": path.display().to_string() }))
+                .collect();
+            let result = json!({ "files": files, "values": effective_values(&config) });
+            print_output(Ok(result), cli.json, &Default::default(), |v| {
+                format_config(v, origin)
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Runs `command` under `--dry-run`, printing the upstream requests it
+/// would have made in place of its output.
+pub async fn dry_run_command(
+    command: Commands,
+    settings: &Settings,
+    json_mode: bool,
+) -> Result<(), AppError> {
+    match handle_command(command, settings, json_mode).await {
+        Ok(()) | Err(AppError::DryRun) => {}
+        Err(e) => return Err(e),
+    }
+    let requests = settings
+        .request_log
+        .as_ref()
+        .map(|log| log.lock().unwrap().clone())
+        .unwrap_or_default();
+    print_output(
+        Ok(json!({ "requests": requests })),
+        json_mode,
+        &settings.labels,
+        format_planned_requests,
+    );
+    Ok(())
+}
+
 pub async fn handle_command(
     command: Commands,
     settings: &Settings,
     json_mode: bool,
 ) -> Result<(), AppError> {
     match command {
-        Commands::Server | Commands::Http(_) => {
-            // These should be handled by the caller (main.rs)
-            Ok(())
-        }
+        // These should be handled by the caller (main.rs)
+        Commands::Server => Ok(()),
+        #[cfg(feature = "http")]
+        Commands::Http(_) => Ok(()),
         Commands::Search(args) => {
-            print_output(fetch_pets(settings, args).await, json_mode, |v| {
-                format_animal_results(v)
-            });
+            print_output(
+                search_animals(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_animal_results,
+            );
             Ok(())
         }
         Commands::ListSpecies => {
-            print_output(list_species(settings).await, json_mode, |v| {
-                format_species_results(v)
-            });
+            print_output(
+                list_species(settings).await,
+                json_mode,
+                &settings.labels,
+                format_species_results,
+            );
             Ok(())
         }
         Commands::GetAnimal(args) => {
-            print_output(get_animal_details(settings, args).await, json_mode, |v| {
-                let animal_data = v.get("data").ok_or(AppError::NotFound)?;
-                let animal = extract_single_item(animal_data).ok_or(AppError::NotFound)?;
-                Ok(format_single_animal(animal))
+            let animal_id = args.animal_id.clone();
+            // Fetched with its org and location, to show where a fostered pet is
+            let result = match get_contact(settings, args).await {
+                Ok(mut data) => attach_notes(settings, &animal_id, &mut data)
+                    .await
+                    .map(|_| data),
+                Err(e) => Err(e),
+            };
+            print_output(result, json_mode, &settings.labels, |v| {
+                Ok(with_call_to_action(
+                    format_animal_with_notes(v)?,
+                    &settings.labels,
+                ))
             });
             Ok(())
         }
         Commands::GetContact(args) => {
-            print_output(get_contact_info(settings, args).await, json_mode, |v| {
-                format_contact_info(v)
-            });
+            print_output(
+                get_contact(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_contact_info,
+            );
+            Ok(())
+        }
+        Commands::Photos(args) => {
+            print_output(
+                get_animal_photos(settings, args).await,
+                json_mode,
+                &settings.labels,
+                |v| Ok(format_animal_photos(v)),
+            );
             Ok(())
         }
         Commands::Compare(args) => {
-            print_output(compare_animals(settings, args).await, json_mode, |v| {
-                format_comparison_table(v)
-            });
+            print_output(
+                compare_animals(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_comparison_table,
+            );
+            Ok(())
+        }
+        Commands::SaveComparison(args) => {
+            let result = save_comparison(settings, args)
+                .await
+                .and_then(|c| Ok(serde_json::to_value(c)?));
+            print_output(result, json_mode, &settings.labels, format_saved_comparison);
+            Ok(())
+        }
+        Commands::GetComparison(args) => {
+            let result = get_comparison(settings, &args.token)
+                .await
+                .and_then(|c| Ok(serde_json::to_value(c)?));
+            print_output(result, json_mode, &settings.labels, format_saved_comparison);
             Ok(())
         }
         Commands::SearchOrgs(args) => {
-            print_output(search_organizations(settings, args).await, json_mode, |v| {
-                format_org_results(v)
-            });
+            print_output(
+                search_orgs(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_org_results,
+            );
+            Ok(())
+        }
+        Commands::FindBreedRescues(args) => {
+            print_output(
+                find_breed_rescues(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_breed_rescues,
+            );
             Ok(())
         }
         Commands::GetOrg(args) => {
             print_output(
                 get_organization_details(settings, args).await,
                 json_mode,
+                &settings.labels,
                 |v| {
                     let org_data = v.get("data").ok_or(AppError::NotFound)?;
                     let org = extract_single_item(org_data).ok_or(AppError::NotFound)?;
@@ -79,63 +307,174 @@ pub async fn handle_command(
             );
             Ok(())
         }
+        Commands::GetOrgContact(args) => {
+            print_output(
+                get_org_contact(settings, args).await,
+                json_mode,
+                &settings.labels,
+                |v| Ok(format_org_contact(v)),
+            );
+            Ok(())
+        }
+        Commands::AdoptionProcess(args) => {
+            print_output(
+                get_adoption_process(settings, args).await,
+                json_mode,
+                &settings.labels,
+                |v| Ok(format_adoption_process(v)),
+            );
+            Ok(())
+        }
+        Commands::Sponsorship(args) => {
+            print_output(
+                get_sponsorship_info(settings, args).await,
+                json_mode,
+                &settings.labels,
+                |v| Ok(format_sponsorship_info(v)),
+            );
+            Ok(())
+        }
+        Commands::FindByReference(args) => {
+            print_output(
+                find_by_reference(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_animal_results,
+            );
+            Ok(())
+        }
+        Commands::ListOrgLocations(args) => {
+            print_output(
+                list_org_locations(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_org_locations,
+            );
+            Ok(())
+        }
         Commands::ListOrgAnimals(args) => {
-            print_output(list_org_animals(settings, args).await, json_mode, |v| {
-                format_animal_results(v)
-            });
+            print_output(
+                list_org_animals(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_org_animals,
+            );
             Ok(())
         }
         Commands::RandomPet { species } => {
-            print_output(get_random_pet(settings, species).await, json_mode, |v| {
-                format_animal_results(v)
-            });
+            print_output(
+                get_random_pet(settings, species).await,
+                json_mode,
+                &settings.labels,
+                format_animal_results,
+            );
             Ok(())
         }
         Commands::ListAdopted(args) => {
-            print_output(fetch_adopted_pets(settings, args).await, json_mode, |v| {
-                format_animal_results(v)
-            });
+            print_output(
+                fetch_adopted_pets(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_animal_results,
+            );
             Ok(())
         }
         Commands::ListBreeds(args) => {
             let species = args.species.clone();
-            print_output(list_breeds(settings, args).await, json_mode, |v| {
-                format_breed_results(v, &species)
-            });
+            print_output(
+                get_breeds(settings, args).await,
+                json_mode,
+                &settings.labels,
+                |v| format_breed_results(v, &species),
+            );
+            Ok(())
+        }
+        Commands::SuggestBreeds(args) => {
+            print_output(
+                suggest_breeds(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_breed_suggestions,
+            );
             Ok(())
         }
         Commands::GetBreed(args) => {
-            print_output(get_breed_details(settings, args).await, json_mode, |v| {
-                let breed_data = v.get("data").ok_or(AppError::NotFound)?;
-                let breed = extract_single_item(breed_data).ok_or(AppError::NotFound)?;
-                Ok(format_breed_details(breed))
-            });
+            print_output(
+                get_breed_details(settings, args).await,
+                json_mode,
+                &settings.labels,
+                |v| {
+                    let breed_data = v.get("data").ok_or(AppError::NotFound)?;
+                    let breed = extract_single_item(breed_data).ok_or(AppError::NotFound)?;
+                    Ok(format_breed_details(breed))
+                },
+            );
             Ok(())
         }
         Commands::ListMetadata(args) => {
             let metadata_type = args.metadata_type.clone();
-            print_output(list_metadata(settings, args).await, json_mode, |v| {
-                format_metadata_results(v, &metadata_type)
-            });
+            print_output(
+                list_metadata(settings, args).await,
+                json_mode,
+                &settings.labels,
+                |v| format_metadata_results(v, &metadata_type),
+            );
+            Ok(())
+        }
+        Commands::LookupLocation(args) => {
+            print_output(
+                lookup_location(settings, &args.query),
+                json_mode,
+                &settings.labels,
+                format_location_results,
+            );
+            Ok(())
+        }
+        Commands::ExplainFilters(args) => {
+            print_output(
+                explain_filters(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_filter_reference,
+            );
             Ok(())
         }
         Commands::ListMetadataTypes => {
-            print_output(list_metadata_types().await, json_mode, |v| {
-                let types = v["data"].as_array().unwrap();
-                let content = types
-                    .iter()
-                    .map(|t| t.as_str().unwrap_or(""))
-                    .collect::<Vec<&str>>()
-                    .join("\n");
-                Ok(format!("### Supported Metadata Types\n\n{}", content))
-            });
+            print_output(
+                list_metadata_types().await,
+                json_mode,
+                &settings.labels,
+                |v| {
+                    let types = v["data"].as_array().unwrap();
+                    let content = types
+                        .iter()
+                        .map(|t| t.as_str().unwrap_or(""))
+                        .collect::<Vec<&str>>()
+                        .join("\n");
+                    Ok(format!("### Supported Metadata Types\n\n{}", content))
+                },
+            );
             Ok(())
         }
         Commands::Generate(args) => {
             let mut cmd = Cli::command();
             let bin_name = cmd.get_name().to_string();
 
-            if let Some(shell) = args.shell {
+            if let Some(ref completions_dir) = args.completions_dir {
+                let out_dir = Path::new(completions_dir);
+                if !out_dir.exists() {
+                    fs::create_dir_all(out_dir).map_err(AppError::Io)?;
+                }
+                let shells = match args.shell {
+                    Some(shell) => vec![shell],
+                    None => Shell::value_variants().to_vec(),
+                };
+                for shell in shells {
+                    let path =
+                        generate_to(shell, &mut cmd, &bin_name, out_dir).map_err(AppError::Io)?;
+                    info!("Completion file generated: {}", path.display());
+                }
+            } else if let Some(shell) = args.shell {
                 generate(shell, &mut cmd, bin_name, &mut io::stdout());
             }
 
@@ -144,48 +483,316 @@ pub async fn handle_command(
                 if !out_dir.exists() {
                     fs::create_dir_all(out_dir).map_err(AppError::Io)?;
                 }
-                Man::new(cmd)
-                    .render(
-                        &mut fs::File::create(out_dir.join("rescue-groups-mcp.1"))
-                            .map_err(AppError::Io)?,
-                    )
-                    .map_err(AppError::Io)?;
-                info!("Man page generated in {}", man_dir);
+                // Renders the top-level page plus one page per subcommand
+                // (e.g. rescue-groups-mcp-search.1)
+                clap_mangen::generate_to(Cli::command(), out_dir).map_err(AppError::Io)?;
+                info!("Man pages generated in {}", man_dir);
             }
 
-            if args.shell.is_none() && args.man.is_none() {
-                warn!("Please specify --shell <SHELL> or --man <DIR>");
+            if args.shell.is_none() && args.man.is_none() && args.completions_dir.is_none() {
+                warn!("Please specify --shell <SHELL>, --completions-dir <DIR> or --man <DIR>");
             }
             Ok(())
         }
+        Commands::Batch(args) => {
+            let stdin = io::stdin();
+            let results = run_batch(stdin.lock(), settings, args.concurrency).await?;
+            for (line, item, result) in results {
+                match result {
+                    // The requests are printed instead
+                    Err(AppError::DryRun) => {}
+                    result if json_mode => {
+                        let value = batch_json_line(&line, item.as_ref(), result.as_ref().cloned());
+                        println!("{}", value);
+                    }
+                    Err(e) => println!("{}: Error: {}", line, e),
+                    Ok(value) => {
+                        let text = match item {
+                            Some(BatchItem::Query(_)) => format_animal_results(&value),
+                            _ => value
+                                .get("data")
+                                .and_then(extract_single_item)
+                                .map(format_single_animal)
+                                .ok_or(AppError::NotFound),
+                        };
+                        match text {
+                            Ok(text) => println!("{}", relabel(&text, &settings.labels)),
+                            Err(e) => println!("{}: Error: {}", line, e),
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+        Commands::SaveSearch(args) => {
+            let name = args.name.clone();
+            let result = save_search(settings, &args.name, args.search)
+                .await
+                .map(|_| json!({ "saved": name }));
+            print_output(result, json_mode, &settings.labels, |_| {
+                Ok(format!("Saved search '{}'.", name))
+            });
+            Ok(())
+        }
+        Commands::ListSavedSearches => {
+            let result = list_saved_searches(settings)
+                .await
+                .map(|searches| json!({ "data": searches }));
+            print_output(result, json_mode, &settings.labels, format_saved_searches);
+            Ok(())
+        }
+        Commands::DeleteSavedSearch(args) => {
+            let name = args.name.clone();
+            let result = delete_saved_search(settings, &args.name)
+                .await
+                .map(|_| json!({ "deleted": name }));
+            print_output(result, json_mode, &settings.labels, |_| {
+                Ok(format!("Deleted saved search '{}'.", name))
+            });
+            Ok(())
+        }
+        Commands::CheckAlerts => {
+            let result = match check_saved_searches(settings).await {
+                Ok(alerts) => {
+                    deliver(&alerts, &build_channels(settings, None)).await;
+                    Ok(json!({ "data": alerts }))
+                }
+                Err(e) => Err(e),
+            };
+            print_output(result, json_mode, &settings.labels, format_alerts);
+            Ok(())
+        }
+        Commands::SaveFavorite(args) => {
+            let result = save_favorite(settings, &args.animal_id, args.note)
+                .await
+                .map(|favorite| json!(favorite));
+            print_output(result, json_mode, &settings.labels, |fav| {
+                Ok(format!(
+                    "Saved {} (ID: {}) to favorites.",
+                    fav["name"].as_str().unwrap_or("Unknown"),
+                    fav["id"].as_str().unwrap_or("")
+                ))
+            });
+            Ok(())
+        }
+        Commands::SemanticSearch(args) => {
+            print_output(
+                semantic_search(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_semantic_results,
+            );
+            Ok(())
+        }
+        Commands::NewArrivals(args) => {
+            print_output(
+                list_new_arrivals(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_new_arrivals,
+            );
+            Ok(())
+        }
+        Commands::CompareLocations(args) => {
+            print_output(
+                compare_locations(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_location_comparison,
+            );
+            Ok(())
+        }
+        Commands::MatchMeAPet(args) => {
+            print_output(
+                match_pets(settings, args).await,
+                json_mode,
+                &settings.labels,
+                format_match_results,
+            );
+            Ok(())
+        }
+        Commands::ExportResults(args) => {
+            print_output(
+                export_results(settings, args).await,
+                json_mode,
+                &settings.labels,
+                |v| {
+                    Ok(format!(
+                        "Saved {} animals to {}",
+                        v["animals"],
+                        v["path"].as_str().unwrap_or("")
+                    ))
+                },
+            );
+            Ok(())
+        }
+        Commands::ExportOrg(args) => {
+            let output = args
+                .output
+                .clone()
+                .unwrap_or_else(|| format!("org-{}.{}", args.org_id, args.format.extension()));
+            // Errors propagate so scheduled exports see a failing exit status
+            let summary =
+                export_org(settings, &args.org_id, args.format, Path::new(&output)).await?;
+            print_output(Ok(summary), json_mode, &settings.labels, |v| {
+                Ok(format!(
+                    "Exported {} animals from organization {} to {}",
+                    v["animals"],
+                    v["org_id"].as_str().unwrap_or(""),
+                    v["path"].as_str().unwrap_or("")
+                ))
+            });
+            Ok(())
+        }
+        Commands::ExportSite(args) => {
+            // Errors propagate so cron jobs see a failing exit status
+            let summary = export_site(settings, &args.org_id, Path::new(&args.out_dir)).await?;
+            print_output(Ok(summary), json_mode, &settings.labels, |v| {
+                Ok(format!(
+                    "Exported {} animals from {} to {}",
+                    v["animals"],
+                    v["org_name"].as_str().unwrap_or("organization"),
+                    v["path"].as_str().unwrap_or("")
+                ))
+            });
+            Ok(())
+        }
+        Commands::Digest(args) => {
+            // Errors propagate so cron jobs see a failing exit status
+            let report = run_digest(settings).await?;
+            if args.html {
+                print_output(Ok(report), json_mode, &settings.labels, format_digest_html);
+            } else {
+                print_output(Ok(report), json_mode, &settings.labels, format_digest);
+            }
+            Ok(())
+        }
+        Commands::ParseQuery(args) => {
+            print_output(
+                parse_search_query(settings, &args.query).await,
+                json_mode,
+                &settings.labels,
+                format_parsed_query,
+            );
+            Ok(())
+        }
+        Commands::CheckUpdates(args) => {
+            let result = check_updates(settings, args.animal_ids, args.favorites).await;
+            print_output(result, json_mode, &settings.labels, format_updates);
+            Ok(())
+        }
+        Commands::ListFavorites => {
+            let result = list_favorites(settings)
+                .await
+                .map(|favorites| json!({ "data": favorites }));
+            print_output(result, json_mode, &settings.labels, format_favorites);
+            Ok(())
+        }
+        Commands::Favorites { action } => {
+            match action {
+                FavoritesAction::Export(args) => {
+                    let output = args
+                        .output
+                        .unwrap_or_else(|| format!("favorites.{}", args.format.extension()));
+                    let summary =
+                        export_favorites(settings, args.format, Path::new(&output)).await?;
+                    print_output(Ok(summary), json_mode, &settings.labels, |v| {
+                        Ok(format!(
+                            "Exported {} favorites to {}",
+                            v["animals"],
+                            v["path"].as_str().unwrap_or("")
+                        ))
+                    });
+                }
+            }
+            Ok(())
+        }
+        // Run by the caller before settings are built, see `config_command`
+        Commands::Config { .. } => Ok(()),
+        Commands::Stats { action } => {
+            match action {
+                StatsAction::Report => print_output(
+                    usage_report(settings),
+                    json_mode,
+                    &settings.labels,
+                    format_usage_report,
+                ),
+                StatsAction::Reset => {
+                    let result = reset_usage(settings)
+                        .await
+                        .map(|_| json!({ "reset": true }));
+                    print_output(result, json_mode, &settings.labels, |_| {
+                        Ok("Usage statistics cleared.".to_string())
+                    });
+                }
+            }
+            Ok(())
+        }
+        Commands::Private { action } => {
+            match action {
+                PrivateAction::ListAnimals => print_output(
+                    list_private_animals(settings).await,
+                    json_mode,
+                    &settings.labels,
+                    format_animal_results,
+                ),
+                PrivateAction::GetAnimal(args) => print_output(
+                    get_private_animal(settings, &args.animal_id).await,
+                    json_mode,
+                    &settings.labels,
+                    |v| {
+                        let animal_data = v.get("data").ok_or(AppError::NotFound)?;
+                        let animal = extract_single_item(animal_data).ok_or(AppError::NotFound)?;
+                        Ok(format_single_animal(animal))
+                    },
+                ),
+                PrivateAction::Org => print_output(
+                    get_private_org(settings).await,
+                    json_mode,
+                    &settings.labels,
+                    format_org_results,
+                ),
+            }
+            Ok(())
+        }
+        Commands::AddNote(args) => {
+            let animal_id = args.animal_id.clone();
+            let result = add_note(settings, &args.animal_id, &args.note)
+                .await
+                .map(|note| json!({ "animal_id": animal_id, "note": note }));
+            print_output(result, json_mode, &settings.labels, |_| {
+                Ok(format!("Added a note to animal {}.", animal_id))
+            });
+            Ok(())
+        }
+        Commands::GetNotes(args) => {
+            let result = get_notes(settings, &args.animal_id)
+                .await
+                .map(|notes| json!({ "animal_id": args.animal_id, "notes": notes }));
+            print_output(result, json_mode, &settings.labels, format_notes);
+            Ok(())
+        }
+        Commands::RemoveFavorite(args) => {
+            let animal_id = args.animal_id.clone();
+            let result = remove_favorite(settings, &args.animal_id)
+                .await
+                .map(|_| json!({ "removed": animal_id }));
+            print_output(result, json_mode, &settings.labels, |_| {
+                Ok(format!("Removed {} from favorites.", animal_id))
+            });
+            Ok(())
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cli::{AnimalIdArgs, SpeciesArgs};
+    use crate::cli::SpeciesArgs;
     use crate::config::Settings;
-    use governor::{Quota, RateLimiter};
-    use moka::future::Cache;
-    use std::num::NonZeroU32;
-    use std::sync::Arc;
-    use std::time::Duration;
 
     fn get_test_settings(url: String) -> Settings {
-        Settings {
-            api_key: "test_key".to_string(),
-            base_url: url,
-            default_postal_code: "00000".to_string(),
-            default_miles: 50,
-            default_species: "dogs".to_string(),
-            timeout: Duration::from_secs(1),
-            lazy: false,
-            cache: Arc::new(Cache::new(10)),
-            limiter: Arc::new(RateLimiter::direct(Quota::per_second(
-                NonZeroU32::new(100).unwrap(),
-            ))),
-        }
+        Settings::for_test(url, std::env::temp_dir().join("rescue-groups-mcp-test"))
     }
 
     #[tokio::test]
@@ -276,6 +883,7 @@ mod tests {
                 postal_code: None,
                 miles: None,
                 query: None,
+                species: None,
             }),
             &settings,
             false,
@@ -330,24 +938,7 @@ mod tests {
             .await;
 
         let res = handle_command(
-            Commands::Search(crate::cli::ToolArgs {
-                postal_code: None,
-                miles: None,
-                species: None,
-                breeds: None,
-                sex: None,
-                age: None,
-                size: None,
-                good_with_children: None,
-                good_with_dogs: None,
-                good_with_cats: None,
-                house_trained: None,
-                special_needs: None,
-                needs_foster: None,
-                color: None,
-                pattern: None,
-                sort_by: None,
-            }),
+            Commands::Search(crate::cli::ToolArgs::default()),
             &settings,
             false,
         )
@@ -361,7 +952,7 @@ mod tests {
         let settings = get_test_settings(server.url());
 
         let _mock = server
-            .mock("GET", "/public/animals/123?include=orgs")
+            .mock("GET", "/public/animals/123?include=orgs,locations")
             .with_status(200)
             .with_body(r#"{"data": {"id": "123", "attributes": {"name": "Buddy"}}}"#)
             .create_async()
@@ -430,15 +1021,19 @@ mod tests {
         let settings = get_test_settings(server.url());
 
         let _mock = server
-            .mock("GET", "/public/orgs/866/animals/search/available")
+            .mock(
+                "GET",
+                "/public/orgs/866/animals/search/available?include=orgs,species&limit=250&page=1",
+            )
             .with_status(200)
             .with_body(r#"{"data": []}"#)
             .create_async()
             .await;
 
         let res = handle_command(
-            Commands::ListOrgAnimals(crate::cli::OrgIdArgs {
+            Commands::ListOrgAnimals(crate::cli::OrgAnimalsArgs {
                 org_id: "866".to_string(),
+                species: None,
             }),
             &settings,
             false,
@@ -526,6 +1121,7 @@ mod tests {
             Commands::Generate(crate::cli::GenerateArgs {
                 shell: Some(clap_complete::Shell::Bash),
                 man: None,
+                completions_dir: None,
             }),
             &settings,
             false,
@@ -540,49 +1136,247 @@ mod tests {
         let res = handle_command(Commands::Server, &settings, false).await;
         assert!(res.is_ok());
 
+        #[cfg(feature = "http")]
+        {
+            let res = handle_command(
+                Commands::Http(crate::cli::HttpArgs {
+                    host: "localhost".to_string(),
+                    port: 3000,
+                    auth_token: None,
+                    daemonize: false,
+                    pid_file: None,
+                    log_file: None,
+                }),
+                &settings,
+                false,
+            )
+            .await;
+            assert!(res.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_command_generate_man() {
+        let settings = get_test_settings("http://localhost".to_string());
+        let temp_dir = std::env::temp_dir().join("rescue-groups-mcp-man");
         let res = handle_command(
-            Commands::Http(crate::cli::HttpArgs {
-                host: "localhost".to_string(),
-                port: 3000,
-                auth_token: None,
+            Commands::Generate(crate::cli::GenerateArgs {
+                shell: None,
+                man: Some(temp_dir.to_str().unwrap().to_string()),
+                completions_dir: None,
             }),
             &settings,
             false,
         )
         .await;
         assert!(res.is_ok());
+        assert!(temp_dir.join("rescue-groups-mcp.1").exists());
+        assert!(temp_dir.join("rescue-groups-mcp-search.1").exists());
+        assert_eq!(
+            temp_dir.join("rescue-groups-mcp-http.1").exists(),
+            cfg!(feature = "http")
+        );
+        fs::remove_dir_all(temp_dir).unwrap();
     }
 
     #[tokio::test]
-    async fn test_handle_command_generate_man() {
+    async fn test_handle_command_generate_none() {
         let settings = get_test_settings("http://localhost".to_string());
-        let temp_dir = std::env::temp_dir().join("rescue-groups-mcp-man");
         let res = handle_command(
             Commands::Generate(crate::cli::GenerateArgs {
                 shell: None,
-                man: Some(temp_dir.to_str().unwrap().to_string()),
+                man: None,
+                completions_dir: None,
             }),
             &settings,
             false,
         )
         .await;
         assert!(res.is_ok());
-        assert!(temp_dir.join("rescue-groups-mcp.1").exists());
-        fs::remove_dir_all(temp_dir).unwrap();
     }
 
     #[tokio::test]
-    async fn test_handle_command_generate_none() {
+    async fn test_handle_command_generate_completions_dir() {
         let settings = get_test_settings("http://localhost".to_string());
+        let temp_dir = std::env::temp_dir().join("rescue-groups-mcp-completions");
         let res = handle_command(
             Commands::Generate(crate::cli::GenerateArgs {
                 shell: None,
                 man: None,
+                completions_dir: Some(temp_dir.to_str().unwrap().to_string()),
+            }),
+            &settings,
+            false,
+        )
+        .await;
+        assert!(res.is_ok());
+        assert!(temp_dir.join("rescue-groups-mcp.bash").exists());
+        assert!(temp_dir.join("_rescue-groups-mcp").exists());
+        assert!(temp_dir.join("rescue-groups-mcp.fish").exists());
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_batch_line() {
+        assert!(parse_batch_line("").is_none());
+        assert!(parse_batch_line("  # comment").is_none());
+        assert!(matches!(
+            parse_batch_line(" 123 "),
+            Some(Ok(BatchItem::AnimalId(id))) if id == "123"
+        ));
+        assert!(matches!(
+            parse_batch_line(r#"{"species": "cats"}"#),
+            Some(Ok(BatchItem::Query(args))) if args.species.as_deref() == Some("cats")
+        ));
+        assert!(matches!(
+            parse_batch_line("{not json"),
+            Some(Err(AppError::Serialization(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_batch_preserves_order() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _mock1 = server
+            .mock("GET", "/public/animals/1")
+            .with_status(200)
+            .with_body(r#"{"data": {"id": "1", "attributes": {"name": "Buddy"}}}"#)
+            .create_async()
+            .await;
+
+        let _mock2 = server
+            .mock("GET", "/public/animals/2")
+            .with_status(200)
+            .with_body(r#"{"data": {"id": "2", "attributes": {"name": "Lucy"}}}"#)
+            .create_async()
+            .await;
+
+        let _mock_search = server
+            .mock("POST", "/public/animals/search/available/cats/haspic")
+            .with_status(200)
+            .with_body(r#"{"data": []}"#)
+            .create_async()
+            .await;
+
+        let input = "1\n\n{\"species\": \"cats\"}\n2\n{bad\n";
+        let results = run_batch(io::Cursor::new(input), &settings, 2)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].2.as_ref().unwrap()["data"]["id"], "1");
+        assert!(matches!(results[1].1, Some(BatchItem::Query(_))));
+        assert_eq!(results[2].2.as_ref().unwrap()["data"]["id"], "2");
+        assert_eq!(results[3].0, "{bad");
+        assert!(results[3].1.is_none());
+        assert!(results[3].2.is_err());
+    }
+
+    #[test]
+    fn test_batch_json_line() {
+        let id = BatchItem::AnimalId("1".to_string());
+        let line = batch_json_line("1", Some(&id), Ok(json!({"data": {"id": "1"}})));
+        assert_eq!(line["id"], "1");
+        assert_eq!(line["ok"], true);
+        assert_eq!(line["data"]["data"]["id"], "1");
+
+        let query = parse_batch_line(r#"{"species": "cats"}"#).unwrap().unwrap();
+        let line = batch_json_line("", Some(&query), Err(&AppError::NotFound));
+        assert_eq!(line["query"]["species"], "cats");
+        assert_eq!(line["ok"], false);
+        assert_eq!(line["error"]["code"], "not_found");
+
+        let err = AppError::validation("bad line".to_string());
+        let line = batch_json_line("{bad", None, Err(&err));
+        assert_eq!(line["input"], "{bad");
+        // One object per line
+        assert!(!line.to_string().contains('\n'));
+    }
+
+    #[tokio::test]
+    async fn test_handle_command_saved_searches() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings(server.url());
+        settings.data_dir = std::env::temp_dir().join("rescue-groups-mcp-commands-alerts");
+        let _ = fs::remove_dir_all(&settings.data_dir);
+
+        let _mock = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .with_status(200)
+            .with_body(r#"{"data": []}"#)
+            .create_async()
+            .await;
+
+        let res = handle_command(
+            Commands::SaveSearch(crate::cli::SaveSearchArgs {
+                name: "dogs".to_string(),
+                search: ToolArgs::default(),
+            }),
+            &settings,
+            false,
+        )
+        .await;
+        assert!(res.is_ok());
+        assert!(
+            handle_command(Commands::ListSavedSearches, &settings, false)
+                .await
+                .is_ok()
+        );
+        assert!(handle_command(Commands::CheckAlerts, &settings, true)
+            .await
+            .is_ok());
+        let res = handle_command(
+            Commands::DeleteSavedSearch(crate::cli::SavedSearchNameArgs {
+                name: "dogs".to_string(),
+            }),
+            &settings,
+            false,
+        )
+        .await;
+        assert!(res.is_ok());
+        assert!(list_saved_searches(&settings).await.unwrap().is_empty());
+        fs::remove_dir_all(&settings.data_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_handle_command_favorites() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings(server.url());
+        settings.data_dir = std::env::temp_dir().join("rescue-groups-mcp-commands-favorites");
+        let _ = fs::remove_dir_all(&settings.data_dir);
+
+        let _mock = server
+            .mock("GET", "/public/animals/42")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "42", "attributes": {"name": "Biscuit"}}]}"#)
+            .create_async()
+            .await;
+
+        let res = handle_command(
+            Commands::SaveFavorite(crate::cli::FavoriteArgs {
+                animal_id: "42".to_string(),
+                note: None,
+            }),
+            &settings,
+            false,
+        )
+        .await;
+        assert!(res.is_ok());
+        assert!(handle_command(Commands::ListFavorites, &settings, false)
+            .await
+            .is_ok());
+        let res = handle_command(
+            Commands::RemoveFavorite(AnimalIdArgs {
+                animal_id: "42".to_string(),
             }),
             &settings,
             false,
         )
         .await;
         assert!(res.is_ok());
+        assert!(list_favorites(&settings).await.unwrap().is_empty());
+        fs::remove_dir_all(&settings.data_dir).unwrap();
     }
 }
@@ -0,0 +1,130 @@
+use crate::alerts::now_secs;
+use crate::cli::CompareArgs;
+use crate::client::compare_animals;
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::fmt::format_comparison_table;
+use crate::store::{self, load_json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct ComparisonsFile {
+    #[serde(default)]
+    pub comparisons: BTreeMap<String, Comparison>,
+}
+
+/// A comparison frozen at the time it was saved, so it reads the same for
+/// whoever opens it later even after the listings change.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Comparison {
+    pub token: String,
+    pub animal_ids: Vec<String>,
+    /// The rendered Markdown table
+    pub table: String,
+    /// The compared animal records, for the HTML page
+    pub animals: Vec<Value>,
+    /// Unix timestamp of when the comparison was saved
+    pub created_at: u64,
+}
+
+fn comparisons_path(settings: &Settings) -> PathBuf {
+    settings.data_dir.join("comparisons.json")
+}
+
+/// Compares the animals and stores the result under a new short token.
+pub async fn save_comparison(
+    settings: &Settings,
+    args: CompareArgs,
+) -> Result<Comparison, AppError> {
+    let data = compare_animals(settings, args).await?;
+    let animals = data["data"].as_array().cloned().unwrap_or_default();
+    if animals.is_empty() {
+        return Err(AppError::NotFound);
+    }
+
+    let table = format_comparison_table(&data)?;
+    store::update(&comparisons_path(settings), |file: &mut ComparisonsFile| {
+        let token = loop {
+            let candidate = Uuid::new_v4().simple().to_string()[..10].to_string();
+            if !file.comparisons.contains_key(&candidate) {
+                break candidate;
+            }
+        };
+        let comparison = Comparison {
+            token: token.clone(),
+            animal_ids: animals
+                .iter()
+                .filter_map(|a| a["id"].as_str().map(str::to_string))
+                .collect(),
+            table,
+            animals,
+            created_at: now_secs(),
+        };
+        file.comparisons.insert(token, comparison.clone());
+        Ok(comparison)
+    })
+    .await
+}
+
+pub async fn get_comparison(settings: &Settings, token: &str) -> Result<Comparison, AppError> {
+    let file: ComparisonsFile = load_json(&comparisons_path(settings))?;
+    file.comparisons
+        .get(token)
+        .cloned()
+        .ok_or(AppError::NotFound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_settings(url: String, data_dir: PathBuf) -> Settings {
+        Settings::for_test(url, data_dir)
+    }
+
+    #[tokio::test]
+    async fn test_save_and_get_comparison() {
+        let mut server = mockito::Server::new_async().await;
+        let _first = server
+            .mock("GET", "/public/animals/1")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Buddy", "sex": "Male"}}]}"#)
+            .create_async()
+            .await;
+        let _second = server
+            .mock("GET", "/public/animals/2")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "2", "attributes": {"name": "Rex", "sex": "Male"}}]}"#)
+            .create_async()
+            .await;
+
+        let dir = std::env::temp_dir().join("rescue-groups-mcp-comparisons");
+        let _ = std::fs::remove_dir_all(&dir);
+        let settings = get_test_settings(server.url(), dir.clone());
+
+        let saved = save_comparison(
+            &settings,
+            CompareArgs {
+                animal_ids: vec!["1".to_string(), "2".to_string()],
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(saved.token.len(), 10);
+        assert_eq!(saved.animals.len(), 2);
+        assert!(saved.table.contains("Buddy"));
+
+        let loaded = get_comparison(&settings, &saved.token).await.unwrap();
+        assert_eq!(loaded.table, saved.table);
+        assert!(matches!(
+            get_comparison(&settings, "missing").await,
+            Err(AppError::NotFound)
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
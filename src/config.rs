@@ -1,5 +1,12 @@
-use crate::cli::Cli;
+use crate::cassette::{Cassette, CassetteMode};
+use crate::cli::{Cli, ToolArgs};
 use crate::error::AppError;
+use crate::keys::KeyPool;
+use crate::mcp::check_tool_names;
+use crate::queue::{Priority, RequestQueue, DEFAULT_QUEUE_LIMIT};
+use crate::shared_cache::SharedCache;
+use crate::store::default_data_dir;
+use crate::trace::RequestLog;
 use governor::{
     clock::DefaultClock,
     state::{InMemoryState, NotKeyed},
@@ -9,20 +16,386 @@ use moka::future::Cache;
 use nonzero_ext::nonzero;
 use serde::Deserialize;
 use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 #[derive(Deserialize, Debug, Clone)]
 struct ConfigFile {
     api_key: Option<String>,
+    base_url: Option<String>,
+    api_keys: Option<Vec<String>>,
     postal_code: Option<String>,
     species: Option<String>,
     miles: Option<u32>,
     timeout_seconds: Option<u64>,
+    call_timeout_seconds: Option<u64>,
+    idle_timeout_minutes: Option<u64>,
+    max_response_kb: Option<usize>,
     lazy: Option<bool>,
     rate_limit_requests: Option<u32>,
     rate_limit_window: Option<u64>,
+    rate_limit_queue: Option<usize>,
+    fan_out_concurrency: Option<usize>,
+    update_check: Option<bool>,
+    strict: Option<bool>,
+    deterministic: Option<bool>,
+    preflight: Option<PreflightMode>,
+    presets: Option<HashMap<String, SearchPreset>>,
+    plugins: Option<HashMap<String, PluginTool>>,
+    data_dir: Option<PathBuf>,
+    alerts: Option<AlertsConfig>,
+    notifications: Option<NotificationsConfig>,
+    sources: Option<Vec<SourceKind>>,
+    petfinder: Option<PetfinderConfig>,
+    private_api: Option<PrivateApiConfig>,
+    embeddings: Option<EmbeddingsConfig>,
+    digest: Option<DigestConfig>,
+    metrics: Option<MetricsConfig>,
+    export: Option<ExportConfig>,
+    widening: Option<WideningConfig>,
+    default_filters: Option<DefaultFilters>,
+    labels: Option<Labels>,
+    output: Option<OutputConfig>,
+    sse: Option<SseConfig>,
+    http: Option<HttpConfig>,
+    postal_db: Option<PathBuf>,
+    cache: Option<CacheConfig>,
+    /// Cassette directory to answer from, for profiles of recorded traffic
+    replay: Option<PathBuf>,
+}
+
+/// A named, canned set of search arguments defined under `[presets.<name>]`.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct SearchPreset {
+    /// Shown as the tool description when the preset is exposed over MCP
+    pub description: Option<String>,
+    /// Expose the preset as its own MCP tool (`search_<name>`)
+    #[serde(default)]
+    pub tool: bool,
+    #[serde(flatten)]
+    pub args: ToolArgs,
+}
+
+/// An extra MCP tool defined under `[plugins.<name>]` that runs an
+/// executable or forwards to an HTTP endpoint.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct PluginTool {
+    pub description: Option<String>,
+    /// Program and arguments, split on whitespace and run without a shell.
+    /// `{name}` in an argument is replaced by that tool argument; the full
+    /// arguments object is written to stdin as JSON.
+    pub command: Option<String>,
+    /// Endpoint the arguments object is POSTed to as JSON
+    pub url: Option<String>,
+    /// Extra headers sent to `url` (e.g. an Authorization token)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// JSON schema of the tool arguments, advertised in `tools/list`
+    pub input_schema: Option<Value>,
+    /// Defaults to `timeout_seconds`
+    pub timeout_seconds: Option<u64>,
+}
+
+/// Background polling of saved searches, configured under `[alerts]`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AlertsConfig {
+    pub enabled: bool,
+    pub interval_minutes: u64,
+    /// Notification channels to deliver new matches to ("log", "sse", "webhook", "email")
+    pub channels: Vec<String>,
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 60,
+            channels: vec!["log".to_string()],
+        }
+    }
+}
+
+/// Delivery targets for alert channels, configured under `[notifications]`.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    pub webhooks: Vec<WebhookConfig>,
+    pub email: Option<EmailConfig>,
+}
+
+/// A webhook endpoint, configured as a `[[notifications.webhooks]]` entry.
+#[derive(Deserialize, Debug, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// When set, the payload is signed with HMAC-SHA256 and the signature is
+    /// sent in the `X-Signature-256` header.
+    pub secret: Option<String>,
+    #[serde(default = "default_webhook_retries")]
+    pub max_retries: u32,
+}
+
+fn default_webhook_retries() -> u32 {
+    3
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// Raw alert JSON (`{"alerts": [...]}`)
+    #[default]
+    Json,
+    /// Slack incoming webhook message (`{"text": ...}`)
+    Slack,
+    /// Discord webhook message (`{"content": ...}`)
+    Discord,
+}
+
+/// SMTP settings for the "email" digest channel, configured under
+/// `[notifications.email]`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct EmailConfig {
+    pub smtp_host: String,
+    /// Defaults to the standard port for the selected `tls` mode
+    pub smtp_port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default)]
+    pub frequency: DigestFrequency,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    /// Upgrade a plain connection with STARTTLS (port 587)
+    #[default]
+    Starttls,
+    /// Implicit TLS (port 465)
+    Tls,
+    /// Unencrypted, e.g. for a local relay (port 25)
+    None,
+}
+
+/// What the servers do at startup to check the API key works.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PreflightMode {
+    /// No check; a bad key shows up on the first tool call
+    #[default]
+    Off,
+    /// Log a warning and pass it to clients in the `initialize` instructions
+    Warn,
+    /// Refuse to start when the API rejects the key
+    Fail,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFrequency {
+    #[default]
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    pub fn period(&self) -> std::time::Duration {
+        match self {
+            DigestFrequency::Daily => std::time::Duration::from_secs(24 * 60 * 60),
+            DigestFrequency::Weekly => std::time::Duration::from_secs(7 * 24 * 60 * 60),
+        }
+    }
+}
+
+/// A backend animal listings are fetched from. Listing several in
+/// `sources` federates searches across them.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    RescueGroups,
+    Petfinder,
+}
+
+/// Petfinder v2 API credentials, configured under `[petfinder]`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct PetfinderConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    #[serde(default = "default_petfinder_url")]
+    pub base_url: String,
+}
+
+fn default_petfinder_url() -> String {
+    "https://api.petfinder.com/v2".to_string()
+}
+
+/// RescueGroups account credentials for the private, account-scoped
+/// endpoints, configured under `[private_api]`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct PrivateApiConfig {
+    pub username: String,
+    pub password: String,
+    /// Endpoint, relative to the API base URL, exchanging the credentials
+    /// for a bearer token
+    #[serde(default = "default_login_path")]
+    pub login_path: String,
+}
+
+fn default_login_path() -> String {
+    "/login".to_string()
+}
+
+/// Embedding backend for `semantic_search_pets`, configured under
+/// `[embeddings]`. Without it a local hashing embedder is used.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct EmbeddingsConfig {
+    /// OpenAI-compatible embeddings endpoint (e.g. `https://api.openai.com/v1/embeddings`)
+    pub url: Option<String>,
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+}
+
+/// Searches run by the `digest` command, configured under `[digest]`.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DigestConfig {
+    /// Preset names to include; all presets when empty
+    pub searches: Vec<String>,
+}
+
+/// Opt-in local usage statistics, configured under `[metrics]`.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// Record per-tool call counts, argument names, latencies and errors
+    /// to `usage.json` in the data directory
+    pub enabled: bool,
+}
+
+/// Where the `export_results` tool may write files, configured under
+/// `[export]`.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ExportConfig {
+    /// Directories (and their subdirectories) files may be written to; a
+    /// leading `~` is the home directory. Exporting is disabled when empty.
+    pub allowed_dirs: Vec<PathBuf>,
+}
+
+/// Retrying empty searches farther out, configured under `[widening]`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct WideningConfig {
+    pub enabled: bool,
+    /// Each retry multiplies the radius by this
+    pub factor: u32,
+    /// Largest radius tried, in miles
+    pub max_miles: u32,
+}
+
+impl Default for WideningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            factor: 2,
+            max_miles: 250,
+        }
+    }
+}
+
+/// Filters applied to every search unless its arguments say otherwise,
+/// configured under `[default_filters]`.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct DefaultFilters {
+    /// Species searches may not ask for, e.g. for a dogs-and-cats-only
+    /// shelter
+    #[serde(default)]
+    pub exclude_species: Vec<String>,
+    #[serde(flatten)]
+    pub args: ToolArgs,
+}
+
+/// Wording of the formatted output, for white-labeled deployments,
+/// configured under `[labels]`.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Labels {
+    /// Words and phrases replaced wherever they appear as whole words, e.g.
+    /// `"Adopt" = "Rescue"`
+    #[serde(default)]
+    pub replace: BTreeMap<String, String>,
+    /// A line added to the end of every animal's details
+    pub call_to_action: Option<String>,
+}
+
+/// Length of the formatted output, configured under `[output]`.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// Longest animal or organization description shown on a profile, in
+    /// characters
+    pub detail_description_chars: usize,
+    /// Longest description snippet shown in a list of results
+    pub list_description_chars: usize,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            detail_description_chars: 1500,
+            list_description_chars: 200,
+        }
+    }
+}
+
+/// The HTTP server's event streams, configured under `[sse]`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SseConfig {
+    /// Seconds between keep-alive comments on an idle stream
+    pub keep_alive_seconds: u64,
+    /// Text of the keep-alive comment
+    pub keep_alive_text: String,
+    /// Recent events kept per session to replay to a reconnecting client
+    pub replay_events: usize,
+}
+
+impl Default for SseConfig {
+    fn default() -> Self {
+        Self {
+            keep_alive_seconds: 15,
+            keep_alive_text: "keep-alive".to_string(),
+            replay_events: 100,
+        }
+    }
+}
+
+/// The HTTP server, configured under `[http]`.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Browser origins (e.g. "https://app.example.com") allowed to call the
+    /// MCP endpoints; "*" allows any. When empty only localhost pages may,
+    /// which stops DNS-rebinding attacks on a locally bound server.
+    pub allowed_origins: Vec<String>,
+}
+
+/// Cache shared between replicas, configured under `[cache]`.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Redis or Valkey server holding cached responses and the rate limit
+    /// counter (e.g. "redis://cache:6379/0"); requires the `redis` feature
+    pub redis_url: Option<String>,
+    /// Prefix for every key, so several deployments can share one server
+    pub key_prefix: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -33,91 +406,741 @@ pub struct Settings {
     pub default_miles: u32,
     pub default_species: String,
     pub timeout: std::time::Duration,
+    /// Overall time allowed for one tool call, however many requests it makes
+    pub call_budget: Option<std::time::Duration>,
+    /// When the tool call being handled has to finish, set by `for_call`
+    pub deadline: Option<std::time::Instant>,
+    /// How long the stdio server waits for a request before exiting
+    pub idle_timeout: Option<std::time::Duration>,
+    /// Largest upstream response body read before giving up
+    pub max_response_bytes: Option<usize>,
+    /// Log upstream requests and responses (`--trace-api`)
+    pub trace_api: bool,
+    /// Collect upstream requests in `request_log` instead of sending them
+    /// (`--dry-run`)
+    pub dry_run: bool,
+    /// Keep output reproducible: random order replaced by ID order, ties
+    /// broken by ID and the current time fixed (`--deterministic`)
+    pub deterministic: bool,
+    /// Collects the upstream requests of a tool call called with `debug`
+    pub request_log: Option<RequestLog>,
     pub lazy: bool,
+    pub update_check: bool,
+    /// Reject tool arguments the tool's schema doesn't declare
+    pub strict: bool,
+    pub preflight: PreflightMode,
+    /// Problem found by the startup key check, told to clients on `initialize`
+    pub key_warning: Option<String>,
+    pub presets: HashMap<String, SearchPreset>,
+    pub plugins: HashMap<String, PluginTool>,
+    pub data_dir: PathBuf,
+    pub alerts: AlertsConfig,
+    pub notifications: NotificationsConfig,
+    pub sources: Vec<SourceKind>,
+    pub petfinder: Option<PetfinderConfig>,
+    pub private_api: Option<PrivateApiConfig>,
+    pub embeddings: EmbeddingsConfig,
+    pub digest: DigestConfig,
+    pub metrics: MetricsConfig,
+    pub export: ExportConfig,
+    pub widening: WideningConfig,
+    pub default_filters: DefaultFilters,
+    pub labels: Labels,
+    pub output: OutputConfig,
+    pub sse: SseConfig,
+    pub http: HttpConfig,
+    /// GeoNames-format postal code file replacing the bundled dataset
+    pub postal_db: Option<PathBuf>,
+    /// Records or replays upstream API traffic (`--record`/`--replay`)
+    pub cassette: Option<Cassette>,
+    /// Redis/Valkey cache and rate limit shared with other replicas
+    pub shared_cache: Option<Arc<SharedCache>>,
+    /// Extra API keys rotated with `api_key`, each with its own rate limit
+    pub key_pool: Option<Arc<KeyPool>>,
     pub cache: Arc<Cache<String, Value>>,
     pub limiter: Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
+    /// Requests waiting for `limiter`, taken in turn by session
+    pub queue: Arc<RequestQueue>,
+    /// MCP session the tool call being handled came from, for `queue`
+    pub session: Option<String>,
+    /// Whether requests wait behind tool calls, set by `background`
+    pub priority: Priority,
+    /// Requests one tool call makes at once when fanning out (comparisons,
+    /// several locations)
+    pub concurrency: usize,
 }
 
-pub fn merge_configuration(cli: &Cli) -> Result<Settings, AppError> {
-    let config_path = Path::new(&cli.config);
-
-    let file_config: Option<ConfigFile> = if config_path.exists() {
-        let content = fs::read_to_string(config_path).map_err(AppError::Io)?;
-        let ext = config_path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("");
-        match ext {
-            "toml" => Some(toml::from_str(&content).map_err(AppError::Toml)?),
-            "json" => Some(serde_json::from_str(&content).map_err(AppError::Serialization)?),
-            "yaml" | "yml" => Some(serde_yaml::from_str(&content).map_err(AppError::Yaml)?),
-            _ => None,
-        }
-    } else {
-        None
-    };
+const DEFAULT_BASE_URL: &str = "https://api.rescuegroups.org/v5";
+/// Where the `mock` profile expects a fixture server, such as Prism serving
+/// the API's OpenAPI document
+const MOCK_BASE_URL: &str = "http://localhost:4010/v5";
 
-    let api_key = cli
-        .api_key
-        .clone()
-        .or(file_config.as_ref().and_then(|c| c.api_key.clone()))
-        .ok_or_else(|| {
-            AppError::ConfigError(
-                "API Key is missing! Set RESCUE_GROUPS_API_KEY or use config.toml".to_string(),
-            )
-        })?;
+const DEFAULT_MAX_RESPONSE_KB: usize = 8 * 1024;
 
-    let cache = Cache::builder()
-        .max_capacity(100)
-        .time_to_live(std::time::Duration::from_secs(15 * 60)) // 15 minutes
-        .build();
+const MAX_DEFAULT_CONCURRENCY: usize = 8;
 
-    // Default: 60 requests per 60 seconds (1 req/sec)
-    let max_requests = std::num::NonZeroU32::new(
-        file_config
-            .as_ref()
-            .and_then(|c| c.rate_limit_requests)
-            .unwrap_or(60),
+const DEFAULT_CALL_BUDGET: std::time::Duration = std::time::Duration::from_secs(60);
+
+fn build_cache() -> Arc<Cache<String, Value>> {
+    Arc::new(
+        Cache::builder()
+            .max_capacity(100)
+            .time_to_live(std::time::Duration::from_secs(15 * 60)) // 15 minutes
+            .build(),
     )
-    .unwrap_or(nonzero!(60u32));
+}
 
-    let window = std::time::Duration::from_secs(
-        file_config
-            .as_ref()
-            .and_then(|c| c.rate_limit_window)
-            .unwrap_or(60),
-    );
+/// Fan-out concurrency for a rate limit: about one second's worth of
+/// requests, so a fan-out doesn't drain the quota at once.
+fn default_concurrency(max_requests: std::num::NonZeroU32, window: std::time::Duration) -> usize {
+    let per_second = max_requests.get() as f64 / window.as_secs_f64().max(1.0);
+    (per_second.ceil() as usize).clamp(1, MAX_DEFAULT_CONCURRENCY)
+}
 
+fn build_limiter(
+    max_requests: std::num::NonZeroU32,
+    window: std::time::Duration,
+) -> Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>> {
     let quota = Quota::with_period(window)
         .unwrap()
         .allow_burst(max_requests);
-    let limiter = Arc::new(RateLimiter::direct(quota));
+    Arc::new(RateLimiter::direct(quota))
+}
+
+impl Settings {
+    /// Settings with the built-in defaults and no config file, for using the
+    /// client as a library.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Settings {
+            api_key: api_key.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            default_postal_code: "90210".to_string(),
+            default_miles: 50,
+            default_species: "dogs".to_string(),
+            timeout: std::time::Duration::from_secs(30),
+            call_budget: Some(DEFAULT_CALL_BUDGET),
+            deadline: None,
+            idle_timeout: None,
+            max_response_bytes: Some(DEFAULT_MAX_RESPONSE_KB * 1024),
+            trace_api: false,
+            dry_run: false,
+            deterministic: false,
+            request_log: None,
+            lazy: true,
+            update_check: true,
+            strict: false,
+            preflight: PreflightMode::Off,
+            key_warning: None,
+            presets: HashMap::new(),
+            plugins: HashMap::new(),
+            data_dir: default_data_dir(),
+            alerts: AlertsConfig::default(),
+            notifications: NotificationsConfig::default(),
+            sources: vec![SourceKind::RescueGroups],
+            petfinder: None,
+            private_api: None,
+            embeddings: EmbeddingsConfig::default(),
+            digest: DigestConfig::default(),
+            metrics: MetricsConfig::default(),
+            export: ExportConfig::default(),
+            widening: WideningConfig::default(),
+            default_filters: DefaultFilters::default(),
+            labels: Labels::default(),
+            output: OutputConfig::default(),
+            sse: SseConfig::default(),
+            http: HttpConfig::default(),
+            postal_db: None,
+            cassette: None,
+            shared_cache: None,
+            key_pool: None,
+            cache: build_cache(),
+            limiter: build_limiter(nonzero!(60u32), std::time::Duration::from_secs(60)),
+            queue: Arc::default(),
+            session: None,
+            priority: Priority::Interactive,
+            concurrency: default_concurrency(nonzero!(60u32), std::time::Duration::from_secs(60)),
+        }
+    }
+
+    /// These settings with the deadline for a tool call starting now.
+    pub fn for_call(&self) -> Settings {
+        Settings {
+            deadline: self
+                .call_budget
+                .map(|budget| std::time::Instant::now() + budget),
+            ..self.clone()
+        }
+    }
 
-    let base_url = std::env::var("RESCUE_GROUPS_BASE_URL")
-        .unwrap_or_else(|_| "https://api.rescuegroups.org/v5".to_string());
+    /// These settings for a job running unattended (alert polling, digests),
+    /// whose requests yield the rate limit to tool calls.
+    pub fn background(&self) -> Settings {
+        Settings {
+            priority: Priority::Background,
+            ..self.clone()
+        }
+    }
 
-    Ok(Settings {
+    /// How long the next upstream request may take: `timeout`, cut short by
+    /// the call's deadline. Fails once the deadline has passed.
+    pub fn request_timeout(&self) -> Result<std::time::Duration, AppError> {
+        match self.deadline {
+            None => Ok(self.timeout),
+            Some(deadline) => match deadline.checked_duration_since(std::time::Instant::now()) {
+                Some(left) if !left.is_zero() => Ok(left.min(self.timeout)),
+                _ => Err(AppError::DeadlineExceeded),
+            },
+        }
+    }
+
+    /// `error`, or `DeadlineExceeded` if it happened because the call ran
+    /// out of time.
+    pub fn deadline_error(&self, error: impl Into<AppError>) -> AppError {
+        match self.request_timeout() {
+            Err(e) => e,
+            Ok(_) => error.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Settings {
+    /// Settings for unit tests against a mock server at `base_url`, with
+    /// their files under `data_dir`: a short timeout, a generous rate limit,
+    /// no call budget and the `00000` default postal code mocks expect.
+    pub(crate) fn for_test(base_url: impl Into<String>, data_dir: impl Into<PathBuf>) -> Settings {
+        Settings {
+            base_url: base_url.into(),
+            data_dir: data_dir.into(),
+            default_postal_code: "00000".to_string(),
+            timeout: std::time::Duration::from_secs(1),
+            call_budget: None,
+            max_response_bytes: None,
+            lazy: false,
+            update_check: false,
+            concurrency: 4,
+            cache: Arc::new(Cache::new(10)),
+            limiter: Arc::new(RateLimiter::direct(Quota::per_second(nonzero!(100u32)))),
+            ..Settings::new("test_key")
+        }
+    }
+}
+
+/// Directory name of the system and user configuration.
+const CONFIG_DIR: &str = "rescue-groups-mcp";
+/// Formats a system or user `config.<ext>` may be written in, in the order
+/// they are looked for.
+const CONFIG_EXTENSIONS: [&str; 4] = ["toml", "yaml", "yml", "json"];
+/// Prefix of the environment variables setting top-level values.
+const ENV_PREFIX: &str = "RESCUE_GROUPS_";
+/// Top-level values that can be set from the environment, as
+/// `RESCUE_GROUPS_<NAME>`, and whether they are text rather than a number
+/// or boolean. The API key is read by the `--api-key` flag.
+const ENV_KEYS: &[(&str, bool)] = &[
+    ("base_url", true),
+    ("profile", true),
+    ("postal_code", true),
+    ("species", true),
+    ("miles", false),
+    ("timeout_seconds", false),
+    ("call_timeout_seconds", false),
+    ("idle_timeout_minutes", false),
+    ("max_response_kb", false),
+    ("lazy", false),
+    ("rate_limit_requests", false),
+    ("rate_limit_window", false),
+    ("rate_limit_queue", false),
+    ("fan_out_concurrency", false),
+    ("update_check", false),
+    ("strict", false),
+    ("deterministic", false),
+    ("preflight", true),
+    ("data_dir", true),
+    ("postal_db", true),
+];
+
+/// The configuration every layer adds up to, and the layer each value
+/// came from, by dotted key (`alerts.enabled`).
+#[derive(Debug, Default)]
+pub struct LayeredConfig {
+    pub value: Value,
+    pub origins: BTreeMap<String, String>,
+}
+
+/// The configuration files read, lowest precedence first: system-wide,
+/// the user's, then the project's (`--config`). Missing ones are skipped,
+/// and only the project's is read with `--no-system-config`.
+pub fn config_files(cli: &Cli) -> Vec<(&'static str, PathBuf)> {
+    let find = |dir: PathBuf| {
+        CONFIG_EXTENSIONS
+            .iter()
+            .map(|ext| dir.join(format!("config.{}", ext)))
+            .find(|path| path.is_file())
+    };
+    let mut files = Vec::new();
+    if !cli.no_system_config {
+        if cfg!(unix) {
+            files.extend(find(Path::new("/etc").join(CONFIG_DIR)).map(|p| ("system", p)));
+        }
+        files.extend(
+            dirs::config_dir()
+                .and_then(|dir| find(dir.join(CONFIG_DIR)))
+                .map(|p| ("user", p)),
+        );
+    }
+    let project = PathBuf::from(&cli.config);
+    if project.is_file() {
+        files.push(("project", project));
+    }
+    files
+}
+
+/// A configuration file as JSON, whatever format its extension says it is
+/// in. Files in other formats are ignored.
+fn read_config_file(path: &Path) -> Result<Option<Value>, AppError> {
+    let content = fs::read_to_string(path).map_err(AppError::Io)?;
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let value = match ext {
+        "toml" => serde_json::to_value(toml::from_str::<toml::Value>(&content)?)?,
+        "json" => serde_json::from_str(&content)?,
+        "yaml" | "yml" => serde_yaml::from_str(&content)?,
+        _ => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+/// Top-level values set by `RESCUE_GROUPS_<NAME>` variables in `vars`.
+/// Numbers and booleans are parsed unless the value is text, like a postal
+/// code.
+fn env_layer(vars: impl IntoIterator<Item = (String, String)>) -> Vec<(String, String, Value)> {
+    let mut layer: Vec<(String, String, Value)> = vars
+        .into_iter()
+        .filter_map(|(var, raw)| {
+            let key = var.strip_prefix(ENV_PREFIX)?.to_lowercase();
+            let &(_, text) = ENV_KEYS.iter().find(|(name, _)| *name == key)?;
+            let value = match (text, raw.parse::<u64>(), raw.as_str()) {
+                (true, ..) => Value::String(raw),
+                (false, Ok(n), _) => Value::from(n),
+                (false, Err(_), "true") => Value::Bool(true),
+                (false, Err(_), "false") => Value::Bool(false),
+                _ => Value::String(raw),
+            };
+            Some((var, key, value))
+        })
+        .collect();
+    layer.sort_by(|a, b| a.0.cmp(&b.0));
+    layer
+}
+
+/// Merges `layer` into `target`: tables key by key, anything else (lists
+/// included) replacing what was there. Each value set is recorded as
+/// coming from `origin`.
+fn merge_layer(
+    target: &mut Value,
+    layer: Value,
+    origin: &str,
+    path: &str,
+    origins: &mut BTreeMap<String, String>,
+) {
+    match (target, layer) {
+        (Value::Object(target), Value::Object(layer)) => {
+            for (key, value) in layer {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                let slot = target.entry(key).or_insert(Value::Null);
+                if !slot.is_object() || !value.is_object() {
+                    // What a lower layer set here is replaced, not merged
+                    let prefix = format!("{}.", path);
+                    origins.retain(|k, _| *k != path && !k.starts_with(&prefix));
+                    if value.is_object() {
+                        *slot = Value::Object(Default::default());
+                    }
+                }
+                merge_layer(slot, value, origin, &path, origins);
+            }
+        }
+        (target, layer) => {
+            *target = layer;
+            origins.insert(path.to_string(), origin.to_string());
+        }
+    }
+}
+
+/// Profiles available without configuration. A `[profiles.<name>]` table
+/// of the same name is merged over them.
+fn builtin_profile(name: &str) -> Option<Value> {
+    match name {
+        "production" => Some(serde_json::json!({ "base_url": DEFAULT_BASE_URL })),
+        // Fixture servers accept any key
+        "mock" => Some(serde_json::json!({ "base_url": MOCK_BASE_URL, "api_key": "mock" })),
+        _ => None,
+    }
+}
+
+/// The settings of profile `name`: the built-in one, if any, with the
+/// configured `[profiles.<name>]` merged over it.
+fn profile_layer(config: &Value, name: &str) -> Result<Value, AppError> {
+    let builtin = builtin_profile(name);
+    let configured = config["profiles"].get(name).cloned();
+    if builtin.is_none() && configured.is_none() {
+        let mut names = vec!["mock", "production"];
+        if let Some(profiles) = config["profiles"].as_object() {
+            names.extend(profiles.keys().map(String::as_str));
+        }
+        names.sort_unstable();
+        names.dedup();
+        return Err(AppError::ConfigError(format!(
+            "Unknown profile '{}'. Available profiles: {}",
+            name,
+            names.join(", ")
+        )));
+    }
+    let mut profile = builtin.unwrap_or_else(|| Value::Object(Default::default()));
+    if let Some(configured) = configured {
+        if !configured.is_object() {
+            return Err(AppError::ConfigError(format!(
+                "Profile '{}' must be a table of settings",
+                name
+            )));
+        }
+        merge_layer(&mut profile, configured, "", "", &mut BTreeMap::new());
+    }
+    Ok(profile)
+}
+
+/// Reads every configuration layer and merges them, later ones winning:
+/// the system file (`/etc/rescue-groups-mcp/config.toml`), the user's
+/// (`~/.config/rescue-groups-mcp/config.toml`), the project's
+/// (`--config`), the selected profile, `RESCUE_GROUPS_<NAME>` variables,
+/// then flags.
+pub fn load_layers(cli: &Cli) -> Result<LayeredConfig, AppError> {
+    let mut config = LayeredConfig {
+        value: Value::Object(Default::default()),
+        origins: BTreeMap::new(),
+    };
+    for (layer, path) in config_files(cli) {
+        if let Some(value) = read_config_file(&path)? {
+            if !value.is_object() {
+                return Err(AppError::ConfigError(format!(
+                    "{} must contain a table of settings",
+                    path.display()
+                )));
+            }
+            let origin = format!("{} ({})", layer, path.display());
+            merge_layer(&mut config.value, value, &origin, "", &mut config.origins);
+        }
+    }
+    let env = env_layer(std::env::vars());
+    let profile = cli
+        .profile
+        .clone()
+        .or_else(|| {
+            env.iter()
+                .find(|(_, key, _)| key == "profile")
+                .and_then(|(_, _, value)| value.as_str().map(str::to_string))
+        })
+        .or_else(|| config.value["profile"].as_str().map(str::to_string));
+    if let Some(name) = profile {
+        let layer = profile_layer(&config.value, &name)?;
+        let origin = format!("profile ({})", name);
+        merge_layer(&mut config.value, layer, &origin, "", &mut config.origins);
+    }
+
+    for (var, key, value) in env {
+        let origin = format!("env ({})", var);
+        merge_layer(
+            &mut config.value,
+            serde_json::json!({ key: value }),
+            &origin,
+            "",
+            &mut config.origins,
+        );
+    }
+
+    let mut flags = Vec::new();
+    if let Some(api_key) = &cli.api_key {
+        // Clap reads the flag from the environment too
+        let var = format!("{}API_KEY", ENV_PREFIX);
+        let origin = if std::env::var(&var).ok() == cli.api_key {
+            format!("env ({})", var)
+        } else {
+            "flag (--api-key)".to_string()
+        };
+        flags.push(("api_key", Value::from(api_key.clone()), origin));
+    }
+    // `--timeout` bounds the whole command, not just each request
+    if let Some(timeout) = cli.timeout {
+        for key in ["timeout_seconds", "call_timeout_seconds"] {
+            flags.push((key, Value::from(timeout), "flag (--timeout)".to_string()));
+        }
+    }
+    if let Some(profile) = &cli.profile {
+        flags.push((
+            "profile",
+            Value::from(profile.clone()),
+            "flag (--profile)".to_string(),
+        ));
+    }
+    if cli.deterministic {
+        flags.push((
+            "deterministic",
+            Value::Bool(true),
+            "flag (--deterministic)".to_string(),
+        ));
+    }
+    if let Some(base_url) = &cli.base_url {
+        flags.push((
+            "base_url",
+            Value::from(base_url.clone()),
+            "flag (--base-url)".to_string(),
+        ));
+    }
+    for (key, value, origin) in flags {
+        let value = serde_json::json!({ key: value });
+        merge_layer(&mut config.value, value, &origin, "", &mut config.origins);
+    }
+    Ok(config)
+}
+
+/// Whether the value under `key` is a credential, to be masked when shown.
+fn is_secret(key: &str) -> bool {
+    let name = key.rsplit('.').next().unwrap_or(key);
+    name.ends_with("api_key")
+        || name.ends_with("api_keys")
+        || ["secret", "password", "token"]
+            .iter()
+            .any(|word| name.contains(word))
+}
+
+fn mask(value: &Value) -> Value {
+    match value {
+        Value::String(s) => {
+            let shown: String = s
+                .chars()
+                .skip(s.chars().count().saturating_sub(4))
+                .collect();
+            Value::from(format!("****{}", shown))
+        }
+        Value::Array(items) => Value::Array(items.iter().map(mask).collect()),
+        _ => Value::from("****"),
+    }
+}
+
+/// Every value the layers set, by dotted key, with the layer it came from.
+/// Credentials are masked.
+pub fn effective_values(config: &LayeredConfig) -> Vec<Value> {
+    fn walk(value: &Value, path: &str, config: &LayeredConfig, out: &mut Vec<Value>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    let path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    walk(value, &path, config, out);
+                }
+            }
+            value => out.push(serde_json::json!({
+                "key": path,
+                "value": if is_secret(path) { mask(value) } else { value.clone() },
+                "origin": config.origins.get(path),
+            })),
+        }
+    }
+    let mut out = Vec::new();
+    walk(&config.value, "", config, &mut out);
+    out
+}
+
+pub fn merge_configuration(cli: &Cli) -> Result<Settings, AppError> {
+    let layered = load_layers(cli)?;
+    let file_config: ConfigFile = serde_json::from_value(layered.value)
+        .map_err(|e| AppError::ConfigError(format!("Invalid configuration: {}", e)))?;
+
+    let mut api_keys: Vec<String> = file_config
+        .api_key
+        .clone()
+        .into_iter()
+        .chain(file_config.api_keys.clone().unwrap_or_default())
+        .filter(|k| !k.trim().is_empty())
+        .collect();
+    let mut seen = std::collections::HashSet::new();
+    api_keys.retain(|k| seen.insert(k.clone()));
+    let api_key = api_keys.first().cloned().ok_or_else(|| {
+        AppError::ConfigError(
+            "API Key is missing! Set RESCUE_GROUPS_API_KEY or use config.toml".to_string(),
+        )
+    })?;
+
+    // Default: 60 requests per 60 seconds (1 req/sec)
+    let max_requests = std::num::NonZeroU32::new(file_config.rate_limit_requests.unwrap_or(60))
+        .unwrap_or(nonzero!(60u32));
+
+    let window = std::time::Duration::from_secs(file_config.rate_limit_window.unwrap_or(60));
+
+    let limiter = build_limiter(max_requests, window);
+    let concurrency = file_config
+        .fan_out_concurrency
+        .filter(|&n| n > 0)
+        .unwrap_or_else(|| default_concurrency(max_requests, window));
+    let queue = Arc::new(RequestQueue::new(
+        file_config.rate_limit_queue.unwrap_or(DEFAULT_QUEUE_LIMIT),
+    ));
+    let key_count = api_keys.len() as u32;
+    let key_pool = (key_count > 1).then(|| Arc::new(KeyPool::new(api_keys, max_requests, window)));
+
+    let cache_config = file_config.cache.clone().unwrap_or_default();
+    let shared_cache = match &cache_config.redis_url {
+        Some(url) => Some(Arc::new(SharedCache::connect(
+            url,
+            cache_config
+                .key_prefix
+                .as_deref()
+                .unwrap_or("rescue-groups-mcp"),
+            // Every key brings its own quota
+            max_requests.get() * key_count,
+            window,
+        )?)),
+        None => None,
+    };
+
+    let petfinder = file_config.petfinder.clone();
+    let sources = file_config
+        .sources
+        .clone()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| vec![SourceKind::RescueGroups]);
+    if sources.contains(&SourceKind::Petfinder) && petfinder.is_none() {
+        return Err(AppError::ConfigError(
+            "The petfinder source requires a [petfinder] section with client_id and client_secret"
+                .to_string(),
+        ));
+    }
+
+    let plugins = file_config.plugins.clone().unwrap_or_default();
+    for (name, plugin) in &plugins {
+        if plugin.command.is_some() == plugin.url.is_some() {
+            return Err(AppError::ConfigError(format!(
+                "Plugin '{}' needs exactly one of command or url",
+                name
+            )));
+        }
+    }
+
+    let cassette = match (
+        &cli.record,
+        cli.replay.as_ref().or(file_config.replay.as_ref()),
+    ) {
+        (Some(dir), _) => Some(Cassette {
+            mode: CassetteMode::Record,
+            dir: dir.clone(),
+        }),
+        (None, Some(dir)) => {
+            if !dir.is_dir() {
+                return Err(AppError::ConfigError(format!(
+                    "Cassette directory '{}' does not exist",
+                    dir.display()
+                )));
+            }
+            Some(Cassette {
+                mode: CassetteMode::Replay,
+                dir: dir.clone(),
+            })
+        }
+        (None, None) => None,
+    };
+
+    let base_url = match &file_config.base_url {
+        Some(url) => {
+            let parsed = reqwest::Url::parse(url)
+                .map_err(|e| AppError::ConfigError(format!("Invalid base_url '{}': {}", url, e)))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(AppError::ConfigError(format!(
+                    "Invalid base_url '{}': must be an http or https URL",
+                    url
+                )));
+            }
+            url.trim_end_matches('/').to_string()
+        }
+        None => DEFAULT_BASE_URL.to_string(),
+    };
+
+    let settings = Settings {
         api_key,
         base_url,
         default_postal_code: file_config
-            .as_ref()
-            .and_then(|c| c.postal_code.clone())
+            .postal_code
+            .clone()
             .unwrap_or_else(|| "90210".to_string()),
-        default_miles: file_config.as_ref().and_then(|c| c.miles).unwrap_or(50),
+        default_miles: file_config.miles.unwrap_or(50),
         default_species: file_config
-            .as_ref()
-            .and_then(|c| c.species.clone())
+            .species
+            .clone()
             .unwrap_or_else(|| "dogs".to_string()),
-        timeout: std::time::Duration::from_secs(
+        timeout: std::time::Duration::from_secs(file_config.timeout_seconds.unwrap_or(30)),
+        call_budget: Some(
+            file_config
+                .call_timeout_seconds
+                .map(std::time::Duration::from_secs)
+                .unwrap_or(DEFAULT_CALL_BUDGET),
+        ),
+        deadline: None,
+        idle_timeout: file_config
+            .idle_timeout_minutes
+            .map(|minutes| std::time::Duration::from_secs(minutes * 60)),
+        max_response_bytes: Some(
             file_config
-                .as_ref()
-                .and_then(|c| c.timeout_seconds)
-                .unwrap_or(30),
+                .max_response_kb
+                .unwrap_or(DEFAULT_MAX_RESPONSE_KB)
+                * 1024,
         ),
-        lazy: file_config.as_ref().and_then(|c| c.lazy).unwrap_or(true),
-        cache: Arc::new(cache),
+        trace_api: cli.trace_api,
+        dry_run: cli.dry_run,
+        deterministic: file_config.deterministic.unwrap_or(false),
+        request_log: cli.dry_run.then(RequestLog::default),
+        lazy: file_config.lazy.unwrap_or(true),
+        update_check: file_config.update_check.unwrap_or(true),
+        strict: file_config.strict.unwrap_or(false),
+        preflight: file_config.preflight.unwrap_or_default(),
+        key_warning: None,
+        presets: file_config.presets.clone().unwrap_or_default(),
+        plugins,
+        data_dir: file_config
+            .data_dir
+            .clone()
+            .unwrap_or_else(default_data_dir),
+        alerts: file_config.alerts.clone().unwrap_or_default(),
+        notifications: file_config.notifications.clone().unwrap_or_default(),
+        sources,
+        petfinder,
+        private_api: file_config.private_api.clone(),
+        embeddings: file_config.embeddings.clone().unwrap_or_default(),
+        digest: file_config.digest.clone().unwrap_or_default(),
+        metrics: file_config.metrics.clone().unwrap_or_default(),
+        export: file_config.export.clone().unwrap_or_default(),
+        widening: file_config.widening.clone().unwrap_or_default(),
+        default_filters: file_config.default_filters.clone().unwrap_or_default(),
+        labels: file_config.labels.clone().unwrap_or_default(),
+        output: file_config.output.unwrap_or_default(),
+        sse: file_config.sse.clone().unwrap_or_default(),
+        http: file_config.http.clone().unwrap_or_default(),
+        postal_db: file_config.postal_db.clone(),
+        cassette,
+        shared_cache,
+        key_pool,
+        cache: build_cache(),
         limiter,
-    })
+        queue,
+        session: None,
+        priority: Priority::Interactive,
+        concurrency,
+    };
+    check_tool_names(&settings)?;
+    Ok(settings)
 }
 
 #[cfg(test)]
@@ -130,7 +1153,16 @@ mod tests {
         let cli = Cli {
             api_key: Some("cli_key".to_string()),
             config: "non_existent.toml".to_string(),
+            no_system_config: true,
             json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
             command: None,
         };
 
@@ -139,12 +1171,51 @@ mod tests {
         assert_eq!(settings.default_postal_code, "90210"); // Default
     }
 
+    #[test]
+    fn test_merge_configuration_cassette() {
+        let mut cli = Cli {
+            api_key: Some("key".to_string()),
+            config: "non_existent.toml".to_string(),
+            no_system_config: true,
+            json: false,
+            record: Some(PathBuf::from("cassettes/bug-42")),
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
+            command: None,
+        };
+        let settings = merge_configuration(&cli).unwrap();
+        let cassette = settings.cassette.unwrap();
+        assert_eq!(cassette.mode, CassetteMode::Record);
+        assert_eq!(cassette.dir, PathBuf::from("cassettes/bug-42"));
+
+        cli.record = None;
+        cli.replay = Some(PathBuf::from("non_existent_cassette"));
+        match merge_configuration(&cli) {
+            Err(AppError::ConfigError(msg)) => assert!(msg.contains("does not exist")),
+            _ => panic!("Expected ConfigError"),
+        }
+    }
+
     #[test]
     fn test_merge_configuration_missing_key() {
         let cli = Cli {
             api_key: None,
             config: "non_existent.toml".to_string(),
+            no_system_config: true,
             json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
             command: None,
         };
 
@@ -169,7 +1240,16 @@ mod tests {
         let cli = Cli {
             api_key: None,
             config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
             json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
             command: None,
         };
 
@@ -179,6 +1259,230 @@ mod tests {
         fs::remove_file(config_path).unwrap();
     }
 
+    #[test]
+    fn test_merge_layer() {
+        let mut config = LayeredConfig {
+            value: serde_json::json!({}),
+            origins: BTreeMap::new(),
+        };
+        let system = serde_json::json!({
+            "miles": 50,
+            "sources": ["rescuegroups", "petfinder"],
+            "alerts": { "enabled": true, "interval_minutes": 60 },
+        });
+        let user = serde_json::json!({
+            "sources": ["rescuegroups"],
+            "alerts": { "interval_minutes": 15 },
+        });
+        merge_layer(&mut config.value, system, "system", "", &mut config.origins);
+        merge_layer(&mut config.value, user, "user", "", &mut config.origins);
+
+        // Tables merge key by key, lists are replaced
+        assert_eq!(
+            config.value,
+            serde_json::json!({
+                "miles": 50,
+                "sources": ["rescuegroups"],
+                "alerts": { "enabled": true, "interval_minutes": 15 },
+            })
+        );
+        assert_eq!(config.origins["miles"], "system");
+        assert_eq!(config.origins["sources"], "user");
+        assert_eq!(config.origins["alerts.enabled"], "system");
+        assert_eq!(config.origins["alerts.interval_minutes"], "user");
+
+        let values = effective_values(&LayeredConfig {
+            value: serde_json::json!({ "api_key": "abcdef1234", "petfinder": { "client_secret": "s3cret" } }),
+            origins: BTreeMap::from([("api_key".to_string(), "project".to_string())]),
+        });
+        assert_eq!(values[0]["value"], "****1234");
+        assert_eq!(values[0]["origin"], "project");
+        assert_eq!(values[1]["key"], "petfinder.client_secret");
+        assert_eq!(values[1]["value"], "****cret");
+    }
+
+    #[test]
+    fn test_env_layer() {
+        let vars = [
+            ("RESCUE_GROUPS_MILES", "25"),
+            ("RESCUE_GROUPS_STRICT", "true"),
+            ("RESCUE_GROUPS_POSTAL_CODE", "02134"),
+            ("RESCUE_GROUPS_BASE_URL", "http://localhost"),
+            ("HOME", "/root"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let layer: Vec<(String, Value)> = env_layer(vars)
+            .into_iter()
+            .map(|(_, key, value)| (key, value))
+            .collect();
+        assert_eq!(
+            layer,
+            [
+                ("base_url".to_string(), Value::from("http://localhost")),
+                ("miles".to_string(), Value::from(25)),
+                // Postal codes stay text, leading zeros and all
+                ("postal_code".to_string(), Value::from("02134")),
+                ("strict".to_string(), Value::from(true)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_configuration_profiles() {
+        let config_path = std::env::temp_dir().join("config_profiles.toml");
+        fs::write(
+            &config_path,
+            "api_key = \"k\"\nprofile = \"staging\"\n\n\
+             [profiles.staging]\nbase_url = \"https://staging.example.org/v5/\"\n\n\
+             [profiles.mock]\nbase_url = \"http://localhost:9000/v5\"",
+        )
+        .unwrap();
+
+        let mut cli = Cli {
+            api_key: None,
+            config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
+            json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
+            command: None,
+        };
+        // Only the project's file is read, whatever is installed on this machine
+        assert_eq!(config_files(&cli), [("project", config_path.clone())]);
+        let settings = merge_configuration(&cli).unwrap();
+        assert_eq!(settings.base_url, "https://staging.example.org/v5");
+        assert_eq!(settings.api_key, "k");
+
+        // Configured settings are merged over the built-in profile
+        cli.profile = Some("mock".to_string());
+        let layered = load_layers(&cli).unwrap();
+        assert_eq!(layered.value["base_url"], "http://localhost:9000/v5");
+        assert_eq!(layered.value["api_key"], "mock");
+        assert_eq!(layered.origins["base_url"], "profile (mock)");
+
+        cli.base_url = Some("http://127.0.0.1:8080".to_string());
+        let settings = merge_configuration(&cli).unwrap();
+        assert_eq!(settings.base_url, "http://127.0.0.1:8080");
+
+        cli.base_url = Some("ftp://example.org".to_string());
+        assert!(matches!(
+            merge_configuration(&cli),
+            Err(AppError::ConfigError(msg)) if msg.contains("http or https")
+        ));
+
+        cli.base_url = None;
+        cli.profile = Some("qa".to_string());
+        match merge_configuration(&cli) {
+            Err(AppError::ConfigError(msg)) => {
+                assert!(msg.contains("mock, production, staging"), "{}", msg)
+            }
+            _ => panic!("Expected ConfigError"),
+        }
+        fs::remove_file(config_path).unwrap();
+    }
+
+    #[test]
+    fn test_merge_configuration_sse() {
+        let config_path = std::env::temp_dir().join("config_sse.toml");
+        fs::write(
+            &config_path,
+            "api_key = \"k\"\n[sse]\nkeep_alive_seconds = 30",
+        )
+        .unwrap();
+
+        let cli = Cli {
+            api_key: None,
+            config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
+            json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
+            command: None,
+        };
+
+        let settings = merge_configuration(&cli).unwrap();
+        assert_eq!(settings.sse.keep_alive_seconds, 30);
+        assert_eq!(settings.sse.keep_alive_text, "keep-alive");
+        fs::remove_file(config_path).unwrap();
+    }
+
+    #[test]
+    fn test_merge_configuration_timeout_flag() {
+        let config_path = std::env::temp_dir().join("config_timeout_flag.toml");
+        fs::write(
+            &config_path,
+            "api_key = \"k\"\ntimeout_seconds = 60\ncall_timeout_seconds = 120",
+        )
+        .unwrap();
+        let cli = Cli {
+            api_key: None,
+            config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
+            json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: Some(5),
+            base_url: None,
+            profile: None,
+            deterministic: false,
+            command: None,
+        };
+
+        let settings = merge_configuration(&cli).unwrap();
+        assert_eq!(settings.timeout, std::time::Duration::from_secs(5));
+        assert_eq!(
+            settings.call_budget,
+            Some(std::time::Duration::from_secs(5))
+        );
+        fs::remove_file(config_path).unwrap();
+    }
+
+    #[test]
+    fn test_merge_configuration_concurrency() {
+        let config_path = std::env::temp_dir().join("config_concurrency.toml");
+        let cli = Cli {
+            api_key: None,
+            config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
+            json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
+            command: None,
+        };
+
+        // About a second's worth of the rate limit
+        fs::write(
+            &config_path,
+            "api_key = \"k\"\nrate_limit_requests = 300\nrate_limit_window = 60",
+        )
+        .unwrap();
+        assert_eq!(merge_configuration(&cli).unwrap().concurrency, 5);
+
+        fs::write(&config_path, "api_key = \"k\"\nfan_out_concurrency = 2").unwrap();
+        assert_eq!(merge_configuration(&cli).unwrap().concurrency, 2);
+        fs::remove_file(config_path).unwrap();
+    }
+
     #[test]
     fn test_merge_configuration_json() {
         let temp_dir = std::env::temp_dir();
@@ -188,7 +1492,16 @@ mod tests {
         let cli = Cli {
             api_key: None,
             config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
             json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
             command: None,
         };
 
@@ -207,7 +1520,16 @@ mod tests {
         let cli = Cli {
             api_key: None,
             config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
             json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
             command: None,
         };
 
@@ -226,7 +1548,16 @@ mod tests {
         let cli = Cli {
             api_key: Some("fallback".to_string()),
             config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
             json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
             command: None,
         };
 
@@ -235,6 +1566,303 @@ mod tests {
         fs::remove_file(config_path).unwrap();
     }
 
+    #[test]
+    fn test_merge_configuration_presets() {
+        let temp_dir = std::env::temp_dir();
+        let config_path = temp_dir.join("presets.toml");
+        fs::write(
+            &config_path,
+            r#"
+api_key = "toml_key"
+
+[presets.senior-cats]
+description = "Senior cats near the shelter"
+tool = true
+species = "cats"
+age = "Senior"
+miles = 25
+
+[default_filters]
+good_with_children = true
+exclude_species = ["reptiles"]
+
+[labels]
+call_to_action = "Call us at 555-0100 to meet them!"
+
+[labels.replace]
+Adopt = "Rescue"
+"#,
+        )
+        .unwrap();
+
+        let cli = Cli {
+            api_key: None,
+            config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
+            json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
+            command: None,
+        };
+
+        let settings = merge_configuration(&cli).unwrap();
+        let preset = settings.presets.get("senior-cats").unwrap();
+        assert!(preset.tool);
+        assert_eq!(preset.args.species.as_deref(), Some("cats"));
+        assert_eq!(preset.args.age.as_deref(), Some("Senior"));
+        assert_eq!(preset.args.miles, Some(25));
+        assert_eq!(settings.default_filters.args.good_with_children, Some(true));
+        assert_eq!(settings.default_filters.exclude_species, ["reptiles"]);
+        assert_eq!(settings.labels.replace["Adopt"], "Rescue");
+        assert!(settings.labels.call_to_action.is_some());
+        assert!(!settings.alerts.enabled);
+
+        fs::write(
+            &config_path,
+            r#"
+api_key = "toml_key"
+
+[presets.adoptable-pets]
+tool = true
+species = "dogs"
+"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            merge_configuration(&cli),
+            Err(AppError::ConfigError(msg)) if msg.contains("'adoptable-pets'")
+        ));
+        fs::remove_file(config_path).unwrap();
+    }
+
+    #[test]
+    fn test_merge_configuration_api_keys() {
+        let config_path = std::env::temp_dir().join("api_keys.toml");
+        fs::write(
+            &config_path,
+            "api_key = \"first\"\napi_keys = [\"first\", \"second\"]",
+        )
+        .unwrap();
+        let cli = Cli {
+            api_key: None,
+            config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
+            json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
+            command: None,
+        };
+
+        let settings = merge_configuration(&cli).unwrap();
+        assert_eq!(settings.api_key, "first");
+        let pool = settings.key_pool.unwrap();
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.key(1), "second");
+
+        // A single key needs no rotation
+        fs::write(&config_path, "api_keys = [\"only\"]").unwrap();
+        let settings = merge_configuration(&cli).unwrap();
+        assert_eq!(settings.api_key, "only");
+        assert!(settings.key_pool.is_none());
+        fs::remove_file(config_path).unwrap();
+    }
+
+    #[test]
+    fn test_merge_configuration_plugins() {
+        let temp_dir = std::env::temp_dir();
+        let config_path = temp_dir.join("plugins.toml");
+        let write = |plugin: &str| {
+            fs::write(
+                &config_path,
+                format!(
+                    "api_key = \"toml_key\"\n\n[plugins.foster_lookup]\n{}",
+                    plugin
+                ),
+            )
+            .unwrap()
+        };
+        let cli = Cli {
+            api_key: None,
+            config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
+            json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
+            command: None,
+        };
+
+        write(
+            r#"command = "foster-db lookup {animal_id}"
+input_schema = { type = "object", properties = { animal_id = { type = "string" } } }
+"#,
+        );
+        let settings = merge_configuration(&cli).unwrap();
+        let plugin = &settings.plugins["foster_lookup"];
+        assert_eq!(
+            plugin.command.as_deref(),
+            Some("foster-db lookup {animal_id}")
+        );
+        assert_eq!(
+            plugin.input_schema.as_ref().unwrap()["properties"]["animal_id"]["type"],
+            "string"
+        );
+
+        write("description = \"No way to run it\"\n");
+        assert!(matches!(
+            merge_configuration(&cli),
+            Err(AppError::ConfigError(msg)) if msg.contains("exactly one of command or url")
+        ));
+
+        write("command = \"true\"\n\n[plugins.list_species]\ncommand = \"true\"\n");
+        assert!(matches!(
+            merge_configuration(&cli),
+            Err(AppError::ConfigError(msg)) if msg.contains("'list_species'")
+        ));
+        fs::remove_file(config_path).unwrap();
+    }
+
+    #[test]
+    fn test_merge_configuration_alerts() {
+        let temp_dir = std::env::temp_dir();
+        let config_path = temp_dir.join("alerts.toml");
+        fs::write(
+            &config_path,
+            r#"
+api_key = "toml_key"
+data_dir = "/tmp/rescue-groups-data"
+
+[alerts]
+enabled = true
+channels = ["log", "sse"]
+
+[[notifications.webhooks]]
+url = "https://hooks.slack.com/services/T000/B000/XXX"
+format = "slack"
+secret = "s3cret"
+
+[notifications.email]
+smtp_host = "smtp.example.com"
+from = "alerts@example.com"
+to = ["me@example.com"]
+frequency = "weekly"
+"#,
+        )
+        .unwrap();
+
+        let cli = Cli {
+            api_key: None,
+            config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
+            json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
+            command: None,
+        };
+
+        let settings = merge_configuration(&cli).unwrap();
+        assert_eq!(settings.data_dir, PathBuf::from("/tmp/rescue-groups-data"));
+        assert!(settings.alerts.enabled);
+        assert_eq!(settings.alerts.interval_minutes, 60);
+        assert_eq!(settings.alerts.channels, vec!["log", "sse"]);
+        let webhook = &settings.notifications.webhooks[0];
+        assert_eq!(webhook.format, WebhookFormat::Slack);
+        assert_eq!(webhook.secret.as_deref(), Some("s3cret"));
+        assert_eq!(webhook.max_retries, 3);
+        let email = settings.notifications.email.as_ref().unwrap();
+        assert_eq!(email.tls, SmtpTls::Starttls);
+        assert_eq!(email.frequency, DigestFrequency::Weekly);
+        assert_eq!(email.frequency.period().as_secs(), 604800);
+        fs::remove_file(config_path).unwrap();
+    }
+
+    #[test]
+    fn test_settings_new_defaults() {
+        let settings = Settings::new("key");
+        assert_eq!(settings.api_key, "key");
+        assert_eq!(settings.base_url, "https://api.rescuegroups.org/v5");
+        assert_eq!(settings.default_miles, 50);
+        assert_eq!(settings.sources, vec![SourceKind::RescueGroups]);
+    }
+
+    #[test]
+    fn test_merge_configuration_sources() {
+        let temp_dir = std::env::temp_dir();
+        let config_path = temp_dir.join("sources.toml");
+        fs::write(
+            &config_path,
+            r#"
+api_key = "toml_key"
+sources = ["rescuegroups", "petfinder"]
+
+[petfinder]
+client_id = "id"
+client_secret = "secret"
+"#,
+        )
+        .unwrap();
+
+        let cli = Cli {
+            api_key: None,
+            config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
+            json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
+            command: None,
+        };
+
+        let settings = merge_configuration(&cli).unwrap();
+        assert_eq!(
+            settings.sources,
+            vec![SourceKind::RescueGroups, SourceKind::Petfinder]
+        );
+        let petfinder = settings.petfinder.unwrap();
+        assert_eq!(petfinder.client_id, "id");
+        assert_eq!(petfinder.base_url, "https://api.petfinder.com/v2");
+
+        // Selecting petfinder without credentials is a config error
+        fs::write(
+            &config_path,
+            "api_key = \"toml_key\"\nsources = [\"petfinder\"]\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            merge_configuration(&cli),
+            Err(AppError::ConfigError(_))
+        ));
+        fs::remove_file(config_path).unwrap();
+    }
+
     #[test]
     fn test_merge_configuration_invalid_toml() {
         let temp_dir = std::env::temp_dir();
@@ -244,7 +1872,16 @@ mod tests {
         let cli = Cli {
             api_key: None,
             config: config_path.to_str().unwrap().to_string(),
+            no_system_config: true,
             json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
             command: None,
         };
 
@@ -0,0 +1,132 @@
+use crate::cli::ToolArgs;
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::fmt::LISTED_ANIMALS;
+use crate::preferences::{rank, Preferences};
+use crate::source::search_animals;
+use moka::future::Cache;
+use serde_json::{json, Value};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// How long a session's last search can still be continued.
+const CONTINUATION_IDLE: Duration = Duration::from_secs(60 * 60);
+
+/// Pages `next_results` fetches at most looking for animals to list.
+const MAX_PAGES_AHEAD: usize = 3;
+
+/// Where a session's last search left off.
+#[derive(Clone, Debug)]
+struct LastSearch {
+    args: ToolArgs,
+    /// What the results were ranked by
+    preferences: Option<Preferences>,
+    page: u32,
+    /// Animals of `page` already shown
+    shown: usize,
+}
+
+// Last search by session
+static LAST_SEARCHES: LazyLock<Cache<String, LastSearch>> = LazyLock::new(|| {
+    Cache::builder()
+        .max_capacity(1_000)
+        .time_to_idle(CONTINUATION_IDLE)
+        .build()
+});
+
+/// Identifies the MCP session `settings` are serving. Requests outside a
+/// session have no state of their own to keep, so they are refused rather
+/// than sharing one.
+pub(crate) fn session_key(settings: &Settings) -> Result<String, AppError> {
+    match settings.session.as_deref() {
+        Some(session) if !session.is_empty() => Ok(format!("{}|{}", settings.base_url, session)),
+        _ => Err(AppError::validation(
+            "This needs an MCP session: send the Mcp-Session-Id header issued by initialize"
+                .to_string(),
+        )),
+    }
+}
+
+/// Records a search so `next_results` can carry on from the animals it
+/// listed, ranked the same way. A widened search is continued at the radius
+/// that found them.
+pub async fn remember_search(
+    settings: &Settings,
+    mut args: ToolArgs,
+    preferences: Option<Preferences>,
+    data: &Value,
+) {
+    if let Some(miles) = data["widened_miles"].as_u64() {
+        args.miles = u32::try_from(miles).ok();
+    }
+    let shown = data["data"].as_array().map_or(0, Vec::len);
+    let search = LastSearch {
+        page: args.page.unwrap_or(1),
+        shown: shown.min(LISTED_ANIMALS),
+        preferences,
+        args,
+    };
+    if let Ok(key) = session_key(settings) {
+        LAST_SEARCHES.insert(key, search).await;
+    }
+}
+
+/// The animals following those the session's last search listed, from the
+/// same page while it lasts and then from the next one. `data` is empty
+/// once the search is exhausted.
+pub async fn next_results(settings: &Settings) -> Result<Value, AppError> {
+    let key = session_key(settings)?;
+    let mut search = LAST_SEARCHES.get(&key).await.ok_or_else(|| {
+        AppError::validation("No search to continue: run search_adoptable_pets first".to_string())
+    })?;
+
+    // The page was fetched before, so this is usually a cache hit
+    let mut data = fetch_page(settings, &search).await?;
+    let mut animals = take_animals(&mut data);
+    // Required preferences can leave a whole page out; look a little further
+    for _ in 0..MAX_PAGES_AHEAD {
+        let fetched = animals.len() as u64 + data["excluded"].as_u64().unwrap_or(0);
+        let more_pages = data["truncated"] == true
+            && data["meta"]["pages"]
+                .as_u64()
+                .is_none_or(|pages| u64::from(search.page) < pages);
+        if search.shown < animals.len() || !more_pages || fetched == 0 {
+            break;
+        }
+        search.page += 1;
+        search.shown = 0;
+        data = fetch_page(settings, &search).await?;
+        animals = take_animals(&mut data);
+    }
+
+    let rest: Vec<Value> = animals
+        .into_iter()
+        .skip(search.shown)
+        .take(LISTED_ANIMALS)
+        .collect();
+    search.shown += rest.len();
+    data["data"] = json!(rest);
+    data["page"] = json!(search.page);
+    LAST_SEARCHES.insert(key, search).await;
+    Ok(data)
+}
+
+async fn fetch_page(settings: &Settings, search: &LastSearch) -> Result<Value, AppError> {
+    let args = ToolArgs {
+        page: Some(search.page),
+        ..search.args.clone()
+    };
+    let mut data = search_animals(settings, args).await?;
+    if let Some(preferences) = &search.preferences {
+        let miles = search.args.miles.unwrap_or(settings.default_miles);
+        rank(&mut data, preferences, miles);
+    }
+    Ok(data)
+}
+
+fn take_animals(data: &mut Value) -> Vec<Value> {
+    match data["data"].take() {
+        Value::Array(animals) => animals,
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,180 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// A log file that can be reopened in place, so external tools like
+/// `logrotate` can move the file away and signal the server (SIGHUP) to
+/// continue writing to a fresh file at the original path.
+#[derive(Clone, Debug)]
+pub struct LogFile {
+    path: PathBuf,
+    file: Arc<Mutex<File>>,
+}
+
+impl LogFile {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = open_append(&path)?;
+        Ok(Self {
+            path,
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+
+    pub fn reopen(&self) -> io::Result<()> {
+        let file = open_append(&self.path)?;
+        let mut guard = self
+            .file
+            .lock()
+            .map_err(|_| io::Error::other("log file lock poisoned"))?;
+        *guard = file;
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+pub struct LogFileWriter(Arc<Mutex<File>>);
+
+impl Write for LogFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .map_err(|_| io::Error::other("log file lock poisoned"))?
+            .write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0
+            .lock()
+            .map_err(|_| io::Error::other("log file lock poisoned"))?
+            .flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogFile {
+    type Writer = LogFileWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LogFileWriter(self.file.clone())
+    }
+}
+
+/// Reopens `log` every time the process receives SIGHUP.
+#[cfg(unix)]
+pub fn spawn_reopen_on_sighup(log: LogFile) -> io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            match log.reopen() {
+                Ok(()) => tracing::info!("Reopened log file {}", log.path.display()),
+                Err(e) => eprintln!("Failed to reopen log file: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+pub fn write_pid_file(path: impl AsRef<Path>) -> io::Result<()> {
+    fs::write(path, format!("{}\n", std::process::id()))
+}
+
+pub fn remove_pid_file(path: impl AsRef<Path>) {
+    let _ = fs::remove_file(path);
+}
+
+/// Detaches the process from the controlling terminal using the classic
+/// double-fork. Must be called before the Tokio runtime (or any other
+/// thread) is started, since only the calling thread survives a fork.
+///
+/// The working directory is left unchanged so relative `--config`,
+/// `--pid-file` and `--log-file` paths keep resolving as the user expects.
+#[cfg(unix)]
+#[cfg_attr(test, allow(dead_code))]
+pub fn daemonize() -> io::Result<()> {
+    // SAFETY: the process is still single-threaded at this point, and only
+    // async-signal-safe libc calls are made between fork and continuing.
+    unsafe {
+        match libc::fork() {
+            -1 => return Err(io::Error::last_os_error()),
+            0 => {}
+            _ => libc::_exit(0),
+        }
+
+        if libc::setsid() == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        match libc::fork() {
+            -1 => return Err(io::Error::last_os_error()),
+            0 => {}
+            _ => libc::_exit(0),
+        }
+
+        let devnull = libc::open(c"/dev/null".as_ptr(), libc::O_RDWR);
+        if devnull == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        for fd in [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+            if libc::dup2(devnull, fd) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        if devnull > libc::STDERR_FILENO {
+            libc::close(devnull);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+#[cfg_attr(test, allow(dead_code))]
+pub fn daemonize() -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "--daemonize is only supported on unix platforms",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_file_write_and_reopen() {
+        let dir = std::env::temp_dir().join("rescue-groups-mcp-log-test");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("server.log");
+        let rotated = dir.join("server.log.1");
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(&rotated);
+
+        let log = LogFile::open(&path).unwrap();
+        log.make_writer().write_all(b"first\n").unwrap();
+
+        // Simulate logrotate moving the file away
+        fs::rename(&path, &rotated).unwrap();
+        log.reopen().unwrap();
+        log.make_writer().write_all(b"second\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&rotated).unwrap(), "first\n");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_pid_file() {
+        let path = std::env::temp_dir().join("rescue-groups-mcp-test.pid");
+        write_pid_file(&path).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content.trim(), std::process::id().to_string());
+        remove_pid_file(&path);
+        assert!(!path.exists());
+    }
+}
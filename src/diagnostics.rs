@@ -0,0 +1,80 @@
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+use tracing::info;
+
+/// How often a server built with the `console` feature logs runtime stats.
+pub const STATS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A snapshot of the runtime's scheduler: worker and task counts, queue
+/// depths and per-worker busy time. Builds with `--cfg tokio_unstable` add
+/// spawn, blocking-pool and poll-time figures.
+pub fn runtime_stats(handle: &Handle) -> Value {
+    let metrics = handle.metrics();
+    let workers = metrics.num_workers();
+    let busy_ms: Vec<u128> = (0..workers)
+        .map(|worker| metrics.worker_total_busy_duration(worker).as_millis())
+        .collect();
+    #[allow(unused_mut)]
+    let mut stats = json!({
+        "workers": workers,
+        "alive_tasks": metrics.num_alive_tasks(),
+        "global_queue_depth": metrics.global_queue_depth(),
+        "worker_busy_ms": busy_ms,
+        "worker_parks": (0..workers).map(|w| metrics.worker_park_count(w)).collect::<Vec<_>>(),
+    });
+    #[cfg(tokio_unstable)]
+    {
+        stats["spawned_tasks"] = json!(metrics.spawned_tasks_count());
+        stats["blocking_threads"] = json!(metrics.num_blocking_threads());
+        stats["blocking_queue_depth"] = json!(metrics.blocking_queue_depth());
+        stats["worker_polls"] = json!((0..workers)
+            .map(|w| metrics.worker_poll_count(w))
+            .collect::<Vec<_>>());
+        stats["worker_mean_poll_us"] = json!((0..workers)
+            .map(|w| metrics.worker_mean_poll_time(w).as_micros())
+            .collect::<Vec<_>>());
+        stats["worker_local_queue_depth"] = json!((0..workers)
+            .map(|w| metrics.worker_local_queue_depth(w))
+            .collect::<Vec<_>>());
+    }
+    stats
+}
+
+/// Logs [`runtime_stats`] every `interval`, so a task count that only grows
+/// or a queue that never drains shows up in a long-running server's log.
+pub fn spawn_runtime_stats(interval: Duration) -> JoinHandle<()> {
+    let handle = Handle::current();
+    info!("Runtime stats logged every {} seconds", interval.as_secs());
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // The first tick completes immediately, before anything is running
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            info!(target: "rescue_groups_mcp::runtime", stats = %runtime_stats(&handle), "Runtime stats");
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_runtime_stats() {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            let _ = rx.await;
+        });
+
+        let stats = runtime_stats(&Handle::current());
+        assert_eq!(stats["workers"], 2);
+        assert!(stats["alive_tasks"].as_u64().unwrap() >= 1);
+        assert_eq!(stats["worker_busy_ms"].as_array().unwrap().len(), 2);
+
+        tx.send(()).unwrap();
+        task.await.unwrap();
+    }
+}
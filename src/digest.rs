@@ -0,0 +1,260 @@
+use crate::alerts::{now_secs, summarize_animal};
+use crate::cli::ToolArgs;
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::source::search_animals;
+use crate::store;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use tracing::warn;
+
+/// Results of the previous digest run, per search.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct DigestSnapshot {
+    /// Unix timestamp of the run that took the snapshot
+    pub taken_at: Option<u64>,
+    #[serde(default)]
+    pub searches: HashMap<String, Vec<Value>>,
+}
+
+fn snapshot_path(settings: &Settings) -> PathBuf {
+    settings.data_dir.join("digest.json")
+}
+
+/// The preset names the digest runs: `[digest] searches`, or every preset.
+fn digest_searches(settings: &Settings) -> Result<Vec<String>, AppError> {
+    let mut names = if settings.digest.searches.is_empty() {
+        settings.presets.keys().cloned().collect()
+    } else {
+        settings.digest.searches.clone()
+    };
+    if names.is_empty() {
+        return Err(AppError::ConfigError(
+            "No digest searches configured. Define [presets.<name>] or [digest] searches"
+                .to_string(),
+        ));
+    }
+    if settings.digest.searches.is_empty() {
+        names.sort();
+    }
+    Ok(names)
+}
+
+/// Runs the digest searches, diffs them against the previous run's snapshot
+/// and stores the new snapshot. A search seen for the first time only
+/// records a baseline, so the first report isn't the whole inventory.
+/// Animals are only reported as removed when the listing came back whole.
+pub async fn run_digest(settings: &Settings) -> Result<Value, AppError> {
+    let settings = &settings.background();
+    // Search before taking the store lock, so tool calls don't wait on the API
+    let mut results = Vec::new();
+    for name in digest_searches(settings)? {
+        let args = ToolArgs {
+            preset: Some(name.clone()),
+            ..Default::default()
+        };
+        let result = search_animals(settings, args).await;
+        if let Err(e) = &result {
+            warn!("Digest search '{}' failed: {}", name, e);
+        }
+        results.push((name, result));
+    }
+
+    store::update(&snapshot_path(settings), |snapshot: &mut DigestSnapshot| {
+        let since = snapshot.taken_at;
+        let mut previous = std::mem::take(&mut snapshot.searches);
+        let mut report = Vec::new();
+
+        for (name, result) in results {
+            let old = previous.remove(&name);
+            let data = match result {
+                Ok(data) => data,
+                Err(e) => {
+                    // Keep the old snapshot so the next run diffs against it
+                    if let Some(old) = old {
+                        snapshot.searches.insert(name.clone(), old);
+                    }
+                    report.push(json!({ "name": name, "error": e.to_string() }));
+                    continue;
+                }
+            };
+            let mut current: Vec<Value> = data["data"]
+                .as_array()
+                .map(|animals| animals.iter().map(summarize_animal).collect())
+                .unwrap_or_default();
+            // Only a complete listing confirms that a missing animal is gone
+            let complete = !data["truncated"].as_bool().unwrap_or(false);
+            let total = data["total"].as_u64().unwrap_or(current.len() as u64);
+
+            let baseline = old.is_none();
+            let (new, removed) = match old {
+                Some(old) => {
+                    let old_ids: HashSet<&Value> = old.iter().map(|a| &a["id"]).collect();
+                    let new: Vec<Value> = current
+                        .iter()
+                        .filter(|a| !old_ids.contains(&a["id"]))
+                        .cloned()
+                        .collect();
+                    let current_ids: HashSet<&Value> = current.iter().map(|a| &a["id"]).collect();
+                    let missing: Vec<Value> = old
+                        .iter()
+                        .filter(|a| !current_ids.contains(&a["id"]))
+                        .cloned()
+                        .collect();
+                    if complete {
+                        (new, missing)
+                    } else {
+                        // Possibly just past the page that was fetched; kept
+                        // so they aren't reported as new when they come back
+                        current.extend(missing);
+                        (new, Vec::new())
+                    }
+                }
+                None => (Vec::new(), Vec::new()),
+            };
+
+            report.push(json!({
+                "name": name,
+                "baseline": baseline,
+                "total": total,
+                "new": new,
+                "removed": removed,
+            }));
+            snapshot.searches.insert(name, current);
+        }
+
+        snapshot.taken_at = Some(now_secs());
+        Ok(json!({
+            "since": since,
+            "generated_at": snapshot.taken_at,
+            "searches": report,
+        }))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SearchPreset;
+
+    use moka::future::Cache;
+
+    use std::sync::Arc;
+
+    fn get_test_settings(url: String) -> Settings {
+        let data_dir = std::env::temp_dir().join("rescue-groups-mcp-digest");
+        let _ = std::fs::remove_dir_all(&data_dir);
+        let mut presets = HashMap::new();
+        presets.insert(
+            "cats".to_string(),
+            SearchPreset {
+                args: ToolArgs {
+                    species: Some("cats".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        Settings {
+            presets,
+            // No caching, so each run sees the current mock
+            cache: Arc::new(Cache::new(0)),
+            ..Settings::for_test(url, data_dir)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_digest_diffs_snapshots() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let first = server
+            .mock("POST", "/public/animals/search/available/cats/haspic")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Old"}}, {"id": "2", "attributes": {"name": "Adopted"}}]}"#)
+            .create_async()
+            .await;
+
+        let report = run_digest(&settings).await.unwrap();
+        assert_eq!(report["since"], Value::Null);
+        assert_eq!(report["searches"][0]["baseline"], true);
+        assert_eq!(report["searches"][0]["new"], json!([]));
+        first.remove_async().await;
+
+        let _second = server
+            .mock("POST", "/public/animals/search/available/cats/haspic")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Old"}}, {"id": "3", "attributes": {"name": "Fresh"}}]}"#)
+            .create_async()
+            .await;
+
+        let report = run_digest(&settings).await.unwrap();
+        let search = &report["searches"][0];
+        assert!(report["since"].is_u64());
+        assert_eq!(search["name"], "cats");
+        assert_eq!(search["baseline"], false);
+        assert_eq!(search["total"], 2);
+        assert_eq!(search["new"][0]["name"], "Fresh");
+        assert_eq!(search["removed"][0]["name"], "Adopted");
+
+        std::fs::remove_dir_all(&settings.data_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_digest_only_reports_confirmed_removals() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings(server.url());
+        settings.data_dir = std::env::temp_dir().join("rescue-groups-mcp-digest-partial");
+        let _ = std::fs::remove_dir_all(&settings.data_dir);
+        let path = "/public/animals/search/available/cats/haspic";
+
+        let first = server
+            .mock("POST", path)
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Old"}}, {"id": "2", "attributes": {"name": "Moved"}}]}"#)
+            .create_async()
+            .await;
+        run_digest(&settings).await.unwrap();
+        first.remove_async().await;
+
+        // One page of a longer listing: "Moved" may just be on a later page
+        let partial = server
+            .mock("POST", path)
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Old"}}], "meta": {"count": 30, "pages": 2}}"#)
+            .create_async()
+            .await;
+        let report = run_digest(&settings).await.unwrap();
+        let search = &report["searches"][0];
+        assert_eq!(search["total"], 30);
+        assert_eq!(search["removed"], json!([]));
+        partial.remove_async().await;
+
+        // Back on the first page, it isn't new either
+        let _complete = server
+            .mock("POST", path)
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "2", "attributes": {"name": "Moved"}}]}"#)
+            .create_async()
+            .await;
+        let report = run_digest(&settings).await.unwrap();
+        let search = &report["searches"][0];
+        assert_eq!(search["new"], json!([]));
+        assert_eq!(search["removed"][0]["name"], "Old");
+
+        std::fs::remove_dir_all(&settings.data_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_digest_requires_searches() {
+        let mut settings = get_test_settings("http://unused".to_string());
+        settings.presets.clear();
+        assert!(matches!(
+            run_digest(&settings).await,
+            Err(AppError::ConfigError(_))
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::{LazyLock, Mutex};
+use tracing::{debug, warn};
+
+/// Attributes the formatters rely on, by resource type. If upstream stops
+/// sending one, output quietly degrades to "Unknown", so missing ones are
+/// counted instead.
+const EXPECTED: &[(&str, &[&str])] =
+    &[("animals", &["name", "breedString", "orgsAnimalsPictures"])];
+
+#[derive(Default, Debug)]
+struct Drift {
+    /// Resources inspected, by type
+    checked: BTreeMap<&'static str, u64>,
+    /// Resources lacking an attribute, by type and attribute
+    missing: BTreeMap<&'static str, BTreeMap<&'static str, u64>>,
+}
+
+static DRIFT: LazyLock<Mutex<Drift>> = LazyLock::new(Mutex::default);
+
+/// Counts expected attributes missing from the resources in an upstream
+/// response, logging a warning the first time each one goes missing.
+pub fn inspect(response: &Value) {
+    inspect_into(&mut DRIFT.lock().unwrap(), response);
+}
+
+fn inspect_into(drift: &mut Drift, response: &Value) {
+    let resources = match &response["data"] {
+        Value::Array(items) => items.iter().collect(),
+        item @ Value::Object(_) => vec![item],
+        _ => Vec::new(),
+    };
+    let included = response["included"].as_array().into_iter().flatten();
+
+    for resource in resources.into_iter().chain(included) {
+        let Some((kind, attributes)) = EXPECTED.iter().find(|(kind, _)| resource["type"] == *kind)
+        else {
+            continue;
+        };
+        *drift.checked.entry(kind).or_default() += 1;
+        for attribute in attributes.iter() {
+            if !resource["attributes"][attribute].is_null() {
+                continue;
+            }
+            let count = drift
+                .missing
+                .entry(kind)
+                .or_default()
+                .entry(attribute)
+                .or_default();
+            *count += 1;
+            if *count == 1 {
+                warn!(
+                    "Upstream {} are missing '{}' (resource {}); the API may have changed",
+                    kind, attribute, resource["id"]
+                );
+            } else {
+                debug!(
+                    "Upstream {} {} is missing '{}'",
+                    kind, resource["id"], attribute
+                );
+            }
+        }
+    }
+}
+
+/// How many resources of each type were inspected and how often each
+/// expected attribute was missing, since the server started.
+pub fn report() -> Value {
+    report_from(&DRIFT.lock().unwrap())
+}
+
+fn report_from(drift: &Drift) -> Value {
+    drift
+        .checked
+        .iter()
+        .map(|(kind, checked)| {
+            let missing = drift.missing.get(kind).cloned().unwrap_or_default();
+            (
+                kind.to_string(),
+                json!({ "checked": checked, "missing": missing }),
+            )
+        })
+        .collect::<serde_json::Map<String, Value>>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect_counts_missing_attributes() {
+        let mut drift = Drift::default();
+        inspect_into(
+            &mut drift,
+            &json!({
+                "data": [
+                    {"type": "animals", "id": "1", "attributes": {"name": "Rex", "breedString": "Boxer", "orgsAnimalsPictures": []}},
+                    {"type": "animals", "id": "2", "attributes": {"animalName": "Biscuit"}}
+                ],
+                "included": [{"type": "orgs", "id": "9", "attributes": {}}]
+            }),
+        );
+        inspect_into(
+            &mut drift,
+            &json!({"data": {"type": "animals", "id": "3", "attributes": {"name": "Zoom"}}}),
+        );
+
+        let report = report_from(&drift);
+        assert_eq!(report["animals"]["checked"], 3);
+        assert_eq!(report["animals"]["missing"]["name"], 1);
+        assert_eq!(report["animals"]["missing"]["breedString"], 2);
+        assert_eq!(report["animals"]["missing"]["orgsAnimalsPictures"], 2);
+        assert!(report.get("orgs").is_none());
+    }
+}
@@ -7,12 +7,33 @@ pub enum AppError {
     #[error("API Error: {0}")]
     ApiError(String),
 
+    /// An upstream API answered with an error `status`
+    #[error("API Error: {message}")]
+    Upstream { status: u16, message: String },
+
+    /// Too many requests waiting for the rate limiter
+    #[error("API Error: {0}")]
+    RateLimited(String),
+
     #[error("Configuration Error: {0}")]
     ConfigError(String),
 
+    #[error("Validation Error: {message}")]
+    ValidationError {
+        /// The argument at fault, when the error is about one
+        parameter: Option<String>,
+        message: String,
+    },
+
     #[error("Resource Not Found")]
     NotFound,
 
+    #[error("Deadline Exceeded: the time allowed for this call ran out")]
+    DeadlineExceeded,
+
+    #[error("Dry run: the request was not sent")]
+    DryRun,
+
     #[error("Internal Error: {0}")]
     Internal(String),
 
@@ -45,13 +66,116 @@ impl From<&str> for AppError {
     }
 }
 
+/// Seconds to wait before retrying a rate-limited call; neither the API nor
+/// the queue says how long.
+pub const RATE_LIMIT_RETRY_SECONDS: u64 = 10;
+
 impl AppError {
+    /// A validation error about the argument `parameter`.
+    pub fn invalid_argument(parameter: &str, message: impl Into<String>) -> Self {
+        AppError::ValidationError {
+            parameter: Some(parameter.to_string()),
+            message: message.into(),
+        }
+    }
+
+    /// A validation error not about any one argument.
+    pub fn validation(message: impl Into<String>) -> Self {
+        AppError::ValidationError {
+            parameter: None,
+            message: message.into(),
+        }
+    }
+
+    /// A stable name for the kind of error, for scripts, agents and usage
+    /// stats.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            _ if self.is_rate_limited() => "rate_limited",
+            AppError::ApiError(_) => "api",
+            AppError::Upstream { status, .. } => match status {
+                400..=499 => "upstream_4xx",
+                500..=599 => "upstream_5xx",
+                _ => "api",
+            },
+            AppError::RateLimited(_) => "rate_limited",
+            AppError::ConfigError(_) => "config",
+            AppError::ValidationError { .. } => "validation",
+            AppError::NotFound => "not_found",
+            AppError::DeadlineExceeded => "deadline",
+            AppError::DryRun => "dry_run",
+            AppError::Internal(_) => "internal",
+            AppError::Io(_) => "io",
+            AppError::Network(_) => "network",
+            AppError::Serialization(_) => "validation",
+            AppError::Toml(_) | AppError::Yaml(_) => "config",
+        }
+    }
+
+    /// The HTTP status an upstream API answered with.
+    pub fn upstream_status(&self) -> Option<u16> {
+        match self {
+            AppError::Upstream { status, .. } => Some(*status),
+            AppError::Network(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Whether this server or the API turned the request away for going
+    /// over a rate limit.
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, AppError::RateLimited(_)) || self.upstream_status() == Some(429)
+    }
+
+    /// Whether the same call may succeed if made again later.
+    pub fn retryable(&self) -> bool {
+        match self {
+            AppError::Upstream { status, .. } => self.is_rate_limited() || *status >= 500,
+            AppError::RateLimited(_) | AppError::Network(_) | AppError::DeadlineExceeded => true,
+            _ => false,
+        }
+    }
+
+    /// The argument a validation error is about.
+    pub fn parameter(&self) -> Option<&str> {
+        match self {
+            AppError::ValidationError { parameter, .. } => parameter.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// What agents can branch on instead of the message: the error's
+    /// `kind`, whether to `retry`, and where known the argument at fault,
+    /// the upstream HTTP status and how long to wait.
+    pub fn details(&self) -> Value {
+        let mut data = json!({
+            "kind": self.kind(),
+            "retryable": self.retryable(),
+        });
+        if let Some(parameter) = self.parameter() {
+            data["parameter"] = json!(parameter);
+        }
+        if let Some(status) = self.upstream_status() {
+            data["status"] = json!(status);
+        }
+        if self.is_rate_limited() {
+            data["retry_after_seconds"] = json!(RATE_LIMIT_RETRY_SECONDS);
+        }
+        data
+    }
+
     pub fn to_json_rpc_error(&self) -> Value {
         let (code, message) = match self {
+            AppError::ValidationError { .. } => (-32602, self.to_string()),
             AppError::NotFound => (-32004, self.to_string()),
-            AppError::ApiError(_) | AppError::Network(_) => (-32005, self.to_string()),
+            AppError::ApiError(_)
+            | AppError::Upstream { .. }
+            | AppError::RateLimited(_)
+            | AppError::Network(_)
+            | AppError::DeadlineExceeded => (-32005, self.to_string()),
             AppError::ConfigError(_) => (-32603, self.to_string()),
             AppError::Internal(_)
+            | AppError::DryRun
             | AppError::Io(_)
             | AppError::Serialization(_)
             | AppError::Toml(_)
@@ -60,7 +184,8 @@ impl AppError {
 
         json!({
             "code": code,
-            "message": message
+            "message": message,
+            "data": self.details()
         })
     }
 }
@@ -76,6 +201,10 @@ mod tests {
             AppError::ApiError("test".to_string()).to_string(),
             "API Error: test"
         );
+        assert_eq!(
+            AppError::validation("test").to_string(),
+            "Validation Error: test"
+        );
         assert_eq!(
             AppError::ConfigError("test".to_string()).to_string(),
             "Configuration Error: test"
@@ -122,6 +251,10 @@ mod tests {
         let json = e.to_json_rpc_error();
         assert_eq!(json["code"], -32005);
 
+        let e = AppError::validation("test");
+        let json = e.to_json_rpc_error();
+        assert_eq!(json["code"], -32602);
+
         let e = AppError::ConfigError("test".to_string());
         let json = e.to_json_rpc_error();
         assert_eq!(json["code"], -32603);
@@ -129,5 +262,73 @@ mod tests {
         let e = AppError::Internal("test".to_string());
         let json = e.to_json_rpc_error();
         assert_eq!(json["code"], -32603);
+        assert_eq!(
+            json["data"],
+            json!({ "kind": "internal", "retryable": false })
+        );
+    }
+
+    #[test]
+    fn test_details() {
+        let e = AppError::Upstream {
+            status: 400,
+            message: "API Error: 400 Bad Request: Invalid filter field: animals.colour".to_string(),
+        };
+        assert_eq!(
+            e.details(),
+            json!({ "kind": "upstream_4xx", "retryable": false, "status": 400 })
+        );
+
+        let e = AppError::Upstream {
+            status: 503,
+            message: "API Error: 503 Service Unavailable".to_string(),
+        };
+        assert_eq!(
+            e.details(),
+            json!({ "kind": "upstream_5xx", "retryable": true, "status": 503 })
+        );
+
+        let e = AppError::Upstream {
+            status: 429,
+            message: "Petfinder API Error: 429 Too Many Requests".to_string(),
+        };
+        assert_eq!(e.details()["kind"], "rate_limited");
+        assert_eq!(e.details()["retryable"], true);
+        assert_eq!(e.details()["retry_after_seconds"], RATE_LIMIT_RETRY_SECONDS);
+
+        let e = AppError::RateLimited("Rate limited, 3 requests queued".to_string());
+        assert_eq!(
+            e.details(),
+            json!({
+                "kind": "rate_limited",
+                "retryable": true,
+                "retry_after_seconds": RATE_LIMIT_RETRY_SECONDS
+            })
+        );
+
+        // Whatever the message says, a plain API error carries no status
+        let e = AppError::ApiError("API Error: 503 Service Unavailable".to_string());
+        assert_eq!(e.details(), json!({ "kind": "api", "retryable": false }));
+
+        assert_eq!(
+            AppError::NotFound.details(),
+            json!({ "kind": "not_found", "retryable": false })
+        );
+
+        let e = AppError::invalid_argument("miles", "miles 0 is out of range: use 1 to 500");
+        assert_eq!(
+            e.details(),
+            json!({ "kind": "validation", "retryable": false, "parameter": "miles" })
+        );
+        assert_eq!(
+            e.to_string(),
+            "Validation Error: miles 0 is out of range: use 1 to 500"
+        );
+        let e = AppError::validation("No search to continue: run search_adoptable_pets first");
+        assert_eq!(e.parameter(), None);
+        assert_eq!(
+            e.details(),
+            json!({ "kind": "validation", "retryable": false })
+        );
     }
 }
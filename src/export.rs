@@ -0,0 +1,832 @@
+use crate::cli::{AnimalIdArgs, ExportResultsArgs};
+use crate::client::{fan_out, list_org_inventory};
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::favorites::list_favorites;
+use crate::feeds::rfc3339;
+use crate::fmt::{
+    extract_single_item, format_animal_results, format_shortlist, render_time, SHORTLIST_FIELDS,
+};
+use crate::notes::get_notes;
+use crate::source::{get_contact, search_animals};
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::{Seek, Write};
+use std::path::{Component, Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+/// File formats of the `export_results` tool.
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultsFormat {
+    Csv,
+    Json,
+    #[value(alias = "md")]
+    #[serde(alias = "md")]
+    Markdown,
+}
+
+impl ResultsFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ResultsFormat::Csv => "csv",
+            ResultsFormat::Json => "json",
+            ResultsFormat::Markdown => "md",
+        }
+    }
+
+    fn from_path(path: &Path) -> Option<ResultsFormat> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "csv" => Some(ResultsFormat::Csv),
+            "json" => Some(ResultsFormat::Json),
+            "md" | "markdown" => Some(ResultsFormat::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// Spreadsheet columns: header and the animal attribute it comes from.
+/// "Status" and "Species" are resolved from `included`, "Photos" joins
+/// every photo URL.
+const COLUMNS: &[(&str, &str)] = &[
+    ("ID", "id"),
+    ("Name", "name"),
+    ("Status", "status"),
+    ("Species", "species"),
+    ("Breed", "breedString"),
+    ("Sex", "sex"),
+    ("Age", "ageGroup"),
+    ("Birth Date", "birthDate"),
+    ("Size", "sizeGroup"),
+    ("Color", "colorDetails"),
+    ("Rescue ID", "rescueId"),
+    ("Intake Date", "createdDate"),
+    ("Available Date", "availableDate"),
+    ("Adopted Date", "adoptedDate"),
+    ("Updated Date", "updatedDate"),
+    ("Special Needs", "isSpecialNeeds"),
+    ("URL", "url"),
+    ("Photos", "photos"),
+];
+
+/// Name of the `included` item an animal relates to as `kind`.
+fn related_name(animal: &Value, included: &[Value], kind: &str) -> String {
+    let id = &animal["relationships"][kind]["data"][0]["id"];
+    included
+        .iter()
+        .find(|item| item["type"] == kind && item["id"] == *id)
+        .and_then(|item| {
+            let attrs = &item["attributes"];
+            attrs["name"].as_str().or(attrs["singular"].as_str())
+        })
+        .unwrap_or("")
+        .to_string()
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The inventory as spreadsheet rows, one per animal.
+pub fn inventory_rows(data: &Value) -> Vec<Vec<String>> {
+    let included = data["included"].as_array().cloned().unwrap_or_default();
+    data["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|animal| {
+            let attrs = &animal["attributes"];
+            COLUMNS
+                .iter()
+                .map(|(_, field)| match *field {
+                    "id" => cell(&animal["id"]),
+                    "status" => related_name(animal, &included, "statuses"),
+                    "species" => related_name(animal, &included, "species"),
+                    "photos" => attrs["orgsAnimalsPictures"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|p| p["urlSecureFullsize"].as_str())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    field => cell(&attrs[field]),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn headers() -> Vec<&'static str> {
+    COLUMNS.iter().map(|(header, _)| *header).collect()
+}
+
+pub fn write_csv<W: Write>(writer: W, rows: &[Vec<String>]) -> Result<(), AppError> {
+    write_table(writer, &headers(), rows)
+}
+
+/// Writes `rows` as CSV under a header row.
+fn write_table<W: Write>(
+    writer: W,
+    headers: &[&str],
+    rows: &[Vec<String>],
+) -> Result<(), AppError> {
+    let mut csv = csv::Writer::from_writer(writer);
+    let to_error = |e: csv::Error| AppError::Internal(format!("Failed to write CSV: {}", e));
+    csv.write_record(headers).map_err(to_error)?;
+    for row in rows {
+        csv.write_record(row).map_err(to_error)?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+/// Spreadsheet column name for a zero-based index (0 → A, 26 → AA).
+fn column_name(mut index: usize) -> String {
+    let mut name = String::new();
+    loop {
+        name.insert(0, (b'A' + (index % 26) as u8) as char);
+        if index < 26 {
+            return name;
+        }
+        index = index / 26 - 1;
+    }
+}
+
+fn xml_text(text: &str) -> String {
+    // Control characters other than tab and newlines are invalid in XML
+    let text: String = text
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn sheet_xml(rows: &[Vec<String>]) -> String {
+    let header: Vec<String> = headers().iter().map(|h| h.to_string()).collect();
+    let body: String = std::iter::once(&header)
+        .chain(rows)
+        .enumerate()
+        .map(|(r, row)| {
+            let cells: String = row
+                .iter()
+                .enumerate()
+                .map(|(c, value)| {
+                    format!(
+                        "<c r=\"{}{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+                        column_name(c),
+                        r + 1,
+                        xml_text(value)
+                    )
+                })
+                .collect();
+            format!("<row r=\"{}\">{}</row>", r + 1, cells)
+        })
+        .collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\"><sheetData>{}</sheetData></worksheet>",
+        body
+    )
+}
+
+const XLSX_PARTS: &[(&str, &str)] = &[
+    (
+        "[Content_Types].xml",
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\"><Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/><Default Extension=\"xml\" ContentType=\"application/xml\"/><Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/><Override PartName=\"/xl/worksheets/sheet1.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/></Types>",
+    ),
+    (
+        "_rels/.rels",
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\"><Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/></Relationships>",
+    ),
+    (
+        "xl/workbook.xml",
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\"><sheets><sheet name=\"Animals\" sheetId=\"1\" r:id=\"rId1\"/></sheets></workbook>",
+    ),
+    (
+        "xl/_rels/workbook.xml.rels",
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\"><Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet1.xml\"/></Relationships>",
+    ),
+];
+
+/// Writes a single-sheet Excel workbook. Every cell is an inline string,
+/// which keeps IDs and dates exactly as the API sent them.
+pub fn write_xlsx<W: Write + Seek>(writer: W, rows: &[Vec<String>]) -> Result<(), AppError> {
+    let to_error = |e: zip::result::ZipError| {
+        AppError::Internal(format!("Failed to write spreadsheet: {}", e))
+    };
+    let mut zip = ZipWriter::new(writer);
+    let options = SimpleFileOptions::default();
+    for (name, content) in XLSX_PARTS {
+        zip.start_file(*name, options).map_err(to_error)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.start_file("xl/worksheets/sheet1.xml", options)
+        .map_err(to_error)?;
+    zip.write_all(sheet_xml(rows).as_bytes())?;
+    zip.finish().map_err(to_error)?;
+    Ok(())
+}
+
+/// Dumps every animal an organization has published to `output`: a flat
+/// spreadsheet (CSV/XLSX) or, as JSON, the complete API records.
+pub async fn export_org(
+    settings: &Settings,
+    org_id: &str,
+    format: ExportFormat,
+    output: &Path,
+) -> Result<Value, AppError> {
+    let data = list_org_inventory(settings, org_id).await?;
+    let count = data["data"].as_array().map_or(0, |a| a.len());
+
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = File::create(output)?;
+    match format {
+        ExportFormat::Csv => write_csv(file, &inventory_rows(&data))?,
+        ExportFormat::Xlsx => write_xlsx(file, &inventory_rows(&data))?,
+        ExportFormat::Json => {
+            let dump = json!({
+                "org_id": org_id,
+                "exported_at": rfc3339(render_time()),
+                "animals": data["data"],
+                "included": data["included"],
+            });
+            serde_json::to_writer_pretty(file, &dump)?;
+        }
+    }
+
+    Ok(json!({
+        "org_id": org_id,
+        "format": format.extension(),
+        "path": output.display().to_string(),
+        "animals": count,
+    }))
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Resolves `path` (symlinks included) and checks that it lies inside one of
+/// the configured `[export]` directories. The file itself may not be a
+/// symlink, which could point anywhere once written through.
+fn resolve_export_path(settings: &Settings, path: &str) -> Result<PathBuf, AppError> {
+    if settings.export.allowed_dirs.is_empty() {
+        return Err(AppError::ConfigError(
+            "Exporting is disabled; list the directories it may write to under [export] allowed_dirs"
+                .to_string(),
+        ));
+    }
+    let path = expand_home(Path::new(path.trim()));
+    if !path.is_absolute() {
+        return Err(AppError::invalid_argument(
+            "path",
+            format!("Export path '{}' must be absolute", path.display()),
+        ));
+    }
+    if path.components().any(|c| c == Component::ParentDir) {
+        return Err(AppError::invalid_argument(
+            "path",
+            format!("Export path '{}' must not contain '..'", path.display()),
+        ));
+    }
+
+    if path
+        .symlink_metadata()
+        .is_ok_and(|meta| meta.file_type().is_symlink())
+    {
+        return Err(AppError::invalid_argument(
+            "path",
+            format!("Export path '{}' is a symbolic link", path.display()),
+        ));
+    }
+
+    // Canonicalize the deepest part that exists; the rest is created later
+    let mut existing = path.as_path();
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        missing.extend(existing.file_name());
+        existing = existing.parent().unwrap_or(Path::new("/"));
+    }
+    let mut resolved = existing.canonicalize()?;
+    resolved.extend(missing.into_iter().rev());
+
+    let allowed: Vec<PathBuf> = settings
+        .export
+        .allowed_dirs
+        .iter()
+        .filter_map(|dir| expand_home(dir).canonicalize().ok())
+        .collect();
+    if allowed
+        .iter()
+        .any(|dir| resolved.starts_with(dir) && resolved != *dir)
+    {
+        Ok(resolved)
+    } else {
+        Err(AppError::invalid_argument(
+            "path",
+            format!(
+                "'{}' is outside the allowed export directories: {}",
+                path.display(),
+                settings
+                    .export
+                    .allowed_dirs
+                    .iter()
+                    .map(|d| d.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ))
+    }
+}
+
+/// Creates or truncates a checked export file, refusing (on unix) to follow
+/// a symlink put in its place since it was checked.
+fn create_export_file(path: &Path) -> std::io::Result<File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.custom_flags(libc::O_NOFOLLOW);
+    }
+    options.open(path)
+}
+
+/// Runs a search and writes the results to a file: a flat spreadsheet
+/// (CSV), the complete API records (JSON) or the usual result list
+/// (Markdown).
+pub async fn export_results(
+    settings: &Settings,
+    args: ExportResultsArgs,
+) -> Result<Value, AppError> {
+    let output = resolve_export_path(settings, &args.path)?;
+    let format = args
+        .format
+        .or_else(|| ResultsFormat::from_path(&output))
+        .unwrap_or(ResultsFormat::Csv);
+    let data = search_animals(settings, args.search.clone()).await?;
+    let count = data["data"].as_array().map_or(0, |a| a.len());
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = create_export_file(&output)?;
+    match format {
+        ResultsFormat::Csv => write_csv(file, &inventory_rows(&data))?,
+        ResultsFormat::Markdown => file.write_all(format_animal_results(&data)?.as_bytes())?,
+        ResultsFormat::Json => {
+            let dump = json!({
+                "search": args.search,
+                "exported_at": rfc3339(render_time()),
+                "animals": data["data"],
+                "included": data["included"],
+            });
+            serde_json::to_writer_pretty(file, &dump)?;
+        }
+    }
+
+    Ok(json!({
+        "format": format.extension(),
+        "path": output.display().to_string(),
+        "animals": count,
+    }))
+}
+
+/// Each favorite with what a shelter visit needs: its details and first
+/// photo, its organization's contact and the user's notes. Favorites whose
+/// listing is gone are kept, marked unavailable, with what was saved.
+pub async fn shortlist(settings: &Settings) -> Result<Value, AppError> {
+    let favorites = list_favorites(settings).await?;
+    let details = fan_out(
+        settings,
+        favorites.iter().map(|favorite| {
+            get_contact(
+                settings,
+                AnimalIdArgs {
+                    animal_id: favorite.id.clone(),
+                },
+            )
+        }),
+    )
+    .await;
+
+    let mut animals = Vec::new();
+    for (favorite, details) in favorites.iter().zip(details) {
+        let details = match details {
+            Ok(details) => Some(details),
+            Err(AppError::NotFound) => None,
+            Err(e) => return Err(e),
+        };
+        let animal = details
+            .as_ref()
+            .and_then(|d| d.get("data"))
+            .and_then(extract_single_item);
+        let org = details.as_ref().and_then(|d| {
+            d["included"]
+                .as_array()?
+                .iter()
+                .find(|item| item["type"] == "orgs")
+        });
+        let attrs = animal.map(|a| &a["attributes"]);
+        animals.push(json!({
+            "id": favorite.id,
+            "name": favorite.name,
+            "url": favorite.url,
+            "available": animal.is_some(),
+            "note": favorite.note,
+            "photo": attrs
+                .and_then(|a| a["orgsAnimalsPictures"].as_array())
+                .and_then(|p| p.first())
+                .and_then(|p| p["urlSecureFullsize"].as_str()),
+            "attributes": attrs.cloned().unwrap_or_else(|| json!({ "breedString": favorite.breed })),
+            "org": org.map(|o| &o["attributes"]),
+            "notes": get_notes(settings, &favorite.id).await?,
+        }));
+    }
+    Ok(json!({ "exported_at": render_time(), "animals": animals }))
+}
+
+/// The shortlist as spreadsheet rows, one per favorite.
+fn shortlist_rows(data: &Value) -> Vec<Vec<String>> {
+    data["animals"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|animal| {
+            let org = &animal["org"];
+            let mut row = vec![cell(&animal["id"]), cell(&animal["name"])];
+            row.extend(
+                SHORTLIST_FIELDS
+                    .iter()
+                    .map(|(_, field)| cell(&animal["attributes"][field])),
+            );
+            let notes: Vec<&str> = animal["notes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|n| n["text"].as_str())
+                .collect();
+            row.extend([
+                if animal["available"] == true {
+                    "Yes"
+                } else {
+                    "No"
+                }
+                .to_string(),
+                cell(&org["name"]),
+                cell(&org["phone"]),
+                cell(&org["email"]),
+                [cell(&org["city"]), cell(&org["state"])]
+                    .into_iter()
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                cell(&animal["note"]),
+                notes.join(" | "),
+                cell(&animal["photo"]),
+                cell(&animal["url"]),
+            ]);
+            row
+        })
+        .collect()
+}
+
+/// Writes the favorites to `output` as a document to bring to a shelter
+/// visit or share: a Markdown page, a CSV spreadsheet or JSON.
+pub async fn export_favorites(
+    settings: &Settings,
+    format: ResultsFormat,
+    output: &Path,
+) -> Result<Value, AppError> {
+    let data = shortlist(settings).await?;
+    let count = data["animals"].as_array().map_or(0, |a| a.len());
+
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = File::create(output)?;
+    match format {
+        ResultsFormat::Markdown => file.write_all(format_shortlist(&data)?.as_bytes())?,
+        ResultsFormat::Csv => {
+            let mut headers = vec!["ID", "Name"];
+            headers.extend(SHORTLIST_FIELDS.iter().map(|(header, _)| *header));
+            headers.extend([
+                "Available",
+                "Organization",
+                "Phone",
+                "Email",
+                "Location",
+                "Note",
+                "Notes",
+                "Photo",
+                "URL",
+            ]);
+            write_table(file, &headers, &shortlist_rows(&data))?;
+        }
+        ResultsFormat::Json => serde_json::to_writer_pretty(file, &data)?,
+    }
+
+    Ok(json!({
+        "format": format.extension(),
+        "path": output.display().to_string(),
+        "animals": count,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::{Cursor, Read};
+
+    fn get_test_settings(url: String) -> Settings {
+        Settings::for_test(url, std::env::temp_dir().join("rescue-groups-mcp-test"))
+    }
+
+    fn inventory() -> Value {
+        json!({
+            "data": [{
+                "id": "1",
+                "attributes": {
+                    "name": "Ace & Co",
+                    "createdDate": "2024-01-02T03:04:05Z",
+                    "orgsAnimalsPictures": [{"urlSecureFullsize": "http://a.jpg"}, {"urlSecureFullsize": "http://b.jpg"}]
+                },
+                "relationships": {
+                    "statuses": {"data": [{"type": "statuses", "id": "1"}]},
+                    "species": {"data": [{"type": "species", "id": "8"}]}
+                }
+            }],
+            "included": [
+                {"type": "statuses", "id": "1", "attributes": {"name": "Adopted"}},
+                {"type": "species", "id": "8", "attributes": {"singular": "Dog"}}
+            ]
+        })
+    }
+
+    #[test]
+    fn test_column_name() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(27), "AB");
+    }
+
+    #[test]
+    fn test_inventory_rows_and_csv() {
+        let rows = inventory_rows(&inventory());
+        let row = &rows[0];
+        assert_eq!(row[1], "Ace & Co");
+        assert_eq!(row[2], "Adopted");
+        assert_eq!(row[3], "Dog");
+        assert_eq!(row[11], "2024-01-02T03:04:05Z");
+        assert_eq!(row.last().unwrap(), "http://a.jpg http://b.jpg");
+
+        let mut out = Vec::new();
+        write_csv(&mut out, &rows).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.starts_with("ID,Name,Status,Species,"));
+        assert!(csv.contains("1,Ace & Co,Adopted,Dog,"));
+    }
+
+    #[test]
+    fn test_write_xlsx() {
+        let mut out = Cursor::new(Vec::new());
+        write_xlsx(&mut out, &inventory_rows(&inventory())).unwrap();
+
+        let mut archive = zip::ZipArchive::new(out).unwrap();
+        assert!(archive.by_name("[Content_Types].xml").is_ok());
+        let mut sheet = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut sheet)
+            .unwrap();
+        assert!(sheet.contains("<c r=\"A1\" t=\"inlineStr\"><is><t xml:space=\"preserve\">ID</t>"));
+        assert!(sheet.contains(">Ace &amp; Co</t>"));
+        assert!(sheet.contains("<row r=\"2\">"));
+    }
+
+    #[tokio::test]
+    async fn test_export_org_paginates() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+        let _page1 = server
+            .mock(
+                "GET",
+                "/public/orgs/9/animals?include=statuses,species&limit=250&page=1",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "1", "attributes": {"name": "Ace"}}], "included": [{"type": "statuses", "id": "1", "attributes": {"name": "Available"}}], "meta": {"pages": 2}}"#,
+            )
+            .create_async()
+            .await;
+        let _page2 = server
+            .mock(
+                "GET",
+                "/public/orgs/9/animals?include=statuses,species&limit=250&page=2",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "2", "attributes": {"name": "Bo"}}], "included": [{"type": "statuses", "id": "1", "attributes": {"name": "Available"}}], "meta": {"pages": 2}}"#,
+            )
+            .create_async()
+            .await;
+
+        let output = std::env::temp_dir().join("rescue-groups-mcp-export/org-9.json");
+        let summary = export_org(&settings, "9", ExportFormat::Json, &output)
+            .await
+            .unwrap();
+        assert_eq!(summary["animals"], 2);
+
+        let dump: Value = serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(dump["animals"][1]["attributes"]["name"], "Bo");
+        assert_eq!(dump["included"].as_array().unwrap().len(), 1);
+        std::fs::remove_dir_all(output.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_export_results() {
+        let mut server = mockito::Server::new_async().await;
+        let _search = server
+            .mock("POST", mockito::Matcher::Regex("^/public/animals/search".to_string()))
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Old Blue", "ageGroup": "Senior"}}]}"#)
+            .create_async()
+            .await;
+
+        let dir = std::env::temp_dir().join("rescue-groups-mcp-export-results");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut settings = get_test_settings(server.url());
+        let args = |path: &Path| ExportResultsArgs {
+            path: path.display().to_string(),
+            format: None,
+            search: Default::default(),
+        };
+
+        // Disabled until directories are configured
+        assert!(matches!(
+            export_results(&settings, args(&dir.join("dogs.csv"))).await,
+            Err(AppError::ConfigError(_))
+        ));
+
+        settings.export.allowed_dirs = vec![dir.clone()];
+        let summary = export_results(&settings, args(&dir.join("senior/dogs.csv")))
+            .await
+            .unwrap();
+        assert_eq!(summary["format"], "csv");
+        assert_eq!(summary["animals"], 1);
+        let csv = std::fs::read_to_string(dir.join("senior/dogs.csv")).unwrap();
+        assert!(csv.starts_with("ID,Name,"));
+        assert!(csv.contains("Old Blue"));
+
+        let summary = export_results(&settings, args(&dir.join("dogs.md")))
+            .await
+            .unwrap();
+        assert_eq!(summary["format"], "md");
+
+        for outside in [
+            std::env::temp_dir().join("dogs.csv"),
+            dir.join("../dogs.csv"),
+            dir.clone(),
+        ] {
+            assert!(matches!(
+                export_results(&settings, args(&outside)).await,
+                Err(AppError::ValidationError { .. })
+            ));
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_export_path_rejects_symlinks() {
+        let dir = std::env::temp_dir().join("rescue-groups-mcp-export-symlink");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let outside = std::env::temp_dir().join("rescue-groups-mcp-export-outside.csv");
+        let _ = std::fs::remove_file(&outside);
+        let mut settings = get_test_settings("http://unused".to_string());
+        settings.export.allowed_dirs = vec![dir.clone()];
+
+        // A dangling link inside the allowed directory
+        let link = dir.join("dogs.csv");
+        std::os::unix::fs::symlink(&outside, &link).unwrap();
+        assert!(matches!(
+            resolve_export_path(&settings, &link.display().to_string()),
+            Err(AppError::ValidationError { .. })
+        ));
+        assert!(create_export_file(&link).is_err());
+        assert!(!outside.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_export_favorites() {
+        let mut server = mockito::Server::new_async().await;
+        let _biscuit = server
+            .mock("GET", mockito::Matcher::Regex("^/public/animals/42".to_string()))
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "42", "attributes": {"name": "Biscuit", "breedString": "Corgi", "isGoodWithCats": "Yes", "url": "https://example.com/42", "orgsAnimalsPictures": [{"urlSecureFullsize": "https://example.com/42.jpg"}]}}],
+                    "included": [{"type": "orgs", "id": "9", "attributes": {"name": "Happy Tails", "phone": "555-0100", "city": "Austin", "state": "TX"}}]}"#,
+            )
+            .create_async()
+            .await;
+        let saved = server
+            .mock("GET", "/public/animals/43")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "43", "attributes": {"name": "Bo", "breedString": "Beagle"}}]}"#)
+            .create_async()
+            .await;
+
+        let dir = std::env::temp_dir().join("rescue-groups-mcp-export-favorites");
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut settings = get_test_settings(server.url());
+        settings.data_dir = dir.join("data");
+        crate::favorites::save_favorite(&settings, "42", Some("Great with our cat".to_string()))
+            .await
+            .unwrap();
+        crate::favorites::save_favorite(&settings, "43", None)
+            .await
+            .unwrap();
+        crate::notes::add_note(&settings, "42", "Meet-and-greet Saturday")
+            .await
+            .unwrap();
+        // Bo was adopted since
+        saved.remove_async().await;
+        let _adopted = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex("^/public/animals/43".to_string()),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let summary = export_favorites(
+            &settings,
+            ResultsFormat::Markdown,
+            &dir.join("shortlist.md"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary["animals"], 2);
+        let md = std::fs::read_to_string(dir.join("shortlist.md")).unwrap();
+        assert!(md.starts_with("# Adoption Shortlist"));
+        assert!(md.contains("## [Biscuit](https://example.com/42)"));
+        assert!(md.contains("![Biscuit](https://example.com/42.jpg)"));
+        assert!(md.contains("| Good with Cats | Yes |"));
+        assert!(md.contains("**Organization:** Happy Tails"));
+        assert!(md.contains("**Phone:** 555-0100"));
+        assert!(md.contains("**Note:** Great with our cat"));
+        assert!(md.contains(": Meet-and-greet Saturday"));
+        assert!(md.contains("## Bo\n\n**ID:** 43\n\n**No longer available**"));
+
+        export_favorites(&settings, ResultsFormat::Csv, &dir.join("shortlist.csv"))
+            .await
+            .unwrap();
+        let csv = std::fs::read_to_string(dir.join("shortlist.csv")).unwrap();
+        assert!(csv.starts_with("ID,Name,Breed,"));
+        assert!(csv.contains(
+            "Happy Tails,555-0100,,\"Austin, TX\",Great with our cat,Meet-and-greet Saturday"
+        ));
+        assert!(csv.contains("43,Bo,Beagle,"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
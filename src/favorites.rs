@@ -0,0 +1,137 @@
+use crate::alerts::{now_secs, summarize_animal};
+use crate::cli::AnimalIdArgs;
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::fmt::extract_single_item;
+use crate::source::get_animal;
+use crate::store::{self, load_json};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct FavoritesFile {
+    #[serde(default)]
+    pub favorites: Vec<Favorite>,
+}
+
+/// An animal on the user's shortlist. The summary fields are captured when
+/// saved so the list can be shown without hitting the API.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Favorite {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub breed: String,
+    #[serde(default)]
+    pub url: String,
+    pub note: Option<String>,
+    /// Unix timestamp of when the animal was saved
+    pub added_at: u64,
+}
+
+fn favorites_path(settings: &Settings) -> PathBuf {
+    settings.data_dir.join("favorites.json")
+}
+
+/// Adds an animal to the favorites, or updates its note if already saved.
+pub async fn save_favorite(
+    settings: &Settings,
+    animal_id: &str,
+    note: Option<String>,
+) -> Result<Favorite, AppError> {
+    let details = get_animal(
+        settings,
+        AnimalIdArgs {
+            animal_id: animal_id.to_string(),
+        },
+    )
+    .await?;
+    let animal = details
+        .get("data")
+        .and_then(extract_single_item)
+        .ok_or(AppError::NotFound)?;
+    let summary = summarize_animal(animal);
+
+    let favorite = Favorite {
+        id: animal_id.to_string(),
+        name: summary["name"].as_str().unwrap_or("Unknown").to_string(),
+        breed: summary["breed"].as_str().unwrap_or("Mix").to_string(),
+        url: summary["url"].as_str().unwrap_or("").to_string(),
+        note,
+        added_at: now_secs(),
+    };
+    store::update(&favorites_path(settings), |file: &mut FavoritesFile| {
+        match file.favorites.iter_mut().find(|f| f.id == animal_id) {
+            Some(existing) => {
+                existing.note = favorite.note.clone();
+                existing.name = favorite.name.clone();
+            }
+            None => file.favorites.push(favorite.clone()),
+        }
+        Ok(())
+    })
+    .await?;
+    Ok(favorite)
+}
+
+pub async fn list_favorites(settings: &Settings) -> Result<Vec<Favorite>, AppError> {
+    let file: FavoritesFile = load_json(&favorites_path(settings))?;
+    Ok(file.favorites)
+}
+
+pub async fn remove_favorite(settings: &Settings, animal_id: &str) -> Result<(), AppError> {
+    store::update(&favorites_path(settings), |file: &mut FavoritesFile| {
+        let before = file.favorites.len();
+        file.favorites.retain(|f| f.id != animal_id);
+        if file.favorites.len() == before {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_settings(url: String) -> Settings {
+        let data_dir = std::env::temp_dir().join("rescue-groups-mcp-favorites");
+        let _ = std::fs::remove_dir_all(&data_dir);
+        Settings::for_test(url, data_dir)
+    }
+
+    #[tokio::test]
+    async fn test_favorites_roundtrip() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _mock = server
+            .mock("GET", "/public/animals/42")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "42", "attributes": {"name": "Biscuit", "breedString": "Corgi"}}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let favorite = save_favorite(&settings, "42", None).await.unwrap();
+        assert_eq!(favorite.name, "Biscuit");
+        save_favorite(&settings, "42", Some("Loves kids".to_string()))
+            .await
+            .unwrap();
+
+        let favorites = list_favorites(&settings).await.unwrap();
+        assert_eq!(favorites.len(), 1);
+        assert_eq!(favorites[0].breed, "Corgi");
+        assert_eq!(favorites[0].note.as_deref(), Some("Loves kids"));
+
+        remove_favorite(&settings, "42").await.unwrap();
+        assert!(list_favorites(&settings).await.unwrap().is_empty());
+        assert!(matches!(
+            remove_favorite(&settings, "42").await,
+            Err(AppError::NotFound)
+        ));
+        std::fs::remove_dir_all(&settings.data_dir).unwrap();
+    }
+}
@@ -0,0 +1,216 @@
+use crate::alerts::now_secs;
+use crate::cli::ToolArgs;
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::fmt::escape_html;
+use crate::source::search_animals;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Query parameters of `/feeds/animals.xml`.
+#[derive(Deserialize, Debug, Default)]
+pub struct FeedParams {
+    pub postal_code: Option<String>,
+    pub species: Option<String>,
+    pub miles: Option<u32>,
+    pub age: Option<String>,
+    pub size: Option<String>,
+    /// Alternative to the Authorization header, since most feed readers
+    /// can't send one.
+    pub token: Option<String>,
+}
+
+/// Formats a Unix timestamp as an RFC 3339 UTC date.
+pub fn rfc3339(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil-from-days (Howard Hinnant), valid for the proleptic Gregorian calendar
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// The listing date of an animal, if the API reports one in RFC 3339 form.
+fn listed_at(animal: &Value) -> Option<&str> {
+    animal["attributes"]["createdDate"]
+        .as_str()
+        .filter(|d| d.contains('T'))
+}
+
+fn render_entry(animal: &Value, fallback_date: &str) -> String {
+    let attrs = &animal["attributes"];
+    let name = attrs["name"].as_str().unwrap_or("Unknown");
+    let url = attrs["url"].as_str().unwrap_or("");
+    let summary: Vec<&str> = [
+        attrs["breedString"].as_str(),
+        attrs["ageGroup"].as_str(),
+        attrs["sex"].as_str(),
+        attrs["sizeGroup"].as_str(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let description: String = attrs["descriptionText"]
+        .as_str()
+        .unwrap_or("")
+        .chars()
+        .take(500)
+        .collect();
+
+    let mut entry = format!(
+        "  <entry>\n    <id>urn:rescue-groups-mcp:animal:{}</id>\n    <title>{}</title>\n    <updated>{}</updated>\n",
+        escape_html(animal["id"].as_str().unwrap_or("")),
+        escape_html(name),
+        escape_html(listed_at(animal).unwrap_or(fallback_date))
+    );
+    if !url.is_empty() {
+        entry.push_str(&format!(
+            "    <link rel=\"alternate\" href=\"{}\"/>\n",
+            escape_html(url)
+        ));
+    }
+    if let Some(picture) = attrs["orgsAnimalsPictures"]
+        .as_array()
+        .and_then(|p| p.first())
+        .and_then(|p| p["urlSecureFullsize"].as_str())
+    {
+        entry.push_str(&format!(
+            "    <link rel=\"enclosure\" type=\"image/jpeg\" href=\"{}\"/>\n",
+            escape_html(picture)
+        ));
+    }
+    entry.push_str(&format!(
+        "    <summary>{}</summary>\n",
+        escape_html(&summary.join(", "))
+    ));
+    if !description.is_empty() {
+        entry.push_str(&format!(
+            "    <content type=\"text\">{}</content>\n",
+            escape_html(&description)
+        ));
+    }
+    entry.push_str("  </entry>\n");
+    entry
+}
+
+/// Renders a search response as an Atom 1.0 feed.
+pub fn render_atom(data: &Value, title: &str, self_url: &str) -> String {
+    let animals = data["data"].as_array().cloned().unwrap_or_default();
+    let now = rfc3339(now_secs());
+    // RFC 3339 UTC dates sort lexicographically
+    let updated = animals
+        .iter()
+        .filter_map(listed_at)
+        .max()
+        .unwrap_or(&now)
+        .to_string();
+
+    let entries: String = animals.iter().map(|a| render_entry(a, &now)).collect();
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n  <id>urn:rescue-groups-mcp:feed:{}</id>\n  <title>{}</title>\n  <link rel=\"self\" href=\"{}\"/>\n  <updated>{}</updated>\n  <generator>rescue-groups-mcp</generator>\n{}</feed>\n",
+        escape_html(self_url),
+        escape_html(title),
+        escape_html(self_url),
+        updated,
+        entries
+    )
+}
+
+/// Fetches the newest listings matching `params` and renders them as a feed.
+/// Searches go through the regular client, so feed polling is cached and
+/// rate limited like any other request.
+pub async fn animals_feed(
+    settings: &Settings,
+    params: FeedParams,
+    self_url: &str,
+) -> Result<String, AppError> {
+    let species = params
+        .species
+        .clone()
+        .unwrap_or_else(|| settings.default_species.clone());
+    let postal_code = params
+        .postal_code
+        .clone()
+        .unwrap_or_else(|| settings.default_postal_code.clone());
+
+    let args = ToolArgs {
+        postal_code: Some(postal_code.clone()),
+        species: Some(species.clone()),
+        miles: params.miles,
+        age: params.age,
+        size: params.size,
+        sort_by: Some("Newest".to_string()),
+        ..Default::default()
+    };
+    let data = search_animals(settings, args).await?;
+
+    let title = format!("Adoptable {} near {}", species, postal_code);
+    Ok(render_atom(&data, &title, self_url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rfc3339() {
+        assert_eq!(rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339(951_827_696), "2000-02-29T12:34:56Z");
+        assert_eq!(rfc3339(1_735_689_599), "2024-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn test_render_atom() {
+        let data = json!({
+            "data": [
+                {
+                    "id": "1",
+                    "attributes": {
+                        "name": "Tom & Jerry",
+                        "url": "http://pets/1",
+                        "breedString": "Tabby",
+                        "createdDate": "2024-05-01T10:00:00Z",
+                        "orgsAnimalsPictures": [{"urlSecureFullsize": "http://pets/1.jpg"}]
+                    }
+                },
+                {
+                    "id": "2",
+                    "attributes": {"name": "Rex", "createdDate": "2024-05-02T08:00:00Z"}
+                }
+            ]
+        });
+        let feed = render_atom(
+            &data,
+            "Adoptable cats near 90210",
+            "http://host/feeds/animals.xml",
+        );
+        assert!(feed.starts_with("<?xml"));
+        assert!(feed.contains("<title>Adoptable cats near 90210</title>"));
+        assert!(feed.contains("<title>Tom &amp; Jerry</title>"));
+        assert!(feed.contains("<link rel=\"alternate\" href=\"http://pets/1\"/>"));
+        assert!(feed.contains("href=\"http://pets/1.jpg\""));
+        assert!(feed.contains("<summary>Tabby</summary>"));
+        // The feed is as recent as its newest entry
+        assert!(feed.contains("  <updated>2024-05-02T08:00:00Z</updated>\n  <generator>"));
+        assert_eq!(feed.matches("<entry>").count(), 2);
+    }
+}
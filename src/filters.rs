@@ -0,0 +1,343 @@
+use crate::cli::{FilterReferenceArgs, MetadataArgs};
+use crate::client::{fan_out, list_metadata};
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::synonyms::suggest;
+use serde_json::{json, Value};
+
+/// Operations the search endpoints accept in a filter.
+pub const OPERATIONS: &[&str] = &[
+    "equal",
+    "notequal",
+    "lessthan",
+    "lessthanorequal",
+    "greaterthan",
+    "greaterthanorequal",
+    "contains",
+    "notcontains",
+    "blank",
+    "notblank",
+];
+
+/// Live values listed per field, so a long breed list stays readable.
+const MAX_LIVE_VALUES: usize = 40;
+
+/// An upstream filter field the tools build searches from.
+struct FilterField {
+    field: &'static str,
+    /// Tool argument that sets it, as "tool.argument"
+    argument: &'static str,
+    operations: &'static [&'static str],
+    description: &'static str,
+    examples: &'static [&'static str],
+    /// Metadata type whose names are the valid criteria
+    metadata: Option<&'static str>,
+}
+
+const YES_NO: &[&str] = &["Yes", "No"];
+
+const FILTER_FIELDS: &[FilterField] = &[
+    FilterField {
+        field: "breeds.name",
+        argument: "search_adoptable_pets.breeds",
+        operations: &["contains", "equal"],
+        description: "Primary or secondary breed name.",
+        examples: &["Labrador", "Siamese"],
+        metadata: Some("breeds"),
+    },
+    FilterField {
+        field: "animals.sex",
+        argument: "search_adoptable_pets.sex",
+        operations: &["equal"],
+        description: "Sex of the animal.",
+        examples: &["Male", "Female"],
+        metadata: None,
+    },
+    FilterField {
+        field: "animals.ageGroup",
+        argument: "search_adoptable_pets.age",
+        operations: &["equal"],
+        description: "Age group.",
+        examples: &["Baby", "Young", "Adult", "Senior"],
+        metadata: None,
+    },
+    FilterField {
+        field: "animals.sizeGroup",
+        argument: "search_adoptable_pets.size",
+        operations: &["equal"],
+        description: "Size group.",
+        examples: &["Small", "Medium", "Large", "X-Large"],
+        metadata: None,
+    },
+    FilterField {
+        field: "animals.isGoodWithChildren",
+        argument: "search_adoptable_pets.good_with_children",
+        operations: &["equal"],
+        description: "Known to be good with children.",
+        examples: YES_NO,
+        metadata: None,
+    },
+    FilterField {
+        field: "animals.isGoodWithDogs",
+        argument: "search_adoptable_pets.good_with_dogs",
+        operations: &["equal"],
+        description: "Known to be good with dogs.",
+        examples: YES_NO,
+        metadata: None,
+    },
+    FilterField {
+        field: "animals.isGoodWithCats",
+        argument: "search_adoptable_pets.good_with_cats",
+        operations: &["equal"],
+        description: "Known to be good with cats.",
+        examples: YES_NO,
+        metadata: None,
+    },
+    FilterField {
+        field: "animals.isHouseTrained",
+        argument: "search_adoptable_pets.house_trained",
+        operations: &["equal"],
+        description: "House trained.",
+        examples: YES_NO,
+        metadata: None,
+    },
+    FilterField {
+        field: "animals.isSpecialNeeds",
+        argument: "search_adoptable_pets.special_needs",
+        operations: &["equal"],
+        description: "Has special needs.",
+        examples: YES_NO,
+        metadata: None,
+    },
+    FilterField {
+        field: "animals.isNeedingFoster",
+        argument: "search_adoptable_pets.needs_foster",
+        operations: &["equal"],
+        description: "Looking for a foster home.",
+        examples: YES_NO,
+        metadata: None,
+    },
+    FilterField {
+        field: "animals.isCourtesyListing",
+        argument: "search_adoptable_pets.courtesy_listings",
+        operations: &["equal"],
+        description: "Listed by the organization on someone else's behalf.",
+        examples: YES_NO,
+        metadata: None,
+    },
+    FilterField {
+        field: "animals.colorDetails",
+        argument: "search_adoptable_pets.color",
+        operations: &["contains"],
+        description: "Free-text color description written by the organization.",
+        examples: &["black", "smoky"],
+        metadata: None,
+    },
+    FilterField {
+        field: "colors.name",
+        argument: "search_adoptable_pets.color_group",
+        operations: &["equal"],
+        description: "Standard color group for the species.",
+        examples: &["Black", "Tortoiseshell"],
+        metadata: Some("colors"),
+    },
+    FilterField {
+        field: "animals.patternDetails",
+        argument: "search_adoptable_pets.pattern",
+        operations: &["contains"],
+        description: "Coat pattern.",
+        examples: &["Tabby", "Brindle"],
+        metadata: Some("patterns"),
+    },
+    FilterField {
+        field: "animals.rescueId",
+        argument: "find_by_reference.reference",
+        operations: &["equal"],
+        description: "The organization's own reference number for the animal.",
+        examples: &["A-17"],
+        metadata: None,
+    },
+    FilterField {
+        field: "animals.createdDate",
+        argument: "list_new_arrivals.days",
+        operations: &["greaterthanorequal", "lessthan"],
+        description: "When the animal was listed (RFC 3339).",
+        examples: &["2024-06-01T00:00:00Z"],
+        metadata: None,
+    },
+    FilterField {
+        field: "orgs.name",
+        argument: "search_organizations.query",
+        operations: &["contains"],
+        description: "Organization name.",
+        examples: &["Humane Society"],
+        metadata: None,
+    },
+];
+
+/// The filter fields searches are built from, their operations and sample
+/// criteria, with the valid values of metadata-backed fields (breeds,
+/// colors, patterns) for the species looked up live. `field` narrows it to
+/// one field.
+pub async fn explain_filters(
+    settings: &Settings,
+    args: FilterReferenceArgs,
+) -> Result<Value, AppError> {
+    let fields: Vec<&FilterField> = match &args.field {
+        None => FILTER_FIELDS.iter().collect(),
+        Some(name) => {
+            let found: Vec<&FilterField> = FILTER_FIELDS
+                .iter()
+                .filter(|f| {
+                    f.field.eq_ignore_ascii_case(name)
+                        || f.argument
+                            .rsplit('.')
+                            .next()
+                            .is_some_and(|arg| arg.eq_ignore_ascii_case(name))
+                })
+                .collect();
+            if found.is_empty() {
+                let known: Vec<String> =
+                    FILTER_FIELDS.iter().map(|f| f.field.to_string()).collect();
+                let hint = match suggest(name, &known).as_slice() {
+                    [] => format!("Known fields: {}", known.join(", ")),
+                    close => format!("Did you mean: {}?", close.join(", ")),
+                };
+                return Err(AppError::invalid_argument(
+                    "field",
+                    format!("Unknown filter field '{}'. {}", name, hint),
+                ));
+            }
+            found
+        }
+    };
+
+    let species = args
+        .species
+        .unwrap_or_else(|| settings.default_species.clone());
+    let live = fan_out(
+        settings,
+        fields
+            .iter()
+            .map(|f| live_values(settings, &species, f.metadata)),
+    )
+    .await;
+
+    let data: Vec<Value> = fields
+        .iter()
+        .zip(live)
+        .map(|(f, values)| {
+            let mut entry = json!({
+                "field": f.field,
+                "argument": f.argument,
+                "operations": f.operations,
+                "description": f.description,
+                "examples": f.examples,
+            });
+            if let Some((values, total)) = values {
+                entry["values"] = json!(values);
+                if total > values.len() {
+                    entry["values_total"] = json!(total);
+                }
+            }
+            entry
+        })
+        .collect();
+    Ok(json!({ "species": species, "operations": OPERATIONS, "data": data }))
+}
+
+/// The first names of a metadata type for `species`, with how many there
+/// are. A failed lookup leaves the field with its examples only.
+async fn live_values(
+    settings: &Settings,
+    species: &str,
+    metadata: Option<&str>,
+) -> Option<(Vec<String>, usize)> {
+    let args = MetadataArgs {
+        metadata_type: metadata?.to_string(),
+        species: Some(species.to_string()),
+    };
+    let data = list_metadata(settings, args).await.ok()?;
+    let mut names: Vec<String> = data["data"]
+        .as_array()?
+        .iter()
+        .filter_map(|item| item["attributes"]["name"].as_str())
+        .map(str::to_string)
+        .collect();
+    names.sort();
+    names.dedup();
+    let total = names.len();
+    names.truncate(MAX_LIVE_VALUES);
+    Some((names, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use moka::future::Cache;
+
+    use std::sync::Arc;
+
+    fn get_test_settings(url: String) -> Settings {
+        Settings {
+            default_species: "cats".to_string(),
+            cache: Arc::new(Cache::new(0)),
+            ..Settings::for_test(url, std::env::temp_dir().join("rescue-groups-mcp-filters"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_explain_filters() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _species = server
+            .mock("GET", "/public/animals/species")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "3", "attributes": {"singular": "Cat", "plural": "Cats"}}]}"#,
+            )
+            .create_async()
+            .await;
+        let _colors = server
+            .mock("GET", "/public/animals/species/3/colors")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [
+                    {"id": "1", "attributes": {"name": "Tortoiseshell"}},
+                    {"id": "2", "attributes": {"name": "Black"}}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let args = FilterReferenceArgs {
+            species: None,
+            field: Some("color_group".to_string()),
+        };
+        let result = explain_filters(&settings, args).await.unwrap();
+        assert_eq!(result["species"], "cats");
+        let fields = result["data"].as_array().unwrap();
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0]["field"], "colors.name");
+        assert_eq!(fields[0]["values"], json!(["Black", "Tortoiseshell"]));
+
+        let args = FilterReferenceArgs {
+            species: None,
+            field: Some("animals.agegroup".to_string()),
+        };
+        let result = explain_filters(&settings, args).await.unwrap();
+        assert_eq!(result["data"][0]["argument"], "search_adoptable_pets.age");
+        assert!(result["data"][0].get("values").is_none());
+
+        let args = FilterReferenceArgs {
+            species: None,
+            field: Some("animals.ageGrup".to_string()),
+        };
+        let err = explain_filters(&settings, args).await.unwrap_err();
+        assert!(
+            matches!(err, AppError::ValidationError { message: msg, .. } if msg.contains("animals.ageGroup"))
+        );
+    }
+}
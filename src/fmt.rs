@@ -1,6 +1,107 @@
+use crate::config::{Labels, OutputConfig, Settings};
 use crate::error::AppError;
-use serde_json::Value;
+use serde_json::{json, Value};
+use std::borrow::Cow;
+use std::future::Future;
 use tracing::error;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Longest special-needs summary squeezed into a comparison table cell.
+const COMPARISON_NEEDS_CHARS: usize = 60;
+/// Longest before or after value shown for a changed detail.
+const CHANGE_VALUE_CHARS: usize = 60;
+/// Animals listed from one page of search results.
+pub const LISTED_ANIMALS: usize = 5;
+/// Animals listed per species in an organization's listing.
+const ORG_GROUP_ANIMALS: usize = 10;
+
+/// The time `--deterministic` output is rendered at: 2024-01-01T00:00:00Z.
+pub const DETERMINISTIC_TIME: u64 = 1_704_067_200;
+
+tokio::task_local! {
+    static RENDER_TIME: u64;
+    static OUTPUT: OutputConfig;
+}
+
+/// The time ages, listing timelines and export stamps are worked out from:
+/// now, or [`DETERMINISTIC_TIME`] inside [`render`] with `--deterministic`.
+pub fn render_time() -> u64 {
+    RENDER_TIME
+        .try_with(|time| *time)
+        .unwrap_or_else(|_| crate::alerts::now_secs())
+}
+
+/// The `[output]` settings of the output being rendered, or the defaults
+/// outside [`render`].
+fn output_config() -> OutputConfig {
+    OUTPUT.try_with(|output| *output).unwrap_or_default()
+}
+
+/// Runs `task`, which renders output, with the `[output]` settings and at
+/// [`DETERMINISTIC_TIME`] when `settings` are deterministic.
+pub async fn render<F: Future>(settings: &Settings, task: F) -> F::Output {
+    let task = OUTPUT.scope(settings.output, task);
+    if settings.deterministic {
+        RENDER_TIME.scope(DETERMINISTIC_TIME, task).await
+    } else {
+        task.await
+    }
+}
+
+/// Key attributes of an exported shortlist: label and attribute.
+pub const SHORTLIST_FIELDS: [(&str, &str); 11] = [
+    ("Breed", "breedString"),
+    ("Sex", "sex"),
+    ("Age", "ageGroup"),
+    ("Size", "sizeGroup"),
+    ("Color", "colorDetails"),
+    ("Adoption Fee", "adoptionFeeString"),
+    ("Good with Children", "isGoodWithChildren"),
+    ("Good with Dogs", "isGoodWithDogs"),
+    ("Good with Cats", "isGoodWithCats"),
+    ("House-trained", "isHouseTrained"),
+    ("Special Needs", "isSpecialNeeds"),
+];
+
+/// Ongoing-care attributes shown alongside a special-needs description.
+const CARE_FIELDS: [(&str, &str); 4] = [
+    ("Ongoing Medical", "ongoingMedical"),
+    ("Special Diet", "specialDiet"),
+    ("Exercise Needs", "exerciseNeeds"),
+    ("Grooming Needs", "groomingNeeds"),
+];
+
+/// Shortens `text` to at most `max_chars` user-perceived characters plus an
+/// ellipsis. It ends after the last whole sentence that fits, or failing
+/// that the last whole word, and never splits a character.
+pub fn truncate_text(text: &str, max_chars: usize) -> Cow<'_, str> {
+    let text = text.trim();
+    let Some((cut, _)) = text.grapheme_indices(true).nth(max_chars) else {
+        return Cow::Borrowed(text);
+    };
+    let kept = &text[..cut];
+
+    // Ending mid-way through the first sentence or word loses too much
+    let enough = kept.len() / 2;
+    let sentence_end = kept
+        .split_sentence_bound_indices()
+        .map(|(start, sentence)| start + sentence.len())
+        .filter(|&end| end < kept.len() && end >= enough)
+        .last();
+    if let Some(end) = sentence_end {
+        return Cow::Owned(format!("{}…", kept[..end].trim_end()));
+    }
+    let word_end = if text[cut..].starts_with(char::is_whitespace) {
+        Some(cut)
+    } else {
+        kept.char_indices()
+            .rev()
+            .find(|&(i, c)| c.is_whitespace() && i >= enough)
+            .map(|(i, _)| i)
+    };
+    let end = word_end.unwrap_or(cut);
+    Cow::Owned(format!("{}…", kept[..end].trim_end()))
+}
 
 pub fn extract_single_item(data: &Value) -> Option<&Value> {
     match data {
@@ -11,14 +112,23 @@ pub fn extract_single_item(data: &Value) -> Option<&Value> {
 }
 
 pub fn format_single_animal(animal: &Value) -> String {
+    format_animal_profile(animal, None)
+}
+
+/// An animal's profile, noting `foster_city` when it is being looked after
+/// somewhere other than its organization's address.
+fn format_animal_profile(animal: &Value, foster_city: Option<&str>) -> String {
     let attrs = &animal["attributes"];
     let name = attrs["name"].as_str().unwrap_or("Unknown");
     let breed = attrs["breedString"].as_str().unwrap_or("Mix");
-    let description = attrs["descriptionText"]
-        .as_str()
-        .unwrap_or("No description available.");
+    let description = truncate_text(
+        attrs["descriptionText"]
+            .as_str()
+            .unwrap_or("No description available."),
+        output_config().detail_description_chars,
+    );
     let sex = attrs["sex"].as_str().unwrap_or("Unknown");
-    let age = attrs["ageGroup"].as_str().unwrap_or("Unknown");
+    let age = age_label(attrs, render_time() / 86_400, "Unknown");
     let size = attrs["sizeGroup"].as_str().unwrap_or("Unknown");
     let url = attrs["url"].as_str().unwrap_or("");
 
@@ -29,12 +139,245 @@ pub fn format_single_animal(animal: &Value) -> String {
         .map(|u| format!("![{}]({})", name, u))
         .unwrap_or_default();
 
+    let timeline = format_timeline(attrs, render_time() / 86_400);
+    let special_needs = format_special_needs(attrs);
+
+    let (parts, mixed) = breed_parts(attrs);
+    let breed_mix = if parts.len() > 1 || mixed {
+        let parts: Vec<String> = parts
+            .iter()
+            .map(|part| match &part.id {
+                Some(id) => format!("{} (breed ID {})", part.name, id),
+                None => part.name.clone(),
+            })
+            .collect();
+        format!(
+            "\n**Breed Mix:** {}{}",
+            parts.join(", "),
+            if mixed { ", and others" } else { "" }
+        )
+    } else {
+        String::new()
+    };
+
     format!(
-        "# {}\n**Breed:** {}\n**Sex:** {}\n**Age:** {}\n**Size:** {}\n\n{}\n\n{}\n\n[View on RescueGroups]({})",
-        name, breed, sex, age, size, img, description, url
+        "# {}\n{}{}**Breed:** {}{}\n**Sex:** {}\n**Age:** {}\n**Size:** {}\n\n{}\n\n{}\n\n{}{}[View on RescueGroups]({})",
+        name,
+        courtesy_note(attrs),
+        foster_city
+            .map(|city| format!("**Currently in foster in {}**\n", city))
+            .unwrap_or_default(),
+        breed,
+        breed_mix,
+        sex,
+        age,
+        size,
+        img,
+        description,
+        special_needs,
+        timeline,
+        url
     )
 }
 
+/// Describes what a special-needs animal needs, so the flag isn't the only
+/// thing an adopter has to go on. Empty for animals without special needs.
+fn format_special_needs(attrs: &Value) -> String {
+    if attrs["isSpecialNeeds"].as_str() != Some("Yes") {
+        return String::new();
+    }
+    let mut section = String::from("## Special Needs\n");
+    match text_attr(attrs, "specialNeedsDetails") {
+        Some(details) => section.push_str(&format!("{}\n", details)),
+        None => section.push_str(
+            "The organization hasn't described this pet's needs; ask them before applying.\n",
+        ),
+    }
+    for (label, field) in CARE_FIELDS {
+        if let Some(value) = text_attr(attrs, field) {
+            section.push_str(&format!("- **{}:** {}\n", label, value));
+        }
+    }
+    section.push('\n');
+    section
+}
+
+/// A non-blank string attribute, trimmed.
+fn text_attr<'a>(attrs: &'a Value, field: &str) -> Option<&'a str> {
+    attrs[field]
+        .as_str()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Warns that a courtesy listing is posted by an org that isn't caring for
+/// the animal, so the listed contact may only be passing on enquiries.
+fn courtesy_note(attrs: &Value) -> &'static str {
+    if attrs["isCourtesyListing"].as_str() == Some("Yes") {
+        "**Courtesy Listing:** posted on behalf of another caretaker; the listing organization does not have this pet\n"
+    } else {
+        ""
+    }
+}
+
+/// One breed of a (possibly mixed) animal, with its ID for `get_breed` when
+/// the listing gives one.
+#[derive(Debug, PartialEq)]
+pub struct BreedPart {
+    pub name: String,
+    pub id: Option<String>,
+}
+
+/// Splits a `breedString` such as "Labrador Retriever / Hound Mix" into its
+/// breeds. The flag is true when the string says the animal is a mix of more
+/// than the breeds named.
+pub fn split_breeds(breed: &str) -> (Vec<String>, bool) {
+    let mut mixed = false;
+    let mut names: Vec<String> = Vec::new();
+    for part in breed.split(['/', ',', '&', '+']) {
+        let mut name = part.trim();
+        for suffix in [" Mix", " mix", " Mixed", " mixed"] {
+            if let Some(stripped) = name.strip_suffix(suffix) {
+                name = stripped.trim_end();
+                mixed = true;
+            }
+        }
+        if !name.is_empty() && !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+    }
+    (names, mixed)
+}
+
+/// The breeds of an animal, linked to `breedPrimaryId`/`breedSecondaryId`
+/// where the listing's breed names line up with them.
+pub fn breed_parts(attrs: &Value) -> (Vec<BreedPart>, bool) {
+    let (names, mixed) = split_breeds(attrs["breedString"].as_str().unwrap_or(""));
+    let id_for = |name: &str| {
+        [
+            ("breedPrimary", "breedPrimaryId"),
+            ("breedSecondary", "breedSecondaryId"),
+        ]
+        .iter()
+        .find(|(key, _)| {
+            attrs[key]
+                .as_str()
+                .is_some_and(|n| n.trim().eq_ignore_ascii_case(name))
+        })
+        .and_then(|(_, id)| match &attrs[id] {
+            Value::String(id) => Some(id.clone()),
+            Value::Number(id) => Some(id.to_string()),
+            _ => None,
+        })
+    };
+    let parts = names
+        .into_iter()
+        .map(|name| BreedPart {
+            id: id_for(&name),
+            name,
+        })
+        .collect();
+    (parts, mixed)
+}
+
+/// Days since the Unix epoch of an RFC 3339 (or bare `YYYY-MM-DD`) date.
+pub(crate) fn epoch_days(date: &str) -> Option<i64> {
+    let mut parts = date.get(..10)?.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Days-from-civil (Howard Hinnant), the inverse of `feeds::rfc3339`
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+/// The age group (or `unknown`) with, where the listing allows, a more precise age as of
+/// day `today`: computed from `birthDate`, else taken from `ageString`.
+fn age_label(attrs: &Value, today: u64, unknown: &str) -> String {
+    let group = attrs["ageGroup"].as_str().unwrap_or(unknown);
+    let exact = attrs["birthDate"]
+        .as_str()
+        .and_then(|birth| age_between(birth, &crate::feeds::rfc3339(today * 86_400)))
+        .or_else(|| text_attr(attrs, "ageString").map(str::to_lowercase));
+    match exact {
+        Some(exact) => format!("{} (approx. {})", group, exact),
+        None => group.to_string(),
+    }
+}
+
+/// Whole years and months from `birth` to `today`, both `YYYY-MM-DD`
+/// prefixed, e.g. "2 years 3 months". None if `birth` is in the future.
+fn age_between(birth: &str, today: &str) -> Option<String> {
+    epoch_days(birth)?;
+    let ymd = |date: &str| -> Option<(i64, i64, i64)> {
+        let mut parts = date.get(..10)?.splitn(3, '-');
+        Some((
+            parts.next()?.parse().ok()?,
+            parts.next()?.parse().ok()?,
+            parts.next()?.parse().ok()?,
+        ))
+    };
+    let (by, bm, bd) = ymd(birth)?;
+    let (ty, tm, td) = ymd(today)?;
+    let months = (ty - by) * 12 + (tm - bm) - i64::from(td < bd);
+    if months < 0 {
+        return None;
+    }
+    let plural = |n: i64, unit: &str| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" });
+    Some(match (months / 12, months % 12) {
+        (0, 0) => "under 1 month".to_string(),
+        (0, m) => plural(m, "month"),
+        (y, 0) => plural(y, "year"),
+        (y, m) => format!("{} {}", plural(y, "year"), plural(m, "month")),
+    })
+}
+
+/// The "Timeline" section of an animal's details: when it was listed (and
+/// for how long, as of day `today`), made available, last updated and
+/// found. Empty when the listing has none of those dates.
+fn format_timeline(attrs: &Value, today: u64) -> String {
+    let date = |key: &str| {
+        attrs[key]
+            .as_str()
+            .filter(|d| epoch_days(d).is_some())
+            .map(|d| &d[..10])
+    };
+    let mut lines = Vec::new();
+    if let Some(listed) = date("createdDate") {
+        let days = today as i64 - epoch_days(listed).unwrap_or_default();
+        lines.push(match days {
+            ..=0 => format!("**Listed:** {} (today)", listed),
+            1 => format!("**Listed:** {} (listed for 1 day)", listed),
+            days => format!("**Listed:** {} (listed for {} days)", listed, days),
+        });
+    }
+    if let Some(available) = date("availableDate") {
+        lines.push(format!("**Available:** {}", available));
+    }
+    if let Some(updated) = date("updatedDate") {
+        lines.push(format!("**Updated:** {}", updated));
+    }
+    if let Some(found) = date("foundDate") {
+        match attrs["foundPostalcode"].as_str() {
+            Some(postal_code) => lines.push(format!("**Found:** {} near {}", found, postal_code)),
+            None => lines.push(format!("**Found:** {}", found)),
+        }
+    }
+
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!("## Timeline\n{}\n\n", lines.join("\n"))
+}
+
 pub fn format_contact_info(data: &Value) -> Result<String, AppError> {
     let animal_data = data.get("data").ok_or(AppError::NotFound)?;
     let animal = extract_single_item(animal_data).ok_or(AppError::NotFound)?;
@@ -62,6 +405,12 @@ pub fn format_contact_info(data: &Value) -> Result<String, AppError> {
         contact_info.push_str(&format!("**Email:** {}\n", email));
         contact_info.push_str(&format!("**Phone:** {}\n", phone));
         contact_info.push_str(&format!("**Location:** {}, {}\n", city, state));
+        if let Some(foster) = foster_location(data, animal) {
+            contact_info.push_str(&format!(
+                "**Currently in foster in {}** (not at the organization's address)\n",
+                foster
+            ));
+        }
         if !url.is_empty() {
             contact_info.push_str(&format!("**Website:** [{}]({})\n", url, url));
         }
@@ -71,6 +420,31 @@ pub fn format_contact_info(data: &Value) -> Result<String, AppError> {
         );
     }
 
+    // The branch housing the animal, for multi-site organizations
+    let branch = animal["relationships"]["locations"]["data"]
+        .as_array()
+        .and_then(|l| l.first())
+        .and_then(|l| {
+            data["included"]
+                .as_array()?
+                .iter()
+                .find(|item| item["type"] == "locations" && item["id"] == l["id"])
+        });
+    if let Some(branch) = branch {
+        let attrs = &branch["attributes"];
+        contact_info.push_str(&format!(
+            "**Housed At:** {} ({})\n",
+            attrs["name"].as_str().unwrap_or("Unnamed location"),
+            location_address(attrs)
+        ));
+        if let Some(phone) = attrs["phone"].as_str() {
+            contact_info.push_str(&format!("**Branch Phone:** {}\n", phone));
+        }
+        if let Some(hours) = attrs["hours"].as_str() {
+            contact_info.push_str(&format!("**Branch Hours:** {}\n", hours));
+        }
+    }
+
     let animal_url = animal_attrs["url"].as_str().unwrap_or("");
     if !animal_url.is_empty() {
         contact_info.push_str(&format!(
@@ -82,19 +456,114 @@ pub fn format_contact_info(data: &Value) -> Result<String, AppError> {
     Ok(contact_info)
 }
 
+/// "City, ST" of the location an animal is linked to, when that differs
+/// from its organization's city, i.e. it is being fostered elsewhere and
+/// the distance to the org says little about where to meet it. Needs both
+/// the org and the location in the response's `included`.
+fn foster_location(data: &Value, animal: &Value) -> Option<String> {
+    let included = data["included"].as_array()?;
+    let org = included.iter().find(|item| item["type"] == "orgs")?;
+    let location_id = &animal["relationships"]["locations"]["data"]
+        .as_array()?
+        .first()?["id"];
+    let location = included
+        .iter()
+        .find(|item| item["type"] == "locations" && &item["id"] == location_id)?;
+
+    let place = |attrs: &Value| {
+        let part = |key: &str| {
+            attrs[key]
+                .as_str()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        Some((part("city")?, part("state")))
+    };
+    let (city, state) = place(&location["attributes"])?;
+    let same_place = place(&org["attributes"]).is_some_and(|(org_city, org_state)| {
+        org_city.eq_ignore_ascii_case(&city)
+            && match (&org_state, &state) {
+                (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                _ => true,
+            }
+    });
+    if same_place {
+        return None;
+    }
+    Some(match state {
+        Some(state) => format!("{}, {}", city, state),
+        None => city,
+    })
+}
+
+/// "123 Main St, Austin, TX 78704" from whichever address parts are present.
+fn location_address(attrs: &Value) -> String {
+    let part = |key: &str| attrs[key].as_str().filter(|s| !s.trim().is_empty());
+    let state_zip: Vec<&str> = ["state", "postalcode"]
+        .iter()
+        .filter_map(|k| part(k))
+        .collect();
+    let mut parts: Vec<String> = ["street", "city"]
+        .iter()
+        .filter_map(|k| part(k).map(str::to_string))
+        .collect();
+    if !state_zip.is_empty() {
+        parts.push(state_zip.join(" "));
+    }
+    if parts.is_empty() {
+        return "address not listed".to_string();
+    }
+    parts.join(", ")
+}
+
+pub fn format_org_locations(data: &Value) -> Result<String, AppError> {
+    let locations = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    if locations.is_empty() {
+        return Ok("This organization lists no separate locations.".to_string());
+    }
+
+    let mut output = format!("### Locations ({})\n\n", locations.len());
+    for location in locations {
+        let attrs = &location["attributes"];
+        output.push_str(&format!(
+            "**{}** (ID: {})\n**Address:** {}\n",
+            attrs["name"].as_str().unwrap_or("Unnamed location"),
+            location["id"].as_str().unwrap_or("?"),
+            location_address(attrs)
+        ));
+        if let Some(phone) = attrs["phone"].as_str() {
+            output.push_str(&format!("**Phone:** {}\n", phone));
+        }
+        if let Some(hours) = attrs["hours"].as_str() {
+            output.push_str(&format!("**Hours:** {}\n", hours));
+        }
+        output.push('\n');
+    }
+    Ok(output.trim_end().to_string())
+}
+
 pub fn format_animal_results(data: &Value) -> Result<String, AppError> {
     let animals = data
         .get("data")
         .and_then(|d| d.as_array())
         .ok_or(AppError::NotFound)?;
 
+    let note = data["note"].as_str();
     if animals.is_empty() {
-        return Ok("No adoptable animals found.".to_string());
+        return Ok(match note {
+            Some(note) => format!("No adoptable animals found. {}", note),
+            None => "No adoptable animals found.".to_string(),
+        });
     }
 
     let results: Vec<String> = animals
         .iter()
-        .take(5)
+        .take(LISTED_ANIMALS)
         .map(|animal| {
             let attrs = &animal["attributes"];
             let name = attrs["name"].as_str().unwrap_or("Unknown");
@@ -108,216 +577,1959 @@ pub fn format_animal_results(data: &Value) -> Result<String, AppError> {
                 .map(|u| format!("![{}]({})", name, u))
                 .unwrap_or_default();
 
-            format!("### [{}]({})\n**Breed:** {}\n\n{}", name, url, breed, img)
+            // Cross-listed animals can be adopted through any of their orgs
+            let listings = attrs["listings"]
+                .as_array()
+                .map(|listings| {
+                    let links: Vec<String> = listings
+                        .iter()
+                        .map(|l| {
+                            format!(
+                                "[{}]({})",
+                                l["org_id"].as_str().unwrap_or("unknown org"),
+                                l["url"].as_str().unwrap_or("")
+                            )
+                        })
+                        .collect();
+                    format!("**Listed by:** {}\n", links.join(", "))
+                })
+                .unwrap_or_default();
+
+            let distance = attrs["distance"]
+                .as_f64()
+                .map(|d| format!("**Distance:** {:.1} miles\n", d))
+                .unwrap_or_default();
+
+            format!(
+                "### [{}]({})\n{}**Breed:** {}\n{}{}\n{}",
+                name,
+                url,
+                courtesy_note(attrs),
+                breed,
+                distance,
+                listings,
+                img
+            )
         })
         .collect();
 
-    Ok(results.join("\n\n---\n\n"))
+    let mut output = results.join("\n\n---\n\n");
+    if let Some(more) = more_results_note(data, results.len()) {
+        output.push_str(&more);
+    }
+    Ok(match note {
+        Some(note) => format!("⚠️ {}\n\n{}", note, output),
+        None => output,
+    })
 }
 
-pub fn format_comparison_table(data: &Value) -> Result<String, AppError> {
+/// An organization's animals grouped by species, largest group first, under
+/// a line counting each species.
+pub fn format_org_animals(data: &Value) -> Result<String, AppError> {
     let animals = data
         .get("data")
         .and_then(|d| d.as_array())
         .ok_or(AppError::NotFound)?;
 
+    let note = data["note"].as_str();
     if animals.is_empty() {
-        return Ok("No animals to compare.".to_string());
+        return Ok(match note {
+            Some(note) => format!("No adoptable animals found. {}", note),
+            None => "No adoptable animals found.".to_string(),
+        });
     }
 
-    let headers = vec![
-        "Breed", "Age", "Sex", "Size", "Kids?", "Dogs?", "Cats?", "Trained?", "Special?",
-    ];
-
-    let mut markdown = String::new();
-
-    // Header Row
-    markdown.push_str("| Feature |");
+    let included = data["included"].as_array().map_or(&[][..], Vec::as_slice);
+    let related = |animal: &Value, kind: &str| {
+        let id = &animal["relationships"][kind]["data"][0]["id"];
+        included
+            .iter()
+            .find(|item| item["type"] == kind && item["id"] == *id)
+            .map(|item| &item["attributes"])
+    };
+
+    // Species by singular and plural name, in the order first seen
+    let mut groups: Vec<(&str, &str, Vec<&Value>)> = Vec::new();
     for animal in animals {
-        let name = animal["attributes"]["name"].as_str().unwrap_or("Unknown");
-        let url = animal["attributes"]["url"].as_str().unwrap_or("");
-        markdown.push_str(&format!(" [{}]({}) |", name, url));
-    }
-    markdown.push('\n');
-
-    // Separator Row
-    markdown.push_str("| :--- |");
-    for _ in animals {
-        markdown.push_str(" :--- |");
+        let species = related(animal, "species");
+        let singular = species
+            .and_then(|s| s["singular"].as_str())
+            .unwrap_or("Other animal");
+        let plural = species
+            .and_then(|s| s["plural"].as_str())
+            .unwrap_or("Other animals");
+        match groups.iter_mut().find(|(_, name, _)| *name == plural) {
+            Some((_, _, members)) => members.push(animal),
+            None => groups.push((singular, plural, vec![animal])),
+        }
     }
-    markdown.push('\n');
+    groups.sort_by_key(|(_, _, members)| std::cmp::Reverse(members.len()));
 
-    // Data Rows
-    for header in headers {
-        markdown.push_str(&format!("| **{}** |", header));
-        for animal in animals {
+    let org = animals
+        .iter()
+        .find_map(|animal| related(animal, "orgs"))
+        .and_then(|org| org["name"].as_str())
+        .unwrap_or("This organization");
+    let counts: Vec<String> = groups
+        .iter()
+        .map(|(singular, plural, members)| {
+            let name = if members.len() == 1 { singular } else { plural };
+            format!("{} {}", members.len(), name.to_lowercase())
+        })
+        .collect();
+    // Paging may have stopped early, leaving the counts short of the listing
+    let total = data["meta"]["count"]
+        .as_u64()
+        .filter(|&total| total > animals.len() as u64);
+    let mut output = match total {
+        Some(total) => format!(
+            "**{} currently lists {} animals; the {} fetched are {}.**\n",
+            org,
+            total,
+            animals.len(),
+            counts.join(", ")
+        ),
+        None if note.is_some() => format!(
+            "**{} lists at least {} (counted from the pages fetched).**\n",
+            org,
+            counts.join(", ")
+        ),
+        None => format!("**{} currently lists {}.**\n", org, counts.join(", ")),
+    };
+
+    for (_, plural, members) in &groups {
+        output.push_str(&format!("\n## {} ({})\n", plural, members.len()));
+        for animal in members.iter().take(ORG_GROUP_ANIMALS) {
             let attrs = &animal["attributes"];
-            let val = match header {
-                "Breed" => attrs["breedString"].as_str().unwrap_or("-").to_string(),
-                "Age" => attrs["ageGroup"].as_str().unwrap_or("-").to_string(),
-                "Sex" => attrs["sex"].as_str().unwrap_or("-").to_string(),
-                "Size" => attrs["sizeGroup"].as_str().unwrap_or("-").to_string(),
-                "Kids?" => attrs["isGoodWithChildren"]
-                    .as_str()
-                    .unwrap_or("-")
-                    .to_string(),
-                "Dogs?" => attrs["isGoodWithDogs"].as_str().unwrap_or("-").to_string(),
-                "Cats?" => attrs["isGoodWithCats"].as_str().unwrap_or("-").to_string(),
-                "Trained?" => attrs["isHouseTrained"].as_str().unwrap_or("-").to_string(),
-                "Special?" => attrs["isSpecialNeeds"].as_str().unwrap_or("-").to_string(),
-                _ => "-".to_string(),
+            let name = attrs["name"].as_str().unwrap_or("Unknown");
+            let link = match attrs["url"].as_str() {
+                Some(url) => format!("[{}]({})", name, url),
+                None => name.to_string(),
             };
-            markdown.push_str(&format!(" {} |", val));
+            let details: Vec<&str> = ["breedString", "ageGroup", "sex"]
+                .iter()
+                .filter_map(|field| attrs[field].as_str())
+                .collect();
+            if details.is_empty() {
+                output.push_str(&format!("- {}\n", link));
+            } else {
+                output.push_str(&format!("- {}: {}\n", link, details.join(", ")));
+            }
+        }
+        if members.len() > ORG_GROUP_ANIMALS {
+            output.push_str(&format!(
+                "- ...and {} more\n",
+                members.len() - ORG_GROUP_ANIMALS
+            ));
         }
-        markdown.push('\n');
     }
 
-    Ok(markdown)
-}
-
-pub fn format_single_org(org: &Value) -> String {
-    let attrs = &org["attributes"];
-    let name = attrs["name"].as_str().unwrap_or("Unknown");
-    let about = attrs["about"]
-        .as_str()
-        .unwrap_or("No description available.");
-    let address = attrs["street"].as_str().unwrap_or("");
-    let city = attrs["city"].as_str().unwrap_or("Unknown City");
-    let state = attrs["state"].as_str().unwrap_or("");
-    let postal_code = attrs["postalcode"].as_str().unwrap_or("");
-    let email = attrs["email"].as_str().unwrap_or("No email provided");
-    let phone = attrs["phone"].as_str().unwrap_or("No phone provided");
-    let url = attrs["url"].as_str().unwrap_or("");
-    let facebook = attrs["facebookUrl"].as_str().unwrap_or("");
-
-    format!(
-        "# {}\n\n{}\n\n**Address:** {} {} {} {}\n**Phone:** {}\n**Email:** {}\n**Website:** {}\n**Facebook:** {}",
-        name, about, address, city, state, postal_code, phone, email, url, facebook
-    )
-}
-
-pub fn format_breed_details(breed: &Value) -> String {
-    let attrs = &breed["attributes"];
-    let name = attrs["name"].as_str().unwrap_or("Unknown");
-
-    format!("# Breed: {}", name)
+    Ok(match note {
+        Some(note) => format!("⚠️ {}\n\n{}", note, output.trim_end()),
+        None => output.trim_end().to_string(),
+    })
 }
 
-pub fn format_species_results(data: &Value) -> Result<String, AppError> {
-    let species = data
+pub fn format_semantic_results(data: &Value) -> Result<String, AppError> {
+    let animals = data
         .get("data")
         .and_then(|d| d.as_array())
         .ok_or(AppError::NotFound)?;
 
-    if species.is_empty() {
-        return Ok("No species found.".to_string());
+    if animals.is_empty() {
+        return Ok("No adoptable animals found.".to_string());
     }
 
-    let mut names: Vec<String> = species
+    let results: Vec<String> = animals
         .iter()
-        .filter_map(|s| s["attributes"]["singular"].as_str().map(|n| n.to_string()))
-        .collect();
+        .map(|animal| {
+            let attrs = &animal["attributes"];
+            let name = attrs["name"].as_str().unwrap_or("Unknown");
+            let url = attrs["url"].as_str().unwrap_or("");
+            let breed = attrs["breedString"].as_str().unwrap_or("Mix");
+            let score = animal["meta"]["score"].as_f64().unwrap_or(0.0);
+            let description = truncate_text(
+                attrs["descriptionText"]
+                    .as_str()
+                    .unwrap_or("No description available."),
+                output_config().list_description_chars,
+            );
 
-    names.sort();
+            format!(
+                "### [{}]({})\n**Breed:** {}\n**Match:** {:.0}%\n\n{}",
+                name,
+                url,
+                breed,
+                score * 100.0,
+                description
+            )
+        })
+        .collect();
 
-    Ok(format!("### Supported Species\n\n{}", names.join("\n")))
+    Ok(format!(
+        "## Best matches for \"{}\"\n\n{}",
+        data["query"].as_str().unwrap_or(""),
+        results.join("\n\n---\n\n")
+    ))
 }
 
-pub fn format_metadata_results(data: &Value, metadata_type: &str) -> Result<String, AppError> {
-    let items = data
+pub fn format_match_results(data: &Value) -> Result<String, AppError> {
+    let animals = data
         .get("data")
         .and_then(|d| d.as_array())
         .ok_or(AppError::NotFound)?;
 
-    if items.is_empty() {
-        return Ok(format!("No {} found.", metadata_type));
+    if animals.is_empty() {
+        return Ok("No adoptable animals found.".to_string());
     }
 
-    let mut names: Vec<String> = items
+    let results: Vec<String> = animals
         .iter()
-        .filter_map(|i| i["attributes"]["name"].as_str().map(|n| n.to_string()))
-        .collect();
+        .map(|animal| {
+            let attrs = &animal["attributes"];
+            let reasons: Vec<String> = animal["meta"]["reasons"]
+                .as_array()
+                .map(|r| {
+                    r.iter()
+                        .filter_map(|v| v.as_str())
+                        .map(|v| format!("- {}", v))
+                        .collect()
+                })
+                .unwrap_or_default();
 
-    names.sort();
+            format!(
+                "### [{}]({}) - {}% match\n**Breed:** {} (ID: {})\n{}",
+                attrs["name"].as_str().unwrap_or("Unknown"),
+                attrs["url"].as_str().unwrap_or(""),
+                animal["meta"]["score"].as_u64().unwrap_or(0),
+                attrs["breedString"].as_str().unwrap_or("Mix"),
+                animal["id"].as_str().unwrap_or(""),
+                reasons.join("\n")
+            )
+        })
+        .collect();
 
     Ok(format!(
-        "### Supported {}\n\n{}",
-        metadata_type,
-        names.join("\n")
+        "## Your best matches\n\n{}",
+        results.join("\n\n---\n\n")
     ))
 }
 
-pub fn format_org_results(data: &Value) -> Result<String, AppError> {
-    let orgs = data
-        .get("data")
-        .and_then(|d| d.as_array())
-        .ok_or(AppError::NotFound)?;
-
-    if orgs.is_empty() {
-        return Ok("No organizations found.".to_string());
-    }
+pub fn format_parsed_query(data: &Value) -> Result<String, AppError> {
+    let args = data.get("args").ok_or(AppError::NotFound)?;
+    // Only the arguments that were set, ready to pass to search_adoptable_pets
+    let args: serde_json::Map<String, Value> = args
+        .as_object()
+        .map(|a| {
+            a.iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
 
-    let results: Vec<String> = orgs
-        .iter()
-        .take(5)
-        .map(|org| {
-            let attrs = &org["attributes"];
-            let name = attrs["name"].as_str().unwrap_or("Unknown");
-            let city = attrs["city"].as_str().unwrap_or("Unknown City");
-            let state = attrs["state"].as_str().unwrap_or("");
-            let email = attrs["email"].as_str().unwrap_or("No email provided");
-            let url = attrs["url"].as_str().unwrap_or("");
-            let id = org["id"].as_str().unwrap_or("Unknown ID");
+    let mut output = format!(
+        "### Parsed Search\n\nArguments for `search_adoptable_pets`:\n```json\n{}\n```",
+        serde_json::to_string_pretty(&args)?
+    );
 
+    let matched: Vec<String> = data["matched"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|m| {
             format!(
-                "### {}\n**ID:** {}\n**Location:** {}, {}\n**Email:** {}\n**Website:** {}",
-                name, id, city, state, email, url
+                "- \"{}\" → `{}` = {}",
+                m["term"].as_str().unwrap_or(""),
+                m["field"].as_str().unwrap_or(""),
+                m["value"]
             )
         })
         .collect();
+    if !matched.is_empty() {
+        output.push_str(&format!("\n\n**Matched:**\n{}", matched.join("\n")));
+    }
 
-    Ok(results.join("\n\n---\n\n"))
+    for note in data["notes"].as_array().into_iter().flatten() {
+        output.push_str(&format!("\n\n*Note:* {}", note.as_str().unwrap_or("")));
+    }
+
+    let unrecognized: Vec<&str> = data["unrecognized"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|w| w.as_str())
+        .collect();
+    if !unrecognized.is_empty() {
+        output.push_str(&format!(
+            "\n\n**Not understood:** {}",
+            unrecognized.join(", ")
+        ));
+    }
+    Ok(output)
 }
 
-pub fn format_breed_results(data: &Value, species: &str) -> Result<String, AppError> {
-    let breeds = data
-        .get("data")
-        .and_then(|d| d.as_array())
-        .ok_or(AppError::NotFound)?;
+const COMPARISON_ROWS: [&str; 9] = [
+    "Breed", "Age", "Sex", "Size", "Kids?", "Dogs?", "Cats?", "Trained?", "Special?",
+];
 
-    if breeds.is_empty() {
+fn comparison_value(animal: &Value, row: &str) -> String {
+    let attrs = &animal["attributes"];
+    let field = match row {
+        "Breed" => "breedString",
+        "Age" => "ageGroup",
+        "Sex" => "sex",
+        "Size" => "sizeGroup",
+        "Kids?" => "isGoodWithChildren",
+        "Dogs?" => "isGoodWithDogs",
+        "Cats?" => "isGoodWithCats",
+        "Trained?" => "isHouseTrained",
+        "Special?" => "isSpecialNeeds",
+        _ => return "-".to_string(),
+    };
+    if field == "breedString" {
+        let (parts, mixed) = breed_parts(attrs);
+        if parts.len() > 1 || mixed {
+            let names: Vec<&str> = parts.iter().map(|p| p.name.as_str()).collect();
+            return format!("{}{}", names.join(" + "), if mixed { " (mix)" } else { "" });
+        }
+    }
+    if field == "ageGroup" {
+        return age_label(attrs, render_time() / 86_400, "-");
+    }
+    if field == "isSpecialNeeds" && attrs[field].as_str() == Some("Yes") {
+        if let Some(details) = text_attr(attrs, "specialNeedsDetails") {
+            let details = truncate_text(details, COMPARISON_NEEDS_CHARS);
+            return format!("Yes: {}", details.replace('|', "/").replace('\n', " "));
+        }
+    }
+    attrs[field].as_str().unwrap_or("-").to_string()
+}
+
+pub fn format_comparison_table(data: &Value) -> Result<String, AppError> {
+    let animals = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    let mut markdown = String::new();
+    if animals.is_empty() {
+        markdown.push_str("No animals to compare.\n");
+        markdown.push_str(&format_comparison_failures(data));
+        return Ok(markdown.trim_end().to_string());
+    }
+
+    // Header Row
+    markdown.push_str("| Feature |");
+    for animal in animals {
+        let name = animal["attributes"]["name"].as_str().unwrap_or("Unknown");
+        let url = animal["attributes"]["url"].as_str().unwrap_or("");
+        markdown.push_str(&format!(" [{}]({}) |", name, url));
+    }
+    markdown.push('\n');
+
+    // Separator Row
+    markdown.push_str("| :--- |");
+    for _ in animals {
+        markdown.push_str(" :--- |");
+    }
+    markdown.push('\n');
+
+    // Data Rows
+    for row in COMPARISON_ROWS {
+        markdown.push_str(&format!("| **{}** |", row));
+        for animal in animals {
+            markdown.push_str(&format!(" {} |", comparison_value(animal, row)));
+        }
+        markdown.push('\n');
+    }
+    markdown.push_str(&format_comparison_failures(data));
+
+    Ok(markdown)
+}
+
+pub fn format_location_comparison(data: &Value) -> Result<String, AppError> {
+    let locations = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    let mut markdown = format!(
+        "### Matches by Location (within {} miles)\n\n| Location | Matches | Sample Pets |\n| :--- | ---: | :--- |\n",
+        data["miles"]
+    );
+    for location in locations {
+        let code = location["postal_code"].as_str().unwrap_or("?");
+        if let Some(error) = location["error"].as_str() {
+            markdown.push_str(&format!("| {} | - | Search failed: {} |\n", code, error));
+            continue;
+        }
+        let samples: Vec<String> = location["samples"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|a| {
+                let attrs = &a["attributes"];
+                let name = attrs["name"].as_str().unwrap_or("Unknown");
+                match attrs["url"].as_str() {
+                    Some(url) => format!("[{}]({})", name, url),
+                    None => name.to_string(),
+                }
+            })
+            .collect();
+        markdown.push_str(&format!(
+            "| {} | {} | {} |\n",
+            code,
+            location["count"],
+            if samples.is_empty() {
+                "-".to_string()
+            } else {
+                samples.join(", ")
+            }
+        ));
+    }
+    Ok(markdown.trim_end().to_string())
+}
+
+pub fn format_new_arrivals(data: &Value) -> Result<String, AppError> {
+    let groups = data
+        .get("groups")
+        .and_then(|g| g.as_array())
+        .ok_or(AppError::NotFound)?;
+    let days = &data["days"];
+    let count: usize = groups
+        .iter()
+        .map(|g| g["animals"].as_array().map_or(0, Vec::len))
+        .sum();
+
+    if count == 0 {
+        return Ok(format!(
+            "No pets were listed nearby in the last {} days.",
+            days
+        ));
+    }
+
+    let mut markdown = format!(
+        "### New Arrivals ({} {} listed in the last {} days)\n",
+        count,
+        if count == 1 { "pet" } else { "pets" },
+        days
+    );
+    for group in groups {
+        let org = match group["org_name"].as_str() {
+            Some(name) => name.to_string(),
+            None => format!(
+                "Organization {}",
+                group["org_id"].as_str().unwrap_or("unknown")
+            ),
+        };
+        markdown.push_str(&format!("\n#### {}\n", org));
+        for animal in group["animals"].as_array().into_iter().flatten() {
+            let attrs = &animal["attributes"];
+            let name = attrs["name"].as_str().unwrap_or("Unknown");
+            let title = match attrs["url"].as_str() {
+                Some(url) => format!("[{}]({})", name, url),
+                None => name.to_string(),
+            };
+            let listed = attrs["createdDate"].as_str().and_then(|d| d.get(..10));
+            markdown.push_str(&format!(
+                "- {} (ID: {}): {}, {}{}\n",
+                title,
+                animal["id"].as_str().unwrap_or("?"),
+                attrs["breedString"].as_str().unwrap_or("Mix"),
+                attrs["ageGroup"].as_str().unwrap_or("Unknown age"),
+                listed
+                    .map(|d| format!(", listed {}", d))
+                    .unwrap_or_default()
+            ));
+        }
+    }
+    if let Some(more) = more_results_note(data, count) {
+        markdown.push_str(&format!("\n{}", more));
+    }
+    Ok(markdown.trim_end().to_string())
+}
+
+/// A note naming the animals a comparison could not include and why, with
+/// the ones worth asking for again.
+fn format_comparison_failures(data: &Value) -> String {
+    let Some(errors) = data["errors"].as_array().filter(|e| !e.is_empty()) else {
+        return String::new();
+    };
+    let compared = data["data"].as_array().map_or(0, Vec::len);
+    let mut note = format!(
+        "\n> **Note:** {} of {} animals could not be compared:\n",
+        errors.len(),
+        compared + errors.len()
+    );
+    for e in errors {
+        let error = e["error"].as_str().unwrap_or("unknown error");
+        let reason = match e["kind"].as_str() {
+            Some("not_found") => {
+                "not found; the listing may have been adopted or removed".to_string()
+            }
+            Some("deadline") => {
+                "timed out; the time allowed for the comparison ran out".to_string()
+            }
+            Some("rate_limited") => "skipped; the API rate limit was reached".to_string(),
+            _ => error.to_string(),
+        };
+        note.push_str(&format!(
+            "> - `{}`: {}\n",
+            e["animal_id"].as_str().unwrap_or("?"),
+            reason
+        ));
+    }
+    let retry: Vec<&str> = errors
+        .iter()
+        .filter(|e| e["retryable"] == true)
+        .filter_map(|e| e["animal_id"].as_str())
+        .collect();
+    if !retry.is_empty() {
+        note.push_str(&format!(">\n> Try again later: {}\n", retry.join(", ")));
+    }
+    note
+}
+
+/// A saved comparison: its token, where to view it and the table.
+pub fn format_saved_comparison(data: &Value) -> Result<String, AppError> {
+    let token = data["token"].as_str().ok_or(AppError::NotFound)?;
+    let saved = format_timestamp(&data["created_at"])
+        .map(|date| format!(" (saved {})", date))
+        .unwrap_or_default();
+    Ok(format!(
+        "### Comparison `{}`{}\n\nShare it with the token `{}`, or as the page `/c/{}` on the HTTP server.\n\n{}",
+        token,
+        saved,
+        token,
+        token,
+        data["table"].as_str().unwrap_or("")
+    ))
+}
+
+/// Standalone HTML page of a saved comparison (see `save_comparison`).
+pub fn format_comparison_html(data: &Value) -> Result<String, AppError> {
+    let animals = data
+        .get("animals")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    let mut header = String::from("<tr><th>Feature</th>");
+    for animal in animals {
+        header.push_str(&format!(
+            "<th><a href=\"{}\">{}</a></th>",
+            escape_html(animal["attributes"]["url"].as_str().unwrap_or("")),
+            escape_html(animal["attributes"]["name"].as_str().unwrap_or("Unknown"))
+        ));
+    }
+    header.push_str("</tr>");
+
+    let rows: Vec<String> = COMPARISON_ROWS
+        .iter()
+        .map(|row| {
+            let cells: String = animals
+                .iter()
+                .map(|animal| format!("<td>{}</td>", escape_html(&comparison_value(animal, row))))
+                .collect();
+            format!("<tr><th>{}</th>{}</tr>", row, cells)
+        })
+        .collect();
+
+    let saved = format_timestamp(&data["created_at"])
+        .map(|date| format!("<p>Saved {}</p>\n", date))
+        .unwrap_or_default();
+
+    Ok(format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+<title>Pet Comparison</title>\n\
+<style>table{{border-collapse:collapse}}th,td{{border:1px solid #ccc;padding:.4em .6em;text-align:left}}</style>\n\
+</head>\n<body>\n<h1>Pet Comparison</h1>\n{}<table>\n{}\n{}\n</table>\n</body>\n</html>\n",
+        saved,
+        header,
+        rows.join("\n")
+    ))
+}
+
+pub fn format_single_org(org: &Value) -> String {
+    let attrs = &org["attributes"];
+    let name = attrs["name"].as_str().unwrap_or("Unknown");
+    let about = truncate_text(
+        attrs["about"]
+            .as_str()
+            .unwrap_or("No description available."),
+        output_config().detail_description_chars,
+    );
+    let address = attrs["street"].as_str().unwrap_or("");
+    let city = attrs["city"].as_str().unwrap_or("Unknown City");
+    let state = attrs["state"].as_str().unwrap_or("");
+    let postal_code = attrs["postalcode"].as_str().unwrap_or("");
+    let email = attrs["email"].as_str().unwrap_or("No email provided");
+    let phone = attrs["phone"].as_str().unwrap_or("No phone provided");
+    let url = attrs["url"].as_str().unwrap_or("");
+    let facebook = attrs["facebookUrl"].as_str().unwrap_or("");
+
+    format!(
+        "# {}\n\n{}\n\n**Address:** {} {} {} {}\n**Phone:** {}\n**Email:** {}\n**Website:** {}\n**Facebook:** {}",
+        name, about, address, city, state, postal_code, phone, email, url, facebook
+    )
+}
+
+/// Markdown for the contact block built by `get_org_contact`, listing only
+/// the details the organization has filled in.
+pub fn format_org_contact(contact: &Value) -> String {
+    let name = contact["name"].as_str().unwrap_or("Unknown Organization");
+    let mut lines = Vec::new();
+    let mut add = |label: &str, value: Option<String>| {
+        if let Some(value) = value {
+            lines.push(format!("**{}:** {}", label, value));
+        }
+    };
+    let text = |key: &str| contact[key].as_str().map(str::to_string);
+    let link = |key: &str| contact[key].as_str().map(|u| format!("[{}]({})", u, u));
+
+    add("Email", text("email"));
+    add("Phone", text("phone"));
+    let address: Vec<&str> = ["street", "city", "state", "postal_code", "country"]
+        .iter()
+        .filter_map(|key| contact["address"][key].as_str())
+        .collect();
+    add("Address", (!address.is_empty()).then(|| address.join(", ")));
+    add("Website", link("website"));
+    add("Adoption Page", link("adoption_url"));
+    add("Adoption Process", text("adoption_process"));
+    add("Meet and Greet", text("meet_and_greet"));
+    if let Some(social) = contact["social"].as_object() {
+        for (network, url) in social {
+            let url = url.as_str().unwrap_or("");
+            add(
+                &format!("{}{}", network[..1].to_uppercase(), &network[1..]),
+                Some(format!("[{}]({})", url, url)),
+            );
+        }
+    }
+
+    if lines.is_empty() {
+        return format!(
+            "## Contact: {}\n\nNo contact details are listed for this organization.",
+            name
+        );
+    }
+    format!("## Contact: {}\n\n{}", name, lines.join("\n"))
+}
+
+/// Markdown gallery for `get_animal_photos`: each photo with its caption and
+/// the sizes it is available in.
+pub fn format_animal_photos(gallery: &Value) -> String {
+    let name = gallery["name"].as_str().unwrap_or("this pet");
+    let photos = gallery["photos"].as_array().map_or(&[][..], Vec::as_slice);
+    if photos.is_empty() {
+        return format!("No photos of {}.", name);
+    }
+
+    let mut output = format!("## Photos of {} ({})\n", name, photos.len());
+    for photo in photos {
+        let caption = photo["caption"].as_str();
+        output.push_str(&format!(
+            "\n### Photo {}{}\n",
+            photo["order"],
+            caption.map(|c| format!(": {}", c)).unwrap_or_default()
+        ));
+        if let Some(url) = photo["url"].as_str() {
+            output.push_str(&format!("![{}]({})\n", caption.unwrap_or(name), url));
+        }
+        let sizes: Vec<String> = photo["renditions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|r| {
+                let name = r["name"].as_str().unwrap_or("photo");
+                match (r["width"].as_u64(), r["height"].as_u64()) {
+                    (Some(w), Some(h)) => format!(
+                        "[{} {}×{}]({})",
+                        name,
+                        w,
+                        h,
+                        r["url"].as_str().unwrap_or("")
+                    ),
+                    _ => format!("[{}]({})", name, r["url"].as_str().unwrap_or("")),
+                }
+            })
+            .collect();
+        if !sizes.is_empty() {
+            output.push_str(&format!("**Sizes:** {}\n", sizes.join(", ")));
+        }
+    }
+    output
+}
+
+/// The block built by `get_adoption_process` as numbered steps, skipping
+/// the ones the organization has no details for.
+pub fn format_adoption_process(process: &Value) -> String {
+    let text = |key: &str| process[key].as_str();
+    let subject = match (text("animal_name"), text("org_name")) {
+        (Some(animal), Some(org)) => format!("{} from {}", animal, org),
+        (Some(animal), None) => animal.to_string(),
+        (None, Some(org)) => format!("from {}", org),
+        (None, None) => "this pet".to_string(),
+    };
+
+    let mut steps = Vec::new();
+    if let Some(area) = text("service_area") {
+        steps.push(format!(
+            "**Check the service area.** They adopt to: {}",
+            area
+        ));
+    }
+    if let Some(process) = text("adoption_process") {
+        steps.push(format!("**Read the adoption process.** {}", process));
+    }
+    if let Some(fee) = text("fee") {
+        steps.push(format!("**Review the fee.** {}", fee));
+    }
+    match (text("adoption_url"), text("application_url")) {
+        (Some(adoption), Some(listing)) if adoption != listing => steps.push(format!(
+            "**Apply.** Start at [{}]({}); the pet's listing is [{}]({}).",
+            adoption, adoption, listing, listing
+        )),
+        (Some(url), _) | (None, Some(url)) => {
+            steps.push(format!("**Apply.** Start at [{}]({}).", url, url))
+        }
+        (None, None) => {}
+    }
+    let contact: Vec<&str> = ["email", "phone"].iter().filter_map(|k| text(k)).collect();
+    if !contact.is_empty() {
+        steps.push(format!(
+            "**Ask questions.** Contact them at {}.",
+            contact.join(" or ")
+        ));
+    }
+
+    if steps.is_empty() {
+        return format!(
+            "## Adopting {}\n\nThe organization hasn't published adoption details. Contact them directly to ask how to apply.",
+            subject
+        );
+    }
+    let steps: Vec<String> = steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| format!("{}. {}", i + 1, step))
+        .collect();
+    format!("## Adopting {}\n\n{}", subject, steps.join("\n"))
+}
+
+/// The block built by `get_sponsorship_info`, for users who can't adopt
+/// right now.
+pub fn format_sponsorship_info(info: &Value) -> String {
+    let text = |key: &str| info[key].as_str();
+    let org = text("org_name").unwrap_or("the organization");
+    let mut output = match text("animal_name") {
+        Some(animal) => format!("## Helping {} and {}\n\n", animal, org),
+        None => format!("## Helping {}\n\n", org),
+    };
+
+    let mut ways = Vec::new();
+    if info["sponsorable"] == true {
+        let mut line = "**Sponsor:** This pet can be sponsored".to_string();
+        if let Some(minimum) = text("sponsorship_minimum") {
+            line.push_str(&format!(" (minimum {})", minimum));
+        }
+        line.push('.');
+        if let Some(description) = text("sponsorship_description") {
+            line.push_str(&format!(" {}", description));
+        }
+        ways.push(line);
+    }
+    if let Some(url) = text("sponsorship_url") {
+        ways.push(format!("**Sponsorship Program:** [{}]({})", url, url));
+    }
+    if info["needs_foster"] == true {
+        ways.push(
+            "**Foster:** This pet needs a foster home; ask the organization about fostering."
+                .to_string(),
+        );
+    }
+    if let Some(url) = text("donation_url") {
+        ways.push(format!("**Donate:** [{}]({})", url, url));
+    }
+
+    if ways.is_empty() {
+        output.push_str("No sponsorship or donation details are listed. Contact the organization to ask how to help.");
+    } else {
+        output.push_str(&ways.join("\n"));
+    }
+    output
+}
+
+pub fn format_breed_details(breed: &Value) -> String {
+    let attrs = &breed["attributes"];
+    let name = attrs["name"].as_str().unwrap_or("Unknown");
+
+    format!("# Breed: {}", name)
+}
+
+pub fn format_species_results(data: &Value) -> Result<String, AppError> {
+    let species = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    if species.is_empty() {
+        return Ok("No species found.".to_string());
+    }
+
+    let mut names: Vec<String> = species
+        .iter()
+        .filter_map(|s| s["attributes"]["singular"].as_str().map(|n| n.to_string()))
+        .collect();
+
+    names.sort();
+
+    Ok(format!("### Supported Species\n\n{}", names.join("\n")))
+}
+
+pub fn format_location_results(data: &Value) -> Result<String, AppError> {
+    let matches = data
+        .get("matches")
+        .and_then(|m| m.as_array())
+        .ok_or(AppError::NotFound)?;
+    let query = data["query"].as_str().unwrap_or("");
+
+    if matches.is_empty() {
+        return Ok(format!(
+            "No match for '{}' in the postal database ({} entries). It may still be valid; the search API will check it.",
+            query, data["dataset_size"]
+        ));
+    }
+
+    let rows: Vec<String> = matches
+        .iter()
+        .map(|m| {
+            format!(
+                "- **{}** {}, {} ({}) — {:.4}, {:.4}",
+                m["code"].as_str().unwrap_or(""),
+                m["place"].as_str().unwrap_or(""),
+                m["state_code"].as_str().unwrap_or(""),
+                m["country"].as_str().unwrap_or(""),
+                m["latitude"].as_f64().unwrap_or(0.0),
+                m["longitude"].as_f64().unwrap_or(0.0)
+            )
+        })
+        .collect();
+    Ok(format!(
+        "### Locations for '{}'\n\n{}",
+        query,
+        rows.join("\n")
+    ))
+}
+
+pub fn format_metadata_results(data: &Value, metadata_type: &str) -> Result<String, AppError> {
+    let items = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    if items.is_empty() {
+        return Ok(format!("No {} found.", metadata_type));
+    }
+
+    let mut names: Vec<String> = items
+        .iter()
+        .filter_map(|i| i["attributes"]["name"].as_str().map(|n| n.to_string()))
+        .collect();
+
+    names.sort();
+
+    Ok(format!(
+        "### Supported {}\n\n{}",
+        metadata_type,
+        names.join("\n")
+    ))
+}
+
+pub fn format_org_results(data: &Value) -> Result<String, AppError> {
+    let orgs = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    if orgs.is_empty() {
+        return Ok("No organizations found.".to_string());
+    }
+
+    let results: Vec<String> = orgs
+        .iter()
+        .take(5)
+        .map(|org| {
+            let attrs = &org["attributes"];
+            let name = attrs["name"].as_str().unwrap_or("Unknown");
+            let city = attrs["city"].as_str().unwrap_or("Unknown City");
+            let state = attrs["state"].as_str().unwrap_or("");
+            let email = attrs["email"].as_str().unwrap_or("No email provided");
+            let url = attrs["url"].as_str().unwrap_or("");
+            let id = org["id"].as_str().unwrap_or("Unknown ID");
+
+            let listings = attrs["speciesListings"]
+                .as_u64()
+                .zip(data["species"].as_str())
+                .map(|(n, species)| format!("\n**Listing nearby:** {} {}", n, species))
+                .unwrap_or_default();
+            format!(
+                "### {}\n**ID:** {}\n**Location:** {}, {}\n**Email:** {}\n**Website:** {}{}",
+                name, id, city, state, email, url, listings
+            )
+        })
+        .collect();
+
+    let mut output = results.join("\n\n---\n\n");
+    if let Some(more) = more_results_note(data, results.len()) {
+        output.push_str(&more);
+    }
+    Ok(output)
+}
+
+/// Markdown for `explain_filters`: one section per field.
+pub fn format_filter_reference(data: &Value) -> Result<String, AppError> {
+    let fields = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+    let list = |value: &Value| {
+        value
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut output = format!(
+        "# Search Filters ({})\n**Operations:** {}\n",
+        data["species"].as_str().unwrap_or("-"),
+        list(&data["operations"])
+    );
+    for field in fields {
+        output.push_str(&format!(
+            "\n## {}\n{}\n- **Set by:** `{}`\n- **Operations:** {}\n- **Examples:** {}\n",
+            field["field"].as_str().unwrap_or("-"),
+            field["description"].as_str().unwrap_or(""),
+            field["argument"].as_str().unwrap_or("-"),
+            list(&field["operations"]),
+            list(&field["examples"])
+        ));
+        if field.get("values").is_some() {
+            let shown = field["values"].as_array().map_or(0, Vec::len) as u64;
+            let more = match field["values_total"].as_u64() {
+                Some(total) if total > shown => format!(" (and {} more)", total - shown),
+                _ => String::new(),
+            };
+            output.push_str(&format!(
+                "- **Values:** {}{}\n",
+                list(&field["values"]),
+                more
+            ));
+        }
+    }
+    Ok(output)
+}
+
+/// Markdown for `find_breed_rescues`, saying why each organization is
+/// thought to specialize in the breed.
+pub fn format_breed_rescues(data: &Value) -> Result<String, AppError> {
+    let breed = data["breed"].as_str().unwrap_or("this breed");
+    let orgs = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+    if orgs.is_empty() {
+        return Ok(format!(
+            "No {} rescues found nearby. Try a wider radius or search_organizations.",
+            breed
+        ));
+    }
+
+    let results: Vec<String> = orgs
+        .iter()
+        .map(|org| {
+            let mut reasons = Vec::new();
+            if org["matched_name"] == true {
+                reasons.push(format!("name mentions {}", breed));
+            }
+            if org["matched_description"] == true {
+                reasons.push(format!("description mentions {}", breed));
+            }
+            match org["breed_listings"].as_u64().unwrap_or(0) {
+                0 => {}
+                1 => reasons.push(format!("lists 1 {} nearby", breed)),
+                n => reasons.push(format!("lists {} {}s nearby", n, breed)),
+            }
+
+            let mut lines = vec![
+                format!(
+                    "### {} (ID: {})",
+                    org["name"].as_str().unwrap_or("Unknown Organization"),
+                    org["org_id"].as_str().unwrap_or("unknown")
+                ),
+                format!("**Why:** {}", reasons.join("; ")),
+            ];
+            let place: Vec<&str> = ["city", "state"]
+                .iter()
+                .filter_map(|key| org[*key].as_str())
+                .collect();
+            if !place.is_empty() {
+                lines.push(format!("**Location:** {}", place.join(", ")));
+            }
+            for (label, key) in [("Email", "email"), ("Phone", "phone"), ("Website", "url")] {
+                if let Some(value) = org[key].as_str().filter(|v| !v.is_empty()) {
+                    lines.push(format!("**{}:** {}", label, value));
+                }
+            }
+            lines.join("\n")
+        })
+        .collect();
+    Ok(format!(
+        "## {} Rescues ({})\n\n{}",
+        breed,
+        results.len(),
+        results.join("\n\n---\n\n")
+    ))
+}
+
+/// A closing line when a listing matched more than the `shown` entries,
+/// either beyond the page fetched or beyond what is displayed.
+/// Stored search preferences, strongest first.
+pub fn format_preferences(data: &Value) -> String {
+    let mut weights: Vec<(&String, u64)> = data["weights"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(name, weight)| (name, weight.as_u64().unwrap_or(0)))
+        .collect();
+    let required: Vec<&str> = data["required"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    if weights.is_empty() && required.is_empty() {
+        return "Preferences cleared; searches are listed in the API's order.".to_string();
+    }
+    weights.sort_by_key(|(_, weight)| std::cmp::Reverse(*weight));
+
+    let mut output = "## Search Preferences\n".to_string();
+    for (name, weight) in weights {
+        output.push_str(&format!("- **{}**: weight {}\n", name, weight));
+    }
+    for name in required {
+        output.push_str(&format!("- **{}**: required\n", name));
+    }
+    output.push_str("\nSearches in this session are ranked by these preferences.");
+    output
+}
+
+fn more_results_note(data: &Value, shown: usize) -> Option<String> {
+    let fetched = data["data"].as_array().map_or(0, Vec::len) as u64;
+    let total = data["total"].as_u64().unwrap_or(fetched).max(fetched);
+    if total > shown as u64 {
+        Some(format!(
+            "\n\n_Showing {} of {} matches. Narrow the search, or ask for more, to see the others._",
+            shown, total
+        ))
+    } else if data["truncated"] == true {
+        Some(format!(
+            "\n\n_Showing {} matches; there are more. Narrow the search, or ask for more, to see the others._",
+            shown
+        ))
+    } else {
+        None
+    }
+}
+
+pub fn format_breed_results(data: &Value, species: &str) -> Result<String, AppError> {
+    let breeds = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    if breeds.is_empty() {
         return Ok(format!("No breeds found for species '{}'.", species));
     }
 
-    let mut breed_names: Vec<String> = breeds
+    let mut breed_names: Vec<String> = breeds
+        .iter()
+        .filter_map(|b| b["attributes"]["name"].as_str().map(|s| s.to_string()))
+        .collect();
+
+    breed_names.sort();
+
+    Ok(format!(
+        "### Breeds for {}\n\n{}",
+        species,
+        breed_names.join("\n")
+    ))
+}
+
+pub fn format_breed_suggestions(data: &Value) -> Result<String, AppError> {
+    let suggestions = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+    let query = data["query"].as_str().unwrap_or("");
+    let species = data["species"].as_str().unwrap_or("");
+
+    if suggestions.is_empty() {
+        return Ok(format!(
+            "No {} breeds resemble '{}'. Use list_breeds to see them all.",
+            species, query
+        ));
+    }
+
+    let lines: Vec<String> = suggestions
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            format!(
+                "{}. {} (ID: {})",
+                i + 1,
+                b["name"].as_str().unwrap_or("Unknown"),
+                b["id"].as_str().unwrap_or("?")
+            )
+        })
+        .collect();
+    Ok(format!(
+        "### {} breeds like '{}'\n\n{}",
+        species,
+        query,
+        lines.join("\n")
+    ))
+}
+
+pub fn format_saved_searches(data: &Value) -> Result<String, AppError> {
+    let searches = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    if searches.is_empty() {
+        return Ok("No saved searches.".to_string());
+    }
+
+    let results: Vec<String> = searches
+        .iter()
+        .map(|search| {
+            let name = search["name"].as_str().unwrap_or("Unknown");
+            let filters: Vec<String> = search["args"]
+                .as_object()
+                .map(|args| {
+                    args.iter()
+                        .filter(|(_, v)| !v.is_null())
+                        .map(|(k, v)| {
+                            format!(
+                                "{}={}",
+                                k,
+                                v.as_str()
+                                    .map(|s| s.to_string())
+                                    .unwrap_or_else(|| v.to_string())
+                            )
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let filters = if filters.is_empty() {
+                "server defaults".to_string()
+            } else {
+                filters.join(", ")
+            };
+            let seen = search["seen_ids"].as_array().map(|a| a.len()).unwrap_or(0);
+            format!(
+                "### {}\n**Filters:** {}\n**Known Matches:** {}",
+                name, filters, seen
+            )
+        })
+        .collect();
+
+    Ok(results.join("\n\n---\n\n"))
+}
+
+pub fn format_favorites(data: &Value) -> Result<String, AppError> {
+    let favorites = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    if favorites.is_empty() {
+        return Ok("No favorites saved yet.".to_string());
+    }
+
+    let results: Vec<String> = favorites
+        .iter()
+        .map(|fav| {
+            let mut line = format!(
+                "- **{}** (ID: {}) - {} [Link]({})",
+                fav["name"].as_str().unwrap_or("Unknown"),
+                fav["id"].as_str().unwrap_or(""),
+                fav["breed"].as_str().unwrap_or("Mix"),
+                fav["url"].as_str().unwrap_or("")
+            );
+            if let Some(note) = fav["note"].as_str() {
+                line.push_str(&format!("\n  *Note:* {}", note));
+            }
+            line
+        })
+        .collect();
+
+    let ids: Vec<&str> = favorites.iter().filter_map(|f| f["id"].as_str()).collect();
+    Ok(format!(
+        "### Favorites ({})\n\n{}\n\n**Animal IDs:** {}",
+        favorites.len(),
+        results.join("\n"),
+        ids.join(",")
+    ))
+}
+
+/// What changed for each checked animal since its last check, changes
+/// first.
+pub fn format_updates(data: &Value) -> Result<String, AppError> {
+    let animals = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    let line = |animal: &Value| {
+        let name = animal["name"].as_str().unwrap_or("Unknown");
+        let id = animal["id"].as_str().unwrap_or("");
+        match animal["change"].as_str().unwrap_or("") {
+            "new" => format!("- **{}** (ID: {}): first check, now tracked", name, id),
+            "unavailable" => format!("- **{}** (ID: {}): ❌ no longer available", name, id),
+            "available_again" => format!("- **{}** (ID: {}): ✅ available again", name, id),
+            "status_changed" => format!(
+                "- **{}** (ID: {}): status changed from {} to {}",
+                name,
+                id,
+                animal["previous_status"].as_str().unwrap_or("unknown"),
+                animal["status"].as_str().unwrap_or("unknown")
+            ),
+            "updated" => {
+                let changes: Vec<String> = animal["changes"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|c| {
+                        let field = c["field"].as_str().unwrap_or("");
+                        let value = |v: &Value| match v {
+                            Value::Null => "none".to_string(),
+                            Value::String(s) => truncate_text(s, CHANGE_VALUE_CHARS).into_owned(),
+                            other => other.to_string(),
+                        };
+                        if field == "Description" {
+                            format!("  - {} was rewritten", field)
+                        } else {
+                            format!(
+                                "  - {}: {} → {}",
+                                field,
+                                value(&c["before"]),
+                                value(&c["after"])
+                            )
+                        }
+                    })
+                    .collect();
+                format!(
+                    "- **{}** (ID: {}): updated\n{}",
+                    name,
+                    id,
+                    changes.join("\n")
+                )
+            }
+            "error" => format!(
+                "- ID {}: couldn't be checked ({})",
+                id,
+                animal["error"].as_str().unwrap_or("unknown error")
+            ),
+            _ => format!("- **{}** (ID: {}): unchanged", name, id),
+        }
+    };
+
+    let (unchanged, changed): (Vec<&Value>, Vec<&Value>) =
+        animals.iter().partition(|a| a["change"] == "unchanged");
+    let mut text = format!(
+        "### Updates ({} of {} changed)\n\n",
+        changed
+            .iter()
+            .filter(|a| a["change"] != "new" && a["change"] != "error")
+            .count(),
+        animals.len()
+    );
+    let lines: Vec<String> = changed.into_iter().chain(unchanged).map(line).collect();
+    text.push_str(&lines.join("\n"));
+    Ok(text)
+}
+
+/// The favorites as a Markdown document to print or share: each animal's
+/// photo, key attributes, organization contact and the user's notes.
+pub fn format_shortlist(data: &Value) -> Result<String, AppError> {
+    let animals = data
+        .get("animals")
+        .and_then(|a| a.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    let mut text = String::from("# Adoption Shortlist\n\n");
+    if let Some(date) = format_timestamp(&data["exported_at"]) {
+        text.push_str(&format!("*Exported {}*\n\n", date));
+    }
+    if animals.is_empty() {
+        text.push_str("No favorites saved yet.\n");
+        return Ok(text);
+    }
+
+    for animal in animals {
+        let name = animal["name"].as_str().unwrap_or("Unknown");
+        match animal["url"].as_str().filter(|u| !u.is_empty()) {
+            Some(url) => text.push_str(&format!("## [{}]({})\n\n", name, url)),
+            None => text.push_str(&format!("## {}\n\n", name)),
+        }
+        text.push_str(&format!(
+            "**ID:** {}\n\n",
+            animal["id"].as_str().unwrap_or("")
+        ));
+        if animal["available"] != true {
+            text.push_str("**No longer available** (details as saved)\n\n");
+        }
+        if let Some(photo) = animal["photo"].as_str() {
+            text.push_str(&format!("![{}]({})\n\n", name, photo));
+        }
+
+        let attrs = &animal["attributes"];
+        let rows: Vec<String> = SHORTLIST_FIELDS
+            .iter()
+            .filter_map(|(label, field)| {
+                let value = attrs[field].as_str().filter(|v| !v.is_empty())?;
+                Some(format!("| {} | {} |", label, value))
+            })
+            .collect();
+        if !rows.is_empty() {
+            text.push_str(&format!("| | |\n|---|---|\n{}\n\n", rows.join("\n")));
+        }
+
+        let org = &animal["org"];
+        if let Some(org_name) = org["name"].as_str() {
+            let mut contact = vec![format!("**Organization:** {}", org_name)];
+            let location: Vec<&str> = [&org["city"], &org["state"]]
+                .into_iter()
+                .filter_map(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .collect();
+            if !location.is_empty() {
+                contact.push(format!("**Location:** {}", location.join(", ")));
+            }
+            for (label, field) in [("Phone", "phone"), ("Email", "email"), ("Website", "url")] {
+                if let Some(value) = org[field].as_str().filter(|v| !v.is_empty()) {
+                    contact.push(format!("**{}:** {}", label, value));
+                }
+            }
+            text.push_str(&format!("{}\n\n", contact.join("  \n")));
+        }
+
+        if let Some(note) = animal["note"].as_str() {
+            text.push_str(&format!("**Note:** {}\n\n", note));
+        }
+        let notes: Vec<String> = animal["notes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|note| {
+                format!(
+                    "- **{}**: {}",
+                    format_timestamp(&note["added_at"]).unwrap_or_default(),
+                    note["text"].as_str().unwrap_or("")
+                )
+            })
+            .collect();
+        if !notes.is_empty() {
+            text.push_str(&format!("**Notes:**\n{}\n\n", notes.join("\n")));
+        }
+    }
+    Ok(text.trim_end().to_string() + "\n")
+}
+
+/// The effective configuration as `key = value` lines, each followed by
+/// the layer it came from when `origins` is set.
+pub fn format_config(data: &Value, origins: bool) -> Result<String, AppError> {
+    let values = data
+        .get("values")
+        .and_then(|v| v.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    let files: Vec<String> = data["files"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|f| {
+            format!(
+                "#   {}: {}",
+                f["layer"].as_str().unwrap_or(""),
+                f["path"].as_str().unwrap_or("")
+            )
+        })
+        .collect();
+    let mut text = if files.is_empty() {
+        "# No configuration files found\n".to_string()
+    } else {
+        format!(
+            "# Configuration files, lowest precedence first:\n{}\n",
+            files.join("\n")
+        )
+    };
+    if values.is_empty() {
+        text.push_str("# Nothing set: built-in defaults apply\n");
+    }
+    for value in values {
+        let line = format!(
+            "{} = {}",
+            value["key"].as_str().unwrap_or(""),
+            value["value"]
+        );
+        match value["origin"].as_str().filter(|_| origins) {
+            Some(origin) => text.push_str(&format!("{:<40} # {}\n", line, origin)),
+            None => text.push_str(&format!("{}\n", line)),
+        }
+    }
+    Ok(text.trim_end().to_string())
+}
+
+/// Journal entries from `notes`, oldest first.
+pub fn format_notes(data: &Value) -> Result<String, AppError> {
+    let notes = data
+        .get("notes")
+        .and_then(|n| n.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    if notes.is_empty() {
+        return Ok(format!(
+            "No notes for animal {}.",
+            data["animal_id"].as_str().unwrap_or("")
+        ));
+    }
+
+    let results: Vec<String> = notes
+        .iter()
+        .map(|note| {
+            format!(
+                "- **{}**: {}",
+                format_timestamp(&note["added_at"]).unwrap_or_default(),
+                note["text"].as_str().unwrap_or("")
+            )
+        })
+        .collect();
+
+    Ok(format!(
+        "### Notes ({})\n\n{}",
+        notes.len(),
+        results.join("\n")
+    ))
+}
+
+/// Animal details followed by the user's notes on the animal, if any.
+pub fn format_animal_with_notes(data: &Value) -> Result<String, AppError> {
+    let animal_data = data.get("data").ok_or(AppError::NotFound)?;
+    let animal = extract_single_item(animal_data).ok_or(AppError::NotFound)?;
+    let foster = foster_location(data, animal);
+    let mut text = format_animal_profile(animal, foster.as_deref());
+    if data.get("notes").is_some() {
+        text.push_str("\n\n");
+        text.push_str(&format_notes(data)?);
+    }
+    Ok(text)
+}
+
+pub fn format_alerts(data: &Value) -> Result<String, AppError> {
+    let alerts = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    if alerts.is_empty() {
+        return Ok("No new matches for saved searches.".to_string());
+    }
+
+    let results: Vec<String> = alerts
+        .iter()
+        .map(|alert| {
+            let animal = &alert["animal"];
+            format!(
+                "- **{}** matched [{}]({}) ({})",
+                alert["search_name"].as_str().unwrap_or("Unknown"),
+                animal["name"].as_str().unwrap_or("Unknown"),
+                animal["url"].as_str().unwrap_or(""),
+                animal["breed"].as_str().unwrap_or("Mix")
+            )
+        })
+        .collect();
+
+    Ok(format!(
+        "### New Matches ({})\n\n{}",
+        alerts.len(),
+        results.join("\n")
+    ))
+}
+
+/// "2024-05-01 10:00 UTC" for a Unix timestamp.
+fn format_timestamp(value: &Value) -> Option<String> {
+    let date = crate::feeds::rfc3339(value.as_u64()?);
+    Some(format!("{} {} UTC", &date[..10], &date[11..16]))
+}
+
+fn digest_summary(search: &Value) -> String {
+    format!(
+        "{} new, {} no longer listed, {} total",
+        search["new"].as_array().map_or(0, |a| a.len()),
+        search["removed"].as_array().map_or(0, |a| a.len()),
+        search["total"]
+    )
+}
+
+/// Markdown table of the recorded tool usage.
+pub fn format_usage_report(data: &Value) -> Result<String, AppError> {
+    let tools = data
+        .get("tools")
+        .and_then(|t| t.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    let mut output = String::from("### Tool Usage\n");
+    if data["enabled"] == false {
+        output.push_str(
+            "\n*Recording is off; set `enabled = true` under `[metrics]` to collect statistics.*\n",
+        );
+    }
+    if tools.is_empty() {
+        output.push_str("\nNo tool calls recorded yet.");
+        return Ok(output);
+    }
+    if let Some(since) = data["since"].as_u64() {
+        output.push_str(&format!("\nSince {}\n", crate::feeds::rfc3339(since)));
+    }
+
+    output.push_str(
+        "\n| Tool | Calls | Errors | Error Rate | Avg (ms) | Max (ms) | Top Arguments |\n|---|---|---|---|---|---|---|\n",
+    );
+    for tool in tools {
+        let mut arguments: Vec<(&String, u64)> = tool["arguments"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(name, count)| (name, count.as_u64().unwrap_or(0)))
+            .collect();
+        arguments.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        let arguments: Vec<String> = arguments
+            .iter()
+            .take(3)
+            .map(|(name, count)| format!("{} ({})", name, count))
+            .collect();
+        output.push_str(&format!(
+            "| {} | {} | {} | {:.1}% | {} | {} | {} |\n",
+            tool["name"].as_str().unwrap_or(""),
+            tool["calls"],
+            tool["errors"],
+            tool["error_rate"].as_f64().unwrap_or(0.0) * 100.0,
+            tool["avg_ms"],
+            tool["max_ms"],
+            arguments.join(", ")
+        ));
+    }
+
+    let failures: Vec<String> = tools
+        .iter()
+        .filter_map(|tool| {
+            let kinds = tool["error_kinds"].as_object().filter(|k| !k.is_empty())?;
+            let kinds: Vec<String> = kinds
+                .iter()
+                .map(|(kind, count)| format!("{} ×{}", kind, count))
+                .collect();
+            Some(format!(
+                "- **{}**: {}",
+                tool["name"].as_str().unwrap_or(""),
+                kinds.join(", ")
+            ))
+        })
+        .collect();
+    if !failures.is_empty() {
+        output.push_str(&format!("\n**Failures:**\n{}\n", failures.join("\n")));
+    }
+
+    let endpoints = data["endpoints"].as_array().map_or(&[][..], Vec::as_slice);
+    if !endpoints.is_empty() {
+        output.push_str(
+            "\n### Upstream Latency\n\n| Endpoint | Requests | Errors | p50 (ms) | p90 (ms) | p99 (ms) | Max (ms) |\n|---|---|---|---|---|---|---|\n",
+        );
+        for endpoint in endpoints {
+            output.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} | {} | {} |\n",
+                endpoint["endpoint"].as_str().unwrap_or(""),
+                endpoint["requests"],
+                endpoint["errors"],
+                endpoint["p50_ms"],
+                endpoint["p90_ms"],
+                endpoint["p99_ms"],
+                endpoint["max_ms"]
+            ));
+        }
+    }
+    Ok(output.trim_end().to_string())
+}
+
+/// Markdown "what's new" report of a digest run.
+pub fn format_digest(data: &Value) -> Result<String, AppError> {
+    let searches = data
+        .get("searches")
+        .and_then(|s| s.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    let mut output = match format_timestamp(&data["since"]) {
+        Some(since) => format!("# What's New Since {}\n", since),
+        None => "# What's New\n".to_string(),
+    };
+
+    for search in searches {
+        let name = search["name"].as_str().unwrap_or("Unknown");
+        if let Some(error) = search["error"].as_str() {
+            output.push_str(&format!("\n## {}\n\n⚠️ Search failed: {}\n", name, error));
+            continue;
+        }
+        if search["baseline"].as_bool().unwrap_or(false) {
+            output.push_str(&format!(
+                "\n## {}\n\nBaseline recorded ({} animals); changes are reported from the next run.\n",
+                name, search["total"]
+            ));
+            continue;
+        }
+
+        output.push_str(&format!("\n## {} ({})\n", name, digest_summary(search)));
+        for animal in search["new"].as_array().into_iter().flatten() {
+            output.push_str(&format!(
+                "\n- [{}]({}) - {}, {}",
+                animal["name"].as_str().unwrap_or("Unknown"),
+                animal["url"].as_str().unwrap_or(""),
+                animal["breed"].as_str().unwrap_or("Mix"),
+                animal["age"].as_str().unwrap_or("Unknown age")
+            ));
+        }
+        let removed: Vec<&str> = search["removed"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|a| a["name"].as_str())
+            .collect();
+        if !removed.is_empty() {
+            output.push_str(&format!("\n\n*No longer listed:* {}", removed.join(", ")));
+        }
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+/// HTML rendering of `format_digest`, for piping into email.
+pub fn format_digest_html(data: &Value) -> Result<String, AppError> {
+    let searches = data
+        .get("searches")
+        .and_then(|s| s.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    let mut output = match format_timestamp(&data["since"]) {
+        Some(since) => format!("<h2>What's New Since {}</h2>\n", since),
+        None => "<h2>What's New</h2>\n".to_string(),
+    };
+
+    for search in searches {
+        let name = escape_html(search["name"].as_str().unwrap_or("Unknown"));
+        if let Some(error) = search["error"].as_str() {
+            output.push_str(&format!(
+                "<h3>{}</h3>\n<p>Search failed: {}</p>\n",
+                name,
+                escape_html(error)
+            ));
+            continue;
+        }
+        if search["baseline"].as_bool().unwrap_or(false) {
+            output.push_str(&format!(
+                "<h3>{}</h3>\n<p>Baseline recorded ({} animals); changes are reported from the next run.</p>\n",
+                name, search["total"]
+            ));
+            continue;
+        }
+
+        let items: Vec<String> = search["new"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|animal| {
+                let picture = animal["picture"]
+                    .as_str()
+                    .map(|p| {
+                        format!(
+                            "<br><img src=\"{}\" alt=\"\" width=\"200\">",
+                            escape_html(p)
+                        )
+                    })
+                    .unwrap_or_default();
+                format!(
+                    "<li><a href=\"{}\">{}</a> ({}){}</li>",
+                    escape_html(animal["url"].as_str().unwrap_or("")),
+                    escape_html(animal["name"].as_str().unwrap_or("Unknown")),
+                    escape_html(animal["breed"].as_str().unwrap_or("Mix")),
+                    picture
+                )
+            })
+            .collect();
+        output.push_str(&format!(
+            "<h3>{} ({})</h3>\n<ul>\n{}\n</ul>\n",
+            name,
+            digest_summary(search),
+            items.join("\n")
+        ));
+    }
+
+    Ok(output)
+}
+
+pub fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// HTML rendering of `format_alerts`, used for email digests.
+pub fn format_alerts_html(data: &Value) -> Result<String, AppError> {
+    let alerts = data
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or(AppError::NotFound)?;
+
+    let items: Vec<String> = alerts
+        .iter()
+        .map(|alert| {
+            let animal = &alert["animal"];
+            let picture = animal["picture"]
+                .as_str()
+                .map(|p| {
+                    format!(
+                        "<br><img src=\"{}\" alt=\"\" width=\"200\">",
+                        escape_html(p)
+                    )
+                })
+                .unwrap_or_default();
+            format!(
+                "<li><strong>{}</strong> matched <a href=\"{}\">{}</a> ({}){}</li>",
+                escape_html(alert["search_name"].as_str().unwrap_or("Unknown")),
+                escape_html(animal["url"].as_str().unwrap_or("")),
+                escape_html(animal["name"].as_str().unwrap_or("Unknown")),
+                escape_html(animal["breed"].as_str().unwrap_or("Mix")),
+                picture
+            )
+        })
+        .collect();
+
+    Ok(format!(
+        "<h3>New Matches ({})</h3>\n<ul>\n{}\n</ul>",
+        alerts.len(),
+        items.join("\n")
+    ))
+}
+
+pub fn format_server_info(info: &Value) -> String {
+    let defaults = &info["defaults"];
+    let mut output = format!(
+        "# RescueGroups MCP Server\n**Version:** {}\n**Git Revision:** {}\n",
+        info["version"].as_str().unwrap_or("unknown"),
+        info["git_revision"].as_str().unwrap_or("unknown")
+    );
+
+    if let Some(latest) = info["latest_version"].as_str() {
+        if info["update_available"].as_bool().unwrap_or(false) {
+            output.push_str(&format!(
+                "**Update Available:** {} (please update the server)\n",
+                latest
+            ));
+        } else {
+            output.push_str(&format!("**Latest Release:** {} (up to date)\n", latest));
+        }
+    } else if let Some(err) = info["update_error"].as_str() {
+        output.push_str(&format!("**Update Check:** failed ({})\n", err));
+    } else {
+        output.push_str("**Update Check:** disabled\n");
+    }
+
+    output.push_str(&format!(
+        "\n## Defaults\n**API Key:** {}\n**Base URL:** {}\n**Postal Code:** {}\n**Miles:** {}\n**Species:** {}\n**Timeout:** {}s\n**Lazy Loading:** {}",
+        defaults["api_key"].as_str().unwrap_or("-"),
+        defaults["base_url"].as_str().unwrap_or("-"),
+        defaults["postal_code"].as_str().unwrap_or("-"),
+        defaults["miles"],
+        defaults["species"].as_str().unwrap_or("-"),
+        defaults["timeout_seconds"],
+        defaults["lazy"]
+    ));
+
+    if let Some(keys) = info["api_keys"].as_array() {
+        output.push_str("\n\n## API Keys\n");
+        for key in keys {
+            output.push_str(&format!(
+                "- {}: {} requests{}\n",
+                key["key"].as_str().unwrap_or("-"),
+                key["requests"],
+                if key["in_rotation"] == false {
+                    " (out of rotation)"
+                } else {
+                    ""
+                }
+            ));
+        }
+    }
+
+    // Only worth a section once something went missing
+    let drifted: Vec<String> = info["schema_drift"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .flat_map(|(kind, stats)| {
+            stats["missing"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(move |(attribute, count)| {
+                    format!(
+                        "- {}: `{}` missing in {} of {}\n",
+                        kind, attribute, count, stats["checked"]
+                    )
+                })
+        })
+        .collect();
+    if !drifted.is_empty() {
+        output.push_str("\n\n## Schema Drift\n");
+        output.push_str(&drifted.concat());
+    }
+
+    output
+}
+
+/// The requests a dry run would have sent, from `{"requests": [...]}`.
+pub fn format_planned_requests(data: &Value) -> Result<String, AppError> {
+    let requests = data["requests"].as_array().map_or(&[][..], Vec::as_slice);
+    if requests.is_empty() {
+        return Ok("No upstream requests would be sent.".to_string());
+    }
+
+    let mut output = format!("# Dry Run ({} requests, not sent)\n", requests.len());
+    for (i, request) in requests.iter().enumerate() {
+        output.push_str(&format!(
+            "\n## {}. {} {}\n",
+            i + 1,
+            request["method"].as_str().unwrap_or("GET"),
+            request["url"].as_str().unwrap_or("-")
+        ));
+        for (name, value) in request["headers"].as_object().into_iter().flatten() {
+            output.push_str(&format!("{}: {}\n", name, value.as_str().unwrap_or("-")));
+        }
+        if let Some(body) = request.get("body") {
+            output.push_str(&format!(
+                "```json\n{}\n```\n",
+                serde_json::to_string_pretty(body).unwrap_or_default()
+            ));
+        }
+    }
+    Ok(output)
+}
+
+/// The `--json` output of a command: `ok`, then its `data` or an `error`
+/// with a `code` (see `AppError::kind`) and `message`.
+/// `text` in the deployment's own words: each phrase `labels` replaces is
+/// swapped wherever it stands as a whole word, longest phrases first, and
+/// replacements are never replaced again.
+pub fn relabel(text: &str, labels: &Labels) -> String {
+    if labels.replace.is_empty() {
+        return text.to_string();
+    }
+    let mut phrases: Vec<(&String, &String)> = labels
+        .replace
         .iter()
-        .filter_map(|b| b["attributes"]["name"].as_str().map(|s| s.to_string()))
+        .filter(|(phrase, _)| !phrase.is_empty())
         .collect();
+    phrases.sort_by_key(|(phrase, _)| std::cmp::Reverse(phrase.len()));
+    let is_word = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut before = None;
+    while let Some(c) = rest.chars().next() {
+        let found = phrases.iter().find(|(phrase, _)| {
+            rest.starts_with(phrase.as_str())
+                && !(is_word(before) && is_word(phrase.chars().next()))
+                && !(is_word(rest[phrase.len()..].chars().next()) && is_word(phrase.chars().last()))
+        });
+        match found {
+            Some((phrase, replacement)) => {
+                out.push_str(replacement);
+                before = phrase.chars().last();
+                rest = &rest[phrase.len()..];
+            }
+            None => {
+                out.push(c);
+                before = Some(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    out
+}
 
-    breed_names.sort();
+/// An animal's details followed by the deployment's call to action, if it
+/// has one.
+pub fn with_call_to_action(details: String, labels: &Labels) -> String {
+    match &labels.call_to_action {
+        Some(line) if !line.trim().is_empty() => format!("{}\n\n{}", details, line.trim()),
+        _ => details,
+    }
+}
 
-    Ok(format!(
-        "### Breeds for {}\n\n{}",
-        species,
-        breed_names.join("\n")
-    ))
+pub fn json_envelope(result: Result<Value, &AppError>) -> Value {
+    match result {
+        Ok(data) => json!({ "ok": true, "data": data, "error": null }),
+        Err(e) => json!({
+            "ok": false,
+            "data": null,
+            "error": { "code": e.kind(), "message": e.to_string() },
+        }),
+    }
 }
 
-pub fn print_output<F>(result: Result<Value, AppError>, json_mode: bool, formatter: F)
-where
+pub fn print_output<F>(
+    result: Result<Value, AppError>,
+    json_mode: bool,
+    labels: &Labels,
+    formatter: F,
+) where
     F: Fn(&Value) -> Result<String, AppError>,
 {
     match result {
         Ok(value) => {
             if json_mode {
-                println!("{}", serde_json::to_string_pretty(&value).unwrap());
+                let envelope = json_envelope(Ok(value));
+                println!("{}", serde_json::to_string_pretty(&envelope).unwrap());
             } else {
                 match formatter(&value) {
-                    Ok(text) => println!("{}", text),
+                    Ok(text) => println!("{}", relabel(&text, labels)),
                     Err(e) => error!("Error formatting output: {}", e),
                 }
             }
         }
+        // The requests are printed instead
+        Err(AppError::DryRun) => {}
+        Err(e) if json_mode => {
+            let envelope = json_envelope(Err(&e));
+            println!("{}", serde_json::to_string_pretty(&envelope).unwrap());
+        }
         Err(e) => error!("Error: {}", e),
     }
 }
@@ -350,6 +2562,121 @@ mod tests {
         assert!(output.contains("![Fluffy](https://example.com/fluffy.jpg)"));
     }
 
+    #[test]
+    fn test_format_timeline() {
+        assert_eq!(epoch_days("1970-01-01"), Some(0));
+        assert_eq!(epoch_days("2024-03-01T00:00:00Z"), Some(19_783));
+        assert_eq!(epoch_days("soon"), None);
+
+        let attrs = json!({
+            "createdDate": "2024-01-02T03:04:05Z",
+            "availableDate": "2024-01-10",
+            "updatedDate": "not a date",
+            "foundDate": "2023-12-30T00:00:00Z",
+            "foundPostalcode": "78704"
+        });
+        let today = epoch_days("2024-03-01").unwrap() as u64;
+        assert_eq!(
+            format_timeline(&attrs, today),
+            "## Timeline\n**Listed:** 2024-01-02 (listed for 59 days)\n**Available:** 2024-01-10\n\
+             **Found:** 2023-12-30 near 78704\n\n"
+        );
+        assert_eq!(format_timeline(&json!({}), today), "");
+    }
+
+    #[test]
+    fn test_format_org_animals() {
+        let dog = |id: &str, name: &str| {
+            json!({
+                "id": id,
+                "attributes": { "name": name, "url": format!("https://rg/{}", id), "breedString": "Boxer", "ageGroup": "Adult" },
+                "relationships": {
+                    "species": { "data": [{ "type": "species", "id": "8" }] },
+                    "orgs": { "data": [{ "type": "orgs", "id": "866" }] }
+                }
+            })
+        };
+        let cat = json!({
+            "id": "3",
+            "attributes": { "name": "Tom" },
+            "relationships": { "species": { "data": [{ "type": "species", "id": "3" }] } }
+        });
+        let data = json!({
+            "data": [cat, dog("1", "Rex"), dog("2", "Bo")],
+            "included": [
+                { "type": "species", "id": "8", "attributes": { "singular": "Dog", "plural": "Dogs" } },
+                { "type": "species", "id": "3", "attributes": { "singular": "Cat", "plural": "Cats" } },
+                { "type": "orgs", "id": "866", "attributes": { "name": "Happy Tails" } }
+            ]
+        });
+        assert_eq!(
+            format_org_animals(&data).unwrap(),
+            "**Happy Tails currently lists 2 dogs, 1 cat.**\n\n\
+             ## Dogs (2)\n\
+             - [Rex](https://rg/1): Boxer, Adult\n\
+             - [Bo](https://rg/2): Boxer, Adult\n\n\
+             ## Cats (1)\n\
+             - Tom"
+        );
+
+        let many: Vec<Value> = (0..12).map(|i| dog(&i.to_string(), "Pup")).collect();
+        let data = json!({ "data": many, "note": "Only the first 40 of 41 pages were fetched" });
+        let output = format_org_animals(&data).unwrap();
+        assert!(output.starts_with("⚠️ Only the first 40 of 41 pages were fetched\n\n**This organization lists at least 12 other animals (counted from the pages fetched).**"), "{}", output);
+        assert!(output.ends_with("- ...and 2 more"));
+
+        let data = json!({
+            "data": [dog("1", "Rex"), dog("2", "Bo")],
+            "included": [
+                { "type": "species", "id": "8", "attributes": { "singular": "Dog", "plural": "Dogs" } },
+                { "type": "orgs", "id": "866", "attributes": { "name": "Happy Tails" } }
+            ],
+            "meta": { "count": 10250 },
+            "note": "Only the first 40 of 41 pages were fetched"
+        });
+        let output = format_org_animals(&data).unwrap();
+        assert!(
+            output.contains(
+                "**Happy Tails currently lists 10250 animals; the 2 fetched are 2 dogs.**"
+            ),
+            "{}",
+            output
+        );
+        assert_eq!(
+            format_org_animals(&json!({ "data": [] })).unwrap(),
+            "No adoptable animals found."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_render_time() {
+        let mut settings = Settings::new("key");
+        assert_ne!(
+            render(&settings, async { render_time() }).await,
+            DETERMINISTIC_TIME
+        );
+
+        settings.deterministic = true;
+        let animal = json!({ "attributes": { "createdDate": "2023-12-02T00:00:00Z" } });
+        let output = render(&settings, async { format_single_animal(&animal) }).await;
+        assert!(output.contains("(listed for 30 days)"), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn test_render_output_config() {
+        let animal = json!({ "attributes": { "descriptionText": "Biscuit is a happy dog." } });
+        let full = render(&Settings::new("key"), async {
+            format_single_animal(&animal)
+        })
+        .await;
+        assert!(full.contains("Biscuit is a happy dog."), "{}", full);
+
+        let mut settings = Settings::new("key");
+        settings.output.detail_description_chars = 10;
+        let short = render(&settings, async { format_single_animal(&animal) }).await;
+        assert!(short.contains("Biscuit is…"), "{}", short);
+    }
+
     #[test]
     fn test_extract_single_item() {
         let arr = json!([{"id": "1"}, {"id": "2"}]);
@@ -388,24 +2715,166 @@ mod tests {
         assert!(output.contains("123-456"));
         assert!(output.contains("City, State"));
         assert!(output.contains("https://org.com"));
+        assert!(!output.contains("Housed At"));
+    }
+
+    #[test]
+    fn test_org_locations() {
+        let data = json!({
+            "data": [
+                {"type": "locations", "id": "3", "attributes": {
+                    "name": "East Campus", "street": "1 Oak St", "city": "Austin",
+                    "state": "TX", "postalcode": "78702", "hours": "Sat 10-4"
+                }},
+                {"type": "locations", "id": "4", "attributes": {"name": "Foster Hub"}}
+            ]
+        });
+        let output = format_org_locations(&data).unwrap();
+        assert!(output.starts_with("### Locations (2)"));
+        assert!(output.contains(
+            "**East Campus** (ID: 3)\n**Address:** 1 Oak St, Austin, TX 78702\n**Hours:** Sat 10-4"
+        ));
+        assert!(output.contains("**Foster Hub** (ID: 4)\n**Address:** address not listed"));
+
+        let contact = json!({
+            "data": {"id": "1", "attributes": {"name": "Buddy"},
+                "relationships": {"locations": {"data": [{"type": "locations", "id": "3"}]}}},
+            "included": data["data"]
+        });
+        let output = format_contact_info(&contact).unwrap();
+        assert!(output.contains("**Housed At:** East Campus (1 Oak St, Austin, TX 78702)"));
+        assert!(output.contains("**Branch Hours:** Sat 10-4"));
+    }
+
+    #[test]
+    fn test_foster_location() {
+        let contact = |city: &str| {
+            json!({
+                "data": {"id": "1", "attributes": {"name": "Buddy"},
+                    "relationships": {"locations": {"data": [{"type": "locations", "id": "3"}]}}},
+                "included": [
+                    {"type": "orgs", "id": "5", "attributes": {"name": "Org", "city": "Austin", "state": "TX"}},
+                    {"type": "locations", "id": "3", "attributes": {"city": city, "state": "tx"}}
+                ]
+            })
+        };
+
+        let fostered = contact("Pflugerville");
+        assert_eq!(
+            foster_location(&fostered, &fostered["data"]).as_deref(),
+            Some("Pflugerville, tx")
+        );
+        assert!(format_contact_info(&fostered).unwrap().contains(
+            "**Currently in foster in Pflugerville, tx** (not at the organization's address)"
+        ));
+        assert!(format_animal_with_notes(&fostered)
+            .unwrap()
+            .starts_with("# Buddy\n**Currently in foster in Pflugerville, tx**\n"));
+
+        let on_site = contact("austin");
+        assert_eq!(foster_location(&on_site, &on_site["data"]), None);
+        assert!(!format_contact_info(&on_site).unwrap().contains("foster"));
     }
 
     #[test]
     fn test_format_animal_results() {
         let data = json!({
             "data": [
-                {"attributes": {"name": "A", "breedString": "B", "url": "U"}},
-                {"attributes": {"name": "C", "breedString": "D", "url": "V"}}
+                {"attributes": {"name": "A", "breedString": "B", "url": "U", "distance": 4.5}},
+                {"attributes": {"name": "C", "breedString": "D", "url": "V", "listings": [
+                    {"id": "1", "org_id": "5", "url": "V"},
+                    {"id": "pf-2", "org_id": "pf-TX1", "url": "W"}
+                ]}}
             ]
         });
 
         let output = format_animal_results(&data).unwrap();
         assert!(output.contains("### [A](U)"));
+        assert!(output.contains("**Listed by:** [5](V), [pf-TX1](W)"));
+        assert!(output.contains("**Distance:** 4.5 miles"));
         assert!(output.contains("**Breed:** B"));
         assert!(output.contains("---"));
         assert!(output.contains("### [C](V)"));
     }
 
+    #[test]
+    fn test_courtesy_listing_label() {
+        let data = json!({
+            "data": [
+                {"attributes": {"name": "A", "url": "U", "isCourtesyListing": "Yes"}},
+                {"attributes": {"name": "C", "url": "V", "isCourtesyListing": "No"}}
+            ]
+        });
+
+        let output = format_animal_results(&data).unwrap();
+        assert_eq!(output.matches("**Courtesy Listing:**").count(), 1);
+        assert!(format_single_animal(&data["data"][0]).contains("**Courtesy Listing:**"));
+        assert!(!format_single_animal(&data["data"][1]).contains("Courtesy"));
+    }
+
+    #[test]
+    fn test_age_label() {
+        let today = epoch_days("2024-06-15").unwrap() as u64;
+        let aged = |attrs: Value| age_label(&attrs, today, "Unknown");
+
+        assert_eq!(
+            aged(json!({"ageGroup": "Adult", "birthDate": "2022-03-01T00:00:00Z"})),
+            "Adult (approx. 2 years 3 months)"
+        );
+        assert_eq!(
+            aged(json!({"ageGroup": "Young", "birthDate": "2023-06-16"})),
+            "Young (approx. 11 months)"
+        );
+        assert_eq!(
+            aged(json!({"birthDate": "2023-06-15"})),
+            "Unknown (approx. 1 year)"
+        );
+        assert_eq!(
+            aged(json!({"ageGroup": "Baby", "birthDate": "2024-06-01"})),
+            "Baby (approx. under 1 month)"
+        );
+        assert_eq!(
+            aged(json!({"ageGroup": "Senior", "ageString": "10 Years"})),
+            "Senior (approx. 10 years)"
+        );
+        // A future or unparseable birth date falls back to the age group
+        assert_eq!(
+            aged(json!({"ageGroup": "Adult", "birthDate": "2030-01-01"})),
+            "Adult"
+        );
+        assert_eq!(
+            aged(json!({"ageGroup": "Adult", "birthDate": "soon"})),
+            "Adult"
+        );
+    }
+
+    #[test]
+    fn test_special_needs_details() {
+        let animal = json!({"attributes": {
+            "name": "Tripod",
+            "isSpecialNeeds": "Yes",
+            "specialNeedsDetails": "Three legs | needs ramps.",
+            "ongoingMedical": "Joint supplements daily",
+            "groomingNeeds": " "
+        }});
+
+        let output = format_single_animal(&animal);
+        assert!(output.contains("## Special Needs\nThree legs | needs ramps.\n"));
+        assert!(output.contains("- **Ongoing Medical:** Joint supplements daily"));
+        assert!(!output.contains("Grooming"));
+        assert_eq!(
+            comparison_value(&animal, "Special?"),
+            "Yes: Three legs / needs ramps."
+        );
+
+        let vague = json!({"attributes": {"isSpecialNeeds": "Yes"}});
+        assert!(format_single_animal(&vague).contains("hasn't described"));
+        assert_eq!(comparison_value(&vague, "Special?"), "Yes");
+
+        let healthy = json!({"attributes": {"isSpecialNeeds": "No", "specialNeedsDetails": "x"}});
+        assert!(!format_single_animal(&healthy).contains("Special Needs"));
+    }
+
     #[test]
     fn test_format_comparison_table() {
         let data = json!({
@@ -432,6 +2901,106 @@ mod tests {
         assert!(output.contains("| Feature | [Buddy](http://buddy.com) |"));
         assert!(output.contains("| **Breed** | Lab |"));
         assert!(output.contains("| **Kids?** | Yes |"));
+        assert!(!output.contains("**Note:**"));
+
+        // Animals that failed are listed under the partial table
+        let mut data = data;
+        data["errors"] = json!([
+            {"animal_id": "7", "error": "Resource Not Found", "kind": "not_found", "retryable": false},
+            {"animal_id": "8", "error": "Deadline Exceeded", "kind": "deadline", "retryable": true}
+        ]);
+        let output = format_comparison_table(&data).unwrap();
+        assert!(output.contains("| Feature | [Buddy](http://buddy.com) |"));
+        assert!(output.ends_with(
+            "> **Note:** 2 of 3 animals could not be compared:\n\
+             > - `7`: not found; the listing may have been adopted or removed\n\
+             > - `8`: timed out; the time allowed for the comparison ran out\n\
+             >\n> Try again later: 8\n"
+        ));
+    }
+
+    #[test]
+    fn test_format_location_comparison() {
+        let data = json!({
+            "miles": 25,
+            "data": [
+                {"postal_code": "78704", "count": 40, "samples": [
+                    {"attributes": {"name": "Rex", "url": "https://rg/1"}},
+                    {"attributes": {"name": "Bo"}}
+                ]},
+                {"postal_code": "78610", "count": 0, "samples": []},
+                {"postal_code": "90210", "error": "API Error: 500"}
+            ]
+        });
+        let output = format_location_comparison(&data).unwrap();
+        assert!(output.starts_with("### Matches by Location (within 25 miles)"));
+        assert!(output.contains("| 78704 | 40 | [Rex](https://rg/1), Bo |"));
+        assert!(output.contains("| 78610 | 0 | - |"));
+        assert!(output.ends_with("| 90210 | - | Search failed: API Error: 500 |"));
+    }
+
+    #[test]
+    fn test_format_new_arrivals() {
+        let data = json!({
+            "days": 7,
+            "groups": [
+                {"org_id": "9", "org_name": "Austin Pets", "animals": [
+                    {"id": "1", "attributes": {"name": "Rex", "url": "https://rg/1", "breedString": "Beagle",
+                        "ageGroup": "Young", "createdDate": "2024-03-01T10:00:00Z"}}
+                ]},
+                {"org_id": "12", "animals": [{"id": "2", "attributes": {"name": "Bo"}}]}
+            ]
+        });
+        assert_eq!(
+            format_new_arrivals(&data).unwrap(),
+            "### New Arrivals (2 pets listed in the last 7 days)\n\n\
+             #### Austin Pets\n\
+             - [Rex](https://rg/1) (ID: 1): Beagle, Young, listed 2024-03-01\n\n\
+             #### Organization 12\n\
+             - Bo (ID: 2): Mix, Unknown age"
+        );
+        assert_eq!(
+            format_new_arrivals(&json!({ "days": 3, "groups": [] })).unwrap(),
+            "No pets were listed nearby in the last 3 days."
+        );
+    }
+
+    #[test]
+    fn test_breed_parts() {
+        assert_eq!(
+            split_breeds("Labrador Retriever / Hound Mix"),
+            (
+                vec!["Labrador Retriever".to_string(), "Hound".to_string()],
+                true
+            )
+        );
+        assert_eq!(split_breeds("Beagle"), (vec!["Beagle".to_string()], false));
+        assert_eq!(
+            split_breeds("Mixed Breed"),
+            (vec!["Mixed Breed".to_string()], false)
+        );
+        assert_eq!(split_breeds(""), (vec![], false));
+
+        let attrs = json!({
+            "name": "Rex",
+            "breedString": "Labrador Retriever / Hound Mix",
+            "breedPrimary": "Labrador Retriever",
+            "breedPrimaryId": "151",
+            "breedSecondary": "Hound",
+            "breedSecondaryId": 99
+        });
+        let (parts, _) = breed_parts(&attrs);
+        assert_eq!(parts[0].id.as_deref(), Some("151"));
+        assert_eq!(parts[1].id.as_deref(), Some("99"));
+
+        let animal = json!({ "attributes": attrs });
+        assert!(format_single_animal(&animal).contains(
+            "**Breed Mix:** Labrador Retriever (breed ID 151), Hound (breed ID 99), and others"
+        ));
+        assert_eq!(
+            comparison_value(&animal, "Breed"),
+            "Labrador Retriever + Hound (mix)"
+        );
     }
 
     #[test]
@@ -457,6 +3026,87 @@ mod tests {
         assert!(output.contains("123 St City ST 12345"));
     }
 
+    #[test]
+    fn test_format_adoption_process() {
+        let process = json!({
+            "org_name": "Rescue",
+            "animal_name": "Buddy",
+            "service_area": "Travis County",
+            "fee": "$150",
+            "adoption_url": "https://rescue.org/apply",
+            "application_url": "https://rescue.org/apply",
+            "phone": "555-5555"
+        });
+        assert_eq!(
+            format_adoption_process(&process),
+            "## Adopting Buddy from Rescue\n\n\
+             1. **Check the service area.** They adopt to: Travis County\n\
+             2. **Review the fee.** $150\n\
+             3. **Apply.** Start at [https://rescue.org/apply](https://rescue.org/apply).\n\
+             4. **Ask questions.** Contact them at 555-5555."
+        );
+        assert!(format_adoption_process(&json!({ "org_name": "Quiet" }))
+            .starts_with("## Adopting from Quiet\n\nThe organization hasn't published"));
+    }
+
+    #[test]
+    fn test_format_sponsorship_info() {
+        let info = json!({
+            "org_name": "Rescue",
+            "animal_name": "Buddy",
+            "sponsorable": true,
+            "sponsorship_minimum": "$25",
+            "needs_foster": false,
+            "donation_url": "https://rescue.org/give"
+        });
+        assert_eq!(
+            format_sponsorship_info(&info),
+            "## Helping Buddy and Rescue\n\n\
+             **Sponsor:** This pet can be sponsored (minimum $25).\n\
+             **Donate:** [https://rescue.org/give](https://rescue.org/give)"
+        );
+        assert!(format_sponsorship_info(&json!({}))
+            .contains("No sponsorship or donation details are listed"));
+    }
+
+    #[test]
+    fn test_format_breed_suggestions() {
+        let data = json!({
+            "query": "golden doodel",
+            "species": "dogs",
+            "data": [{"id": "2", "name": "Goldendoodle", "distance": 2}]
+        });
+        assert_eq!(
+            format_breed_suggestions(&data).unwrap(),
+            "### dogs breeds like 'golden doodel'\n\n1. Goldendoodle (ID: 2)"
+        );
+        let none = json!({ "query": "zebra", "species": "cats", "data": [] });
+        assert!(format_breed_suggestions(&none)
+            .unwrap()
+            .starts_with("No cats breeds resemble 'zebra'"));
+    }
+
+    #[test]
+    fn test_format_org_contact() {
+        let contact = json!({
+            "name": "Rescue",
+            "email": "rescue@example.com",
+            "phone": null,
+            "address": { "city": "Austin", "state": "TX", "street": null },
+            "adoption_url": "https://rescue.org/adopt",
+            "social": { "instagram": "https://instagram.com/rescue" }
+        });
+        let output = format_org_contact(&contact);
+        assert!(output.starts_with("## Contact: Rescue\n\n**Email:** rescue@example.com\n"));
+        assert!(!output.contains("Phone"));
+        assert!(output.contains("**Address:** Austin, TX"));
+        assert!(output.contains("**Adoption Page:** [https://rescue.org/adopt]"));
+        assert!(output.contains("**Instagram:** [https://instagram.com/rescue]"));
+
+        assert!(format_org_contact(&json!({ "name": "Quiet" }))
+            .contains("No contact details are listed"));
+    }
+
     #[test]
     fn test_format_breed_details() {
         let breed = json!({
@@ -515,6 +3165,29 @@ mod tests {
         assert!(output.contains("**ID:** 866"));
     }
 
+    #[test]
+    fn test_format_breed_rescues() {
+        let data = json!({"breed": "Beagle", "data": [
+            {"org_id": "2", "name": "Hound Haven", "city": "Austin", "state": "TX",
+                "email": "hi@hounds.org", "matched_name": false, "matched_description": true,
+                "breed_listings": 1},
+            {"org_id": "3", "name": "City Shelter", "matched_name": false,
+                "matched_description": false, "breed_listings": 3}
+        ]});
+        let output = format_breed_rescues(&data).unwrap();
+        assert!(output.starts_with("## Beagle Rescues (2)"));
+        assert!(output.contains(
+            "### Hound Haven (ID: 2)\n**Why:** description mentions Beagle; lists 1 Beagle nearby\n\
+             **Location:** Austin, TX\n**Email:** hi@hounds.org"
+        ));
+        assert!(output.contains("**Why:** lists 3 Beagles nearby"));
+
+        let none = json!({"breed": "Otterhound", "data": []});
+        assert!(format_breed_rescues(&none)
+            .unwrap()
+            .starts_with("No Otterhound rescues found nearby."));
+    }
+
     #[test]
     fn test_format_breed_results() {
         let data = json!({
@@ -534,23 +3207,358 @@ mod tests {
         let data = json!({"data": []});
         let output = format_comparison_table(&data).unwrap();
         assert_eq!(output, "No animals to compare.");
+
+        let data = json!({
+            "data": [],
+            "errors": [{"animal_id": "42", "error": "Resource Not Found"}]
+        });
+        let output = format_comparison_table(&data).unwrap();
+        assert!(output.contains(
+            "> **Note:** 1 of 1 animals could not be compared:\n> - `42`: Resource Not Found"
+        ));
+    }
+
+    #[test]
+    fn test_format_saved_searches() {
+        let data = json!({
+            "data": [
+                {"name": "cats", "args": {"species": "cats", "miles": 10, "age": null}, "seen_ids": ["1", "2"]},
+                {"name": "defaults", "args": {}, "seen_ids": []}
+            ]
+        });
+        let output = format_saved_searches(&data).unwrap();
+        assert!(output.contains("### cats"));
+        assert!(output.contains("species=cats"));
+        assert!(output.contains("miles=10"));
+        assert!(!output.contains("age="));
+        assert!(output.contains("**Known Matches:** 2"));
+        assert!(output.contains("server defaults"));
+
+        let empty = json!({"data": []});
+        assert_eq!(format_saved_searches(&empty).unwrap(), "No saved searches.");
+    }
+
+    #[test]
+    fn test_format_semantic_results() {
+        let data = json!({
+            "query": "calm dog",
+            "data": [
+                {"id": "1", "attributes": {"name": "Snooze", "url": "http://snooze", "descriptionText": "Calm"}, "meta": {"score": 0.8123}}
+            ]
+        });
+        let output = format_semantic_results(&data).unwrap();
+        assert!(output.contains("## Best matches for \"calm dog\""));
+        assert!(output.contains("### [Snooze](http://snooze)"));
+        assert!(output.contains("**Match:** 81%"));
+
+        let empty = json!({"data": []});
+        assert_eq!(
+            format_semantic_results(&empty).unwrap(),
+            "No adoptable animals found."
+        );
+    }
+
+    #[test]
+    fn test_format_match_results() {
+        let data = json!({
+            "data": [{
+                "id": "7",
+                "attributes": {"name": "Pudding", "url": "http://pudding", "breedString": "Pug"},
+                "meta": {"score": 75, "reasons": ["✅ Low energy (Low)", "❌ House-trained (No)"]}
+            }]
+        });
+        let output = format_match_results(&data).unwrap();
+        assert!(output.contains("### [Pudding](http://pudding) - 75% match"));
+        assert!(output.contains("**Breed:** Pug (ID: 7)"));
+        assert!(output.contains("- ❌ House-trained (No)"));
+
+        let empty = json!({"data": []});
+        assert_eq!(
+            format_match_results(&empty).unwrap(),
+            "No adoptable animals found."
+        );
+    }
+
+    #[test]
+    fn test_format_digest() {
+        let data = json!({
+            "since": 1714557600,
+            "searches": [
+                {
+                    "name": "cats",
+                    "baseline": false,
+                    "total": 3,
+                    "new": [{"name": "Fresh", "url": "http://fresh", "breed": "Tabby", "age": "Young"}],
+                    "removed": [{"name": "Adopted"}]
+                },
+                {"name": "dogs", "baseline": true, "total": 7, "new": [], "removed": []},
+                {"name": "birds", "error": "API Error: 500"}
+            ]
+        });
+        let output = format_digest(&data).unwrap();
+        assert!(output.starts_with("# What's New Since 2024-05-01 10:00 UTC"));
+        assert!(output.contains("## cats (1 new, 1 no longer listed, 3 total)"));
+        assert!(output.contains("- [Fresh](http://fresh) - Tabby, Young"));
+        assert!(output.contains("*No longer listed:* Adopted"));
+        assert!(output.contains("Baseline recorded (7 animals)"));
+        assert!(output.contains("⚠️ Search failed: API Error: 500"));
+
+        let html = format_digest_html(&data).unwrap();
+        assert!(html.contains("<h3>cats (1 new, 1 no longer listed, 3 total)</h3>"));
+        assert!(html.contains("<li><a href=\"http://fresh\">Fresh</a> (Tabby)</li>"));
+    }
+
+    #[test]
+    fn test_format_parsed_query() {
+        let data = json!({
+            "args": {"species": "dogs", "miles": 30, "breeds": null},
+            "matched": [{"term": "dog", "field": "species", "value": "dogs"}],
+            "notes": ["'Hypoallergenic' is an animal quality"],
+            "unrecognized": ["fluffy"]
+        });
+        let output = format_parsed_query(&data).unwrap();
+        assert!(output.contains("\"species\": \"dogs\""));
+        assert!(!output.contains("breeds"));
+        assert!(output.contains("- \"dog\" → `species` = \"dogs\""));
+        assert!(output.contains("*Note:* 'Hypoallergenic'"));
+        assert!(output.contains("**Not understood:** fluffy"));
+    }
+
+    #[test]
+    fn test_format_usage_report() {
+        let data = json!({
+            "enabled": true,
+            "since": 0,
+            "tools": [{
+                "name": "search_adoptable_pets",
+                "calls": 4,
+                "errors": 1,
+                "error_rate": 0.25,
+                "avg_ms": 120,
+                "max_ms": 400,
+                "arguments": {"species": 4, "postal_code": 2},
+                "error_kinds": {"not_found": 1}
+            }],
+            "endpoints": [{
+                "endpoint": "POST /public/orgs/search",
+                "requests": 12,
+                "errors": 0,
+                "p50_ms": 800,
+                "p90_ms": 2100,
+                "p99_ms": 2600,
+                "max_ms": 2600
+            }]
+        });
+        let output = format_usage_report(&data).unwrap();
+        assert!(output.contains("Since 1970-01-01T00:00:00Z"));
+        assert!(output.contains(
+            "| search_adoptable_pets | 4 | 1 | 25.0% | 120 | 400 | species (4), postal_code (2) |"
+        ));
+        assert!(output.contains("- **search_adoptable_pets**: not_found ×1"));
+        assert!(
+            output.contains("| `POST /public/orgs/search` | 12 | 0 | 800 | 2100 | 2600 | 2600 |")
+        );
+
+        let empty = format_usage_report(&json!({"enabled": false, "tools": []})).unwrap();
+        assert!(empty.contains("Recording is off"));
+        assert!(empty.contains("No tool calls recorded yet."));
+    }
+
+    #[test]
+    fn test_format_location_results() {
+        let data = json!({
+            "query": "Austin, TX",
+            "matches": [{"code": "78704", "place": "Austin", "state_code": "TX", "country": "US", "latitude": 30.243, "longitude": -97.767}],
+            "dataset_size": 3
+        });
+        let output = format_location_results(&data).unwrap();
+        assert!(output.contains("- **78704** Austin, TX (US) — 30.2430, -97.7670"));
+
+        let none =
+            format_location_results(&json!({"query": "Nowhere", "matches": [], "dataset_size": 3}))
+                .unwrap();
+        assert!(none.contains("No match for 'Nowhere'"));
+    }
+
+    #[test]
+    fn test_format_animal_with_notes() {
+        let data = json!({
+            "data": [{"id": "1", "attributes": {"name": "Buddy"}}],
+            "notes": [{"text": "emailed 3/4", "added_at": 1714557600}]
+        });
+        let text = format_animal_with_notes(&data).unwrap();
+        assert!(text.contains("Buddy"));
+        assert!(text.contains("### Notes (1)"));
+        assert!(text.contains("- **2024-05-01 10:00 UTC**: emailed 3/4"));
+
+        let empty = json!({"animal_id": "1", "notes": []});
+        assert_eq!(format_notes(&empty).unwrap(), "No notes for animal 1.");
+    }
+
+    #[test]
+    fn test_format_favorites() {
+        let data = json!({
+            "data": [
+                {"id": "1", "name": "Biscuit", "breed": "Corgi", "url": "http://biscuit", "note": "Loves kids"},
+                {"id": "2", "name": "Rex", "breed": "Boxer", "url": "", "note": null}
+            ]
+        });
+        let output = format_favorites(&data).unwrap();
+        assert!(output.contains("### Favorites (2)"));
+        assert!(output.contains("**Biscuit** (ID: 1) - Corgi"));
+        assert!(output.contains("*Note:* Loves kids"));
+        assert!(output.contains("**Animal IDs:** 1,2"));
+
+        let empty = json!({"data": []});
+        assert_eq!(format_favorites(&empty).unwrap(), "No favorites saved yet.");
+    }
+
+    #[test]
+    fn test_format_alerts() {
+        let data = json!({
+            "data": [
+                {"search_name": "dogs", "animal": {"name": "Lucy", "url": "http://lucy", "breed": "Lab"}}
+            ]
+        });
+        let output = format_alerts(&data).unwrap();
+        assert!(output.contains("### New Matches (1)"));
+        assert!(output.contains("**dogs** matched [Lucy](http://lucy) (Lab)"));
+
+        let empty = json!({"data": []});
+        assert!(format_alerts(&empty).unwrap().contains("No new matches"));
+    }
+
+    #[test]
+    fn test_format_alerts_html() {
+        let data = json!({
+            "data": [
+                {"search_name": "dogs", "animal": {"name": "Lucy & Co", "url": "http://lucy", "breed": "Lab", "picture": "http://lucy.jpg"}}
+            ]
+        });
+        let output = format_alerts_html(&data).unwrap();
+        assert!(output.contains("<h3>New Matches (1)</h3>"));
+        assert!(output.contains("<a href=\"http://lucy\">Lucy &amp; Co</a> (Lab)"));
+        assert!(output.contains("<img src=\"http://lucy.jpg\""));
+        assert!(format_alerts_html(&json!({})).is_err());
+    }
+
+    #[test]
+    fn test_truncate_text() {
+        assert_eq!(
+            truncate_text("  Short and sweet.  ", 40),
+            "Short and sweet."
+        );
+
+        let text = "Biscuit is a happy dog. He loves long walks. He is great with kids.";
+        assert_eq!(
+            truncate_text(text, 50),
+            "Biscuit is a happy dog. He loves long walks.…"
+        );
+        // No sentence ends late enough: cut at a word
+        assert_eq!(truncate_text(text, 18), "Biscuit is a happy…");
+        // Graphemes are never split, even with combining marks and emoji
+        assert_eq!(truncate_text("Zoe\u{301} 🐕‍🦺🐕‍🦺🐕‍🦺", 4), "Zoe\u{301}…");
+        assert_eq!(truncate_text("🐕‍🦺🐕‍🦺🐕‍🦺🐕‍🦺", 2), "🐕‍🦺🐕‍🦺…");
+    }
+
+    #[test]
+    fn test_format_server_info() {
+        let info = json!({
+            "version": "v0.4.0",
+            "git_revision": "abc1234",
+            "defaults": {
+                "api_key": "[redacted]",
+                "base_url": "https://api.rescuegroups.org/v5",
+                "postal_code": "90210",
+                "miles": 50,
+                "species": "dogs",
+                "timeout_seconds": 30,
+                "lazy": true
+            },
+            "latest_version": "v0.5.0",
+            "update_available": true
+        });
+        let output = format_server_info(&info);
+        assert!(output.contains("**Version:** v0.4.0"));
+        assert!(output.contains("**Update Available:** v0.5.0"));
+        assert!(output.contains("**API Key:** [redacted]"));
+        assert!(!output.contains("Schema Drift"));
+
+        let info = json!({
+            "version": "v0.4.0",
+            "defaults": {},
+            "schema_drift": {"animals": {"checked": 40, "missing": {"breedString": 40}}}
+        });
+        assert!(format_server_info(&info).contains("- animals: `breedString` missing in 40 of 40"));
+
+        let info = json!({"version": "v0.4.0", "defaults": {}});
+        assert!(format_server_info(&info).contains("**Update Check:** disabled"));
     }
 
     #[test]
     fn test_print_output_json() {
         let res = Ok(json!({"foo": "bar"}));
-        print_output(res, true, |_| Ok("text".to_string()));
+        print_output(res, true, &Labels::default(), |_| Ok("text".to_string()));
     }
 
     #[test]
     fn test_print_output_error() {
         let res: Result<Value, AppError> = Err(AppError::NotFound);
-        print_output(res, false, |_| Ok("text".to_string()));
+        print_output(res, false, &Labels::default(), |_| Ok("text".to_string()));
     }
 
     #[test]
     fn test_print_output_formatter_error() {
         let res = Ok(json!({}));
-        print_output(res, false, |_| Err(AppError::NotFound));
+        print_output(res, false, &Labels::default(), |_| Err(AppError::NotFound));
+    }
+
+    #[test]
+    fn test_relabel() {
+        let labels = Labels {
+            replace: [
+                ("Adopt", "Rescue"),
+                ("Adoption Fee", "Rehoming Donation"),
+                ("RescueGroups", "Happy Tails"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+            call_to_action: Some("Call 555-0100 to meet them!".to_string()),
+        };
+        assert_eq!(
+            relabel(
+                "Adopt Rex! **Adoption Fee:** $50. Adoptable. [View on RescueGroups](url)",
+                &labels
+            ),
+            // Whole words only, longest first, and "Rescue" isn't replaced again
+            "Rescue Rex! **Rehoming Donation:** $50. Adoptable. [View on Happy Tails](url)"
+        );
+        assert_eq!(relabel("Adopt", &Labels::default()), "Adopt");
+
+        assert_eq!(
+            with_call_to_action("# Rex".to_string(), &labels),
+            "# Rex\n\nCall 555-0100 to meet them!"
+        );
+        assert_eq!(
+            with_call_to_action("# Rex".to_string(), &Labels::default()),
+            "# Rex"
+        );
+    }
+
+    #[test]
+    fn test_json_envelope() {
+        let ok = json_envelope(Ok(json!({"data": []})));
+        assert_eq!(ok, json!({"ok": true, "data": {"data": []}, "error": null}));
+
+        let error = AppError::invalid_argument("miles", "miles 0 is out of range");
+        let failed = json_envelope(Err(&error));
+        assert_eq!(failed["ok"], false);
+        assert!(failed["data"].is_null());
+        assert_eq!(failed["error"]["code"], "validation");
+        assert_eq!(
+            failed["error"]["message"],
+            "Validation Error: miles 0 is out of range"
+        );
     }
 }
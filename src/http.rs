@@ -0,0 +1,1670 @@
+use crate::alerts::{build_channels, now_secs, spawn_alert_scheduler, Alert, NotificationChannel};
+use crate::cli::HttpArgs;
+use crate::client::check_api_key_now;
+use crate::comparisons::get_comparison;
+use crate::config::{Settings, SseConfig};
+use crate::daemon::{remove_pid_file, write_pid_file};
+use crate::error::AppError;
+use crate::feeds::{animals_feed, FeedParams};
+use crate::fmt::format_comparison_html;
+use crate::images::{fetch_animal_image, MAX_THUMBNAIL_SIZE, MIN_THUMBNAIL_SIZE};
+use crate::mcp::{format_json_rpc_response, process_mcp_request, JsonRpcRequest};
+use crate::server::preflight;
+use axum::{
+    extract::{Json, Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode, Uri},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{delete, get, post},
+    Router,
+};
+use futures::future::BoxFuture;
+use futures::stream::Stream;
+use moka::future::Cache;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tower_http::trace::TraceLayer;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// How long an upstream readiness check is reused, so frequent probes don't
+/// spend the rate limit.
+const READINESS_TTL: Duration = Duration::from_secs(30);
+
+/// Header carrying the streamable HTTP transport's session, issued in the
+/// response to `initialize`.
+const MCP_SESSION_ID: &str = "mcp-session-id";
+
+// Upstream readiness by base URL and key
+static READINESS_CACHE: LazyLock<Cache<String, (StatusCode, Value)>> = LazyLock::new(|| {
+    Cache::builder()
+        .max_capacity(16)
+        .time_to_live(READINESS_TTL)
+        .build()
+});
+
+pub type SessionSender = mpsc::UnboundedSender<Result<Event, Infallible>>;
+pub type SessionsMap = Arc<RwLock<HashMap<String, Session>>>;
+
+/// A connected SSE client. Dropping it closes the client's event stream.
+pub struct Session {
+    pub id: String,
+    stream: Mutex<SessionStream>,
+    /// ID of the last event sent, counting from 1
+    pub last_event_id: AtomicU64,
+    /// Unix timestamp of when the client connected
+    pub connected_at: u64,
+    /// Messages received from the client
+    pub requests: AtomicU64,
+    /// Unix timestamp of the latest message (or the connect time)
+    pub last_seen: AtomicU64,
+}
+
+/// Where a session's events go, and the recent ones kept for a client
+/// that reconnects.
+struct SessionStream {
+    sender: SessionSender,
+    /// (ID, event type, data) of the latest events, oldest first
+    recent: VecDeque<(u64, String, String)>,
+    capacity: usize,
+    /// First event sent while the client was away
+    undelivered_from: Option<u64>,
+}
+
+impl Session {
+    pub fn new(id: String, sender: SessionSender) -> Self {
+        let now = now_secs();
+        Session {
+            id,
+            stream: Mutex::new(SessionStream {
+                sender,
+                recent: VecDeque::new(),
+                capacity: SseConfig::default().replay_events,
+                undelivered_from: None,
+            }),
+            last_event_id: AtomicU64::new(0),
+            connected_at: now,
+            requests: AtomicU64::new(0),
+            last_seen: AtomicU64::new(now),
+        }
+    }
+
+    /// Keeps the last `events` events for replay on reconnect.
+    pub fn with_replay_buffer(self, events: usize) -> Self {
+        self.stream().capacity = events;
+        self
+    }
+
+    fn stream(&self) -> std::sync::MutexGuard<'_, SessionStream> {
+        self.stream.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn event(&self, id: u64, event: &str, data: &str) -> Result<Event, Infallible> {
+        Ok(Event::default()
+            .event(event)
+            .id(format!("{}:{}", self.id, id))
+            .data(data))
+    }
+
+    /// Sends an event with the next ID, as "{session_id}:{n}", so clients
+    /// and proxies can tell which session it belongs to and spot gaps.
+    /// False when the client has gone away; the event is still kept for
+    /// replay if it reconnects.
+    pub fn send(&self, event: &str, data: impl AsRef<str>) -> bool {
+        let mut stream = self.stream();
+        let n = self.last_event_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let data = data.as_ref();
+        if stream.capacity > 0 {
+            if stream.recent.len() == stream.capacity {
+                stream.recent.pop_front();
+            }
+            stream
+                .recent
+                .push_back((n, event.to_string(), data.to_string()));
+        }
+        let delivered = stream.sender.send(self.event(n, event, data)).is_ok();
+        if !delivered {
+            stream.undelivered_from.get_or_insert(n);
+        }
+        delivered
+    }
+
+    /// Whether the client's event stream has gone away.
+    pub fn is_closed(&self) -> bool {
+        self.stream().sender.is_closed()
+    }
+
+    /// Moves the session to a reconnected client's stream and replays the
+    /// kept events after `last_event_id`, or without one those sent while
+    /// the client was away. Returns how many were replayed.
+    pub fn resume(&self, sender: SessionSender, last_event_id: Option<u64>) -> usize {
+        let mut stream = self.stream();
+        let from = match last_event_id {
+            Some(n) => Some(n + 1),
+            None => stream.undelivered_from,
+        };
+        stream.undelivered_from = None;
+        let mut replayed = 0;
+        if let Some(from) = from {
+            if stream
+                .recent
+                .front()
+                .is_some_and(|(oldest, _, _)| *oldest > from)
+            {
+                warn!(
+                    "Session {} missed events older than the replay buffer",
+                    self.id
+                );
+            }
+            for (n, event, data) in stream.recent.iter().filter(|(n, _, _)| *n >= from) {
+                let _ = sender.send(self.event(*n, event, data));
+                replayed += 1;
+            }
+        }
+        stream.sender = sender;
+        replayed
+    }
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    pub settings: Settings,
+    pub auth_token: Option<String>,
+    pub sessions: SessionsMap,
+}
+
+#[derive(Deserialize)]
+pub struct MessageParams {
+    session_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct SseParams {
+    /// Session to resume after a dropped connection
+    session_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ImageParams {
+    size: Option<u32>,
+    token: Option<String>,
+}
+
+/// Pushes alerts to every connected SSE client as MCP `notifications/message`
+/// log notifications.
+pub struct SseChannel {
+    pub sessions: SessionsMap,
+}
+
+impl NotificationChannel for SseChannel {
+    fn name(&self) -> &'static str {
+        "sse"
+    }
+
+    fn notify<'a>(&'a self, alerts: &'a [Alert]) -> BoxFuture<'a, Result<(), AppError>> {
+        Box::pin(async move {
+            let sessions = self.sessions.read().await;
+            for alert in alerts {
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/message",
+                    "params": {
+                        "level": "info",
+                        "logger": "rescue-groups-mcp.alerts",
+                        "data": alert
+                    }
+                });
+                for session in sessions.values() {
+                    session.send("message", notification.to_string());
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+pub async fn run_http_server(args: HttpArgs, settings: Settings) -> Result<(), std::io::Error> {
+    let settings = preflight(settings).await?;
+    let sessions: SessionsMap = Arc::new(RwLock::new(HashMap::new()));
+    let sse = Arc::new(SseChannel {
+        sessions: sessions.clone(),
+    });
+    let channels = build_channels(&settings, Some(sse));
+    let _alerts = spawn_alert_scheduler(settings.clone(), channels);
+
+    let app_state = Arc::new(AppState {
+        settings,
+        auth_token: args.auth_token,
+        sessions,
+    });
+
+    let app = create_router(app_state);
+
+    let addr: SocketAddr = format!("{}:{}", args.host, args.port)
+        .parse()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    info!("RescueGroups MCP Server running (HTTP + SSE) on {}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    if let Some(pid_file) = &args.pid_file {
+        write_pid_file(pid_file)?;
+    }
+
+    let result = axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await;
+
+    if let Some(pid_file) = &args.pid_file {
+        remove_pid_file(pid_file);
+    }
+    result
+}
+
+/// Resolves on Ctrl+C or (on unix) SIGTERM, letting in-flight requests finish
+/// and the PID file be cleaned up.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sig) => {
+                sig.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    info!("Shutdown signal received, stopping HTTP server");
+}
+
+pub fn create_router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/", post(http_handler))
+        .route("/sse", get(sse_handler))
+        .route("/message", post(message_handler))
+        .route_layer(middleware::from_fn_with_state(state.clone(), origin_guard))
+        .route("/health", get(health_handler))
+        .route("/ready", get(readiness_handler))
+        .route("/feeds/animals.xml", get(feed_handler))
+        .route("/img/{animal_id}/{index}", get(image_handler))
+        .route("/c/{token}", get(comparison_handler))
+        .route("/admin/sessions", get(list_sessions_handler))
+        .route(
+            "/admin/sessions/{session_id}",
+            delete(close_session_handler),
+        )
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
+
+pub async fn http_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<JsonRpcRequest>,
+) -> impl IntoResponse {
+    // Auth check
+    if let Some(token) = &state.auth_token {
+        let auth_header = headers
+            .get("Authorization")
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or("");
+
+        if auth_header != format!("Bearer {}", token) {
+            warn!("Unauthorized access attempt");
+            return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+        }
+    }
+
+    debug!("Received HTTP request: method={}", req.method);
+    let mut session = headers
+        .get(MCP_SESSION_ID)
+        .and_then(|h| h.to_str().ok())
+        .filter(|id| !id.is_empty())
+        .map(str::to_string);
+    // A new client gets a session of its own to send back on later requests
+    let issued = match session {
+        None if req.method == "initialize" => {
+            session = Some(Uuid::new_v4().to_string());
+            session.clone()
+        }
+        _ => None,
+    };
+    let settings = Settings {
+        session,
+        ..state.settings.clone()
+    };
+    let response = process_mcp_request(req, &settings).await;
+
+    let mut response = if let Some(id) = response.0 {
+        let output = format_json_rpc_response(id, response.1);
+        Json(output).into_response()
+    } else {
+        StatusCode::NO_CONTENT.into_response()
+    };
+    if let Some(session) = issued.and_then(|id| header::HeaderValue::from_str(&id).ok()) {
+        response.headers_mut().insert(MCP_SESSION_ID, session);
+    }
+    response
+}
+
+/// Opens an event stream. A client reconnecting with `Last-Event-ID` (or
+/// `?session_id=`) gets its session back, with the events it missed, rather
+/// than a new one that would lose in-flight responses.
+pub async fn sse_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<SseParams>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    // Event IDs are "{session_id}:{n}"
+    let last_event = headers
+        .get("Last-Event-ID")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|id| id.rsplit_once(':'))
+        .and_then(|(session_id, n)| Some((session_id.to_string(), n.parse::<u64>().ok()?)));
+    let resume_id = params.session_id.or_else(|| {
+        last_event
+            .as_ref()
+            .map(|(session_id, _)| session_id.clone())
+    });
+    let last_event_id = last_event
+        .filter(|(session_id, _)| Some(session_id) == resume_id.as_ref())
+        .map(|(_, n)| n);
+
+    let mut resumed = false;
+    if let Some(session_id) = &resume_id {
+        if let Some(session) = state.sessions.read().await.get(session_id) {
+            let replayed = session.resume(tx.clone(), last_event_id);
+            info!(
+                "Resumed session {}, replaying {} events",
+                session_id, replayed
+            );
+            session.send("endpoint", format!("/message?session_id={}", session_id));
+            resumed = true;
+        }
+    }
+    if !resumed {
+        // Send initial endpoint event
+        let session_id = Uuid::new_v4().to_string();
+        let session = Session::new(session_id.clone(), tx)
+            .with_replay_buffer(state.settings.sse.replay_events);
+        session.send("endpoint", format!("/message?session_id={}", session_id));
+        state.sessions.write().await.insert(session_id, session);
+    }
+
+    let sse = &state.settings.sse;
+    let keep_alive = KeepAlive::new()
+        .interval(std::time::Duration::from_secs(
+            sse.keep_alive_seconds.max(1),
+        ))
+        .text(sse.keep_alive_text.clone());
+    Sse::new(UnboundedReceiverStream::new(rx)).keep_alive(keep_alive)
+}
+
+pub async fn message_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<MessageParams>,
+    Json(req): Json<JsonRpcRequest>,
+) -> impl IntoResponse {
+    // Sessions take turns when requests queue up for the rate limit
+    let settings = Settings {
+        session: Some(params.session_id.clone()),
+        ..state.settings.clone()
+    };
+    let response = process_mcp_request(req, &settings).await;
+
+    if let Some(id) = response.0 {
+        let output = format_json_rpc_response(id, response.1);
+
+        // Find session and send response via SSE
+        if let Some(session) = state.sessions.read().await.get(&params.session_id) {
+            session.send("message", output.to_string());
+        }
+    }
+    if let Some(session) = state.sessions.read().await.get(&params.session_id) {
+        session.requests.fetch_add(1, Ordering::Relaxed);
+        session.last_seen.store(now_secs(), Ordering::Relaxed);
+    }
+
+    StatusCode::ACCEPTED
+}
+
+/// Rejects MCP requests from browser pages whose `Origin` isn't allowed
+/// under `[http] allowed_origins` (by default, anything but localhost), so
+/// a site can't reach a local server through DNS rebinding. Requests with
+/// no `Origin`, i.e. not from a browser, are let through.
+async fn origin_guard(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let origin = request
+        .headers()
+        .get(header::ORIGIN)
+        .map(|o| o.to_str().unwrap_or_default().to_string());
+    match origin {
+        Some(origin) if !origin_allowed(&state.settings.http.allowed_origins, &origin) => {
+            warn!("Rejected request from origin '{}'", origin);
+            (StatusCode::FORBIDDEN, "Origin not allowed").into_response()
+        }
+        _ => next.run(request).await,
+    }
+}
+
+fn origin_allowed(allowed: &[String], origin: &str) -> bool {
+    let origin = origin.trim_end_matches('/');
+    if allowed.is_empty() {
+        // scheme://host[:port], with IPv6 hosts in brackets
+        let host = origin.split_once("://").map_or("", |(_, rest)| rest);
+        let host = match host.strip_prefix('[') {
+            Some(v6) => v6.split(']').next().unwrap_or_default(),
+            None => host.split(':').next().unwrap_or_default(),
+        };
+        return matches!(host, "localhost" | "127.0.0.1" | "::1");
+    }
+    allowed
+        .iter()
+        .any(|a| a == "*" || a.trim_end_matches('/').eq_ignore_ascii_case(origin))
+}
+
+/// Admin routes need the server's bearer token, and are disabled when the
+/// server runs without one.
+fn authorized_admin(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Result<(), (StatusCode, &'static str)> {
+    let Some(token) = &state.auth_token else {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Admin routes require the server to run with --auth-token",
+        ));
+    };
+    let header_ok = headers
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|h| h == format!("Bearer {}", token));
+    if header_ok {
+        Ok(())
+    } else {
+        Err((StatusCode::UNAUTHORIZED, "Unauthorized"))
+    }
+}
+
+/// Liveness: the process is up and serving.
+pub async fn health_handler() -> impl IntoResponse {
+    Json(json!({ "status": "ok" }))
+}
+
+/// Readiness: whether tool calls can succeed right now. Answers 200 with
+/// status "ready", or 503 with "rate_limited" (requests are being turned
+/// away, here or by the API), "key_invalid" (the API refuses the key) or
+/// "upstream_unreachable" (the API can't be reached or is failing), so
+/// orchestrators can tell a bad deployment from an outage.
+pub async fn readiness_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let settings = &state.settings;
+    let queued = settings.queue.len();
+    let rate_limited = || {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            json!({
+                "status": "rate_limited",
+                "checks": { "config": "ok", "rate_limit": "exhausted" },
+                "queued": queued,
+            }),
+        )
+    };
+    if settings.queue.is_full() {
+        let (status, body) = rate_limited();
+        return (status, Json(body));
+    }
+
+    let key = format!("{}|{}", settings.base_url, settings.api_key);
+    if let Some((status, body)) = READINESS_CACHE.get(&key).await {
+        return (status, Json(body));
+    }
+    // The check itself takes a slot; none free means callers are waiting too
+    if settings.limiter.check().is_err() {
+        let (status, body) = rate_limited();
+        return (status, Json(body));
+    }
+
+    let (status, body) = match check_api_key_now(settings).await {
+        Ok(()) => (
+            StatusCode::OK,
+            json!({
+                "status": "ready",
+                "checks": { "config": "ok", "api_key": "ok", "upstream": "ok", "rate_limit": "ok" },
+            }),
+        ),
+        Err(e @ AppError::ConfigError(_)) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            json!({
+                "status": "key_invalid",
+                "checks": { "config": "ok", "api_key": "invalid", "upstream": "ok" },
+                "error": e.to_string(),
+            }),
+        ),
+        // The API answered, so it is reachable; it is throttling this key
+        Err(e) if e.is_rate_limited() => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            json!({
+                "status": "rate_limited",
+                "checks": { "config": "ok", "api_key": "unknown", "upstream": "ok", "rate_limit": "exhausted" },
+                "error": e.to_string(),
+            }),
+        ),
+        Err(e) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            json!({
+                "status": "upstream_unreachable",
+                "checks": { "config": "ok", "api_key": "unknown", "upstream": "unreachable" },
+                "error": e.to_string(),
+            }),
+        ),
+    };
+    READINESS_CACHE.insert(key, (status, body.clone())).await;
+    (status, Json(body))
+}
+
+/// Connected SSE sessions, oldest first. Sessions whose client has gone
+/// away are dropped first.
+pub async fn list_sessions_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(rejection) = authorized_admin(&state, &headers) {
+        warn!("Unauthorized admin request");
+        return rejection.into_response();
+    }
+
+    let mut sessions = state.sessions.write().await;
+    sessions.retain(|_, session| !session.is_closed());
+    let mut list: Vec<Value> = sessions
+        .iter()
+        .map(|(id, session)| {
+            json!({
+                "session_id": id,
+                "connected_at": session.connected_at,
+                "requests": session.requests.load(Ordering::Relaxed),
+                "last_seen": session.last_seen.load(Ordering::Relaxed),
+            })
+        })
+        .collect();
+    list.sort_by_key(|s| s["connected_at"].as_u64());
+    Json(json!({ "sessions": list })).into_response()
+}
+
+/// Disconnects a session by closing its event stream.
+pub async fn close_session_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(rejection) = authorized_admin(&state, &headers) {
+        warn!("Unauthorized admin request");
+        return rejection.into_response();
+    }
+
+    match state.sessions.write().await.remove(&session_id) {
+        Some(_) => {
+            info!("Closed session {} by admin request", session_id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        None => (StatusCode::NOT_FOUND, "Session not found").into_response(),
+    }
+}
+
+/// Auth check for plain GET endpoints (feeds, images), which also accept
+/// the token as a query parameter since feed readers and `<img>` tags
+/// can't send an Authorization header.
+fn authorized_for_get(state: &AppState, headers: &HeaderMap, query_token: Option<&str>) -> bool {
+    let Some(token) = &state.auth_token else {
+        return true;
+    };
+    let header_ok = headers
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|h| h == format!("Bearer {}", token));
+    header_ok || query_token == Some(token.as_str())
+}
+
+pub async fn feed_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Query(params): Query<FeedParams>,
+) -> impl IntoResponse {
+    if !authorized_for_get(&state, &headers, params.token.as_deref()) {
+        warn!("Unauthorized feed request");
+        return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    }
+
+    // Keep the access token out of the feed's self link
+    let query: Vec<&str> = uri
+        .query()
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty() && !pair.starts_with("token="))
+        .collect();
+    let self_url = if query.is_empty() {
+        uri.path().to_string()
+    } else {
+        format!("{}?{}", uri.path(), query.join("&"))
+    };
+
+    match animals_feed(&state.settings, params, &self_url).await {
+        Ok(feed) => (
+            [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+            feed,
+        )
+            .into_response(),
+        Err(e) => {
+            warn!("Failed to build feed: {}", e);
+            (StatusCode::BAD_GATEWAY, e.to_string()).into_response()
+        }
+    }
+}
+
+pub async fn image_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((animal_id, index)): Path<(String, usize)>,
+    Query(params): Query<ImageParams>,
+) -> impl IntoResponse {
+    if !authorized_for_get(&state, &headers, params.token.as_deref()) {
+        warn!("Unauthorized image request");
+        return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    }
+    if let Some(size) = params.size {
+        if !(MIN_THUMBNAIL_SIZE..=MAX_THUMBNAIL_SIZE).contains(&size) {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "size must be between {} and {}",
+                    MIN_THUMBNAIL_SIZE, MAX_THUMBNAIL_SIZE
+                ),
+            )
+                .into_response();
+        }
+    }
+
+    match fetch_animal_image(&state.settings, &animal_id, index, params.size).await {
+        Ok(image) => (
+            [
+                (header::CONTENT_TYPE, image.content_type),
+                (header::CACHE_CONTROL, "public, max-age=86400".to_string()),
+            ],
+            image.bytes.as_ref().clone(),
+        )
+            .into_response(),
+        Err(AppError::NotFound) => (StatusCode::NOT_FOUND, "Image not found").into_response(),
+        Err(e) => {
+            warn!("Failed to proxy image: {}", e);
+            (StatusCode::BAD_GATEWAY, e.to_string()).into_response()
+        }
+    }
+}
+
+/// Saved comparisons are shared with people outside the session, so the
+/// unguessable token is their only access control.
+pub async fn comparison_handler(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    let page = get_comparison(&state.settings, &token)
+        .await
+        .and_then(|c| format_comparison_html(&serde_json::to_value(c)?));
+    match page {
+        Ok(html) => ([(header::CONTENT_TYPE, "text/html; charset=utf-8")], html).into_response(),
+        Err(AppError::NotFound) => (StatusCode::NOT_FOUND, "Comparison not found").into_response(),
+        Err(e) => {
+            warn!("Failed to render comparison: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+    use axum::http::{Request, StatusCode};
+    use governor::{Quota, RateLimiter};
+
+    use serde_json::json;
+    use std::num::NonZeroU32;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    fn get_test_settings() -> Settings {
+        Settings::for_test(
+            "http://test.url",
+            std::env::temp_dir().join("rescue-groups-mcp-test"),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_sse_channel_broadcasts() {
+        let sessions: SessionsMap = Arc::new(RwLock::new(HashMap::new()));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        sessions
+            .write()
+            .await
+            .insert("s1".to_string(), Session::new("s1".to_string(), tx));
+
+        let channel = SseChannel { sessions };
+        let alerts = vec![Alert {
+            search_name: "dogs".to_string(),
+            animal: json!({"id": "1", "name": "Buddy"}),
+        }];
+        channel.notify(&alerts).await.unwrap();
+        assert!(rx.recv().await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_http_handler_no_auth() {
+        let state = Arc::new(AppState {
+            settings: get_test_settings(),
+            auth_token: None,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+
+        let app = Router::new()
+            .route("/", post(http_handler))
+            .with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/")
+                    .header("content-type", "application/json")
+                    .body(axum::body::Body::from(
+                        serde_json::to_string(&json!({
+                            "jsonrpc": "2.0",
+                            "id": 1,
+                            "method": "ping"
+                        }))
+                        .unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_http_handler_unauthorized() {
+        let state = Arc::new(AppState {
+            settings: get_test_settings(),
+            auth_token: Some("secret".to_string()),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+
+        let app = Router::new()
+            .route("/", post(http_handler))
+            .with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/")
+                    .header("content-type", "application/json")
+                    .body(axum::body::Body::from(
+                        serde_json::to_string(&json!({
+                            "jsonrpc": "2.0",
+                            "id": 1,
+                            "method": "ping"
+                        }))
+                        .unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_sse_handler() {
+        let state = Arc::new(AppState {
+            settings: get_test_settings(),
+            auth_token: None,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+
+        let app = Router::new()
+            .route("/sse", get(sse_handler))
+            .with_state(state.clone());
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/sse")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(state.sessions.read().await.len(), 1);
+    }
+
+    #[test]
+    fn test_session_replays_missed_events() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let session = Session::new("abc".to_string(), tx).with_replay_buffer(2);
+        assert!(session.send("message", "one"));
+        drop(rx);
+        assert!(!session.send("message", "two"));
+        assert!(!session.send("message", "three"));
+        assert!(session.is_closed());
+
+        // Without Last-Event-ID, what the client missed while away
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        assert_eq!(session.resume(tx, None), 2);
+        let replayed = format!("{:?}", rx.try_recv().unwrap().unwrap());
+        assert!(replayed.contains("id: abc:2"));
+        assert!(replayed.contains("data: two"));
+        assert!(!session.is_closed());
+
+        // Only the buffered events after the client's last one
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        assert_eq!(session.resume(tx, Some(2)), 1);
+        assert!(format!("{:?}", rx.try_recv().unwrap().unwrap()).contains("id: abc:3"));
+        let (tx, _rx) = mpsc::unbounded_channel();
+        assert_eq!(session.resume(tx, None), 0);
+    }
+
+    #[tokio::test]
+    async fn test_sse_handler_resumes_session() {
+        let state = Arc::new(AppState {
+            settings: get_test_settings(),
+            auth_token: None,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+        let (tx, rx) = mpsc::unbounded_channel();
+        let session = Session::new("abc".to_string(), tx);
+        session.send("endpoint", "/message?session_id=abc");
+        drop(rx);
+        session.send("message", "{}");
+        state
+            .sessions
+            .write()
+            .await
+            .insert("abc".to_string(), session);
+
+        let app = Router::new()
+            .route("/sse", get(sse_handler))
+            .with_state(state.clone());
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/sse")
+                    .header("Last-Event-ID", "abc:1")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let sessions = state.sessions.read().await;
+        assert_eq!(sessions.len(), 1);
+        // The session lives on, with a fresh endpoint event after the replay
+        assert!(!sessions["abc"].is_closed());
+        assert_eq!(sessions["abc"].last_event_id.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn test_http_handler_no_id() {
+        let state = Arc::new(AppState {
+            settings: get_test_settings(),
+            auth_token: None,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+
+        let app = Router::new()
+            .route("/", post(http_handler))
+            .with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/")
+                    .header("content-type", "application/json")
+                    .body(axum::body::Body::from(
+                        serde_json::to_string(&json!({
+                            "jsonrpc": "2.0",
+                            "method": "notifications/initialized"
+                        }))
+                        .unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_message_handler() {
+        let state = Arc::new(AppState {
+            settings: get_test_settings(),
+            auth_token: None,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let session_id = "test-session".to_string();
+        state
+            .sessions
+            .write()
+            .await
+            .insert(session_id.clone(), Session::new(session_id.clone(), tx));
+
+        let app = Router::new()
+            .route("/message", post(message_handler))
+            .with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/message?session_id={}", session_id))
+                    .header("content-type", "application/json")
+                    .body(axum::body::Body::from(
+                        serde_json::to_string(&json!({
+                            "jsonrpc": "2.0",
+                            "id": 1,
+                            "method": "ping"
+                        }))
+                        .unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        // Check if message was sent to SSE, numbered within the session
+        let msg = rx.recv().await.unwrap().unwrap();
+        assert!(format!("{:?}", msg).contains("id: test-session:1\\n"));
+    }
+
+    #[tokio::test]
+    async fn test_message_handler_no_id() {
+        let state = Arc::new(AppState {
+            settings: get_test_settings(),
+            auth_token: None,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+
+        let app = Router::new()
+            .route("/message", post(message_handler))
+            .with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/message?session_id=test")
+                    .header("content-type", "application/json")
+                    .body(axum::body::Body::from(
+                        serde_json::to_string(&json!({
+                            "jsonrpc": "2.0",
+                            "method": "notifications/initialized"
+                        }))
+                        .unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+    }
+
+    #[tokio::test]
+    async fn test_message_handler_invalid_session() {
+        let state = Arc::new(AppState {
+            settings: get_test_settings(),
+            auth_token: None,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+
+        let app = Router::new()
+            .route("/message", post(message_handler))
+            .with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/message?session_id=invalid")
+                    .header("content-type", "application/json")
+                    .body(axum::body::Body::from(
+                        serde_json::to_string(&json!({
+                            "jsonrpc": "2.0",
+                            "id": 1,
+                            "method": "ping"
+                        }))
+                        .unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+    }
+
+    #[tokio::test]
+    async fn test_create_router() {
+        let state = Arc::new(AppState {
+            settings: get_test_settings(),
+            auth_token: None,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+        let _router = create_router(state);
+    }
+
+    #[tokio::test]
+    async fn test_origin_guard() {
+        let mut settings = get_test_settings();
+        let ping = |origin: Option<&str>| {
+            let mut request = Request::builder()
+                .method("POST")
+                .uri("/")
+                .header("content-type", "application/json");
+            if let Some(origin) = origin {
+                request = request.header("Origin", origin);
+            }
+            request
+                .body(axum::body::Body::from(
+                    json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}).to_string(),
+                ))
+                .unwrap()
+        };
+        let status = |settings: &Settings, origin: Option<&'static str>| {
+            let app = create_router(Arc::new(AppState {
+                settings: settings.clone(),
+                auth_token: None,
+                sessions: Arc::new(RwLock::new(HashMap::new())),
+            }));
+            let request = ping(origin);
+            async move { app.oneshot(request).await.unwrap().status() }
+        };
+
+        // By default only non-browser clients and localhost pages
+        assert_eq!(status(&settings, None).await, StatusCode::OK);
+        assert_eq!(
+            status(&settings, Some("http://localhost:5173")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&settings, Some("http://[::1]:8080")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&settings, Some("http://evil.example")).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(&settings, Some("http://localhost.evil.example")).await,
+            StatusCode::FORBIDDEN
+        );
+
+        settings.http.allowed_origins = vec!["https://app.example.com/".to_string()];
+        assert_eq!(
+            status(&settings, Some("https://APP.example.com")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&settings, Some("http://localhost:5173")).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn test_readiness() {
+        let mut server = mockito::Server::new_async().await;
+        let ready = |settings: Settings| {
+            let app = create_router(Arc::new(AppState {
+                settings,
+                auth_token: None,
+                sessions: Arc::new(RwLock::new(HashMap::new())),
+            }));
+            let request = Request::builder()
+                .uri("/ready")
+                .body(axum::body::Body::empty())
+                .unwrap();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (status, serde_json::from_slice::<Value>(&body).unwrap())
+            }
+        };
+
+        let _ok = server
+            .mock("GET", "/public/animals/species?limit=1")
+            .match_header("Authorization", "good_key")
+            .with_status(200)
+            .with_body(r#"{"data": []}"#)
+            .create_async()
+            .await;
+        let _refused = server
+            .mock("GET", "/public/animals/species?limit=1")
+            .match_header("Authorization", "bad_key")
+            .with_status(401)
+            .create_async()
+            .await;
+        let _throttled = server
+            .mock("GET", "/public/animals/species?limit=1")
+            .match_header("Authorization", "busy_key")
+            .with_status(429)
+            .create_async()
+            .await;
+
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+        settings.api_key = "good_key".to_string();
+        let (status, body) = ready(settings.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ready");
+
+        settings.api_key = "bad_key".to_string();
+        let (status, body) = ready(settings.clone()).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "key_invalid");
+        assert_eq!(body["checks"]["api_key"], "invalid");
+
+        settings.api_key = "busy_key".to_string();
+        let (status, body) = ready(settings.clone()).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "rate_limited");
+        assert_eq!(body["checks"]["upstream"], "ok");
+
+        settings.base_url = "http://127.0.0.1:1".to_string();
+        let (status, body) = ready(settings.clone()).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "upstream_unreachable");
+
+        settings.base_url = "http://127.0.0.1:2".to_string();
+        settings.limiter = Arc::new(RateLimiter::direct(Quota::per_hour(
+            NonZeroU32::new(1).unwrap(),
+        )));
+        settings.limiter.check().unwrap();
+        let (status, body) = ready(settings).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "rate_limited");
+    }
+
+    #[tokio::test]
+    async fn test_run_http_server_writes_pid_file() {
+        let settings = get_test_settings();
+        let pid_file = std::env::temp_dir().join("rescue-groups-mcp-http-test.pid");
+        let _ = std::fs::remove_file(&pid_file);
+        let args = HttpArgs {
+            host: "127.0.0.1".to_string(),
+            port: 0,
+            auth_token: None,
+            daemonize: false,
+            pid_file: Some(pid_file.to_str().unwrap().to_string()),
+            log_file: None,
+        };
+
+        let handle = tokio::spawn(async move {
+            let _ = run_http_server(args, settings).await;
+        });
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(pid_file.exists());
+        handle.abort();
+        let _ = std::fs::remove_file(&pid_file);
+    }
+
+    #[tokio::test]
+    async fn test_run_http_server_startup() {
+        let settings = get_test_settings();
+        let args = HttpArgs {
+            host: "127.0.0.1".to_string(),
+            port: 0, // Let OS pick a free port
+            auth_token: None,
+            daemonize: false,
+            pid_file: None,
+            log_file: None,
+        };
+
+        let handle = tokio::spawn(async move {
+            let _ = run_http_server(args, settings).await;
+        });
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_feed_handler() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+
+        let _mock = server
+            .mock(
+                "POST",
+                "/public/animals/search/available/cats/haspic?sort=-animals.createdDate",
+            )
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "data": { "filterRadius": { "postalcode": "90210" } }
+            })))
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "5", "attributes": {"name": "Whiskers"}}]}"#)
+            .create_async()
+            .await;
+
+        let state = Arc::new(AppState {
+            settings,
+            auth_token: Some("secret".to_string()),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+        let app = create_router(state);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/feeds/animals.xml?species=cats&postal_code=90210")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/feeds/animals.xml?species=cats&postal_code=90210&token=secret")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["content-type"],
+            "application/atom+xml; charset=utf-8"
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let feed = String::from_utf8(body.to_vec()).unwrap();
+        assert!(feed.contains("<title>Adoptable cats near 90210</title>"));
+        assert!(feed.contains("<title>Whiskers</title>"));
+        assert!(feed.contains("href=\"/feeds/animals.xml?species=cats&amp;postal_code=90210\""));
+        assert!(!feed.contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn test_image_handler() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+
+        let _animal = server
+            .mock("GET", "/public/animals/9100002")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "9100002", "attributes": {"orgsAnimalsPictures": []}}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let state = Arc::new(AppState {
+            settings,
+            auth_token: None,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+        let app = create_router(state);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/img/9100002/0?size=4")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/img/9100002/0?size=100")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_comparison_handler() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+        settings.data_dir = std::env::temp_dir().join("rescue-groups-mcp-server-comparison");
+        let _ = std::fs::remove_dir_all(&settings.data_dir);
+
+        let _animal = server
+            .mock("GET", "/public/animals/9100003")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "9100003", "attributes": {"name": "Pepper & Salt"}}]}"#)
+            .create_async()
+            .await;
+        let saved = crate::comparisons::save_comparison(
+            &settings,
+            crate::cli::CompareArgs {
+                animal_ids: vec!["9100003".to_string()],
+            },
+        )
+        .await
+        .unwrap();
+
+        let data_dir = settings.data_dir.clone();
+        let state = Arc::new(AppState {
+            settings,
+            // Shared pages don't require the server's token
+            auth_token: Some("secret".to_string()),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+        let app = create_router(state);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/c/{}", saved.token))
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("Pepper &amp; Salt"));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/c/unknown")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_session_admin_routes() {
+        let state = Arc::new(AppState {
+            settings: get_test_settings(),
+            auth_token: Some("secret".to_string()),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let session = Session::new("s1".to_string(), tx);
+        session.requests.store(3, Ordering::Relaxed);
+        state
+            .sessions
+            .write()
+            .await
+            .insert("s1".to_string(), session);
+        let app = create_router(state.clone());
+
+        let admin_request = |method: &str, uri: &str, token: Option<&str>| {
+            let mut builder = Request::builder().method(method).uri(uri);
+            if let Some(token) = token {
+                builder = builder.header("Authorization", format!("Bearer {}", token));
+            }
+            builder.body(axum::body::Body::empty()).unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(admin_request("GET", "/admin/sessions", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .clone()
+            .oneshot(admin_request("GET", "/admin/sessions", Some("secret")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let list: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(list["sessions"][0]["session_id"], "s1");
+        assert_eq!(list["sessions"][0]["requests"], 3);
+
+        let response = app
+            .clone()
+            .oneshot(admin_request(
+                "DELETE",
+                "/admin/sessions/s1",
+                Some("secret"),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        // The client's event stream ends
+        assert!(rx.recv().await.is_none());
+
+        let response = app
+            .oneshot(admin_request(
+                "DELETE",
+                "/admin/sessions/s1",
+                Some("secret"),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    /// Posts a JSON-RPC request to the streamable transport, returning the
+    /// session it issued, if any, and the response body.
+    async fn post_mcp(
+        app: &Router,
+        session: Option<&str>,
+        method: &str,
+        params: Value,
+    ) -> (Option<String>, Value) {
+        let mut request = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header("content-type", "application/json");
+        if let Some(session) = session {
+            request = request.header(MCP_SESSION_ID, session);
+        }
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response = app
+            .clone()
+            .oneshot(
+                request
+                    .body(axum::body::Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let issued = response
+            .headers()
+            .get(MCP_SESSION_ID)
+            .map(|h| h.to_str().unwrap().to_string());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (issued, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_http_sessions_continue_their_own_searches() {
+        let mut server = mockito::Server::new_async().await;
+        let page = |prefix: &str| {
+            let animals: Vec<Value> = (1..=7)
+                .map(|i| json!({ "id": format!("{}{}", prefix, i), "attributes": { "name": format!("{}{}", prefix, i) } }))
+                .collect();
+            json!({ "data": animals, "meta": { "count": 7, "pages": 1 } }).to_string()
+        };
+        let _cats = server
+            .mock("POST", "/public/animals/search/available/cats/haspic")
+            .with_status(200)
+            .with_body(page("Cat"))
+            .create_async()
+            .await;
+        let _dogs = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .with_status(200)
+            .with_body(page("Dog"))
+            .create_async()
+            .await;
+        let state = Arc::new(AppState {
+            settings: Settings::for_test(
+                server.url(),
+                std::env::temp_dir().join("rescue-groups-mcp-http-sessions"),
+            ),
+            auth_token: None,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+        let app = create_router(state);
+        let search = |species: &str| json!({ "name": "search_adoptable_pets", "arguments": { "species": species } });
+        let next = json!({ "name": "next_results" });
+        let text = |body: &Value| {
+            body["result"]["content"][0]["text"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+
+        let (alice, _) = post_mcp(&app, None, "initialize", json!({})).await;
+        let (bob, _) = post_mcp(&app, None, "initialize", json!({})).await;
+        let (alice, bob) = (alice.unwrap(), bob.unwrap());
+        assert_ne!(alice, bob);
+
+        post_mcp(&app, Some(&alice), "tools/call", search("cats")).await;
+        post_mcp(&app, Some(&bob), "tools/call", search("dogs")).await;
+
+        let (_, body) = post_mcp(&app, Some(&alice), "tools/call", next.clone()).await;
+        assert!(text(&body).contains("[Cat6]"), "{}", body);
+        let (_, body) = post_mcp(&app, Some(&bob), "tools/call", next.clone()).await;
+        assert!(text(&body).contains("[Dog6]"), "{}", body);
+
+        // Without a session there is no last search to continue
+        let (_, body) = post_mcp(&app, None, "tools/call", next).await;
+        assert!(body["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Mcp-Session-Id"));
+    }
+
+    #[tokio::test]
+    async fn test_http_sessions_keep_their_own_preferences() {
+        let state = Arc::new(AppState {
+            settings: get_test_settings(),
+            auth_token: None,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+        let app = create_router(state.clone());
+        let set = json!({ "name": "set_preferences", "arguments": { "weights": { "senior": 3 } } });
+
+        let (alice, _) = post_mcp(&app, None, "initialize", json!({})).await;
+        let (bob, _) = post_mcp(&app, None, "initialize", json!({})).await;
+        let (_, body) = post_mcp(&app, alice.as_deref(), "tools/call", set.clone()).await;
+        assert!(body["error"].is_null(), "{}", body);
+
+        let session = |id: Option<String>| Settings {
+            session: id,
+            ..state.settings.clone()
+        };
+        assert!(crate::preferences::session_preferences(&session(alice))
+            .await
+            .is_some());
+        assert!(crate::preferences::session_preferences(&session(bob))
+            .await
+            .is_none());
+        assert!(crate::preferences::session_preferences(&session(None))
+            .await
+            .is_none());
+
+        // Without a session they would apply to every other client
+        let (_, body) = post_mcp(&app, None, "tools/call", set).await;
+        assert!(body["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Mcp-Session-Id"));
+    }
+
+    #[tokio::test]
+    async fn test_session_admin_requires_auth_token() {
+        let state = Arc::new(AppState {
+            settings: get_test_settings(),
+            auth_token: None,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        });
+        let response = create_router(state)
+            .oneshot(
+                Request::builder()
+                    .uri("/admin/sessions")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
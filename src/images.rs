@@ -0,0 +1,353 @@
+use crate::cli::AnimalIdArgs;
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::fmt::extract_single_item;
+use crate::source::get_animal;
+use image::codecs::jpeg::JpegEncoder;
+use moka::future::Cache;
+use serde_json::{json, Value};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+/// Bounds for the `size` parameter, in pixels.
+pub const MIN_THUMBNAIL_SIZE: u32 = 16;
+pub const MAX_THUMBNAIL_SIZE: u32 = 2048;
+
+// Photos larger than this are refused rather than proxied
+const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+const THUMBNAIL_QUALITY: u8 = 85;
+
+#[derive(Debug, Clone)]
+pub struct ProxiedImage {
+    pub content_type: String,
+    pub bytes: Arc<Vec<u8>>,
+}
+
+// Originals and thumbnails, keyed by "{animal_id}:{index}:{size}" and
+// bounded by total bytes.
+static IMAGE_CACHE: LazyLock<Cache<String, ProxiedImage>> = LazyLock::new(|| {
+    Cache::builder()
+        .weigher(|_key, image: &ProxiedImage| image.bytes.len().try_into().unwrap_or(u32::MAX))
+        .max_capacity(64 * 1024 * 1024)
+        .time_to_live(Duration::from_secs(24 * 60 * 60))
+        .build()
+});
+
+/// URL of the `index`-th photo of an animal.
+async fn picture_url(
+    settings: &Settings,
+    animal_id: &str,
+    index: usize,
+) -> Result<String, AppError> {
+    let details = get_animal(
+        settings,
+        AnimalIdArgs {
+            animal_id: animal_id.to_string(),
+        },
+    )
+    .await?;
+    details
+        .get("data")
+        .and_then(extract_single_item)
+        .and_then(|animal| {
+            animal["attributes"]["orgsAnimalsPictures"]
+                .as_array()?
+                .get(index)
+        })
+        .and_then(|picture| picture["urlSecureFullsize"].as_str())
+        .map(str::to_string)
+        .ok_or(AppError::NotFound)
+}
+
+/// Fetches an image, giving up as soon as it passes `MAX_IMAGE_BYTES`
+/// whatever `Content-Length` it claims, so a proxied image can't fill the
+/// memory.
+async fn download(settings: &Settings, url: &str) -> Result<ProxiedImage, AppError> {
+    let client = reqwest::Client::builder()
+        .timeout(settings.timeout)
+        .build()
+        .map_err(|e| AppError::Internal(format!("Failed to build client: {}", e)))?;
+    let mut response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(AppError::ApiError(format!(
+            "Image fetch failed: {}",
+            response.status()
+        )));
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    if !content_type.starts_with("image/") {
+        return Err(AppError::ApiError(format!(
+            "Unexpected image content type '{}'",
+            content_type
+        )));
+    }
+    if response
+        .content_length()
+        .is_some_and(|len| len as usize > MAX_IMAGE_BYTES)
+    {
+        return Err(AppError::ApiError("Image too large".to_string()));
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() > MAX_IMAGE_BYTES {
+            return Err(AppError::ApiError("Image too large".to_string()));
+        }
+    }
+    Ok(ProxiedImage {
+        content_type,
+        bytes: Arc::new(bytes),
+    })
+}
+
+/// Renditions RescueGroups stores for each photo, largest first.
+const RENDITIONS: [&str; 3] = ["original", "large", "small"];
+
+/// An animal's photos in display order, each with its caption and every
+/// rendition's URL, pixel size and file size where the listing gives them,
+/// so clients can pick one that suits their layout.
+pub fn photo_metadata(animal: &Value) -> Vec<Value> {
+    let mut photos: Vec<Value> = animal["attributes"]["orgsAnimalsPictures"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(index, picture)| {
+            let renditions: Vec<Value> = RENDITIONS
+                .iter()
+                .filter_map(|name| {
+                    let rendition = &picture[*name];
+                    let url = rendition["url"].as_str()?;
+                    Some(json!({
+                        "name": name,
+                        "url": url,
+                        "width": rendition["resolutionX"],
+                        "height": rendition["resolutionY"],
+                        "bytes": rendition["fileSize"],
+                    }))
+                })
+                .collect();
+            json!({
+                "index": index,
+                "order": picture["order"].as_u64().unwrap_or(index as u64 + 1),
+                "caption": picture["caption"]
+                    .as_str()
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty()),
+                "url": picture["urlSecureFullsize"],
+                "thumbnail_url": picture["urlSecureThumbnail"],
+                "renditions": renditions,
+            })
+        })
+        .collect();
+    photos.sort_by_key(|photo| photo["order"].as_u64());
+    photos
+}
+
+/// The photo gallery of an animal: its ID, name and `photo_metadata`.
+/// `index` is the position to pass to the image proxy.
+pub async fn get_animal_photos(settings: &Settings, args: AnimalIdArgs) -> Result<Value, AppError> {
+    let details = get_animal(settings, args.clone()).await?;
+    let animal = details
+        .get("data")
+        .and_then(extract_single_item)
+        .ok_or(AppError::NotFound)?;
+    Ok(json!({
+        "animal_id": args.animal_id,
+        "name": animal["attributes"]["name"],
+        "photos": photo_metadata(animal),
+    }))
+}
+
+/// Scales an image to fit within `size`×`size` (keeping its aspect ratio)
+/// and re-encodes it as JPEG.
+pub fn make_thumbnail(bytes: &[u8], size: u32) -> Result<Vec<u8>, AppError> {
+    let image = image::load_from_memory(bytes)
+        .map_err(|e| AppError::ApiError(format!("Failed to decode image: {}", e)))?;
+    let thumbnail = image.thumbnail(size, size).to_rgb8();
+
+    let mut output = Vec::new();
+    thumbnail
+        .write_with_encoder(JpegEncoder::new_with_quality(
+            &mut output,
+            THUMBNAIL_QUALITY,
+        ))
+        .map_err(|e| AppError::Internal(format!("Failed to encode thumbnail: {}", e)))?;
+    Ok(output)
+}
+
+/// Fetches an animal's photo through the server, optionally resized so its
+/// longest side is at most `size` pixels. Originals and thumbnails are
+/// cached, so repeated requests never reach the image CDN.
+pub async fn fetch_animal_image(
+    settings: &Settings,
+    animal_id: &str,
+    index: usize,
+    size: Option<u32>,
+) -> Result<ProxiedImage, AppError> {
+    let key = format!("{}:{}:{}", animal_id, index, size.unwrap_or(0));
+    if let Some(cached) = IMAGE_CACHE.get(&key).await {
+        return Ok(cached);
+    }
+
+    let original_key = format!("{}:{}:0", animal_id, index);
+    let original = match IMAGE_CACHE.get(&original_key).await {
+        Some(cached) => cached,
+        None => {
+            let url = picture_url(settings, animal_id, index).await?;
+            let original = download(settings, &url).await?;
+            IMAGE_CACHE.insert(original_key, original.clone()).await;
+            original
+        }
+    };
+
+    let Some(size) = size else {
+        return Ok(original);
+    };
+
+    // Decoding and resizing is CPU-bound, keep it off the async workers
+    let bytes = original.bytes.clone();
+    let thumbnail = tokio::task::spawn_blocking(move || make_thumbnail(&bytes, size))
+        .await
+        .map_err(|e| AppError::Internal(format!("Thumbnail task failed: {}", e)))??;
+    let image = ProxiedImage {
+        content_type: "image/jpeg".to_string(),
+        bytes: Arc::new(thumbnail),
+    };
+    IMAGE_CACHE.insert(key, image.clone()).await;
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use image::{ImageFormat, RgbImage};
+
+    use std::io::Cursor;
+
+    fn get_test_settings(url: String) -> Settings {
+        Settings::for_test(url, std::env::temp_dir().join("rescue-groups-mcp-test"))
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        RgbImage::new(width, height)
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_make_thumbnail_keeps_aspect_ratio() {
+        let thumbnail = make_thumbnail(&png(400, 200), 100).unwrap();
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (100, 50));
+
+        assert!(make_thumbnail(b"not an image", 100).is_err());
+    }
+
+    #[test]
+    fn test_photo_metadata() {
+        let animal = json!({"attributes": {"orgsAnimalsPictures": [
+            {
+                "order": 2,
+                "urlSecureFullsize": "https://cdn/b.jpg",
+                "original": {"url": "https://cdn/b-orig.jpg", "resolutionX": 1200, "resolutionY": 900, "fileSize": 250000},
+                "small": {"url": "https://cdn/b-small.jpg", "resolutionX": 100, "resolutionY": 75}
+            },
+            {"order": 1, "caption": " Napping ", "urlSecureFullsize": "https://cdn/a.jpg"}
+        ]}});
+
+        let photos = photo_metadata(&animal);
+        assert_eq!(photos[0]["caption"], "Napping");
+        assert_eq!(photos[0]["index"], 1);
+        assert_eq!(photos[0]["renditions"], json!([]));
+        assert!(photos[1]["caption"].is_null());
+        assert_eq!(
+            photos[1]["renditions"],
+            json!([
+                {"name": "original", "url": "https://cdn/b-orig.jpg", "width": 1200, "height": 900, "bytes": 250000},
+                {"name": "small", "url": "https://cdn/b-small.jpg", "width": 100, "height": 75, "bytes": null}
+            ])
+        );
+
+        let text = crate::fmt::format_animal_photos(&json!({"name": "Rex", "photos": photos}));
+        assert!(text.starts_with("## Photos of Rex (2)"));
+        assert!(text.contains("### Photo 1: Napping\n![Napping](https://cdn/a.jpg)"));
+        assert!(text.contains(
+            "**Sizes:** [original 1200×900](https://cdn/b-orig.jpg), [small 100×75](https://cdn/b-small.jpg)"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_animal_image() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _animal = server
+            .mock("GET", "/public/animals/9100001")
+            .with_status(200)
+            .with_body(format!(
+                r#"{{"data": [{{"id": "9100001", "attributes": {{"orgsAnimalsPictures": [{{"urlSecureFullsize": "{}/photo.png"}}]}}}}]}}"#,
+                server.url()
+            ))
+            .create_async()
+            .await;
+        let photo = server
+            .mock("GET", "/photo.png")
+            .with_status(200)
+            .with_header("content-type", "image/png")
+            .with_body(png(64, 32))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let original = fetch_animal_image(&settings, "9100001", 0, None)
+            .await
+            .unwrap();
+        assert_eq!(original.content_type, "image/png");
+
+        // The thumbnail is derived from the cached original
+        let thumbnail = fetch_animal_image(&settings, "9100001", 0, Some(16))
+            .await
+            .unwrap();
+        assert_eq!(thumbnail.content_type, "image/jpeg");
+        let decoded = image::load_from_memory(&thumbnail.bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (16, 8));
+        photo.assert_async().await;
+
+        let missing = fetch_animal_image(&settings, "9100001", 3, None).await;
+        assert!(matches!(missing, Err(AppError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_download_stops_at_size_limit() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+        // Chunked, so no Content-Length gives the size away up front
+        let _huge = server
+            .mock("GET", "/huge.png")
+            .with_status(200)
+            .with_header("content-type", "image/png")
+            .with_chunked_body(|w| {
+                let chunk = vec![0u8; 1024 * 1024];
+                for _ in 0..=MAX_IMAGE_BYTES / chunk.len() {
+                    w.write_all(&chunk)?;
+                }
+                Ok(())
+            })
+            .create_async()
+            .await;
+
+        let result = download(&settings, &format!("{}/huge.png", server.url())).await;
+        assert!(matches!(result, Err(AppError::ApiError(msg)) if msg == "Image too large"));
+    }
+}
@@ -0,0 +1,205 @@
+use governor::{
+    clock::DefaultClock,
+    state::{InMemoryState, NotKeyed},
+    Quota, RateLimiter,
+};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// How long a key that was refused as unauthorized stays out of rotation.
+const REVOKED_BENCH: Duration = Duration::from_secs(60 * 60);
+
+struct PooledKey {
+    key: String,
+    limiter: RateLimiter<NotKeyed, InMemoryState, DefaultClock>,
+    requests: AtomicU64,
+    benched_until: Mutex<Option<Instant>>,
+}
+
+impl PooledKey {
+    fn benched(&self) -> bool {
+        self.benched_until
+            .lock()
+            .unwrap()
+            .is_some_and(|until| until > Instant::now())
+    }
+}
+
+/// Several API keys used in turn, each with its own rate limit. Keys that
+/// are rate-limited or refused upstream sit out for a while, and requests
+/// fail over to the others.
+pub struct KeyPool {
+    keys: Vec<PooledKey>,
+    next: AtomicUsize,
+    window: Duration,
+}
+
+impl std::fmt::Debug for KeyPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyPool")
+            .field("keys", &self.keys.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyPool {
+    /// A pool allowing `max_requests` per `window` for every key.
+    pub fn new(keys: Vec<String>, max_requests: NonZeroU32, window: Duration) -> Self {
+        let quota = Quota::with_period(window)
+            .unwrap()
+            .allow_burst(max_requests);
+        KeyPool {
+            keys: keys
+                .into_iter()
+                .map(|key| PooledKey {
+                    key,
+                    limiter: RateLimiter::direct(quota),
+                    requests: AtomicU64::new(0),
+                    benched_until: Mutex::new(None),
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+            window,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn key(&self, index: usize) -> &str {
+        &self.keys[index].key
+    }
+
+    /// Picks the next key in turn that is in rotation and has capacity,
+    /// waiting for one if all are busy. Returns its index.
+    pub async fn acquire(&self) -> usize {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let order: Vec<usize> = (0..self.keys.len())
+            .map(|i| (start + i) % self.keys.len())
+            .collect();
+        let available: Vec<usize> = order
+            .iter()
+            .copied()
+            .filter(|&i| !self.keys[i].benched())
+            .collect();
+
+        let index = match available
+            .iter()
+            .find(|&&i| self.keys[i].limiter.check().is_ok())
+        {
+            Some(&index) => index,
+            None => {
+                // Every key is busy (or benched; then try the one back soonest)
+                let index = available.first().copied().unwrap_or_else(|| {
+                    *order
+                        .iter()
+                        .min_by_key(|&&i| *self.keys[i].benched_until.lock().unwrap())
+                        .unwrap()
+                });
+                self.keys[index].limiter.until_ready().await;
+                index
+            }
+        };
+        self.keys[index].requests.fetch_add(1, Ordering::Relaxed);
+        index
+    }
+
+    /// Records the upstream response to a request made with key `index`.
+    /// Returns true when the key was taken out of rotation and the request
+    /// is worth retrying with another key.
+    pub fn report(&self, index: usize, status: StatusCode) -> bool {
+        let bench = match status {
+            StatusCode::TOO_MANY_REQUESTS => self.window,
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => REVOKED_BENCH,
+            _ => return false,
+        };
+        warn!(
+            "API key {} got {}; using other keys for {}s",
+            redact(self.key(index)),
+            status,
+            bench.as_secs()
+        );
+        *self.keys[index].benched_until.lock().unwrap() = Some(Instant::now() + bench);
+        self.keys.len() > 1
+    }
+
+    /// Per-key request counts and rotation state, with the keys redacted.
+    pub fn usage(&self) -> Value {
+        self.keys
+            .iter()
+            .map(|k| {
+                json!({
+                    "key": redact(&k.key),
+                    "requests": k.requests.load(Ordering::Relaxed),
+                    "in_rotation": !k.benched(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Only the last four characters of a key, for logs and server info.
+fn redact(key: &str) -> String {
+    let tail: String = key
+        .chars()
+        .rev()
+        .take(4)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    format!("…{}", tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(keys: &[&str]) -> KeyPool {
+        KeyPool::new(
+            keys.iter().map(|k| k.to_string()).collect(),
+            NonZeroU32::new(100).unwrap(),
+            Duration::from_secs(60),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_rotation_and_failover() {
+        let pool = pool(&["key-one", "key-two", "key-three"]);
+        let mut used = vec![
+            pool.acquire().await,
+            pool.acquire().await,
+            pool.acquire().await,
+        ];
+        used.sort();
+        assert_eq!(used, vec![0, 1, 2]);
+
+        assert!(pool.report(1, StatusCode::TOO_MANY_REQUESTS));
+        assert!(!pool.report(0, StatusCode::OK));
+        for _ in 0..4 {
+            assert_ne!(pool.acquire().await, 1);
+        }
+
+        let usage = pool.usage();
+        assert_eq!(usage[1]["key"], "…-two");
+        assert_eq!(usage[1]["in_rotation"], false);
+        assert_eq!(usage[1]["requests"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_all_benched_still_serves() {
+        let pool = pool(&["only"]);
+        assert!(!pool.report(0, StatusCode::UNAUTHORIZED));
+        assert_eq!(pool.acquire().await, 0);
+    }
+}
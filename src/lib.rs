@@ -0,0 +1,79 @@
+//! RescueGroups.org API client, formatters and MCP server.
+//!
+//! The `rescue-groups-mcp` binary is a thin wrapper around this crate. Other
+//! projects can use [`RescueGroupsClient`] directly:
+//!
+//! ```no_run
+//! use rescue_groups_mcp::{cli::ToolArgs, RescueGroupsClient};
+//!
+//! # async fn run() -> Result<(), rescue_groups_mcp::AppError> {
+//! let client = RescueGroupsClient::new("YOUR_API_KEY");
+//! let cats = client
+//!     .search_animals(ToolArgs {
+//!         species: Some("cats".to_string()),
+//!         postal_code: Some("90210".to_string()),
+//!         ..Default::default()
+//!     })
+//!     .await?;
+//! for cat in cats {
+//!     println!("{}", cat.attributes.name.unwrap_or_default());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod alerts;
+pub mod arrivals;
+pub mod breed_rescues;
+pub mod cassette;
+pub mod cli;
+pub mod client;
+pub mod commands;
+pub mod comparisons;
+pub mod config;
+pub mod continuation;
+pub mod daemon;
+pub mod diagnostics;
+pub mod digest;
+pub mod drift;
+pub mod error;
+pub mod export;
+pub mod favorites;
+pub mod feeds;
+pub mod filters;
+pub mod fmt;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod images;
+pub mod keys;
+pub mod locations;
+pub mod matching;
+pub mod mcp;
+pub mod metrics;
+pub mod models;
+pub mod notes;
+#[cfg(feature = "notify")]
+pub mod notifications;
+pub mod petfinder;
+pub mod plugins;
+pub mod postal;
+pub mod preferences;
+pub mod private_api;
+pub mod query;
+pub mod queue;
+pub mod resources;
+pub mod semantic;
+pub mod server;
+pub mod shared_cache;
+pub mod site;
+pub mod source;
+pub mod store;
+pub mod synonyms;
+pub mod trace;
+pub mod updates;
+pub mod validate;
+
+pub use client::RescueGroupsClient;
+pub use config::Settings;
+pub use error::AppError;
+pub use models::{Animal, Breed, Organization, Species};
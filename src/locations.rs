@@ -0,0 +1,138 @@
+use crate::cli::{CompareLocationsArgs, ToolArgs};
+use crate::client::fan_out;
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::source::search_animals;
+use crate::validate;
+use serde_json::{json, Value};
+
+/// Animals shown per location alongside its match count.
+const SAMPLES: usize = 3;
+
+/// Runs the same search around each of several postal codes at once, for
+/// people deciding how far they are willing to drive. Each location gets a
+/// match count and a few sample animals, or the error its search ran into.
+pub async fn compare_locations(
+    settings: &Settings,
+    args: CompareLocationsArgs,
+) -> Result<Value, AppError> {
+    let codes = validate::location_codes(&args.postal_codes)?;
+    // A wider radius for one location would make the counts incomparable
+    let mut settings = settings.clone();
+    settings.widening.enabled = false;
+
+    let searches = codes.iter().map(|code| {
+        search_animals(
+            &settings,
+            ToolArgs {
+                postal_code: Some(code.clone()),
+                ..args.search.clone()
+            },
+        )
+    });
+    let locations: Vec<Value> = fan_out(&settings, searches)
+        .await
+        .into_iter()
+        .zip(&codes)
+        .map(|(result, code)| match result {
+            Ok(data) => {
+                let animals = data["data"].as_array().cloned().unwrap_or_default();
+                let count = data["total"].as_u64().unwrap_or(animals.len() as u64);
+                json!({
+                    "postal_code": code,
+                    "count": count,
+                    "truncated": data["truncated"] == true,
+                    "samples": animals.into_iter().take(SAMPLES).collect::<Vec<_>>(),
+                })
+            }
+            Err(e) => json!({ "postal_code": code, "error": e.to_string() }),
+        })
+        .collect();
+
+    Ok(json!({
+        "data": locations,
+        "miles": args.search.miles.unwrap_or(settings.default_miles),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use moka::future::Cache;
+
+    use std::sync::Arc;
+
+    fn get_test_settings(url: String) -> Settings {
+        Settings {
+            cache: Arc::new(Cache::new(0)),
+            ..Settings::for_test(
+                url,
+                std::env::temp_dir().join("rescue-groups-mcp-locations"),
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compare_locations() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let _near = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({ "data": { "filterRadius": { "postalcode": "78704" } } }),
+            ))
+            .with_status(200)
+            .with_body(
+                json!({
+                    "data": [
+                        {"type": "animals", "id": "1", "attributes": {"name": "Rex"}},
+                        {"type": "animals", "id": "2", "attributes": {"name": "Bo"}}
+                    ],
+                    "meta": {"count": 40, "pages": 2}
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let _far = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({ "data": { "filterRadius": { "postalcode": "90210" } } }),
+            ))
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let result = compare_locations(
+            &settings,
+            CompareLocationsArgs {
+                postal_codes: vec!["78704".to_string(), "90210".to_string()],
+                search: ToolArgs {
+                    species: Some("dogs".to_string()),
+                    ..Default::default()
+                },
+            },
+        )
+        .await
+        .unwrap();
+        let locations = result["data"].as_array().unwrap();
+        assert_eq!(locations[0]["count"], 40);
+        assert_eq!(locations[0]["truncated"], true);
+        assert_eq!(locations[0]["samples"].as_array().unwrap().len(), 2);
+        assert_eq!(locations[1]["postal_code"], "90210");
+        assert!(locations[1]["error"].is_string());
+
+        let err = compare_locations(
+            &settings,
+            CompareLocationsArgs {
+                postal_codes: vec!["78704".to_string()],
+                search: ToolArgs::default(),
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, AppError::ValidationError { .. }));
+    }
+}
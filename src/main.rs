@@ -1,81 +1,174 @@
-mod cli;
-mod client;
-mod commands;
-mod config;
-mod error;
-mod fmt;
-mod mcp;
-mod server;
-
 #[cfg(not(test))]
 use clap::Parser;
-use cli::{Cli, Commands};
-use commands::handle_command;
-use config::merge_configuration;
-use server::{run_http_server, run_stdio_server};
+use rescue_groups_mcp::cli::{Cli, Commands};
+use rescue_groups_mcp::commands::{config_command, dry_run_command, handle_command};
+use rescue_groups_mcp::config::merge_configuration;
+use rescue_groups_mcp::daemon::{self, LogFile};
+#[cfg(feature = "console")]
+use rescue_groups_mcp::diagnostics;
+use rescue_groups_mcp::error::AppError;
+use rescue_groups_mcp::fmt::{json_envelope, render};
+#[cfg(feature = "http")]
+use rescue_groups_mcp::http::run_http_server;
+use rescue_groups_mcp::server::run_stdio_server;
 use std::error::Error;
 use std::io;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
+
+const SERVER_DRY_RUN: &str =
+    "--dry-run applies to commands; servers take `dry_run: true` per tool call";
 
 #[cfg(not(test))]
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
+fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     let cli = Cli::parse();
-    run_app(cli).await
+
+    // Daemonizing forks the process, so it has to happen before the Tokio
+    // runtime spawns its worker threads.
+    #[cfg(feature = "http")]
+    if let Some(Commands::Http(args)) = &cli.command {
+        if args.daemonize {
+            daemon::daemonize()?;
+        }
+    }
+
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run_app(cli))
 }
 
 pub async fn run_app(cli: Cli) -> Result<(), Box<dyn Error + Send + Sync>> {
     // 0. Initialize Logging
-    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
+    let mut env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| "rescue_groups_mcp=info".into());
+    if cli.trace_api {
+        env_filter = env_filter.add_directive("rescue_groups_mcp::api=debug".parse()?);
+    }
+
+    #[cfg(feature = "http")]
+    let log_file = match &cli.command {
+        Some(Commands::Http(args)) => args.log_file.as_ref().map(LogFile::open).transpose()?,
+        _ => None,
+    };
+    // Only the HTTP server logs to a file
+    #[cfg(not(feature = "http"))]
+    let log_file: Option<LogFile> = None;
+    let use_ansi = log_file.is_none();
+    let writer = match &log_file {
+        Some(file) => BoxMakeWriter::new(file.clone()),
+        None => BoxMakeWriter::new(io::stderr),
+    };
+
+    // The filter applies to the log output only; tokio-console needs the
+    // runtime's own trace events
+    let registry = tracing_subscriber::registry();
+    // Task tracing only exists in runtimes built with tokio_unstable
+    #[cfg(all(feature = "console", tokio_unstable))]
+    let registry = registry.with(console_subscriber::spawn());
 
     // Only initialize if not already initialized (to avoid panics in tests)
     let _ = if std::env::var("RUST_LOG_FORMAT").unwrap_or_default() == "json" {
-        tracing_subscriber::registry()
-            .with(env_filter)
+        registry
             .with(
                 tracing_subscriber::fmt::layer()
                     .json()
-                    .with_writer(io::stderr),
+                    .with_writer(writer)
+                    .with_filter(env_filter),
             )
             .try_init()
     } else {
-        tracing_subscriber::registry()
-            .with(env_filter)
-            .with(tracing_subscriber::fmt::layer().with_writer(io::stderr))
+        registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(use_ansi)
+                    .with_writer(writer)
+                    .with_filter(env_filter),
+            )
             .try_init()
     };
 
+    #[cfg(unix)]
+    if let Some(file) = log_file {
+        daemon::spawn_reopen_on_sighup(file)?;
+    }
+
+    if let Some(Commands::Config { action }) = &cli.command {
+        return config_command(&cli, action.clone()).map_err(|e| json_failure(cli.json, e));
+    }
+
     // 1. Load Settings
     // Clone command to use after merge_configuration (which consumes cli)
     let command = cli.command.clone();
-    let settings = merge_configuration(&cli)?;
+    let settings = merge_configuration(&cli).map_err(|e| json_failure(cli.json, e))?;
 
     match command {
+        Some(Commands::Server) | None if cli.dry_run => {
+            return Err(SERVER_DRY_RUN.into());
+        }
+        #[cfg(feature = "http")]
+        Some(Commands::Http(_)) if cli.dry_run => {
+            return Err(SERVER_DRY_RUN.into());
+        }
         Some(Commands::Server) | None => {
+            #[cfg(feature = "console")]
+            let _stats = diagnostics::spawn_runtime_stats(diagnostics::STATS_INTERVAL);
             run_stdio_server(settings).await?;
         }
+        #[cfg(feature = "http")]
         Some(Commands::Http(args)) => {
+            #[cfg(feature = "console")]
+            let _stats = diagnostics::spawn_runtime_stats(diagnostics::STATS_INTERVAL);
             run_http_server(args, settings).await?;
         }
+        Some(cmd) if cli.dry_run => {
+            render(&settings, dry_run_command(cmd, &settings, cli.json))
+                .await
+                .map_err(|e| json_failure(cli.json, e))?;
+        }
         Some(cmd) => {
-            handle_command(cmd, &settings, cli.json).await?;
+            // `--timeout` is a deadline for the whole command, not each request
+            let settings = match cli.timeout {
+                Some(_) => settings.for_call(),
+                None => settings,
+            };
+            render(&settings, handle_command(cmd, &settings, cli.json))
+                .await
+                .map_err(|e| json_failure(cli.json, e))?;
         }
     }
     Ok(())
 }
 
+/// With `--json`, a failed command also prints the error envelope to
+/// stdout, so scripts see it without reading the log.
+fn json_failure(json_mode: bool, error: AppError) -> Box<dyn Error + Send + Sync> {
+    if json_mode {
+        let envelope = json_envelope(Err(&error));
+        println!("{}", serde_json::to_string_pretty(&envelope).unwrap());
+    }
+    error.into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cli::Cli;
 
     #[test]
     fn test_merge_configuration_integration() {
         let cli = Cli {
             api_key: Some("test".to_string()),
             config: "non_existent.toml".to_string(),
+            no_system_config: true,
             json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
             command: None,
         };
         let res = merge_configuration(&cli);
@@ -97,7 +190,16 @@ mod tests {
         let cli = Cli {
             api_key: Some("test".to_string()),
             config: "non_existent.toml".to_string(),
+            no_system_config: true,
             json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
             command: Some(Commands::ListSpecies),
         };
 
@@ -113,7 +215,16 @@ mod tests {
         let cli = Cli {
             api_key: Some("test".to_string()),
             config: "non_existent.toml".to_string(),
+            no_system_config: true,
             json: false,
+            record: None,
+            replay: None,
+            trace_api: false,
+            dry_run: false,
+            timeout: None,
+            base_url: None,
+            profile: None,
+            deterministic: false,
             command: Some(Commands::ListMetadataTypes),
         };
         let res = run_app(cli).await;
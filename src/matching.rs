@@ -0,0 +1,284 @@
+use crate::cli::{MatchArgs, ToolArgs};
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::source::search_animals;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+const DEFAULT_LIMIT: usize = 5;
+
+/// A soft preference derived from the quiz answers. Animals earn `weight`
+/// points when `attribute` holds one of the `accepted` values.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Criterion {
+    pub label: String,
+    pub attribute: &'static str,
+    pub accepted: Vec<&'static str>,
+    pub weight: u32,
+}
+
+impl Criterion {
+    pub(crate) fn new(
+        label: &str,
+        attribute: &'static str,
+        accepted: &[&'static str],
+        weight: u32,
+    ) -> Self {
+        Criterion {
+            label: label.to_string(),
+            attribute,
+            accepted: accepted.to_vec(),
+            weight,
+        }
+    }
+}
+
+fn level(answer: Option<&str>, field: &str) -> Result<Option<&'static str>, AppError> {
+    match answer.map(|a| a.to_lowercase()).as_deref() {
+        None => Ok(None),
+        Some("low") => Ok(Some("low")),
+        Some("moderate") | Some("medium") => Ok(Some("moderate")),
+        Some("high") => Ok(Some("high")),
+        Some(other) => Err(AppError::Internal(format!(
+            "Invalid {} '{}': expected low, moderate or high",
+            field, other
+        ))),
+    }
+}
+
+/// Translates quiz answers into hard search filters (dealbreakers such as
+/// kids or other pets in the home) and weighted soft preferences.
+pub fn build_criteria(args: &MatchArgs) -> Result<(ToolArgs, Vec<Criterion>), AppError> {
+    let filters = ToolArgs {
+        postal_code: args.postal_code.clone(),
+        miles: args.miles,
+        species: args.species.clone(),
+        good_with_children: args.has_kids.filter(|&kids| kids),
+        good_with_dogs: args.has_dogs.filter(|&dogs| dogs),
+        good_with_cats: args.has_cats.filter(|&cats| cats),
+        ..Default::default()
+    };
+
+    let mut criteria = Vec::new();
+
+    let home = args.home_type.as_deref().map(str::to_lowercase);
+    match home.as_deref() {
+        None | Some("house") | Some("farm") => {}
+        Some("apartment") | Some("condo") => criteria.push(Criterion::new(
+            "Apartment-friendly size",
+            "sizeGroup",
+            &["Small", "Medium"],
+            2,
+        )),
+        Some(other) => {
+            return Err(AppError::Internal(format!(
+                "Invalid home_type '{}': expected apartment, house or farm",
+                other
+            )))
+        }
+    }
+
+    if args.has_yard == Some(false) {
+        criteria.push(Criterion::new(
+            "Doesn't need a yard",
+            "isYardRequired",
+            &["No"],
+            2,
+        ));
+    }
+
+    match args.hours_away {
+        Some(hours) if hours >= 8 => {
+            criteria.push(Criterion::new(
+                "Settled enough to be alone during workdays",
+                "ageGroup",
+                &["Adult", "Senior"],
+                2,
+            ));
+            criteria.push(Criterion::new(
+                "House-trained",
+                "isHouseTrained",
+                &["Yes"],
+                2,
+            ));
+        }
+        Some(hours) if hours >= 4 => criteria.push(Criterion::new(
+            "House-trained",
+            "isHouseTrained",
+            &["Yes"],
+            1,
+        )),
+        _ => {}
+    }
+
+    match level(args.activity_level.as_deref(), "activity_level")? {
+        Some("low") => criteria.push(Criterion::new(
+            "Low energy to match a relaxed lifestyle",
+            "energyLevel",
+            &["Low"],
+            3,
+        )),
+        Some("moderate") => criteria.push(Criterion::new(
+            "Moderate energy",
+            "energyLevel",
+            &["Low", "Moderate"],
+            3,
+        )),
+        Some(_) => criteria.push(Criterion::new(
+            "High energy to keep up with an active lifestyle",
+            "energyLevel",
+            &["Moderate", "High"],
+            3,
+        )),
+        None => {}
+    }
+
+    match level(args.grooming_tolerance.as_deref(), "grooming_tolerance")? {
+        Some("low") => criteria.push(Criterion::new(
+            "Low-maintenance coat",
+            "groomingNeeds",
+            &["Not Required", "Low"],
+            2,
+        )),
+        Some("moderate") => criteria.push(Criterion::new(
+            "Manageable grooming",
+            "groomingNeeds",
+            &["Not Required", "Low", "Moderate"],
+            1,
+        )),
+        _ => {}
+    }
+
+    Ok((filters, criteria))
+}
+
+/// Scores an animal against the criteria, returning a 0-100 score and one
+/// explanation per criterion. Unknown attributes earn no points.
+pub fn score_animal(animal: &Value, criteria: &[Criterion]) -> (u32, Vec<String>) {
+    let total: u32 = criteria.iter().map(|c| c.weight).sum();
+    if total == 0 {
+        return (100, Vec::new());
+    }
+
+    let mut earned = 0;
+    let reasons = criteria
+        .iter()
+        .map(|criterion| {
+            let (met, reason) = check_criterion(animal, criterion);
+            if met {
+                earned += criterion.weight;
+            }
+            reason
+        })
+        .collect();
+
+    (earned * 100 / total, reasons)
+}
+
+/// Whether an animal meets a criterion, with the explanation shown for it.
+pub(crate) fn check_criterion(animal: &Value, criterion: &Criterion) -> (bool, String) {
+    match animal["attributes"][criterion.attribute].as_str() {
+        Some(value) if criterion.accepted.contains(&value) => {
+            (true, format!("✅ {} ({})", criterion.label, value))
+        }
+        Some(value) => (false, format!("❌ {} ({})", criterion.label, value)),
+        None => (false, format!("❔ {} (not listed)", criterion.label)),
+    }
+}
+
+/// Searches with the quiz's hard filters and ranks the candidates by how
+/// well they fit the soft preferences.
+pub async fn match_pets(settings: &Settings, args: MatchArgs) -> Result<Value, AppError> {
+    let (filters, criteria) = build_criteria(&args)?;
+    let data = search_animals(settings, filters.clone()).await?;
+
+    let mut ranked: Vec<(u32, Value)> = data["data"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|mut animal| {
+            let (score, reasons) = score_animal(&animal, &criteria);
+            animal["meta"] = json!({ "score": score, "reasons": reasons });
+            (score, animal)
+        })
+        .collect();
+    // Stable sort keeps the API's order among equally good matches
+    ranked.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    let results: Vec<Value> = ranked
+        .into_iter()
+        .take(args.limit.unwrap_or(DEFAULT_LIMIT))
+        .map(|(_, animal)| animal)
+        .collect();
+
+    Ok(json!({
+        "filters": filters,
+        "criteria": criteria,
+        "data": results,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiz() -> MatchArgs {
+        MatchArgs {
+            home_type: Some("Apartment".to_string()),
+            has_yard: Some(false),
+            hours_away: Some(9),
+            has_kids: Some(true),
+            has_dogs: Some(false),
+            has_cats: None,
+            activity_level: Some("low".to_string()),
+            grooming_tolerance: Some("low".to_string()),
+            species: None,
+            postal_code: None,
+            miles: None,
+            limit: None,
+        }
+    }
+
+    #[test]
+    fn test_build_criteria() {
+        let (filters, criteria) = build_criteria(&quiz()).unwrap();
+        assert_eq!(filters.good_with_children, Some(true));
+        // Not having dogs isn't a filter
+        assert_eq!(filters.good_with_dogs, None);
+
+        let attributes: Vec<&str> = criteria.iter().map(|c| c.attribute).collect();
+        assert_eq!(
+            attributes,
+            vec![
+                "sizeGroup",
+                "isYardRequired",
+                "ageGroup",
+                "isHouseTrained",
+                "energyLevel",
+                "groomingNeeds"
+            ]
+        );
+
+        let mut invalid = quiz();
+        invalid.activity_level = Some("extreme".to_string());
+        assert!(build_criteria(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_score_animal() {
+        let criteria = vec![
+            Criterion::new("Low energy", "energyLevel", &["Low"], 3),
+            Criterion::new("Small", "sizeGroup", &["Small"], 1),
+            Criterion::new("Adult", "ageGroup", &["Adult"], 2),
+        ];
+        let animal = json!({"attributes": {"energyLevel": "Low", "sizeGroup": "Large"}});
+        let (score, reasons) = score_animal(&animal, &criteria);
+        assert_eq!(score, 50);
+        assert_eq!(reasons[0], "✅ Low energy (Low)");
+        assert_eq!(reasons[1], "❌ Small (Large)");
+        assert_eq!(reasons[2], "❔ Adult (not listed)");
+
+        assert_eq!(score_animal(&animal, &[]).0, 100);
+    }
+}
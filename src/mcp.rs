@@ -1,22 +1,78 @@
+use crate::alerts::{check_saved_searches, delete_saved_search, list_saved_searches, save_search};
+use crate::arrivals::list_new_arrivals;
+use crate::breed_rescues::find_breed_rescues;
 use crate::cli::{
-    AdoptedAnimalsArgs, AnimalIdArgs, BreedIdArgs, CompareArgs, MetadataArgs, OrgIdArgs,
-    OrgSearchArgs, SpeciesArgs, ToolArgs,
+    AdoptedAnimalsArgs, AnimalIdArgs, BreedIdArgs, BreedRescueArgs, BreedSuggestArgs,
+    CheckUpdatesArgs, CompareArgs, CompareLocationsArgs, ComparisonTokenArgs, ExportResultsArgs,
+    FavoriteArgs, FilterReferenceArgs, LocationArgs, MatchArgs, MetadataArgs, NewArrivalsArgs,
+    NoteArgs, OrgAnimalsArgs, OrgIdArgs, OrgOrAnimalArgs, OrgSearchArgs, ParseQueryArgs,
+    ReferenceArgs, SaveSearchArgs, SavedSearchNameArgs, SemanticSearchArgs, SpeciesArgs, ToolArgs,
 };
 use crate::client::{
-    compare_animals, fetch_adopted_pets, fetch_pets, get_animal_details, get_breed_details,
-    get_contact_info, get_organization_details, get_random_pet, list_animals, list_breeds,
-    list_metadata, list_metadata_types, list_org_animals, list_species, search_organizations,
+    compare_animals, fetch_adopted_pets, find_by_reference, get_adoption_process,
+    get_breed_details, get_org_contact, get_organization_details, get_random_pet, get_server_info,
+    get_sponsorship_info, list_animals, list_metadata, list_metadata_types, list_org_animals,
+    list_org_locations, list_species, suggest_breeds,
 };
-use crate::config::Settings;
+use crate::comparisons::{get_comparison, save_comparison};
+use crate::config::{Labels, Settings};
+use crate::continuation::{next_results, remember_search};
 use crate::error::AppError;
+use crate::export::export_results;
+use crate::favorites::{list_favorites, remove_favorite, save_favorite};
+use crate::filters::explain_filters;
 use crate::fmt::{
-    extract_single_item, format_animal_results, format_breed_details, format_breed_results,
-    format_comparison_table, format_contact_info, format_metadata_results, format_org_results,
-    format_single_animal, format_single_org, format_species_results,
+    extract_single_item, format_adoption_process, format_alerts, format_animal_photos,
+    format_animal_results, format_animal_with_notes, format_breed_details, format_breed_rescues,
+    format_breed_results, format_breed_suggestions, format_comparison_table, format_contact_info,
+    format_favorites, format_filter_reference, format_location_comparison, format_location_results,
+    format_match_results, format_metadata_results, format_new_arrivals, format_notes,
+    format_org_animals, format_org_contact, format_org_locations, format_org_results,
+    format_parsed_query, format_planned_requests, format_preferences, format_saved_comparison,
+    format_saved_searches, format_semantic_results, format_server_info, format_single_animal,
+    format_single_org, format_species_results, format_sponsorship_info, format_updates, relabel,
+    render, with_call_to_action, LISTED_ANIMALS,
 };
+use crate::images::{get_animal_photos, photo_metadata};
+use crate::locations::compare_locations;
+use crate::matching::match_pets;
+use crate::metrics::record_tool_call;
+use crate::notes::{add_note, attach_notes, get_notes};
+use crate::plugins::{call_plugin, get_plugin_tool_definitions};
+use crate::postal::lookup_location;
+use crate::preferences::{
+    preference_names, rank, ranking, session_preferences, set_session_preferences, Preferences,
+    MAX_WEIGHT,
+};
+use crate::private_api::{get_private_animal, get_private_org, list_private_animals};
+use crate::query::parse_search_query;
+use crate::resources::{resolve_resource, resource_templates};
+use crate::semantic::semantic_search;
+use crate::source::{get_breeds, get_contact, search_animals, search_orgs};
+use crate::synonyms::suggest;
+use crate::trace::RequestLog;
+use crate::updates::check_updates;
+use crate::validate::{
+    AGES, MAX_ARRIVAL_DAYS, MAX_LIMIT, MAX_LOCATIONS, MAX_MILES, MIN_MILES, SEXES, SIZES,
+    SORT_ORDERS,
+};
+use futures::future::{BoxFuture, FutureExt, Shared};
+use jsonschema::error::ValidationErrorKind;
 use serde::Deserialize;
 use serde_json::{json, Value};
-use tracing::warn;
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+use std::time::Instant;
+use tracing::{debug, warn};
+
+type SharedCall = Shared<BoxFuture<'static, Result<Value, Value>>>;
+
+/// Tool calls in progress, so identical concurrent calls (e.g. from
+/// parallel agent branches) run once and share the result.
+static IN_FLIGHT: LazyLock<Mutex<HashMap<String, SharedCall>>> = LazyLock::new(Mutex::default);
+
+/// Tools whose identical calls are expected to give different results.
+const NOT_COALESCED: &[&str] = &["get_random_pet", "next_results", "set_preferences"];
 
 #[derive(Deserialize, Debug)]
 pub struct JsonRpcRequest {
@@ -63,6 +119,17 @@ fn get_all_tool_definitions() -> Vec<Value> {
                 "required": ["metadata_type"]
             }
         }),
+        json!({
+            "name": "explain_filters",
+            "description": "Explain the upstream filter fields searches are built from: each field's name, the tool argument that sets it, its operations and sample criteria, and for breeds, colors and patterns the valid values for the species. Check this before guessing a field name or value.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "species": { "type": "string", "description": "Species whose breeds, colors and patterns to list (default: the configured species)." },
+                    "field": { "type": "string", "description": "Only this filter field (e.g. 'animals.ageGroup') or tool argument (e.g. 'color_group')." }
+                }
+            }
+        }),
         json!({
             "name": "list_metadata_types",
             "description": "List all valid metadata types that can be used with list_metadata.",
@@ -71,6 +138,17 @@ fn get_all_tool_definitions() -> Vec<Value> {
                 "properties": {}
             }
         }),
+        json!({
+            "name": "lookup_location",
+            "description": "Resolve a US ZIP / Canadian postal code or a place name ('Austin, TX') using the offline postal code database. Returns matching postal codes with their city, state and coordinates. Search tools also accept place names directly.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "A postal code or 'City, ST'." }
+                },
+                "required": ["query"]
+            }
+        }),
         json!({
             "name": "list_breeds",
             "description": "List available breeds for a specific species.",
@@ -82,6 +160,19 @@ fn get_all_tool_definitions() -> Vec<Value> {
                 "required": ["species"]
             }
         }),
+        json!({
+            "name": "suggest_breeds",
+            "description": "Match a partial or misspelled breed name (e.g. 'golden doodel') against a species' breeds and return the closest names with their IDs. Use it before filtering search_adoptable_pets by breed.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "The breed name as the user gave it." },
+                    "species": { "type": "string", "description": "Type of animal (e.g., dogs, cats, rabbits). Defaults to the configured species." },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": MAX_LIMIT, "description": "Number of candidates to return (default 5)." }
+                },
+                "required": ["query"]
+            }
+        }),
         json!({
             "name": "get_breed",
             "description": "Get detailed information about a specific breed by its ID.",
@@ -104,6 +195,17 @@ fn get_all_tool_definitions() -> Vec<Value> {
                 "required": ["animal_id"]
             }
         }),
+        json!({
+            "name": "get_animal_photos",
+            "description": "List an animal's photos in display order with their captions and every available rendition's URL, width, height and file size, to pick a size that suits the UI. Each photo's 'index' works with the image proxy.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "animal_id": { "type": "string", "description": "The unique ID of the animal." }
+                },
+                "required": ["animal_id"]
+            }
+        }),
         json!({
             "name": "get_contact_info",
             "description": "Get the primary contact method (email, phone, organization) for a specific animal.",
@@ -124,12 +226,42 @@ fn get_all_tool_definitions() -> Vec<Value> {
                     "animal_ids": {
                         "type": "array",
                         "items": { "type": "string" },
+                        "minItems": 1,
+                        "maxItems": 5,
                         "description": "List of animal IDs to compare (max 5)."
                     }
                 },
                 "required": ["animal_ids"]
             }
         }),
+        json!({
+            "name": "save_comparison",
+            "description": "Compare up to 5 animals and save the table under a short token, so it can be shared with someone outside this chat (via get_comparison or the /c/{token} page of the HTTP server).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "animal_ids": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "minItems": 1,
+                        "maxItems": 5,
+                        "description": "List of animal IDs to compare (max 5)."
+                    }
+                },
+                "required": ["animal_ids"]
+            }
+        }),
+        json!({
+            "name": "get_comparison",
+            "description": "Show a comparison saved with save_comparison, as it was when saved.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "token": { "type": "string", "description": "Token returned by save_comparison." }
+                },
+                "required": ["token"]
+            }
+        }),
         json!({
             "name": "get_organization_details",
             "description": "Get detailed information about a specific rescue organization by its ID.",
@@ -142,8 +274,8 @@ fn get_all_tool_definitions() -> Vec<Value> {
             }
         }),
         json!({
-            "name": "list_org_animals",
-            "description": "List all animals available for adoption at a specific organization.",
+            "name": "get_org_contact",
+            "description": "Get an organization's full contact details: email, phone, address, website, adoption page and process, meet-and-greet policy and social links. Returned as Markdown and as structured JSON. Use get_contact_info to reach the organization caring for a specific animal.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -152,6 +284,65 @@ fn get_all_tool_definitions() -> Vec<Value> {
                 "required": ["org_id"]
             }
         }),
+        json!({
+            "name": "get_adoption_process",
+            "description": "Explain how to adopt from an organization, step by step: service area, adoption process, fee, where to apply and who to ask. Pass the org_id, or the animal_id of a pet the user picked to include its fee and listing.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "org_id": { "type": "string", "description": "The unique ID of the organization." },
+                    "animal_id": { "type": "string", "description": "The ID of an animal at the organization." }
+                },
+                "oneOf": [{ "required": ["org_id"] }, { "required": ["animal_id"] }]
+            }
+        }),
+        json!({
+            "name": "get_sponsorship_info",
+            "description": "Ways to help without adopting: whether a pet can be sponsored (and the minimum), whether it needs a foster home, and the organization's sponsorship and donation links. Pass the animal_id, or the org_id for the organization alone.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "org_id": { "type": "string", "description": "The unique ID of the organization." },
+                    "animal_id": { "type": "string", "description": "The ID of an animal to sponsor." }
+                },
+                "oneOf": [{ "required": ["org_id"] }, { "required": ["animal_id"] }]
+            }
+        }),
+        json!({
+            "name": "find_by_reference",
+            "description": "Find an organization's animal by the reference number the organization assigned it (e.g. 'A-17'), as shelter staff quote them, rather than its RescueGroups ID. Finds the animal whatever its adoption status.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "org_id": { "type": "string", "description": "The unique ID of the organization." },
+                    "reference": { "type": "string", "description": "The organization's reference number for the animal." }
+                },
+                "required": ["org_id", "reference"]
+            }
+        }),
+        json!({
+            "name": "list_org_locations",
+            "description": "List the sites of a multi-site organization (shelter branches, adoption centers) with each one's address, phone and hours.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "org_id": { "type": "string", "description": "The unique ID of the organization." }
+                },
+                "required": ["org_id"]
+            }
+        }),
+        json!({
+            "name": "list_org_animals",
+            "description": "List all animals available for adoption at a specific organization, grouped by species with a count of each.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "org_id": { "type": "string", "description": "The unique ID of the organization." },
+                    "species": { "type": "string", "description": "Only animals of this species (e.g. 'cats')." }
+                },
+                "required": ["org_id"]
+            }
+        }),
         json!({
             "name": "search_organizations",
             "description": "Search for animal rescue organizations and shelters by location.",
@@ -159,11 +350,26 @@ fn get_all_tool_definitions() -> Vec<Value> {
                 "type": "object",
                 "properties": {
                     "postal_code": { "type": "string", "description": "Zip code (e.g. 90210)" },
-                    "miles": { "type": "integer", "description": "Search radius (default 50)" },
-                    "query": { "type": "string", "description": "Name of the organization to search for (partial match)" }
+                    "miles": { "type": "integer", "minimum": MIN_MILES, "maximum": MAX_MILES, "description": "Search radius (default 50)" },
+                    "query": { "type": "string", "description": "Name of the organization to search for (partial match)" },
+                    "species": { "type": "string", "description": "Only organizations currently listing this species nearby (e.g. 'rabbits'), judged from their current listings." }
                 }
             }
         }),
+        json!({
+            "name": "find_breed_rescues",
+            "description": "Find rescue organizations near a location that specialize in a breed: ones naming it in their name or description, and ones currently listing several pets of that breed. Purebred seekers are usually best pointed at breed rescues first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "breed": { "type": "string", "description": "Breed name (e.g. 'Beagle')." },
+                    "species": { "type": "string", "description": "Species of the breed (default: the configured species)." },
+                    "postal_code": { "type": "string", "description": "Zip code (e.g. 90210)" },
+                    "miles": { "type": "integer", "minimum": MIN_MILES, "maximum": MAX_MILES, "description": "Search radius (default 50)" }
+                },
+                "required": ["breed"]
+            }
+        }),
         json!({
             "name": "search_adoptable_pets",
             "description": "Search for adoptable pets (dogs, cats, etc) by location and various traits.",
@@ -171,28 +377,89 @@ fn get_all_tool_definitions() -> Vec<Value> {
                 "type": "object",
                 "properties": {
                     "postal_code": { "type": "string", "description": "Zip code (e.g. 90210)" },
-                    "species": { "type": "string", "description": "Type of animal (dogs, cats, rabbits)" },
+                    "species": { "type": "string", "description": "Type of animal (dogs, cats, rabbits). Everyday words work too: 'puppy' and 'kitten' also limit the age to Baby." },
                     "breeds": { "type": "string", "description": "Specific breed name (e.g. Golden Retriever)" },
-                    "miles": { "type": "integer", "description": "Search radius (default 50)" },
-                    "sex": { "type": "string", "description": "Sex of the animal (Male, Female)" },
-                    "age": { "type": "string", "description": "Age group (Baby, Young, Adult, Senior)" },
-                    "size": { "type": "string", "description": "Size group (Small, Medium, Large, X-Large)" },
+                    "miles": { "type": "integer", "minimum": MIN_MILES, "maximum": MAX_MILES, "description": "Search radius (default 50)" },
+                    "sex": { "type": "string", "enum": SEXES, "description": "Sex of the animal (Male, Female)" },
+                    "age": { "type": "string", "enum": AGES, "description": "Age group (Baby, Young, Adult, Senior)" },
+                    "size": { "type": "string", "enum": SIZES, "description": "Size group (Small, Medium, Large, X-Large)" },
                     "good_with_children": { "type": "boolean", "description": "Whether the pet is good with children." },
                     "good_with_dogs": { "type": "boolean", "description": "Whether the pet is good with other dogs." },
                     "good_with_cats": { "type": "boolean", "description": "Whether the pet is good with cats." },
                     "house_trained": { "type": "boolean", "description": "Whether the pet is house trained." },
                     "special_needs": { "type": "boolean", "description": "Whether the pet has special needs." },
                     "needs_foster": { "type": "boolean", "description": "Whether the pet needs a foster home." },
-                    "color": { "type": "string", "description": "Filter by color (partial match)." },
+                    "courtesy_listings": { "type": "boolean", "description": "Set to false to exclude courtesy listings, where the listing organization is not the pet's actual caretaker." },
+                    "color": { "type": "string", "description": "Filter by color description (partial, free-text match)." },
+                    "color_group": { "type": "string", "description": "Filter by color group (exact match against list_metadata colors, e.g. 'Black')." },
                     "pattern": { "type": "string", "description": "Filter by pattern (partial match)." },
                     "sort_by": {
                         "type": "string",
-                        "enum": ["Newest", "Distance", "Random"],
+                        "enum": SORT_ORDERS,
                         "description": "Sort order for results."
-                    }
+                    },
+                    "preset": { "type": "string", "description": "Name of a search preset defined in the server config. Explicit arguments override the preset." },
+                    "page": { "type": "integer", "minimum": 1, "description": "Page of results to fetch (default 1). To see more of the last search, next_results is simpler." },
+                    "preferences": preferences_schema("Rank the results by these preferences instead of the API's order, with each animal's score and reasons. Defaults to the preferences stored with set_preferences."),
+                    "debug": { "type": "boolean", "description": "Append the upstream API requests this search made, to troubleshoot filters that return nothing. Accepted by every tool." },
+                    "dry_run": { "type": "boolean", "description": "Show the upstream API requests this search would make (URL, method, headers with credentials redacted, and body) without sending them. Accepted by every tool." }
+                }
+            }
+        }),
+        json!({
+            "name": "export_results",
+            "description": "Run a search and save the results to a file instead of listing them in the chat (e.g. all senior dogs near 90210 to ~/Desktop/dogs.csv). Only paths inside the directories allowed in the server config can be written.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Absolute path of the file to write; '~' is the home directory." },
+                    "format": {
+                        "type": "string",
+                        "enum": ["csv", "json", "markdown"],
+                        "description": "File format (default: from the file extension, else csv)."
+                    },
+                    "postal_code": { "type": "string", "description": "Zip code (e.g. 90210)" },
+                    "species": { "type": "string", "description": "Type of animal (dogs, cats, rabbits)" },
+                    "breeds": { "type": "string", "description": "Specific breed name (e.g. Golden Retriever)" },
+                    "miles": { "type": "integer", "minimum": MIN_MILES, "maximum": MAX_MILES, "description": "Search radius (default 50)" },
+                    "sex": { "type": "string", "enum": SEXES, "description": "Sex of the animal (Male, Female)" },
+                    "age": { "type": "string", "enum": AGES, "description": "Age group (Baby, Young, Adult, Senior)" },
+                    "size": { "type": "string", "enum": SIZES, "description": "Size group (Small, Medium, Large, X-Large)" },
+                    "good_with_children": { "type": "boolean", "description": "Whether the pet is good with children." },
+                    "good_with_dogs": { "type": "boolean", "description": "Whether the pet is good with other dogs." },
+                    "good_with_cats": { "type": "boolean", "description": "Whether the pet is good with cats." },
+                    "house_trained": { "type": "boolean", "description": "Whether the pet is house trained." },
+                    "special_needs": { "type": "boolean", "description": "Whether the pet has special needs." },
+                    "needs_foster": { "type": "boolean", "description": "Whether the pet needs a foster home." },
+                    "courtesy_listings": { "type": "boolean", "description": "Set to false to exclude courtesy listings, where the listing organization is not the pet's actual caretaker." },
+                    "color": { "type": "string", "description": "Filter by color description (partial, free-text match)." },
+                    "color_group": { "type": "string", "description": "Filter by color group (exact match against list_metadata colors, e.g. 'Black')." },
+                    "pattern": { "type": "string", "description": "Filter by pattern (partial match)." },
+                    "preset": { "type": "string", "description": "Name of a search preset defined in the server config." }
+                },
+                "required": ["path"]
+            }
+        }),
+        json!({
+            "name": "set_preferences",
+            "description": "Remember what the user would like in a pet (e.g. prefer seniors, small, nearby; must be good with cats) for this session, so later searches are ranked by it with a score and reasons per animal.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "weights": preferences_schema("")["properties"]["weights"],
+                    "required": preferences_schema("")["properties"]["required"],
+                    "clear": { "type": "boolean", "description": "Forget the stored preferences." }
                 }
             }
         }),
+        json!({
+            "name": "next_results",
+            "description": "Show more results of the last search_adoptable_pets call in this session, with the same filters. Use when the user asks for \"more\" or \"show me others\".",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        }),
         json!({
             "name": "get_random_pet",
             "description": "Get a random adoptable pet (surpise me!).",
@@ -211,23 +478,380 @@ fn get_all_tool_definitions() -> Vec<Value> {
                 "properties": {
                     "postal_code": { "type": "string", "description": "Zip code (e.g. 90210)" },
                     "species": { "type": "string", "description": "Type of animal (dogs, cats, rabbits)" },
-                    "miles": { "type": "integer", "description": "Search radius (default 50)" }
+                    "miles": { "type": "integer", "minimum": MIN_MILES, "maximum": MAX_MILES, "description": "Search radius (default 50)" }
+                }
+            }
+        }),
+        json!({
+            "name": "semantic_search_pets",
+            "description": "Find pets matching a free-text description of temperament or lifestyle (e.g. 'calm couch-potato dog that is okay alone during workdays'). Candidates are fetched with the regular filters, then ranked by how well their descriptions match the query.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Free-text description of the ideal pet." },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": MAX_LIMIT, "description": "Number of ranked results to return (default 5)." },
+                    "postal_code": { "type": "string", "description": "Zip code (e.g. 90210)" },
+                    "species": { "type": "string", "description": "Type of animal (dogs, cats, rabbits)" },
+                    "miles": { "type": "integer", "minimum": MIN_MILES, "maximum": MAX_MILES, "description": "Search radius (default 50)" },
+                    "age": { "type": "string", "enum": AGES, "description": "Age group (Baby, Young, Adult, Senior)" },
+                    "size": { "type": "string", "enum": SIZES, "description": "Size group (Small, Medium, Large, X-Large)" },
+                    "good_with_children": { "type": "boolean", "description": "Whether the pet is good with children." },
+                    "good_with_dogs": { "type": "boolean", "description": "Whether the pet is good with other dogs." },
+                    "good_with_cats": { "type": "boolean", "description": "Whether the pet is good with cats." }
+                },
+                "required": ["query"]
+            }
+        }),
+        json!({
+            "name": "list_new_arrivals",
+            "description": "List pets listed nearby in the last few days (default 7), newest first, grouped by the organization listing them. The quick answer to 'what's new this week?'.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "postal_code": { "type": "string", "description": "Zip code (e.g. 90210)" },
+                    "species": { "type": "string", "description": "Type of animal (dogs, cats, rabbits)" },
+                    "miles": { "type": "integer", "minimum": MIN_MILES, "maximum": MAX_MILES, "description": "Search radius (default 50)" },
+                    "days": { "type": "integer", "minimum": 1, "maximum": MAX_ARRIVAL_DAYS, "description": "How many days back to look (default 7)." }
+                }
+            }
+        }),
+        json!({
+            "name": "compare_locations",
+            "description": "Run the same pet search around 2 to 5 postal codes at once and compare how many matches each has, with a few sample pets per location. Useful for deciding how far to travel.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "postal_codes": { "type": "array", "items": { "type": "string" }, "minItems": 2, "maxItems": MAX_LOCATIONS, "description": "Zip codes to compare (e.g. [\"78704\", \"78610\"])" },
+                    "species": { "type": "string", "description": "Type of animal (dogs, cats, rabbits)" },
+                    "breeds": { "type": "string", "description": "Specific breed name (e.g. Golden Retriever)" },
+                    "miles": { "type": "integer", "minimum": MIN_MILES, "maximum": MAX_MILES, "description": "Search radius around each location (default 50)" },
+                    "sex": { "type": "string", "enum": SEXES, "description": "Sex of the animal (Male, Female)" },
+                    "age": { "type": "string", "enum": AGES, "description": "Age group (Baby, Young, Adult, Senior)" },
+                    "size": { "type": "string", "enum": SIZES, "description": "Size group (Small, Medium, Large, X-Large)" },
+                    "good_with_children": { "type": "boolean", "description": "Whether the pet is good with children." },
+                    "good_with_dogs": { "type": "boolean", "description": "Whether the pet is good with other dogs." },
+                    "good_with_cats": { "type": "boolean", "description": "Whether the pet is good with cats." }
+                },
+                "required": ["postal_codes"]
+            }
+        }),
+        json!({
+            "name": "match_me_a_pet",
+            "description": "Adoption matching quiz. Translates lifestyle answers into search filters and returns the best-fitting pets with a match score and an explanation for each. All answers are optional; ask the user for the ones that matter to them.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "home_type": { "type": "string", "enum": ["apartment", "house", "farm"], "description": "Type of home." },
+                    "has_yard": { "type": "boolean", "description": "Whether the home has a yard." },
+                    "hours_away": { "type": "integer", "minimum": 0, "maximum": 24, "description": "Hours the pet would be alone on a typical day." },
+                    "has_kids": { "type": "boolean", "description": "Whether there are children in the home." },
+                    "has_dogs": { "type": "boolean", "description": "Whether there are dogs already in the home." },
+                    "has_cats": { "type": "boolean", "description": "Whether there are cats already in the home." },
+                    "activity_level": { "type": "string", "enum": ["low", "moderate", "high"], "description": "The adopter's activity level." },
+                    "grooming_tolerance": { "type": "string", "enum": ["low", "moderate", "high"], "description": "How much grooming the adopter is willing to do." },
+                    "species": { "type": "string", "description": "Type of animal (dogs, cats, rabbits)" },
+                    "postal_code": { "type": "string", "description": "Zip code (e.g. 90210)" },
+                    "miles": { "type": "integer", "minimum": MIN_MILES, "maximum": MAX_MILES, "description": "Search radius (default 50)" },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": MAX_LIMIT, "description": "Number of ranked results to return (default 5)." }
                 }
             }
         }),
         json!({
-            "name": "inspect_tool",
-            "description": "Discover available tools or get detailed schema for a specific tool.",
+            "name": "parse_search_query",
+            "description": "Convert a free-text pet request (e.g. 'young small dog good with cats within 30 miles of 78704') into validated arguments for search_adoptable_pets. Use this when unsure how to map a request onto search filters; it also reports which words it couldn't map.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "The user's request in plain language." }
+                },
+                "required": ["query"]
+            }
+        }),
+        json!({
+            "name": "save_favorite",
+            "description": "Save an animal to the user's favorites (shortlist), optionally with a note.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "animal_id": { "type": "string", "description": "The unique ID of the animal." },
+                    "note": { "type": "string", "description": "Optional note (e.g. 'meet on Saturday')." }
+                },
+                "required": ["animal_id"]
+            }
+        }),
+        json!({
+            "name": "list_favorites",
+            "description": "List the user's favorite animals. The returned IDs can be passed to compare_animals.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        }),
+        json!({
+            "name": "check_updates",
+            "description": "Check animals seen before for changes since their last check: which are no longer available (usually adopted), changed status, or have updated details such as fee, description or photos. Checks the user's favorites when no IDs are given.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "animal_ids": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "IDs of animals seen before."
+                    },
+                    "favorites": { "type": "boolean", "description": "Also check the user's favorites." }
+                }
+            }
+        }),
+        json!({
+            "name": "remove_favorite",
+            "description": "Remove an animal from the user's favorites.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "animal_id": { "type": "string", "description": "The unique ID of the animal." }
+                },
+                "required": ["animal_id"]
+            }
+        }),
+        json!({
+            "name": "add_note",
+            "description": "Add a private note to an animal (e.g. 'emailed 3/4, meet-and-greet Saturday'). Notes are stored locally and shown with get_animal_details.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "animal_id": { "type": "string", "description": "The unique ID of the animal." },
+                    "note": { "type": "string", "description": "The text of the note." }
+                },
+                "required": ["animal_id", "note"]
+            }
+        }),
+        json!({
+            "name": "get_notes",
+            "description": "List the notes kept on an animal, oldest first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "animal_id": { "type": "string", "description": "The unique ID of the animal." }
+                },
+                "required": ["animal_id"]
+            }
+        }),
+        json!({
+            "name": "save_search",
+            "description": "Save a named search. The server polls saved searches in the background and notifies about newly listed matches.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "Name for the saved search (e.g. 'senior dogs')." },
+                    "postal_code": { "type": "string", "description": "Zip code (e.g. 90210)" },
+                    "species": { "type": "string", "description": "Type of animal (dogs, cats, rabbits)" },
+                    "breeds": { "type": "string", "description": "Specific breed name (e.g. Golden Retriever)" },
+                    "miles": { "type": "integer", "minimum": MIN_MILES, "maximum": MAX_MILES, "description": "Search radius (default 50)" },
+                    "sex": { "type": "string", "enum": SEXES, "description": "Sex of the animal (Male, Female)" },
+                    "age": { "type": "string", "enum": AGES, "description": "Age group (Baby, Young, Adult, Senior)" },
+                    "size": { "type": "string", "enum": SIZES, "description": "Size group (Small, Medium, Large, X-Large)" },
+                    "good_with_children": { "type": "boolean", "description": "Whether the pet is good with children." },
+                    "good_with_dogs": { "type": "boolean", "description": "Whether the pet is good with other dogs." },
+                    "good_with_cats": { "type": "boolean", "description": "Whether the pet is good with cats." },
+                    "house_trained": { "type": "boolean", "description": "Whether the pet is house trained." },
+                    "special_needs": { "type": "boolean", "description": "Whether the pet has special needs." },
+                    "needs_foster": { "type": "boolean", "description": "Whether the pet needs a foster home." },
+                    "courtesy_listings": { "type": "boolean", "description": "Set to false to exclude courtesy listings, where the listing organization is not the pet's actual caretaker." },
+                    "color": { "type": "string", "description": "Filter by color description (partial, free-text match)." },
+                    "color_group": { "type": "string", "description": "Filter by color group (exact match against list_metadata colors, e.g. 'Black')." },
+                    "pattern": { "type": "string", "description": "Filter by pattern (partial match)." }
+                },
+                "required": ["name"]
+            }
+        }),
+        json!({
+            "name": "list_saved_searches",
+            "description": "List saved searches that are polled for new matches.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        }),
+        json!({
+            "name": "delete_saved_search",
+            "description": "Delete a saved search by name.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "Name of the saved search." }
+                },
+                "required": ["name"]
+            }
+        }),
+        json!({
+            "name": "check_saved_searches",
+            "description": "Run all saved searches now and list animals that are new since the last check.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        }),
+        json!({
+            "name": "get_server_info",
+            "description": "Get the server version, git revision, configured defaults (secrets redacted), and whether a newer release is available.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        }),
+        json!({
+            "name": "inspect_tool",
+            "description": "Discover available tools or get detailed schema for a specific tool.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "tool_name": {
+                        "type": "string",
+                        "description": "The name of the tool to inspect. If omitted, lists all available tools."
+                    }
+                }
+            }
+        }),
+    ]
+}
+
+fn preset_tool_name(preset: &str) -> String {
+    format!("search_{}", preset.replace(['-', ' '], "_"))
+}
+
+/// Schema of the preferences search results can be ranked by.
+fn preferences_schema(description: &str) -> Value {
+    json!({
+        "type": "object",
+        "description": description,
+        "properties": {
+            "weights": {
+                "type": "object",
+                "description": format!(
+                    "Points (1 to {}) per preference an animal meets, e.g. {{\"senior\": 3, \"nearby\": 1}}. Preferences: {}.",
+                    MAX_WEIGHT,
+                    preference_names().join(", ")
+                ),
+                "additionalProperties": { "type": "integer", "minimum": 1, "maximum": MAX_WEIGHT }
+            },
+            "required": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Preferences an animal must meet to be listed at all, e.g. [\"good_with_cats\"]."
+            }
+        }
+    })
+}
+
+/// Presets marked with `tool = true` become their own search tools. Only the
+/// location can be overridden; every other filter is fixed by the preset.
+fn get_preset_tool_definitions(settings: &Settings) -> Vec<Value> {
+    let mut names: Vec<&String> = settings
+        .presets
+        .iter()
+        .filter(|(_, p)| p.tool)
+        .map(|(name, _)| name)
+        .collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let description = settings.presets[name]
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("Search adoptable pets using the '{}' preset.", name));
+            json!({
+                "name": preset_tool_name(name),
+                "description": description,
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "postal_code": { "type": "string", "description": "Zip code (e.g. 90210)" },
+                        "miles": { "type": "integer", "minimum": MIN_MILES, "maximum": MAX_MILES, "description": "Search radius" }
+                    }
+                }
+            })
+        })
+        .collect()
+}
+
+fn find_preset_for_tool<'a>(settings: &'a Settings, tool_name: &str) -> Option<&'a String> {
+    settings
+        .presets
+        .iter()
+        .find(|(name, preset)| preset.tool && preset_tool_name(name) == tool_name)
+        .map(|(name, _)| name)
+}
+
+/// Rejects preset and plugin tools whose name is already taken by a built-in
+/// tool or by another preset, which would otherwise be listed twice and shadow
+/// each other.
+pub fn check_tool_names(settings: &Settings) -> Result<(), AppError> {
+    let mut taken: HashSet<String> = get_all_tool_definitions()
+        .into_iter()
+        .chain(get_private_tool_definitions(settings))
+        .filter_map(|tool| tool["name"].as_str().map(str::to_string))
+        .collect();
+
+    let mut presets: Vec<&String> = settings
+        .presets
+        .iter()
+        .filter(|(_, p)| p.tool)
+        .map(|(name, _)| name)
+        .collect();
+    presets.sort();
+    for name in presets {
+        let tool_name = preset_tool_name(name);
+        if !taken.insert(tool_name.clone()) {
+            return Err(AppError::ConfigError(format!(
+                "Preset '{}' would add the tool '{}', which already exists",
+                name, tool_name
+            )));
+        }
+    }
+
+    let mut plugins: Vec<&String> = settings.plugins.keys().collect();
+    plugins.sort();
+    for name in plugins {
+        if taken.contains(name.as_str()) {
+            return Err(AppError::ConfigError(format!(
+                "Plugin '{}' has the name of an existing tool",
+                name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Account-scoped tools, only listed when `[private_api]` is configured.
+/// Their `private_` prefix keeps them apart from the public tools.
+fn get_private_tool_definitions(settings: &Settings) -> Vec<Value> {
+    if settings.private_api.is_none() {
+        return Vec::new();
+    }
+    vec![
+        json!({
+            "name": "private_list_org_animals",
+            "description": "Staff only: list every animal of your own organization, including ones that are not public yet.",
+            "inputSchema": { "type": "object", "properties": {} }
+        }),
+        json!({
+            "name": "private_get_animal_details",
+            "description": "Staff only: get any animal of your own organization, public or not.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "tool_name": {
-                        "type": "string",
-                        "description": "The name of the tool to inspect. If omitted, lists all available tools."
-                    }
-                }
+                    "animal_id": { "type": "string", "description": "The unique ID of the animal." }
+                },
+                "required": ["animal_id"]
             }
         }),
+        json!({
+            "name": "private_get_organization",
+            "description": "Staff only: show the organization your account belongs to.",
+            "inputSchema": { "type": "object", "properties": {} }
+        }),
     ]
 }
 
@@ -253,7 +877,7 @@ pub async fn handle_tool_call(
         "list_animals" => {
             let data = list_animals(settings).await?;
             let content = format_animal_results(&data)?;
-            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+            Ok(listing_result(content, &data))
         }
         "list_species" => {
             let data = list_species(settings).await?;
@@ -277,6 +901,34 @@ pub async fn handle_tool_call(
             let content = format_metadata_results(&data, &args.metadata_type)?;
             Ok(json!({ "content": [{ "type": "text", "text": content }] }))
         }
+        "lookup_location" => {
+            let args: LocationArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let data = lookup_location(settings, &args.query)?;
+            let content = format_location_results(&data)?;
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
+        "explain_filters" => {
+            let args: FilterReferenceArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let data = explain_filters(settings, args).await?;
+            Ok(json!({
+                "content": [{ "type": "text", "text": format_filter_reference(&data)? }],
+                "structuredContent": data
+            }))
+        }
         "list_metadata_types" => {
             let data = list_metadata_types().await?;
             let types = data["data"].as_array().unwrap();
@@ -301,10 +953,23 @@ pub async fn handle_tool_call(
                 species: settings.default_species.clone(),
             });
 
-            let data = list_breeds(settings, args.clone()).await?;
+            let data = get_breeds(settings, args.clone()).await?;
             let content = format_breed_results(&data, &args.species)?;
             Ok(json!({ "content": [{ "type": "text", "text": content }] }))
         }
+        "suggest_breeds" => {
+            let args: BreedSuggestArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let data = suggest_breeds(settings, args).await?;
+            let content = format_breed_suggestions(&data)?;
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
         "get_breed" => {
             let args: BreedIdArgs = serde_json::from_value(
                 params
@@ -338,14 +1003,38 @@ pub async fn handle_tool_call(
                 animal_id: "0".to_string(),
             });
 
-            let data = get_animal_details(settings, args).await?;
-            let animal_data = data.get("data");
-            match animal_data.and_then(|d| extract_single_item(d)) {
-                Some(a) => {
-                    Ok(json!({ "content": [{ "type": "text", "text": format_single_animal(a) }] }))
-                }
-                None => Err(AppError::NotFound),
-            }
+            let animal_id = args.animal_id.clone();
+            // Fetched with its org and location, to show where a fostered pet is
+            let mut data = get_contact(settings, args).await?;
+            attach_notes(settings, &animal_id, &mut data).await?;
+            let content = with_call_to_action(format_animal_with_notes(&data)?, &settings.labels);
+            let photos = data
+                .get("data")
+                .and_then(extract_single_item)
+                .map(photo_metadata)
+                .unwrap_or_default();
+            Ok(json!({
+                "content": [{ "type": "text", "text": content }],
+                "structuredContent": { "photos": photos }
+            }))
+        }
+        "get_animal_photos" => {
+            let args: AnimalIdArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let gallery = get_animal_photos(settings, args).await?;
+            Ok(json!({
+                "content": [
+                    { "type": "text", "text": format_animal_photos(&gallery) },
+                    { "type": "text", "text": serde_json::to_string_pretty(&gallery)? }
+                ],
+                "structuredContent": gallery
+            }))
         }
         "get_contact_info" => {
             let args: AnimalIdArgs = serde_json::from_value(
@@ -359,7 +1048,7 @@ pub async fn handle_tool_call(
                 animal_id: "0".to_string(),
             });
 
-            let data = get_contact_info(settings, args).await?;
+            let data = get_contact(settings, args).await?;
             let content = format_contact_info(&data)?;
             Ok(json!({ "content": [{ "type": "text", "text": content }] }))
         }
@@ -377,6 +1066,44 @@ pub async fn handle_tool_call(
             let content = format_comparison_table(&data)?;
             Ok(json!({ "content": [{ "type": "text", "text": content }] }))
         }
+        "save_comparison" => {
+            let args: CompareArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let comparison = save_comparison(settings, args).await?;
+            let content = format_saved_comparison(&serde_json::to_value(comparison)?)?;
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
+        "get_comparison" => {
+            let args: ComparisonTokenArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let comparison = get_comparison(settings, &args.token).await?;
+            let content = format_saved_comparison(&serde_json::to_value(comparison)?)?;
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
+        "find_breed_rescues" => {
+            let args: BreedRescueArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let data = find_breed_rescues(settings, args).await?;
+            Ok(json!({ "content": [{ "type": "text", "text": format_breed_rescues(&data)? }] }))
+        }
         "search_organizations" => {
             let args: OrgSearchArgs = serde_json::from_value(
                 params
@@ -389,11 +1116,12 @@ pub async fn handle_tool_call(
                 postal_code: None,
                 miles: None,
                 query: None,
+                species: None,
             });
 
-            let data = search_organizations(settings, args).await?;
+            let data = search_orgs(settings, args).await?;
             let content = format_org_results(&data)?;
-            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+            Ok(listing_result(content, &data))
         }
         "get_organization_details" => {
             let args: OrgIdArgs = serde_json::from_value(
@@ -416,51 +1144,163 @@ pub async fn handle_tool_call(
                 None => Err(AppError::NotFound),
             }
         }
-        "list_org_animals" => {
+        "get_org_contact" => {
             let args: OrgIdArgs = serde_json::from_value(
                 params
                     .unwrap_or_default()
                     .get("arguments")
                     .cloned()
                     .unwrap_or_default(),
-            )
-            .unwrap_or(OrgIdArgs {
-                org_id: "0".to_string(),
-            });
+            )?;
+
+            let contact = get_org_contact(settings, args).await?;
+            Ok(json!({
+                "content": [
+                    { "type": "text", "text": format_org_contact(&contact) },
+                    { "type": "text", "text": serde_json::to_string_pretty(&contact)? }
+                ],
+                "structuredContent": contact
+            }))
+        }
+        "get_adoption_process" => {
+            let args: OrgOrAnimalArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
 
-            let data = list_org_animals(settings, args).await?;
-            let content = format_animal_results(&data)?;
+            let process = get_adoption_process(settings, args).await?;
+            let content = format_adoption_process(&process);
             Ok(json!({ "content": [{ "type": "text", "text": content }] }))
         }
-        "search_adoptable_pets" => {
-            let args: ToolArgs = serde_json::from_value(
+        "get_sponsorship_info" => {
+            let args: OrgOrAnimalArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let info = get_sponsorship_info(settings, args).await?;
+            let content = format_sponsorship_info(&info);
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
+        "find_by_reference" => {
+            let args: ReferenceArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let data = find_by_reference(settings, args).await?;
+            let content = format_animal_results(&data)?;
+            Ok(listing_result(content, &data))
+        }
+        "list_org_locations" => {
+            let args: OrgIdArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let data = list_org_locations(settings, args).await?;
+            let content = format_org_locations(&data)?;
+            Ok(listing_result(content, &data))
+        }
+        "list_org_animals" => {
+            let args: OrgAnimalsArgs = serde_json::from_value(
                 params
                     .unwrap_or_default()
                     .get("arguments")
                     .cloned()
                     .unwrap_or_default(),
             )
-            .unwrap_or(ToolArgs {
-                postal_code: None,
-                miles: None,
+            .unwrap_or(OrgAnimalsArgs {
+                org_id: "0".to_string(),
                 species: None,
-                breeds: None,
-                sex: None,
-                age: None,
-                size: None,
-                good_with_children: None,
-                good_with_dogs: None,
-                good_with_cats: None,
-                house_trained: None,
-                special_needs: None,
-                needs_foster: None,
-                color: None,
-                pattern: None,
-                sort_by: None,
             });
 
-            let data = fetch_pets(settings, args).await?;
-            let content = format_animal_results(&data)?;
+            let data = list_org_animals(settings, args).await?;
+            let content = format_org_animals(&data)?;
+            Ok(listing_result(content, &data))
+        }
+        "search_adoptable_pets" => {
+            let arguments = params
+                .unwrap_or_default()
+                .get("arguments")
+                .cloned()
+                .unwrap_or_default();
+            let args: ToolArgs = serde_json::from_value(arguments.clone()).unwrap_or_default();
+            let preferences = match arguments.get("preferences") {
+                Some(p) if !p.is_null() => {
+                    let preferences: Preferences =
+                        serde_json::from_value(p.clone()).map_err(|e| {
+                            AppError::invalid_argument("preferences", format!("preferences: {}", e))
+                        })?;
+                    preferences.validate()?;
+                    Some(preferences).filter(|p| !p.is_empty())
+                }
+                _ => None,
+            };
+
+            let mut data = search_animals(settings, args.clone()).await?;
+            if let Some(preferences) = &preferences {
+                let miles = args.miles.unwrap_or(settings.default_miles);
+                rank(&mut data, preferences, miles);
+            }
+            remember_search(settings, args, preferences, &data).await;
+            search_result(&data)
+        }
+        "next_results" => {
+            let data = next_results(settings).await?;
+            if data["data"].as_array().is_none_or(Vec::is_empty) {
+                return Ok(json!({
+                    "content": [{ "type": "text", "text": "The last search has no more results." }]
+                }));
+            }
+            search_result(&data)
+        }
+        "set_preferences" => {
+            let arguments = params
+                .unwrap_or_default()
+                .get("arguments")
+                .cloned()
+                .unwrap_or_default();
+            let preferences: Preferences = if arguments["clear"] == true {
+                Preferences::default()
+            } else {
+                serde_json::from_value(arguments)
+                    .map_err(|e| AppError::validation(format!("preferences: {}", e)))?
+            };
+            let content = format_preferences(&json!(preferences));
+            set_session_preferences(settings, preferences.clone()).await?;
+            Ok(json!({
+                "content": [{ "type": "text", "text": content }],
+                "structuredContent": preferences
+            }))
+        }
+        "export_results" => {
+            let args: ExportResultsArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let summary = export_results(settings, args).await?;
+            let content = format!(
+                "Saved {} animals to {}",
+                summary["animals"],
+                summary["path"].as_str().unwrap_or("")
+            );
             Ok(json!({ "content": [{ "type": "text", "text": content }] }))
         }
         "get_random_pet" => {
@@ -474,7 +1314,7 @@ pub async fn handle_tool_call(
             let data = get_random_pet(settings, species).await?;
             // Reuse animal formatter but maybe limit to 1 if not already
             let content = format_animal_results(&data)?;
-            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+            Ok(listing_result(content, &data))
         }
         "list_adopted_animals" => {
             let args: AdoptedAnimalsArgs = serde_json::from_value(
@@ -490,9 +1330,220 @@ pub async fn handle_tool_call(
                 species: None,
             });
 
-            let data = fetch_adopted_pets(settings, args).await?;
-            let content = format_animal_results(&data)?;
-            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+            let data = fetch_adopted_pets(settings, args).await?;
+            let content = format_animal_results(&data)?;
+            Ok(listing_result(content, &data))
+        }
+        "semantic_search_pets" => {
+            let args: SemanticSearchArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let data = semantic_search(settings, args).await?;
+            let content = format_semantic_results(&data)?;
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
+        "list_new_arrivals" => {
+            let args: NewArrivalsArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let data = list_new_arrivals(settings, args).await?;
+            let content = format_new_arrivals(&data)?;
+            Ok(listing_result(content, &data))
+        }
+        "compare_locations" => {
+            let args: CompareLocationsArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let data = compare_locations(settings, args).await?;
+            let content = format_location_comparison(&data)?;
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
+        "match_me_a_pet" => {
+            let args: MatchArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )
+            .unwrap_or_default();
+
+            let data = match_pets(settings, args).await?;
+            let content = format_match_results(&data)?;
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
+        "parse_search_query" => {
+            let args: ParseQueryArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let data = parse_search_query(settings, &args.query).await?;
+            let content = format_parsed_query(&data)?;
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
+        "save_favorite" => {
+            let args: FavoriteArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let favorite = save_favorite(settings, &args.animal_id, args.note).await?;
+            Ok(
+                json!({ "content": [{ "type": "text", "text": format!("Saved {} (ID: {}) to favorites.", favorite.name, favorite.id) }] }),
+            )
+        }
+        "list_favorites" => {
+            let favorites = list_favorites(settings).await?;
+            let content = format_favorites(&json!({ "data": favorites }))?;
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
+        "check_updates" => {
+            let args: CheckUpdatesArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let data = check_updates(settings, args.animal_ids, args.favorites).await?;
+            let content = format_updates(&data)?;
+            Ok(json!({
+                "content": [{ "type": "text", "text": content }],
+                "structuredContent": { "animals": data["data"] }
+            }))
+        }
+        "add_note" => {
+            let args: NoteArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            add_note(settings, &args.animal_id, &args.note).await?;
+            Ok(
+                json!({ "content": [{ "type": "text", "text": format!("Added a note to animal {}.", args.animal_id) }] }),
+            )
+        }
+        "get_notes" => {
+            let args: AnimalIdArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let notes = get_notes(settings, &args.animal_id).await?;
+            let content = format_notes(&json!({ "animal_id": args.animal_id, "notes": notes }))?;
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
+        "remove_favorite" => {
+            let args: AnimalIdArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            remove_favorite(settings, &args.animal_id).await?;
+            Ok(
+                json!({ "content": [{ "type": "text", "text": format!("Removed {} from favorites.", args.animal_id) }] }),
+            )
+        }
+        "save_search" => {
+            let args: SaveSearchArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            save_search(settings, &args.name, args.search).await?;
+            Ok(
+                json!({ "content": [{ "type": "text", "text": format!("Saved search '{}'. New matches will be reported as they are listed.", args.name) }] }),
+            )
+        }
+        "list_saved_searches" => {
+            let searches = list_saved_searches(settings).await?;
+            let content = format_saved_searches(&json!({ "data": searches }))?;
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
+        "delete_saved_search" => {
+            let args: SavedSearchNameArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            delete_saved_search(settings, &args.name).await?;
+            Ok(
+                json!({ "content": [{ "type": "text", "text": format!("Deleted saved search '{}'.", args.name) }] }),
+            )
+        }
+        "check_saved_searches" => {
+            let alerts = check_saved_searches(settings).await?;
+            let content = format_alerts(&json!({ "data": alerts }))?;
+            Ok(json!({ "content": [{ "type": "text", "text": content }] }))
+        }
+        "private_list_org_animals" => {
+            let data = list_private_animals(settings).await?;
+            let content = format_animal_results(&data)?;
+            Ok(listing_result(content, &data))
+        }
+        "private_get_animal_details" => {
+            let args: AnimalIdArgs = serde_json::from_value(
+                params
+                    .unwrap_or_default()
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_default(),
+            )?;
+
+            let data = get_private_animal(settings, &args.animal_id).await?;
+            match data.get("data").and_then(extract_single_item) {
+                Some(animal) => Ok(
+                    json!({ "content": [{ "type": "text", "text": format_single_animal(animal) }] }),
+                ),
+                None => Err(AppError::NotFound),
+            }
+        }
+        "private_get_organization" => {
+            let data = get_private_org(settings).await?;
+            let content = format_org_results(&data)?;
+            Ok(listing_result(content, &data))
+        }
+        "get_server_info" => {
+            let data = get_server_info(settings).await?;
+            Ok(json!({ "content": [{ "type": "text", "text": format_server_info(&data) }] }))
         }
         "inspect_tool" => {
             let tool_name = params
@@ -501,9 +1552,13 @@ pub async fn handle_tool_call(
                 .and_then(|a| a.get("tool_name"))
                 .and_then(|n| n.as_str());
 
+            let mut tools = get_all_tool_definitions();
+            tools.extend(get_preset_tool_definitions(settings));
+            tools.extend(get_private_tool_definitions(settings));
+            tools.extend(get_plugin_tool_definitions(settings));
+
             if let Some(name) = tool_name {
                 // Find specific tool
-                let tools = get_all_tool_definitions();
                 if let Some(tool) = tools.iter().find(|t| t["name"].as_str() == Some(name)) {
                     Ok(
                         json!({ "content": [{ "type": "text", "text": serde_json::to_string_pretty(tool).unwrap() }] }),
@@ -513,7 +1568,6 @@ pub async fn handle_tool_call(
                 }
             } else {
                 // List all tools (name + description)
-                let tools = get_all_tool_definitions();
                 let summary = tools
                     .iter()
                     .map(|t| {
@@ -529,47 +1583,463 @@ pub async fn handle_tool_call(
                 Ok(json!({ "content": [{ "type": "text", "text": summary }] }))
             }
         }
-        _ => Err(AppError::NotFound),
+        _ => {
+            let arguments = params
+                .as_ref()
+                .and_then(|p| p.get("arguments"))
+                .cloned()
+                .unwrap_or_default();
+            let Some(preset) = find_preset_for_tool(settings, name) else {
+                return call_plugin(settings, name, &arguments)
+                    .await?
+                    .ok_or(AppError::NotFound);
+            };
+            let args = ToolArgs {
+                postal_code: arguments["postal_code"].as_str().map(|s| s.to_string()),
+                miles: arguments["miles"].as_u64().map(|m| m as u32),
+                preset: Some(preset.clone()),
+                ..Default::default()
+            };
+
+            let data = search_animals(settings, args.clone()).await?;
+            remember_search(settings, args, None, &data).await;
+            let content = format_animal_results(&data)?;
+            Ok(listing_result(content, &data))
+        }
+    }
+}
+
+/// Checks `tools/call` arguments against the tool's declared `inputSchema`.
+/// Returns a message naming each offending field; unknown tools pass, to be
+/// reported by the call itself.
+fn validate_arguments(settings: &Settings, name: &str, arguments: &Value) -> Result<(), AppError> {
+    let Some(tool) = find_tool(settings, name) else {
+        return Ok(());
+    };
+    let validator = match jsonschema::validator_for(&tool["inputSchema"]) {
+        Ok(validator) => validator,
+        Err(e) => {
+            warn!("Tool '{}' has an invalid inputSchema: {}", name, e);
+            return Ok(());
+        }
+    };
+
+    // (argument at fault, problem)
+    let mut problems: Vec<(Option<String>, String)> = validator
+        .iter_errors(arguments)
+        .map(|e| {
+            let path = e.instance_path().to_string();
+            match path.trim_start_matches('/') {
+                "" => {
+                    let argument = match e.kind() {
+                        ValidationErrorKind::Required { property } => {
+                            property.as_str().map(str::to_string)
+                        }
+                        _ => None,
+                    };
+                    (argument, e.to_string())
+                }
+                field => {
+                    let argument = field.split('/').next().map(str::to_string);
+                    (argument, format!("'{}': {}", field, e))
+                }
+            }
+        })
+        .collect();
+    if settings.strict && tool["inputSchema"]["additionalProperties"] != true {
+        problems.extend(unexpected_arguments(&tool["inputSchema"], arguments));
+    }
+    if problems.is_empty() {
+        return Ok(());
+    }
+    let message = format!(
+        "Invalid arguments for '{}': {}",
+        name,
+        problems
+            .iter()
+            .map(|(_, problem)| problem.as_str())
+            .collect::<Vec<_>>()
+            .join("; ")
+    );
+    Err(AppError::ValidationError {
+        parameter: problems.into_iter().find_map(|(argument, _)| argument),
+        message,
+    })
+}
+
+/// The definition of tool `name`, whether built in, a preset, private or a
+/// plugin.
+fn find_tool(settings: &Settings, name: &str) -> Option<Value> {
+    let mut tools = get_all_tool_definitions();
+    tools.extend(get_preset_tool_definitions(settings));
+    tools.extend(get_private_tool_definitions(settings));
+    tools.extend(get_plugin_tool_definitions(settings));
+    tools.into_iter().find(|t| t["name"] == name)
+}
+
+/// Respells string arguments that match one of their schema's `enum` values
+/// apart from case ("senior" → "Senior"), so only real mismatches fail
+/// validation.
+fn normalize_enum_case(schema: &Value, arguments: &mut Value) {
+    let Some(arguments) = arguments.as_object_mut() else {
+        return;
+    };
+    for (name, value) in arguments.iter_mut() {
+        let property = &schema["properties"][name];
+        if value.is_object() {
+            normalize_enum_case(property, value);
+            continue;
+        }
+        let (Some(given), Some(allowed)) = (value.as_str(), property["enum"].as_array()) else {
+            continue;
+        };
+        if let Some(canonical) = allowed
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|a| a.eq_ignore_ascii_case(given.trim()))
+        {
+            *value = json!(canonical);
+        }
+    }
+}
+
+/// Brings numeric arguments outside their schema's `minimum`/`maximum` back
+/// into range, as done outside strict mode. Returns a note per change.
+fn clamp_arguments(schema: &Value, arguments: &mut Value) -> Vec<String> {
+    let mut notes = Vec::new();
+    let Some(arguments) = arguments.as_object_mut() else {
+        return notes;
+    };
+    for (name, value) in arguments.iter_mut() {
+        let property = &schema["properties"][name];
+        if value.is_object() {
+            notes.extend(clamp_arguments(property, value));
+            continue;
+        }
+        let Some(number) = value.as_f64() else {
+            continue;
+        };
+        let clamped = match (property["minimum"].as_f64(), property["maximum"].as_f64()) {
+            (Some(min), _) if number < min => &property["minimum"],
+            (_, Some(max)) if number > max => &property["maximum"],
+            _ => continue,
+        };
+        notes.push(format!("'{}' {} clamped to {}", name, value, clamped));
+        *value = clamped.clone();
+    }
+    notes
+}
+
+/// Arguments the schema doesn't declare, with the closest declared name.
+fn unexpected_arguments(schema: &Value, arguments: &Value) -> Vec<(Option<String>, String)> {
+    let declared: Vec<String> = schema["properties"]
+        .as_object()
+        .map(|p| p.keys().cloned().collect())
+        .unwrap_or_default();
+    arguments
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| {
+            !["debug", "dry_run"].contains(&name.as_str()) && !declared.contains(name)
+        })
+        .map(|(name, _)| {
+            let problem = match suggest(name, &declared).first() {
+                Some(close) => {
+                    format!("unexpected argument '{}' (did you mean '{}'?)", name, close)
+                }
+                None if declared.is_empty() => {
+                    format!("unexpected argument '{}' (this tool takes none)", name)
+                }
+                None => format!(
+                    "unexpected argument '{}' (expected one of: {})",
+                    name,
+                    declared.join(", ")
+                ),
+            };
+            (Some(name.clone()), problem)
+        })
+        .collect()
+}
+
+/// Tool output for a listing. When the listing is one page of more, the
+/// result's `_meta` says so with the total, so agents know it is partial.
+/// A search's animals, as ranked by preferences when it was.
+fn search_result(data: &Value) -> Result<Value, AppError> {
+    if data.get("preferences").is_none() {
+        let content = format_animal_results(data)?;
+        return Ok(listing_result(content, data));
+    }
+    let mut listed = data.clone();
+    if let Some(animals) = listed["data"].as_array_mut() {
+        animals.truncate(LISTED_ANIMALS);
+    }
+    let mut content = format_match_results(&listed)?;
+    if let Some(excluded) = data["excluded"].as_u64().filter(|&n| n > 0) {
+        content.push_str(&format!(
+            "\n\n_{} more matched the search but not the required preferences._",
+            excluded
+        ));
+    }
+    let mut result = listing_result(content, data);
+    result["structuredContent"] = json!({
+        "preferences": data["preferences"],
+        "results": ranking(data),
+    });
+    Ok(result)
+}
+
+fn listing_result(text: String, data: &Value) -> Value {
+    let mut result = json!({ "content": [{ "type": "text", "text": text }] });
+    if data["truncated"] == true {
+        result["_meta"] = json!({ "truncated": true, "total": data["total"] });
+    }
+    result
+}
+
+/// Adds the upstream requests a call made to its output, for `debug: true`.
+fn append_request_log(output: &mut Value, requests: &[Value]) {
+    let text = format!(
+        "### Upstream Requests\n```json\n{}\n```",
+        serde_json::to_string_pretty(requests).unwrap_or_default()
+    );
+    if let Some(content) = output["content"].as_array_mut() {
+        content.push(json!({ "type": "text", "text": text }));
+    }
+}
+
+/// The output of a `dry_run: true` call: the requests it would have sent.
+fn planned_requests_result(requests: &[Value]) -> Value {
+    let data = json!({ "requests": requests });
+    json!({
+        "content": [{ "type": "text", "text": format_planned_requests(&data).unwrap_or_default() }],
+        "structuredContent": data
+    })
+}
+
+/// Whether a tool records per-session state (the search `next_results`
+/// continues), so only calls from the same session may share a run.
+fn is_session_scoped(settings: &Settings, name: &str) -> bool {
+    name == "search_adoptable_pets" || find_preset_for_tool(settings, name).is_some()
+}
+
+/// Runs a tool call, or joins an identical one already running against
+/// the same backend and data directory (and session, for tools that
+/// record per-session state).
+async fn coalesced_tool_call(
+    name: String,
+    arguments: &Value,
+    params: Value,
+    settings: &Settings,
+) -> Result<Value, Value> {
+    if NOT_COALESCED.contains(&name.as_str()) {
+        return run_tool_call(name, params, settings.clone()).await;
+    }
+
+    let session = if is_session_scoped(settings, &name) {
+        settings.session.as_deref().unwrap_or_default()
+    } else {
+        ""
+    };
+    let key = format!(
+        "{}|{}|{}|{}|{}",
+        settings.base_url,
+        settings.data_dir.display(),
+        session,
+        name,
+        arguments
+    );
+    let call = {
+        let mut in_flight = IN_FLIGHT.lock().unwrap();
+        match in_flight.get(&key) {
+            Some(call) => {
+                debug!("Joining identical in-flight call to '{}'", name);
+                call.clone()
+            }
+            None => {
+                let (settings, done_key) = (settings.clone(), key.clone());
+                let call = async move {
+                    let result = run_tool_call(name, params, settings).await;
+                    IN_FLIGHT.lock().unwrap().remove(&done_key);
+                    result
+                }
+                .boxed()
+                .shared();
+                in_flight.insert(key, call.clone());
+                call
+            }
+        }
+    };
+    call.await
+}
+
+async fn run_tool_call(
+    name: String,
+    mut params: Value,
+    settings: Settings,
+) -> Result<Value, Value> {
+    // `debug: true` works with every tool and is not passed on to it
+    let debug = params["arguments"]
+        .as_object_mut()
+        .and_then(|arguments| arguments.remove("debug"))
+        == Some(Value::Bool(true));
+    // So does `dry_run: true`, which shows the requests instead of sending them
+    let dry_run = params["arguments"]
+        .as_object_mut()
+        .and_then(|arguments| arguments.remove("dry_run"))
+        == Some(Value::Bool(true));
+    let mut call_settings = settings.for_call();
+    if debug || dry_run {
+        call_settings.request_log = Some(RequestLog::default());
+    }
+    call_settings.dry_run = dry_run;
+
+    let started = Instant::now();
+    let mut result = render(
+        &call_settings,
+        handle_tool_call(&name, Some(params.clone()), &call_settings),
+    )
+    .await;
+    if let (true, Some(log)) = (dry_run, &call_settings.request_log) {
+        // Whatever the tool made of no responses doesn't matter
+        return Ok(planned_requests_result(&log.lock().unwrap()));
+    }
+    if let Ok(output) = &mut result {
+        relabel_content(output, &settings.labels);
+    }
+    if let (Ok(output), Some(log)) = (&mut result, &call_settings.request_log) {
+        append_request_log(output, &log.lock().unwrap());
+    }
+    record_tool_call(
+        &settings,
+        &name,
+        Some(&params),
+        started.elapsed(),
+        result.as_ref().map(|_| ()),
+    )
+    .await;
+    result.map_err(|e| {
+        warn!("Tool call '{}' failed: {}", name, e);
+        e.to_json_rpc_error()
+    })
+}
+
+/// Puts a tool result's text in the deployment's own words.
+fn relabel_content(output: &mut Value, labels: &Labels) {
+    if labels.replace.is_empty() {
+        return;
+    }
+    for item in output["content"].as_array_mut().into_iter().flatten() {
+        if let Some(text) = item["text"].as_str() {
+            item["text"] = json!(relabel(text, labels));
+        }
     }
 }
 
+/// A tool result as the contents of the resource at `uri`.
+fn resource_contents(uri: &str, result: &Value) -> Value {
+    let text = result["content"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|c| c["text"].as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    json!({ "contents": [{ "uri": uri, "mimeType": "text/markdown", "text": text }] })
+}
+
 pub async fn process_mcp_request(
     req: JsonRpcRequest,
     settings: &Settings,
 ) -> (Option<Value>, Result<Value, Value>) {
     let response = match req.method.as_str() {
-        "initialize" => Ok(json!({
-            "protocolVersion": "2024-11-05",
-            "capabilities": { "tools": {} },
-            "serverInfo": { "name": "rescue-groups-mcp", "version": env!("PROJECT_VERSION") }
-        })),
+        "initialize" => {
+            let mut result = json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": { "tools": {}, "resources": {} },
+                "serverInfo": { "name": "rescue-groups-mcp", "version": env!("PROJECT_VERSION") }
+            });
+            if let Some(warning) = &settings.key_warning {
+                result["instructions"] = json!(warning);
+            }
+            Ok(result)
+        }
 
         "notifications/initialized" => return (None, Ok(json!({}))), // Notification, no response
 
         "tools/list" => {
-            let tools = if settings.lazy {
+            let mut tools = if settings.lazy {
                 get_core_tool_definitions()
             } else {
                 get_all_tool_definitions()
             };
+            tools.extend(get_preset_tool_definitions(settings));
+            tools.extend(get_private_tool_definitions(settings));
+            tools.extend(get_plugin_tool_definitions(settings));
             Ok(json!({ "tools": tools }))
         }
 
         "tools/call" => {
-            if let Some(params) = req.params {
+            if let Some(mut params) = req.params {
                 let name = params["name"].as_str().unwrap_or("").to_string();
-                match handle_tool_call(&name, Some(params), settings).await {
-                    Ok(val) => Ok(val),
-                    Err(e) => {
-                        warn!("Tool call '{}' failed: {}", name, e);
-                        Err(e.to_json_rpc_error())
+                let mut arguments = match params.get("arguments") {
+                    None | Some(Value::Null) => json!({}),
+                    Some(arguments) => arguments.clone(),
+                };
+                if let Some(tool) = find_tool(settings, &name) {
+                    let schema = &tool["inputSchema"];
+                    normalize_enum_case(schema, &mut arguments);
+                    if !settings.strict {
+                        let notes = clamp_arguments(schema, &mut arguments);
+                        if !notes.is_empty() {
+                            warn!("Tool call '{}': {}", name, notes.join("; "));
+                        }
+                    }
+                    if params.get("arguments").is_some_and(|a| *a != arguments) {
+                        params["arguments"] = arguments.clone();
+                    }
+                }
+                if let Err(error) = validate_arguments(settings, &name, &arguments) {
+                    warn!("Tool call '{}' rejected: {}", name, error);
+                    return (req.id, Err(error.to_json_rpc_error()));
+                }
+                // Part of the arguments, so calls of sessions with different
+                // preferences aren't coalesced
+                if name == "search_adoptable_pets" && arguments.get("preferences").is_none() {
+                    if let Some(preferences) = session_preferences(settings).await {
+                        arguments["preferences"] = json!(preferences);
+                        params["arguments"] = arguments.clone();
                     }
                 }
+                coalesced_tool_call(name, &arguments, params, settings).await
             } else {
                 Err(json!({ "code": -32602, "message": "Missing parameters" }))
             }
         }
 
+        // Resources are only read through templates; there's nothing to list
+        "resources/list" => Ok(json!({ "resources": [] })),
+
+        "resources/templates/list" => Ok(json!({ "resourceTemplates": resource_templates() })),
+
+        "resources/read" => {
+            let uri = req
+                .params
+                .as_ref()
+                .and_then(|p| p["uri"].as_str())
+                .unwrap_or("")
+                .to_string();
+            match resolve_resource(&uri) {
+                Ok((name, arguments)) => {
+                    let params = json!({ "name": name, "arguments": arguments });
+                    run_tool_call(name.to_string(), params, settings.clone())
+                        .await
+                        .map(|result| resource_contents(&uri, &result))
+                }
+                Err(e) => Err(e.to_json_rpc_error()),
+            }
+        }
+
         "ping" => Ok(json!({})),
 
         _ => Err(json!({ "code": -32601, "message": "Method not found" })),
@@ -595,24 +2065,19 @@ mod tests {
     use super::*;
     use crate::config::Settings;
     use governor::{Quota, RateLimiter};
-    use moka::future::Cache;
+
     use std::num::NonZeroU32;
     use std::sync::Arc;
-    use std::time::Duration;
 
     fn get_test_settings() -> Settings {
         Settings {
-            api_key: "test_key".to_string(),
-            base_url: "http://test.url".to_string(),
-            default_postal_code: "00000".to_string(),
-            default_miles: 50,
-            default_species: "dogs".to_string(),
-            timeout: Duration::from_secs(1),
-            lazy: false,
-            cache: Arc::new(Cache::new(10)),
             limiter: Arc::new(RateLimiter::direct(Quota::per_second(
                 NonZeroU32::new(1).unwrap(),
             ))),
+            ..Settings::for_test(
+                "http://test.url",
+                std::env::temp_dir().join("rescue-groups-mcp-test"),
+            )
         }
     }
 
@@ -652,6 +2117,57 @@ mod tests {
         assert_eq!(res["protocolVersion"], "2024-11-05");
     }
 
+    #[tokio::test]
+    async fn test_process_mcp_request_resources() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+        let request = |method: &str, params: Option<Value>| JsonRpcRequest {
+            _jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: method.to_string(),
+            params,
+        };
+
+        let (_, result) =
+            process_mcp_request(request("resources/templates/list", None), &settings).await;
+        let templates = result.unwrap()["resourceTemplates"].clone();
+        assert!(templates
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|t| t["uriTemplate"] == "rescuegroups://animal/{animal_id}"));
+
+        let _mock = server
+            .mock(
+                "GET",
+                "/public/orgs/866/animals/search/available/cats?include=orgs,species&limit=250&page=1",
+            )
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Tom"}}]}"#)
+            .create_async()
+            .await;
+        let uri = "rescuegroups://org/866/animals?species=cats";
+        let (_, result) = process_mcp_request(
+            request("resources/read", Some(json!({ "uri": uri }))),
+            &settings,
+        )
+        .await;
+        let contents = &result.unwrap()["contents"][0];
+        assert_eq!(contents["uri"], uri);
+        assert!(contents["text"].as_str().unwrap().contains("Tom"));
+
+        let (_, result) = process_mcp_request(
+            request(
+                "resources/read",
+                Some(json!({ "uri": "rescuegroups://shelter/1" })),
+            ),
+            &settings,
+        )
+        .await;
+        assert_eq!(result.unwrap_err()["code"], -32602);
+    }
+
     #[tokio::test]
     async fn test_process_mcp_request_tools_list() {
         let settings = get_test_settings();
@@ -693,6 +2209,175 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_identical_concurrent_calls_are_coalesced() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+
+        let mock = server
+            .mock("GET", "/public/animals/breeds/42")
+            .with_status(200)
+            .with_body_from_request(|_| {
+                std::thread::sleep(std::time::Duration::from_millis(200));
+                br#"{"data": [{"id": "42", "attributes": {"name": "Beagle"}}]}"#.to_vec()
+            })
+            .expect(1)
+            .create_async()
+            .await;
+
+        let call = || JsonRpcRequest {
+            _jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/call".to_string(),
+            params: Some(json!({ "name": "get_breed", "arguments": { "breed_id": "42" } })),
+        };
+        let (first, second) = tokio::join!(
+            process_mcp_request(call(), &settings),
+            process_mcp_request(call(), &settings)
+        );
+        assert_eq!(first.1.unwrap(), second.1.unwrap());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_searches_of_two_sessions_are_each_remembered() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+        settings.limiter = Arc::new(RateLimiter::direct(Quota::per_second(
+            NonZeroU32::new(100).unwrap(),
+        )));
+
+        let page = |names: &[&str]| {
+            let animals: Vec<Value> = names
+                .iter()
+                .map(|name| json!({ "id": name, "attributes": { "name": name } }))
+                .collect();
+            json!({ "data": animals, "meta": { "count": 8, "pages": 2 } }).to_string()
+        };
+        let first = page(&["A", "B", "C", "D", "E", "F", "G"]);
+        let _first = server
+            .mock("POST", "/public/animals/search/available/birds/haspic")
+            .with_status(200)
+            .with_body_from_request(move |_| {
+                std::thread::sleep(std::time::Duration::from_millis(200));
+                first.clone().into_bytes()
+            })
+            .create_async()
+            .await;
+        let _second = server
+            .mock(
+                "POST",
+                "/public/animals/search/available/birds/haspic?page=2",
+            )
+            .with_status(200)
+            .with_body(page(&["H"]))
+            .create_async()
+            .await;
+
+        let call = || JsonRpcRequest {
+            _jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/call".to_string(),
+            params: Some(json!({
+                "name": "search_adoptable_pets",
+                "arguments": { "species": "birds" }
+            })),
+        };
+        let mut alice = settings.clone();
+        alice.session = Some("coalesce-alice".to_string());
+        let mut bob = settings.clone();
+        bob.session = Some("coalesce-bob".to_string());
+        let (first, second) = tokio::join!(
+            process_mcp_request(call(), &alice),
+            process_mcp_request(call(), &bob)
+        );
+        assert!(first.1.is_ok() && second.1.is_ok());
+
+        for session in [&alice, &bob] {
+            let more = handle_tool_call("next_results", None, session)
+                .await
+                .unwrap();
+            assert!(more["content"][0]["text"].as_str().unwrap().contains("[F]"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_argument_shows_requests_without_sending() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+        settings.strict = true;
+
+        let mock = server
+            .mock("POST", "/public/animals/search/available/rabbits/haspic")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let req = JsonRpcRequest {
+            _jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/call".to_string(),
+            params: Some(json!({
+                "name": "search_adoptable_pets",
+                "arguments": { "species": "rabbits", "color": "Grey", "dry_run": true }
+            })),
+        };
+        let (_, result) = process_mcp_request(req, &settings).await;
+        let result = result.unwrap();
+        let requests = result["structuredContent"]["requests"].as_array().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["method"], "POST");
+        assert!(requests[0]["url"]
+            .as_str()
+            .unwrap()
+            .ends_with("/public/animals/search/available/rabbits/haspic"));
+        assert_eq!(requests[0]["headers"]["Authorization"], "[redacted]");
+        assert!(requests[0]["body"]
+            .to_string()
+            .contains("animals.colorDetails"));
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.starts_with("# Dry Run (1 requests, not sent)"));
+        assert!(!text.contains("test_key"));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_debug_argument_appends_requests() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+        settings.strict = true;
+
+        let _mock = server
+            .mock("POST", "/public/animals/search/available/rabbits/haspic")
+            .with_status(200)
+            .with_body(r#"{"data": [], "meta": {"count": 0}}"#)
+            .create_async()
+            .await;
+
+        let req = JsonRpcRequest {
+            _jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/call".to_string(),
+            params: Some(json!({
+                "name": "search_adoptable_pets",
+                "arguments": { "species": "rabbits", "color": "Grey", "debug": true }
+            })),
+        };
+        let (_, result) = process_mcp_request(req, &settings).await;
+        let content = result.unwrap()["content"].clone();
+        assert_eq!(content.as_array().unwrap().len(), 2);
+        let requests = content[1]["text"].as_str().unwrap();
+        assert!(requests.starts_with("### Upstream Requests"));
+        assert!(requests.contains("/public/animals/search/available/rabbits/haspic"));
+        assert!(requests.contains("animals.colorDetails"));
+        assert!(requests.contains("200 OK 0 items of 0"));
+        assert!(!requests.contains("test_key"));
+    }
+
     #[tokio::test]
     async fn test_handle_tool_call_list_species() {
         let mut server = mockito::Server::new_async().await;
@@ -776,9 +2461,19 @@ mod tests {
         settings.base_url = server.url();
 
         let _mock = server
-            .mock("GET", "/public/animals/123")
+            .mock("GET", "/public/animals/123?include=orgs,locations")
             .with_status(200)
-            .with_body(r#"{"data": {"id": "123", "attributes": {"name": "Buddy"}}}"#)
+            .with_body(
+                json!({
+                    "data": {"id": "123", "attributes": {"name": "Buddy"},
+                        "relationships": {"locations": {"data": [{"type": "locations", "id": "9"}]}}},
+                    "included": [
+                        {"type": "orgs", "id": "1", "attributes": {"city": "Austin", "state": "TX"}},
+                        {"type": "locations", "id": "9", "attributes": {"city": "Round Rock", "state": "TX"}}
+                    ]
+                })
+                .to_string(),
+            )
             .create_async()
             .await;
 
@@ -788,8 +2483,45 @@ mod tests {
             }
         });
 
-        let res = handle_tool_call("get_animal_details", Some(params), &settings).await;
-        assert!(res.is_ok());
+        let res = handle_tool_call("get_animal_details", Some(params), &settings)
+            .await
+            .unwrap();
+        assert!(res["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("**Currently in foster in Round Rock, TX**"));
+    }
+
+    #[tokio::test]
+    async fn test_handle_tool_call_get_animal_photos() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+
+        let _mock = server
+            .mock("GET", "/public/animals/55")
+            .with_status(200)
+            .with_body(
+                json!({"data": {"id": "55", "attributes": {"name": "Pip", "orgsAnimalsPictures": [
+                    {"order": 1, "caption": "Pip", "urlSecureFullsize": "https://cdn/p.jpg",
+                        "large": {"url": "https://cdn/p-l.jpg", "resolutionX": 500, "resolutionY": 400}}
+                ]}}})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let params = json!({ "arguments": { "animal_id": "55" } });
+        let res = handle_tool_call("get_animal_photos", Some(params), &settings)
+            .await
+            .unwrap();
+        let gallery = &res["structuredContent"];
+        assert_eq!(gallery["name"], "Pip");
+        assert_eq!(gallery["photos"][0]["renditions"][0]["width"], 500);
+        assert!(res["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("[large 500×400](https://cdn/p-l.jpg)"));
     }
 
     #[tokio::test]
@@ -800,7 +2532,7 @@ mod tests {
         settings.base_url = server.url();
 
         let _mock = server
-            .mock("GET", "/public/animals/123?include=orgs")
+            .mock("GET", "/public/animals/123?include=orgs,locations")
             .with_status(200)
             .with_body(r#"{"data": {"id": "123", "attributes": {"name": "Buddy"}}}"#)
             .create_async()
@@ -816,6 +2548,47 @@ mod tests {
         assert!(res.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_handle_tool_call_list_new_arrivals() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+
+        let today = crate::feeds::rfc3339(crate::alerts::now_secs());
+        let _search = server
+            .mock(
+                "POST",
+                "/public/animals/search/available/dogs/haspic?sort=-animals.createdDate",
+            )
+            .with_status(200)
+            .with_body(
+                json!({"data": [
+                    {"type": "animals", "id": "1", "attributes": {"name": "Rex", "createdDate": today},
+                        "relationships": {"orgs": {"data": [{"type": "orgs", "id": "9"}]}}},
+                    {"type": "animals", "id": "2", "attributes": {"name": "Old Timer", "createdDate": "2001-01-01T00:00:00Z"},
+                        "relationships": {"orgs": {"data": [{"type": "orgs", "id": "9"}]}}}
+                ]})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let _org = server
+            .mock("GET", "/public/orgs/9")
+            .with_status(200)
+            .with_body(r#"{"data": {"id": "9", "attributes": {"name": "Austin Pets"}}}"#)
+            .create_async()
+            .await;
+
+        let params = json!({ "arguments": { "species": "dogs" } });
+        let res = handle_tool_call("list_new_arrivals", Some(params), &settings)
+            .await
+            .unwrap();
+        let text = res["content"][0]["text"].as_str().unwrap();
+        assert!(text.starts_with("### New Arrivals (1 pet listed in the last 7 days)"));
+        assert!(text.contains("#### Austin Pets\n- Rex (ID: 1)"));
+        assert!(!text.contains("Old Timer"));
+    }
+
     #[tokio::test]
     async fn test_handle_tool_call_compare_animals() {
         let mut server = mockito::Server::new_async().await;
@@ -860,8 +2633,49 @@ mod tests {
             }
         });
 
-        let res = handle_tool_call("search_organizations", Some(params), &settings).await;
-        assert!(res.is_ok());
+        let res = handle_tool_call("search_organizations", Some(params), &settings).await;
+        assert!(res.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handle_tool_call_search_organizations_by_species() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+
+        let _orgs = server
+            .mock("POST", "/public/orgs/search")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [
+                    {"id": "1", "attributes": {"name": "Bunny Burrow"}},
+                    {"id": "2", "attributes": {"name": "Dog Pound"}}
+                ], "total": 2}"#,
+            )
+            .create_async()
+            .await;
+        let _rabbits = server
+            .mock("POST", "/public/animals/search/available/rabbits/haspic")
+            .with_status(200)
+            .with_body(
+                json!({"data": [
+                    {"id": "10", "relationships": {"orgs": {"data": [{"id": "1"}]}}},
+                    {"id": "11", "relationships": {"orgs": {"data": [{"id": "1"}]}}}
+                ]})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let params = json!({ "arguments": { "postal_code": "02139", "species": "rabbits" } });
+        let res = handle_tool_call("search_organizations", Some(params), &settings)
+            .await
+            .unwrap();
+        let text = res["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("### Bunny Burrow"));
+        assert!(text.contains("**Listing nearby:** 2 rabbits"));
+        assert!(!text.contains("Dog Pound"));
+        assert!(!text.contains("Showing"));
     }
 
     #[tokio::test]
@@ -896,7 +2710,10 @@ mod tests {
         settings.base_url = server.url();
 
         let _mock = server
-            .mock("GET", "/public/orgs/866/animals/search/available")
+            .mock(
+                "GET",
+                "/public/orgs/866/animals/search/available?include=orgs,species&limit=250&page=1",
+            )
             .with_status(200)
             .with_body(r#"{"data": []}"#)
             .create_async()
@@ -936,6 +2753,208 @@ mod tests {
         assert!(res.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_next_results_continues_last_search() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+        settings.session = Some("continuation".to_string());
+        settings.limiter = Arc::new(RateLimiter::direct(Quota::per_second(
+            NonZeroU32::new(100).unwrap(),
+        )));
+        let page = |names: &[&str]| {
+            let animals: Vec<Value> = names
+                .iter()
+                .map(|name| json!({ "id": name, "attributes": { "name": name } }))
+                .collect();
+            json!({ "data": animals, "meta": { "count": 8, "pages": 2 } }).to_string()
+        };
+        let _first = server
+            .mock("POST", "/public/animals/search/available/cats/haspic")
+            .match_body(mockito::Matcher::Regex("Female".to_string()))
+            .with_status(200)
+            .with_body(page(&["A", "B", "C", "D", "E", "F", "G"]))
+            .expect(1)
+            .create_async()
+            .await;
+        let _second = server
+            .mock(
+                "POST",
+                "/public/animals/search/available/cats/haspic?page=2",
+            )
+            .match_body(mockito::Matcher::Regex("Female".to_string()))
+            .with_status(200)
+            .with_body(page(&["H"]))
+            .expect(1)
+            .create_async()
+            .await;
+        let text = |result: Result<Value, AppError>| {
+            result.unwrap()["content"][0]["text"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+
+        let err = handle_tool_call("next_results", None, &settings)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::ValidationError { .. }));
+
+        let params = json!({ "arguments": { "species": "cats", "sex": "Female" } });
+        let listed = text(handle_tool_call("search_adoptable_pets", Some(params), &settings).await);
+        assert!(listed.contains("[E]") && !listed.contains("[F]"));
+
+        let more = text(handle_tool_call("next_results", None, &settings).await);
+        assert!(more.contains("[F]") && more.contains("[G]") && !more.contains("[E]"));
+        let more = text(handle_tool_call("next_results", None, &settings).await);
+        assert!(more.contains("[H]"));
+        let more = text(handle_tool_call("next_results", None, &settings).await);
+        assert_eq!(more, "The last search has no more results.");
+    }
+
+    #[tokio::test]
+    async fn test_session_preferences_rank_search() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+        settings.session = Some("preferences".to_string());
+        let _mock = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .with_status(200)
+            .with_body(
+                json!({ "data": [
+                    { "id": "1", "attributes": { "name": "Pup", "ageGroup": "Baby", "isGoodWithCats": "Yes" } },
+                    { "id": "2", "attributes": { "name": "Gus", "ageGroup": "Senior", "isGoodWithCats": "Yes" } },
+                    { "id": "3", "attributes": { "name": "Rex", "ageGroup": "Senior", "isGoodWithCats": "No" } }
+                ]})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let call = |name: &str, arguments: Value| JsonRpcRequest {
+            _jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/call".to_string(),
+            params: Some(json!({ "name": name, "arguments": arguments })),
+        };
+
+        let (_, result) = process_mcp_request(
+            call(
+                "set_preferences",
+                json!({ "weights": { "senior": 3 }, "required": ["good_with_cats"] }),
+            ),
+            &settings,
+        )
+        .await;
+        assert!(result.unwrap()["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("**senior**: weight 3"));
+
+        let (_, result) = process_mcp_request(
+            call("search_adoptable_pets", json!({ "species": "dogs" })),
+            &settings,
+        )
+        .await;
+        let result = result.unwrap();
+        let ranked = &result["structuredContent"]["results"];
+        assert_eq!(ranked.as_array().unwrap().len(), 2);
+        assert_eq!(ranked[0]["name"], "Gus");
+        assert_eq!(ranked[0]["score"], 100);
+        assert_eq!(ranked[1]["score"], 0);
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("1 more matched the search but not the required preferences"));
+
+        let (_, result) = process_mcp_request(
+            call(
+                "search_adoptable_pets",
+                json!({ "species": "dogs", "preferences": { "weights": { "senoir": 1 } } }),
+            ),
+            &settings,
+        )
+        .await;
+        assert!(result.unwrap_err()["message"]
+            .as_str()
+            .unwrap()
+            .contains("Did you mean: senior?"));
+
+        let (_, result) =
+            process_mcp_request(call("set_preferences", json!({ "clear": true })), &settings).await;
+        assert!(result.is_ok());
+        assert!(crate::preferences::session_preferences(&settings)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_adoptable_pets_widens_radius() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+        settings.widening.enabled = true;
+
+        let radius = |miles: u32| {
+            mockito::Matcher::PartialJson(json!({ "data": { "filterRadius": { "miles": miles } } }))
+        };
+        let _near = server
+            .mock("POST", "/public/animals/search/available/cats/haspic")
+            .match_body(radius(50))
+            .with_status(200)
+            .with_body(r#"{"data": []}"#)
+            .create_async()
+            .await;
+        let _far = server
+            .mock("POST", "/public/animals/search/available/cats/haspic")
+            .match_body(radius(100))
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "5", "attributes": {"name": "Tiger"}}]}"#)
+            .create_async()
+            .await;
+
+        let params = json!({ "arguments": { "species": "cats", "miles": 50 } });
+        let res = handle_tool_call("search_adoptable_pets", Some(params), &settings)
+            .await
+            .unwrap();
+        let text = res["content"][0]["text"].as_str().unwrap();
+        assert!(
+            text.starts_with("⚠️ No matches within 50 miles; showing results within 100 miles.")
+        );
+        assert!(text.contains("Tiger"));
+    }
+
+    #[tokio::test]
+    async fn test_search_adoptable_pets_flags_truncation() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+
+        let _mock = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "meta": {"count": 140, "pages": 6, "limit": 25},
+                    "data": [
+                        {"id": "1", "attributes": {"name": "Rex"}},
+                        {"id": "2", "attributes": {"name": "Biscuit"}}
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let params = json!({ "arguments": { "species": "dogs" } });
+        let res = handle_tool_call("search_adoptable_pets", Some(params), &settings)
+            .await
+            .unwrap();
+        assert_eq!(res["_meta"], json!({ "truncated": true, "total": 140 }));
+        assert!(res["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("_Showing 2 of 140 matches."));
+    }
+
     #[tokio::test]
     async fn test_handle_tool_call_get_random_pet() {
         let mut server = mockito::Server::new_async().await;
@@ -1003,6 +3022,17 @@ mod tests {
         assert!(res.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_handle_tool_call_get_server_info() {
+        let settings = get_test_settings();
+        let res = handle_tool_call("get_server_info", None, &settings)
+            .await
+            .unwrap();
+        let text = res["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("**Version:**"));
+        assert!(!text.contains("test_key"));
+    }
+
     #[tokio::test]
     async fn test_process_mcp_request_notifications() {
         let settings = get_test_settings();
@@ -1049,6 +3079,109 @@ mod tests {
         assert!(tools.len() < get_all_tool_definitions().len());
     }
 
+    #[test]
+    fn test_tool_schemas_compile() {
+        for tool in get_all_tool_definitions() {
+            assert!(
+                jsonschema::validator_for(&tool["inputSchema"]).is_ok(),
+                "{}",
+                tool["name"]
+            );
+        }
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_unknown_arguments() {
+        let mut settings = get_test_settings();
+        let arguments = json!({ "zip_code": "90210", "specie": "dogs" });
+        assert!(validate_arguments(&settings, "search_adoptable_pets", &arguments).is_ok());
+
+        settings.strict = true;
+        let message = validate_arguments(&settings, "search_adoptable_pets", &arguments)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("unexpected argument 'zip_code' (expected one of:"));
+        assert!(message.contains("unexpected argument 'specie' (did you mean 'species'?)"));
+        assert!(
+            validate_arguments(&settings, "list_animals", &json!({ "x": 1 }))
+                .unwrap_err()
+                .to_string()
+                .contains("this tool takes none")
+        );
+    }
+
+    #[test]
+    fn test_enum_arguments() {
+        let settings = get_test_settings();
+        let schema = find_tool(&settings, "search_adoptable_pets").unwrap()["inputSchema"].clone();
+        let mut arguments = json!({ "age": "senior", "sort_by": " newest", "sex": "Boy" });
+        normalize_enum_case(&schema, &mut arguments);
+        assert_eq!(
+            arguments,
+            json!({ "age": "Senior", "sort_by": "Newest", "sex": "Boy" })
+        );
+
+        let error = validate_arguments(&settings, "search_adoptable_pets", &arguments).unwrap_err();
+        assert_eq!(error.parameter(), Some("sex"));
+        let message = error.to_string();
+        assert!(message.contains("'sex': \"Boy\" is not one of"));
+        assert!(message.contains("Male"));
+    }
+
+    #[test]
+    fn test_numeric_arguments_clamped_or_rejected() {
+        let mut settings = get_test_settings();
+        let schema = find_tool(&settings, "match_me_a_pet").unwrap()["inputSchema"].clone();
+        let mut arguments = json!({ "miles": 5000, "hours_away": -2, "limit": 3 });
+        let notes = clamp_arguments(&schema, &mut arguments);
+        assert_eq!(
+            arguments,
+            json!({ "miles": 1000, "hours_away": 0, "limit": 3 })
+        );
+        assert_eq!(
+            notes,
+            vec![
+                "'hours_away' -2 clamped to 0",
+                "'miles' 5000 clamped to 1000"
+            ]
+        );
+
+        settings.strict = true;
+        let error = validate_arguments(&settings, "search_adoptable_pets", &json!({ "miles": 0 }))
+            .unwrap_err();
+        assert_eq!(error.parameter(), Some("miles"));
+        assert!(error
+            .to_string()
+            .contains("'miles': 0 is less than the minimum of 1"));
+    }
+
+    #[tokio::test]
+    async fn test_process_mcp_request_tools_call_invalid_arguments() {
+        let settings = get_test_settings();
+        let call = |arguments: Value| JsonRpcRequest {
+            _jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/call".to_string(),
+            params: Some(json!({ "name": "get_animal_details", "arguments": arguments })),
+        };
+
+        // A typo'd field no longer silently becomes animal "0"
+        let (_, result) = process_mcp_request(call(json!({ "animalid": "123" })), &settings).await;
+        let error = result.unwrap_err();
+        assert_eq!(error["code"], -32602);
+        assert!(error["message"].as_str().unwrap().contains("animal_id"));
+        assert_eq!(error["data"]["kind"], "validation");
+        assert_eq!(error["data"]["parameter"], "animal_id");
+
+        let (_, result) = process_mcp_request(call(json!({ "animal_id": 123 })), &settings).await;
+        let error = result.unwrap_err();
+        assert_eq!(error["code"], -32602);
+        assert!(error["message"].as_str().unwrap().starts_with(
+            "Validation Error: Invalid arguments for 'get_animal_details': 'animal_id':"
+        ));
+        assert_eq!(error["data"]["parameter"], "animal_id");
+    }
+
     #[tokio::test]
     async fn test_process_mcp_request_tools_call_missing_params() {
         let settings = get_test_settings();
@@ -1135,4 +3268,401 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(result.unwrap_err()["code"], -32601);
     }
+
+    #[tokio::test]
+    async fn test_preset_tools() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+        settings.presets.insert(
+            "senior-cats".to_string(),
+            crate::config::SearchPreset {
+                description: Some("Senior cats".to_string()),
+                tool: true,
+                args: ToolArgs {
+                    species: Some("cats".to_string()),
+                    age: Some("Senior".to_string()),
+                    ..Default::default()
+                },
+            },
+        );
+        settings
+            .presets
+            .insert("hidden".to_string(), crate::config::SearchPreset::default());
+
+        let req = JsonRpcRequest {
+            _jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/list".to_string(),
+            params: None,
+        };
+        let (_, result) = process_mcp_request(req, &settings).await;
+        let res = result.unwrap();
+        let tools = res["tools"].as_array().unwrap();
+        assert!(tools.iter().any(|t| t["name"] == "search_senior_cats"));
+        assert!(!tools.iter().any(|t| t["name"] == "search_hidden"));
+
+        let _mock = server
+            .mock("POST", "/public/animals/search/available/cats/haspic")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Whiskers"}}]}"#)
+            .create_async()
+            .await;
+
+        let params = json!({ "arguments": { "postal_code": "12345" } });
+        let res = handle_tool_call("search_senior_cats", Some(params), &settings)
+            .await
+            .unwrap();
+        assert!(res["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("Whiskers"));
+
+        let res = handle_tool_call("search_hidden", None, &settings).await;
+        assert!(matches!(res, Err(AppError::NotFound)));
+    }
+
+    #[test]
+    fn test_preset_tool_names_must_be_unique() {
+        let mut settings = get_test_settings();
+        let tool = crate::config::SearchPreset {
+            tool: true,
+            ..Default::default()
+        };
+        settings
+            .presets
+            .insert("senior-cats".to_string(), tool.clone());
+        assert!(check_tool_names(&settings).is_ok());
+
+        settings
+            .presets
+            .insert("senior_cats".to_string(), tool.clone());
+        match check_tool_names(&settings) {
+            Err(AppError::ConfigError(msg)) => assert!(msg.contains("'senior_cats'")),
+            other => panic!("Expected ConfigError, got {:?}", other),
+        }
+
+        settings.presets.remove("senior_cats");
+        settings.presets.insert("adoptable_pets".to_string(), tool);
+        match check_tool_names(&settings) {
+            Err(AppError::ConfigError(msg)) => {
+                assert!(msg.contains("'adoptable_pets'"));
+                assert!(msg.contains("search_adoptable_pets"));
+            }
+            other => panic!("Expected ConfigError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_plugin_tool_names_must_be_unique() {
+        let mut settings = get_test_settings();
+        settings.presets.insert(
+            "senior-cats".to_string(),
+            crate::config::SearchPreset {
+                tool: true,
+                ..Default::default()
+            },
+        );
+        let plugin = crate::config::PluginTool {
+            command: Some("true".to_string()),
+            ..Default::default()
+        };
+        settings
+            .plugins
+            .insert("foster_lookup".to_string(), plugin.clone());
+        assert!(check_tool_names(&settings).is_ok());
+
+        for name in ["get_animal_details", "search_senior_cats"] {
+            settings.plugins.insert(name.to_string(), plugin.clone());
+            match check_tool_names(&settings) {
+                Err(AppError::ConfigError(msg)) => assert!(msg.contains(name)),
+                other => panic!("Expected ConfigError, got {:?}", other),
+            }
+            settings.plugins.remove(name);
+        }
+    }
+
+    #[test]
+    fn test_private_tools_require_config() {
+        let mut settings = get_test_settings();
+        assert!(get_private_tool_definitions(&settings).is_empty());
+
+        settings.private_api = Some(crate::config::PrivateApiConfig {
+            username: "staff".to_string(),
+            password: "secret".to_string(),
+            login_path: "/login".to_string(),
+        });
+        let tools = get_private_tool_definitions(&settings);
+        assert!(tools
+            .iter()
+            .all(|t| t["name"].as_str().unwrap().starts_with("private_")));
+        assert_eq!(tools.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_plugin_tools() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/foster")
+            .with_status(200)
+            .with_body(r#"{"content": [{"type": "text", "text": "Foster: Jane D."}]}"#)
+            .create_async()
+            .await;
+        let mut settings = get_test_settings();
+        settings.plugins.insert(
+            "foster_lookup".to_string(),
+            crate::config::PluginTool {
+                url: Some(format!("{}/foster", server.url())),
+                ..Default::default()
+            },
+        );
+
+        let req = JsonRpcRequest {
+            _jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: "tools/list".to_string(),
+            params: None,
+        };
+        let (_, result) = process_mcp_request(req, &settings).await;
+        let res = result.unwrap();
+        assert!(res["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|t| t["name"] == "foster_lookup"));
+
+        let params = json!({ "arguments": { "animal_id": "42" } });
+        let res = handle_tool_call("foster_lookup", Some(params), &settings)
+            .await
+            .unwrap();
+        assert_eq!(res["content"][0]["text"], "Foster: Jane D.");
+    }
+
+    #[tokio::test]
+    async fn test_handle_tool_call_saved_searches() {
+        let mut settings = get_test_settings();
+        settings.data_dir = std::env::temp_dir().join("rescue-groups-mcp-mcp-alerts");
+        let _ = std::fs::remove_dir_all(&settings.data_dir);
+
+        let params = json!({ "arguments": { "name": "cats", "species": "cats" } });
+        let res = handle_tool_call("save_search", Some(params), &settings).await;
+        assert!(res.is_ok());
+
+        let res = handle_tool_call("list_saved_searches", None, &settings)
+            .await
+            .unwrap();
+        assert!(res["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("species=cats"));
+
+        let params = json!({ "arguments": { "name": "cats" } });
+        let res = handle_tool_call("delete_saved_search", Some(params), &settings).await;
+        assert!(res.is_ok());
+
+        let res = handle_tool_call("check_saved_searches", None, &settings)
+            .await
+            .unwrap();
+        assert!(res["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("No new matches"));
+
+        // Name is required
+        let res = handle_tool_call("save_search", None, &settings).await;
+        assert!(res.is_err());
+        std::fs::remove_dir_all(&settings.data_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_handle_tool_call_notes() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+        settings.data_dir = std::env::temp_dir().join("rescue-groups-mcp-mcp-notes");
+        let _ = std::fs::remove_dir_all(&settings.data_dir);
+
+        let _mock = server
+            .mock("GET", "/public/animals/7?include=orgs,locations")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "7", "attributes": {"name": "Biscuit"}}]}"#)
+            .create_async()
+            .await;
+
+        let params =
+            json!({ "arguments": { "animal_id": "7", "note": "meet-and-greet Saturday" } });
+        handle_tool_call("add_note", Some(params), &settings)
+            .await
+            .unwrap();
+
+        let params = json!({ "arguments": { "animal_id": "7" } });
+        let res = handle_tool_call("get_notes", Some(params.clone()), &settings)
+            .await
+            .unwrap();
+        assert!(res["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("meet-and-greet Saturday"));
+
+        // Notes are appended to the animal details
+        let res = handle_tool_call("get_animal_details", Some(params), &settings)
+            .await
+            .unwrap();
+        let text = res["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("Biscuit"));
+        assert!(text.contains("### Notes (1)"));
+
+        std::fs::remove_dir_all(&settings.data_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_handle_tool_call_favorites() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+        settings.data_dir = std::env::temp_dir().join("rescue-groups-mcp-mcp-favorites");
+        let _ = std::fs::remove_dir_all(&settings.data_dir);
+
+        let _mock = server
+            .mock("GET", "/public/animals/7")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "7", "attributes": {"name": "Biscuit"}}]}"#)
+            .create_async()
+            .await;
+
+        let params = json!({ "arguments": { "animal_id": "7", "note": "shortlist" } });
+        let res = handle_tool_call("save_favorite", Some(params), &settings)
+            .await
+            .unwrap();
+        assert!(res["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("Saved Biscuit"));
+
+        let res = handle_tool_call("list_favorites", None, &settings)
+            .await
+            .unwrap();
+        assert!(res["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("**Animal IDs:** 7"));
+
+        let params = json!({ "arguments": { "animal_id": "7" } });
+        assert!(
+            handle_tool_call("remove_favorite", Some(params.clone()), &settings)
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            handle_tool_call("remove_favorite", Some(params), &settings).await,
+            Err(AppError::NotFound)
+        ));
+        std::fs::remove_dir_all(&settings.data_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_handle_tool_call_semantic_search() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+
+        let _mock = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [
+                    {"id": "mcp-sem-1", "attributes": {"name": "Zoom", "descriptionText": "Loves running marathons"}},
+                    {"id": "mcp-sem-2", "attributes": {"name": "Snooze", "descriptionText": "Quiet couch potato"}}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let params = json!({ "arguments": { "query": "quiet couch potato", "limit": 1 } });
+        let res = handle_tool_call("semantic_search_pets", Some(params), &settings)
+            .await
+            .unwrap();
+        let text = res["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("Snooze"));
+        assert!(!text.contains("Zoom"));
+
+        // query is required
+        let res = handle_tool_call("semantic_search_pets", None, &settings).await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_tool_call_match_me_a_pet() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+
+        let _mock = server
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "data": { "filters": [
+                    { "fieldName": "animals.isGoodWithChildren", "operation": "equal", "criteria": "Yes" }
+                ]}
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"data": [
+                    {"id": "m1", "attributes": {"name": "Zoom", "energyLevel": "High"}},
+                    {"id": "m2", "attributes": {"name": "Snooze", "energyLevel": "Low"}}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let params = json!({ "arguments": { "has_kids": true, "activity_level": "low" } });
+        let res = handle_tool_call("match_me_a_pet", Some(params), &settings)
+            .await
+            .unwrap();
+        let text = res["content"][0]["text"].as_str().unwrap();
+        assert!(text.find("Snooze").unwrap() < text.find("Zoom").unwrap());
+        assert!(text.contains("100% match"));
+    }
+
+    #[tokio::test]
+    async fn test_handle_tool_call_parse_search_query() {
+        let mut server = mockito::Server::new_async().await;
+        let mut settings = get_test_settings();
+        settings.base_url = server.url();
+
+        let _species = server
+            .mock("GET", "/public/animals/species")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "3", "attributes": {"singular": "Cat", "plural": "Cats"}}]}"#,
+            )
+            .create_async()
+            .await;
+        let _breeds = server
+            .mock("GET", "/public/animals/species/3/breeds")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Siamese"}}]}"#)
+            .create_async()
+            .await;
+        // Colors, patterns and qualities are optional vocabularies
+        let _metadata = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(
+                    "^/public/animals/(species/3/)?(colors|patterns|qualities)$".to_string(),
+                ),
+            )
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let params = json!({ "arguments": { "query": "senior siamese cat near 90210" } });
+        let res = handle_tool_call("parse_search_query", Some(params), &settings)
+            .await
+            .unwrap();
+        let text = res["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("\"species\": \"cats\""));
+        assert!(text.contains("\"breeds\": \"Siamese\""));
+        assert!(text.contains("\"age\": \"Senior\""));
+        assert!(text.contains("\"postal_code\": \"90210\""));
+    }
 }
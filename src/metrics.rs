@@ -0,0 +1,372 @@
+use crate::alerts::now_secs;
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::store::{self, load_json};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::warn;
+
+/// Aggregated calls of one tool. Only argument names are kept, never their
+/// values, so nothing identifying (postal codes, animal IDs) is stored.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct ToolStats {
+    pub calls: u64,
+    pub errors: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+    /// How often each argument was set
+    #[serde(default)]
+    pub arguments: BTreeMap<String, u64>,
+    /// Failures by error kind (e.g. "not_found")
+    #[serde(default)]
+    pub error_kinds: BTreeMap<String, u64>,
+}
+
+/// Latencies kept per upstream endpoint for its percentiles.
+const LATENCY_SAMPLES: usize = 200;
+
+/// Upstream requests to one endpoint pattern, with the latencies of the
+/// most recent ones.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct EndpointStats {
+    pub requests: u64,
+    pub errors: u64,
+    pub max_ms: u64,
+    /// The last `LATENCY_SAMPLES` latencies, oldest first
+    #[serde(default)]
+    pub recent_ms: VecDeque<u64>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct UsageStats {
+    /// Unix timestamp of the first recorded call
+    pub since: Option<u64>,
+    #[serde(default)]
+    pub tools: BTreeMap<String, ToolStats>,
+    /// Upstream requests by endpoint pattern (e.g. "GET /public/orgs/{id}")
+    #[serde(default)]
+    pub endpoints: BTreeMap<String, EndpointStats>,
+}
+
+fn usage_path(settings: &Settings) -> PathBuf {
+    settings.data_dir.join("usage.json")
+}
+
+/// Adds one tool call to the local usage file when `[metrics] enabled` is
+/// set. Failing to record is logged and never fails the call itself.
+pub async fn record_tool_call(
+    settings: &Settings,
+    tool: &str,
+    params: Option<&Value>,
+    elapsed: Duration,
+    result: Result<(), &AppError>,
+) {
+    if !settings.metrics.enabled {
+        return;
+    }
+    let recorded = store::update(&usage_path(settings), |usage: &mut UsageStats| {
+        add_tool_call(usage, tool, params, elapsed, result);
+        Ok(())
+    })
+    .await;
+    if let Err(e) = recorded {
+        warn!("Failed to record usage metrics: {}", e);
+    }
+}
+
+fn add_tool_call(
+    usage: &mut UsageStats,
+    tool: &str,
+    params: Option<&Value>,
+    elapsed: Duration,
+    result: Result<(), &AppError>,
+) {
+    usage.since.get_or_insert_with(now_secs);
+
+    let stats = usage.tools.entry(tool.to_string()).or_default();
+    let ms = elapsed.as_millis() as u64;
+    stats.calls += 1;
+    stats.total_ms += ms;
+    stats.max_ms = stats.max_ms.max(ms);
+    if let Err(e) = result {
+        stats.errors += 1;
+        *stats.error_kinds.entry(e.kind().to_string()).or_default() += 1;
+    }
+
+    let arguments = params
+        .and_then(|p| p.get("arguments"))
+        .and_then(Value::as_object);
+    for (name, value) in arguments.into_iter().flatten() {
+        if !value.is_null() {
+            *stats.arguments.entry(name.clone()).or_default() += 1;
+        }
+    }
+}
+
+/// Adds one upstream request to the usage file when `[metrics] enabled` is
+/// set. `elapsed` excludes waiting for the rate limit.
+pub async fn record_upstream(
+    settings: &Settings,
+    method: &str,
+    url: &str,
+    elapsed: Duration,
+    ok: bool,
+) {
+    if !settings.metrics.enabled {
+        return;
+    }
+    let result = store::update(&usage_path(settings), |usage: &mut UsageStats| {
+        usage.since.get_or_insert_with(now_secs);
+        let stats = usage
+            .endpoints
+            .entry(endpoint_pattern(&settings.base_url, method, url))
+            .or_default();
+        let ms = elapsed.as_millis() as u64;
+        stats.requests += 1;
+        stats.max_ms = stats.max_ms.max(ms);
+        if !ok {
+            stats.errors += 1;
+        }
+        stats.recent_ms.push_back(ms);
+        if stats.recent_ms.len() > LATENCY_SAMPLES {
+            stats.recent_ms.pop_front();
+        }
+        Ok(())
+    })
+    .await;
+    if let Err(e) = result {
+        warn!("Failed to record upstream latency: {}", e);
+    }
+}
+
+/// `url` with IDs and species replaced by placeholders, so requests to the
+/// same endpoint are counted together: "POST
+/// /public/animals/search/available/{species}/haspic".
+pub fn endpoint_pattern(base_url: &str, method: &str, url: &str) -> String {
+    let path = url.strip_prefix(base_url).unwrap_or(url);
+    let path = path.split('?').next().unwrap_or(path);
+    let segments: Vec<&str> = path.split('/').collect();
+    let pattern: Vec<&str> = segments
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| {
+            let before = |n: usize| i.checked_sub(n).map(|j| segments[j]);
+            if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+                "{id}"
+            } else if matches!(before(1), Some("available" | "adopted"))
+                && before(2) == Some("search")
+            {
+                "{species}"
+            } else {
+                segment
+            }
+        })
+        .collect();
+    format!("{} {}", method, pattern.join("/"))
+}
+
+/// The latency below which `percent` of `samples` fall (nearest rank).
+fn percentile(sorted: &[u64], percent: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Per-tool call counts, error rates and latencies, busiest tool first.
+pub fn usage_report(settings: &Settings) -> Result<Value, AppError> {
+    let usage: UsageStats = load_json(&usage_path(settings))?;
+    let mut tools: Vec<(&String, &ToolStats)> = usage.tools.iter().collect();
+    tools.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.calls));
+
+    let tools: Vec<Value> = tools
+        .into_iter()
+        .map(|(name, stats)| {
+            let calls = stats.calls.max(1);
+            json!({
+                "name": name,
+                "calls": stats.calls,
+                "errors": stats.errors,
+                "error_rate": stats.errors as f64 / calls as f64,
+                "avg_ms": stats.total_ms / calls,
+                "max_ms": stats.max_ms,
+                "arguments": stats.arguments,
+                "error_kinds": stats.error_kinds,
+            })
+        })
+        .collect();
+
+    let mut endpoints: Vec<Value> = usage
+        .endpoints
+        .iter()
+        .map(|(endpoint, stats)| {
+            let mut sorted: Vec<u64> = stats.recent_ms.iter().copied().collect();
+            sorted.sort_unstable();
+            json!({
+                "endpoint": endpoint,
+                "requests": stats.requests,
+                "errors": stats.errors,
+                "p50_ms": percentile(&sorted, 50),
+                "p90_ms": percentile(&sorted, 90),
+                "p99_ms": percentile(&sorted, 99),
+                "max_ms": stats.max_ms,
+            })
+        })
+        .collect();
+    // Slowest first, as the ones worth a longer timeout or cache TTL
+    endpoints.sort_by_key(|e| std::cmp::Reverse(e["p90_ms"].as_u64()));
+
+    Ok(json!({
+        "enabled": settings.metrics.enabled,
+        "since": usage.since,
+        "tools": tools,
+        "endpoints": endpoints,
+    }))
+}
+
+/// Deletes the recorded usage.
+pub async fn reset_usage(settings: &Settings) -> Result<(), AppError> {
+    store::update(&usage_path(settings), |usage: &mut UsageStats| {
+        *usage = UsageStats::default();
+        Ok(())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MetricsConfig;
+
+    fn get_test_settings(data_dir: PathBuf, enabled: bool) -> Settings {
+        Settings {
+            metrics: MetricsConfig { enabled },
+            ..Settings::for_test("http://unused", data_dir)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_report() {
+        let dir = std::env::temp_dir().join("rescue-groups-mcp-metrics");
+        let _ = std::fs::remove_dir_all(&dir);
+        let settings = get_test_settings(dir.clone(), true);
+
+        let params = json!({
+            "name": "search_adoptable_pets",
+            "arguments": {"postal_code": "78704", "species": "dogs", "age": null}
+        });
+        record_tool_call(
+            &settings,
+            "search_adoptable_pets",
+            Some(&params),
+            Duration::from_millis(100),
+            Ok(()),
+        )
+        .await;
+        record_tool_call(
+            &settings,
+            "search_adoptable_pets",
+            Some(&params),
+            Duration::from_millis(300),
+            Err(&AppError::NotFound),
+        )
+        .await;
+        record_tool_call(&settings, "list_species", None, Duration::ZERO, Ok(())).await;
+
+        let report = usage_report(&settings).unwrap();
+        let search = &report["tools"][0];
+        assert_eq!(search["name"], "search_adoptable_pets");
+        assert_eq!(search["calls"], 2);
+        assert_eq!(search["error_rate"], 0.5);
+        assert_eq!(search["avg_ms"], 200);
+        assert_eq!(search["max_ms"], 300);
+        assert_eq!(search["arguments"], json!({"postal_code": 2, "species": 2}));
+        assert_eq!(search["error_kinds"], json!({"not_found": 1}));
+        assert_eq!(report["tools"][1]["name"], "list_species");
+
+        // Only argument names are stored
+        let stored = std::fs::read_to_string(dir.join("usage.json")).unwrap();
+        assert!(!stored.contains("78704"));
+
+        reset_usage(&settings).await.unwrap();
+        assert_eq!(usage_report(&settings).unwrap()["tools"], json!([]));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upstream_latency_percentiles() {
+        let dir = std::env::temp_dir().join("rescue-groups-mcp-metrics-upstream");
+        let _ = std::fs::remove_dir_all(&dir);
+        let settings = get_test_settings(dir.clone(), true);
+
+        for ms in 1..=10 {
+            let url = format!("http://unused/public/orgs/{}", ms);
+            record_upstream(
+                &settings,
+                "GET",
+                &url,
+                Duration::from_millis(ms * 10),
+                ms != 3,
+            )
+            .await;
+        }
+        let url = "http://unused/public/orgs/search";
+        record_upstream(&settings, "POST", url, Duration::from_millis(900), true).await;
+
+        let report = usage_report(&settings).unwrap();
+        let endpoints = report["endpoints"].as_array().unwrap();
+        assert_eq!(endpoints[0]["endpoint"], "POST /public/orgs/search");
+        let org = &endpoints[1];
+        assert_eq!(org["endpoint"], "GET /public/orgs/{id}");
+        assert_eq!(org["requests"], 10);
+        assert_eq!(org["errors"], 1);
+        assert_eq!(org["p50_ms"], 50);
+        assert_eq!(org["p90_ms"], 90);
+        assert_eq!(org["p99_ms"], 100);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_endpoint_pattern() {
+        let base = "https://api.rescuegroups.org/v5";
+        assert_eq!(
+            endpoint_pattern(
+                base,
+                "POST",
+                "https://api.rescuegroups.org/v5/public/animals/search/available/cats/haspic?sort=random"
+            ),
+            "POST /public/animals/search/available/{species}/haspic"
+        );
+        assert_eq!(
+            endpoint_pattern(
+                base,
+                "POST",
+                &format!("{}/public/orgs/42/animals/search/available", base)
+            ),
+            "POST /public/orgs/{id}/animals/search/available"
+        );
+        assert_eq!(
+            endpoint_pattern(
+                base,
+                "GET",
+                &format!("{}/public/animals/species/3/colors", base)
+            ),
+            "GET /public/animals/species/{id}/colors"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disabled_records_nothing() {
+        let dir = std::env::temp_dir().join("rescue-groups-mcp-metrics-off");
+        let _ = std::fs::remove_dir_all(&dir);
+        let settings = get_test_settings(dir.clone(), false);
+
+        record_tool_call(&settings, "list_species", None, Duration::ZERO, Ok(())).await;
+        assert!(!dir.join("usage.json").exists());
+        assert_eq!(usage_report(&settings).unwrap()["enabled"], false);
+    }
+}
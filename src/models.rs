@@ -0,0 +1,150 @@
+use crate::error::AppError;
+use crate::fmt::extract_single_item;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A JSON:API resource as returned by the RescueGroups API.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct Resource<A> {
+    pub id: String,
+    #[serde(default)]
+    pub attributes: A,
+}
+
+pub type Animal = Resource<AnimalAttributes>;
+pub type Organization = Resource<OrgAttributes>;
+pub type Breed = Resource<BreedAttributes>;
+pub type Species = Resource<SpeciesAttributes>;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AnimalAttributes {
+    pub name: Option<String>,
+    pub breed_string: Option<String>,
+    pub sex: Option<String>,
+    pub age_group: Option<String>,
+    pub size_group: Option<String>,
+    pub description_text: Option<String>,
+    pub url: Option<String>,
+    pub color_details: Option<String>,
+    pub pattern_details: Option<String>,
+    /// "Yes" / "No" as reported by the API
+    pub is_good_with_children: Option<String>,
+    pub is_good_with_dogs: Option<String>,
+    pub is_good_with_cats: Option<String>,
+    pub is_house_trained: Option<String>,
+    pub is_special_needs: Option<String>,
+    pub orgs_animals_pictures: Vec<Picture>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Picture {
+    pub url_secure_fullsize: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OrgAttributes {
+    pub name: Option<String>,
+    pub about: Option<String>,
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    #[serde(rename = "postalcode")]
+    pub postal_code: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub url: Option<String>,
+    pub facebook_url: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct BreedAttributes {
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct SpeciesAttributes {
+    pub singular: Option<String>,
+    pub plural: Option<String>,
+}
+
+/// Parses the `data` array of an API response into typed resources.
+pub fn parse_list<A: DeserializeOwned + Default>(
+    response: &Value,
+) -> Result<Vec<Resource<A>>, AppError> {
+    match response.get("data") {
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| Ok(serde_json::from_value(item.clone())?))
+            .collect(),
+        Some(Value::Object(_)) => Ok(vec![parse_single(response)?]),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Parses a single-resource response (where `data` may also be a
+/// one-element array).
+pub fn parse_single<A: DeserializeOwned + Default>(
+    response: &Value,
+) -> Result<Resource<A>, AppError> {
+    let item = response
+        .get("data")
+        .and_then(extract_single_item)
+        .ok_or(AppError::NotFound)?;
+    Ok(serde_json::from_value(item.clone())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_animals() {
+        let response = json!({
+            "data": [{
+                "id": "1",
+                "type": "animals",
+                "attributes": {
+                    "name": "Buddy",
+                    "breedString": "Beagle",
+                    "isGoodWithChildren": "Yes",
+                    "orgsAnimalsPictures": [{"urlSecureFullsize": "http://buddy.jpg"}]
+                }
+            }]
+        });
+        let animals: Vec<Animal> = parse_list(&response).unwrap();
+        assert_eq!(animals[0].id, "1");
+        assert_eq!(animals[0].attributes.name.as_deref(), Some("Buddy"));
+        assert_eq!(
+            animals[0].attributes.is_good_with_children.as_deref(),
+            Some("Yes")
+        );
+        assert_eq!(
+            animals[0].attributes.orgs_animals_pictures[0]
+                .url_secure_fullsize
+                .as_deref(),
+            Some("http://buddy.jpg")
+        );
+    }
+
+    #[test]
+    fn test_parse_single_org() {
+        let response = json!({
+            "data": {"id": "9", "attributes": {"name": "Shelter", "postalcode": "90210"}}
+        });
+        let org: Organization = parse_single(&response).unwrap();
+        assert_eq!(org.attributes.postal_code.as_deref(), Some("90210"));
+
+        let orgs: Vec<Organization> = parse_list(&response).unwrap();
+        assert_eq!(orgs.len(), 1);
+
+        let missing: Result<Organization, AppError> = parse_single(&json!({}));
+        assert!(matches!(missing, Err(AppError::NotFound)));
+    }
+}
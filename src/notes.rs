@@ -0,0 +1,108 @@
+use crate::alerts::now_secs;
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::store::{self, load_json};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct NotesFile {
+    /// Notes by animal ID, oldest first
+    #[serde(default)]
+    pub notes: BTreeMap<String, Vec<Note>>,
+}
+
+/// A free-text journal entry about an animal ("emailed 3/4, meet-and-greet
+/// Saturday").
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Note {
+    pub text: String,
+    /// Unix timestamp of when the note was added
+    pub added_at: u64,
+}
+
+fn notes_path(settings: &Settings) -> PathBuf {
+    settings.data_dir.join("notes.json")
+}
+
+/// Appends a note to an animal's journal. The animal is not looked up, so
+/// notes can be kept on animals that are no longer (or not yet) listed.
+pub async fn add_note(settings: &Settings, animal_id: &str, text: &str) -> Result<Note, AppError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::invalid_argument("note", "A note needs some text"));
+    }
+
+    store::update(&notes_path(settings), |file: &mut NotesFile| {
+        let note = Note {
+            text: text.to_string(),
+            added_at: now_secs(),
+        };
+        file.notes
+            .entry(animal_id.to_string())
+            .or_default()
+            .push(note.clone());
+        Ok(note)
+    })
+    .await
+}
+
+pub async fn get_notes(settings: &Settings, animal_id: &str) -> Result<Vec<Note>, AppError> {
+    let file: NotesFile = load_json(&notes_path(settings))?;
+    Ok(file.notes.get(animal_id).cloned().unwrap_or_default())
+}
+
+/// Adds the stored notes of `animal_id` to an animal details response as
+/// `notes`, if there are any.
+pub async fn attach_notes(
+    settings: &Settings,
+    animal_id: &str,
+    data: &mut Value,
+) -> Result<(), AppError> {
+    let notes = get_notes(settings, animal_id).await?;
+    if !notes.is_empty() {
+        if let Some(object) = data.as_object_mut() {
+            object.insert("notes".to_string(), json!(notes));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_settings(data_dir: PathBuf) -> Settings {
+        Settings::for_test("http://unused", data_dir)
+    }
+
+    #[tokio::test]
+    async fn test_add_and_attach_notes() {
+        let dir = std::env::temp_dir().join("rescue-groups-mcp-notes");
+        let _ = std::fs::remove_dir_all(&dir);
+        let settings = get_test_settings(dir.clone());
+
+        add_note(&settings, "123", "emailed 3/4").await.unwrap();
+        add_note(&settings, "123", " meet-and-greet Saturday ")
+            .await
+            .unwrap();
+        assert!(add_note(&settings, "123", "  ").await.is_err());
+
+        let notes = get_notes(&settings, "123").await.unwrap();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[1].text, "meet-and-greet Saturday");
+        assert!(get_notes(&settings, "456").await.unwrap().is_empty());
+
+        let mut details = json!({"data": [{"id": "123"}]});
+        attach_notes(&settings, "123", &mut details).await.unwrap();
+        assert_eq!(details["notes"][0]["text"], "emailed 3/4");
+
+        let mut details = json!({"data": [{"id": "456"}]});
+        attach_notes(&settings, "456", &mut details).await.unwrap();
+        assert!(details.get("notes").is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,441 @@
+use crate::alerts::{now_secs, Alert, NotificationChannel};
+use crate::config::{EmailConfig, SmtpTls, WebhookConfig, WebhookFormat};
+use crate::error::AppError;
+use crate::fmt::{format_alerts, format_alerts_html};
+use crate::store::{load_json, save_json};
+use futures::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+pub const SIGNATURE_HEADER: &str = "X-Signature-256";
+
+/// POSTs alerts to a webhook URL, retrying failed deliveries with
+/// exponential backoff.
+pub struct WebhookChannel {
+    config: WebhookConfig,
+    client: reqwest::Client,
+    retry_delay: Duration,
+}
+
+impl WebhookChannel {
+    pub fn new(config: WebhookConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    async fn post(&self, body: &str) -> Result<(), AppError> {
+        let mut request = self
+            .client
+            .post(&self.config.url)
+            .header("Content-Type", "application/json")
+            .timeout(Duration::from_secs(10))
+            .body(body.to_string());
+        if let Some(secret) = &self.config.secret {
+            request = request.header(SIGNATURE_HEADER, sign_payload(secret, body));
+        }
+
+        let response = request.send().await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(AppError::Upstream {
+                status: response.status().as_u16(),
+                message: format!("Webhook returned status {}", response.status()),
+            })
+        }
+    }
+}
+
+impl NotificationChannel for WebhookChannel {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn notify<'a>(&'a self, alerts: &'a [Alert]) -> BoxFuture<'a, Result<(), AppError>> {
+        Box::pin(async move {
+            let body = webhook_payload(self.config.format, alerts).to_string();
+            let mut delay = self.retry_delay;
+            let mut attempt = 0;
+            loop {
+                match self.post(&body).await {
+                    Ok(()) => return Ok(()),
+                    Err(e) if attempt < self.config.max_retries => {
+                        attempt += 1;
+                        warn!(
+                            "Webhook delivery failed (attempt {}/{}): {}",
+                            attempt,
+                            self.config.max_retries + 1,
+                            e
+                        );
+                        tokio::time::sleep(delay).await;
+                        delay *= 2;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        })
+    }
+}
+
+/// Hex-encoded HMAC-SHA256 of `body`, prefixed with `sha256=` (the same
+/// scheme GitHub uses), so receivers can verify the payload came from us.
+pub fn sign_payload(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn alert_line(alert: &Alert, link: impl Fn(&str, &str) -> String) -> String {
+    let animal = &alert.animal;
+    let name = animal["name"].as_str().unwrap_or("Unknown");
+    let url = animal["url"].as_str().unwrap_or("");
+    let title = if url.is_empty() {
+        name.to_string()
+    } else {
+        link(name, url)
+    };
+    format!(
+        "New match for \"{}\": {} ({}, {} {})",
+        alert.search_name,
+        title,
+        animal["breed"].as_str().unwrap_or("Mix"),
+        animal["age"].as_str().unwrap_or("Unknown age"),
+        animal["sex"].as_str().unwrap_or("")
+    )
+    .trim_end()
+    .to_string()
+}
+
+pub fn webhook_payload(format: WebhookFormat, alerts: &[Alert]) -> Value {
+    match format {
+        WebhookFormat::Json => json!({
+            "event": "new_matches",
+            "alerts": alerts,
+        }),
+        WebhookFormat::Slack => {
+            let lines: Vec<String> = alerts
+                .iter()
+                .map(|a| alert_line(a, |name, url| format!("<{}|{}>", url, name)))
+                .collect();
+            json!({ "text": lines.join("\n") })
+        }
+        WebhookFormat::Discord => {
+            let lines: Vec<String> = alerts
+                .iter()
+                .map(|a| alert_line(a, |name, url| format!("[{}]({})", name, url)))
+                .collect();
+            json!({ "content": lines.join("\n") })
+        }
+    }
+}
+
+/// Alerts waiting for the next email digest.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct DigestQueue {
+    #[serde(default)]
+    pub pending: Vec<Alert>,
+    /// Unix timestamp of the last digest sent
+    #[serde(default)]
+    pub last_sent: Option<u64>,
+}
+
+impl DigestQueue {
+    pub fn is_due(&self, now: u64, period: Duration) -> bool {
+        !self.pending.is_empty()
+            && self
+                .last_sent
+                .is_none_or(|last| now.saturating_sub(last) >= period.as_secs())
+    }
+}
+
+/// Collects alerts and emails them as a daily or weekly digest over SMTP.
+/// Pending alerts are persisted so they survive restarts between digests.
+pub struct EmailChannel {
+    config: EmailConfig,
+    queue_path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl EmailChannel {
+    pub fn new(config: EmailConfig, queue_path: PathBuf) -> Self {
+        Self {
+            config,
+            queue_path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn build_message(&self, alerts: &[Alert]) -> Result<Message, AppError> {
+        let data = json!({ "data": alerts });
+        let mut builder = Message::builder()
+            .from(parse_mailbox(&self.config.from)?)
+            .subject(format!(
+                "Rescue Groups: {} new match{}",
+                alerts.len(),
+                if alerts.len() == 1 { "" } else { "es" }
+            ));
+        for to in &self.config.to {
+            builder = builder.to(parse_mailbox(to)?);
+        }
+        builder
+            .multipart(MultiPart::alternative_plain_html(
+                format_alerts(&data)?,
+                format_alerts_html(&data)?,
+            ))
+            .map_err(|e| AppError::Internal(format!("Failed to build email: {}", e)))
+    }
+
+    fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>, AppError> {
+        let host = &self.config.smtp_host;
+        let mut builder = match self.config.tls {
+            SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+            SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                host,
+            )),
+        }
+        .map_err(|e| AppError::ConfigError(format!("Invalid SMTP settings: {}", e)))?;
+
+        if let Some(port) = self.config.smtp_port {
+            builder = builder.port(port);
+        }
+        if let (Some(username), Some(password)) = (&self.config.username, &self.config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        Ok(builder.build())
+    }
+
+    async fn send_if_due(&self, new_alerts: &[Alert]) -> Result<(), AppError> {
+        let _guard = self.lock.lock().await;
+        let mut queue: DigestQueue = load_json(&self.queue_path)?;
+        queue.pending.extend_from_slice(new_alerts);
+
+        let now = now_secs();
+        if queue.is_due(now, self.config.frequency.period()) {
+            let message = self.build_message(&queue.pending)?;
+            self.transport()?
+                .send(message)
+                .await
+                .map_err(|e| AppError::Internal(format!("SMTP error: {}", e)))?;
+            info!("Sent email digest with {} new matches", queue.pending.len());
+            queue.pending.clear();
+            queue.last_sent = Some(now);
+        }
+        save_json(&self.queue_path, &queue)
+    }
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox, AppError> {
+    address
+        .parse()
+        .map_err(|e| AppError::ConfigError(format!("Invalid email address '{}': {}", address, e)))
+}
+
+impl NotificationChannel for EmailChannel {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn notify<'a>(&'a self, alerts: &'a [Alert]) -> BoxFuture<'a, Result<(), AppError>> {
+        Box::pin(self.send_if_due(alerts))
+    }
+
+    fn flush(&self) -> BoxFuture<'_, Result<(), AppError>> {
+        Box::pin(self.send_if_due(&[]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_alerts() -> Vec<Alert> {
+        vec![Alert {
+            search_name: "dogs".to_string(),
+            animal: json!({
+                "id": "1",
+                "name": "Buddy",
+                "breed": "Beagle",
+                "age": "Young",
+                "sex": "Male",
+                "url": "http://buddy"
+            }),
+        }]
+    }
+
+    fn test_config(url: String) -> WebhookConfig {
+        WebhookConfig {
+            url,
+            format: WebhookFormat::Json,
+            secret: None,
+            max_retries: 2,
+        }
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign_payload("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_webhook_payload_formats() {
+        let alerts = test_alerts();
+
+        let payload = webhook_payload(WebhookFormat::Json, &alerts);
+        assert_eq!(payload["event"], "new_matches");
+        assert_eq!(payload["alerts"][0]["search_name"], "dogs");
+
+        let payload = webhook_payload(WebhookFormat::Slack, &alerts);
+        assert_eq!(
+            payload["text"],
+            "New match for \"dogs\": <http://buddy|Buddy> (Beagle, Young Male)"
+        );
+
+        let payload = webhook_payload(WebhookFormat::Discord, &alerts);
+        assert!(payload["content"]
+            .as_str()
+            .unwrap()
+            .contains("[Buddy](http://buddy)"));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_signed_delivery() {
+        let mut server = mockito::Server::new_async().await;
+        let alerts = test_alerts();
+        let body = webhook_payload(WebhookFormat::Json, &alerts).to_string();
+
+        let mock = server
+            .mock("POST", "/hook")
+            .match_header(SIGNATURE_HEADER, sign_payload("secret", &body).as_str())
+            .match_body(body.as_str())
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let mut config = test_config(format!("{}/hook", server.url()));
+        config.secret = Some("secret".to_string());
+        WebhookChannel::new(config).notify(&alerts).await.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retries_then_fails() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .with_status(500)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let channel = WebhookChannel::new(test_config(format!("{}/hook", server.url())))
+            .with_retry_delay(Duration::from_millis(1));
+        let res = channel.notify(&test_alerts()).await;
+        assert!(matches!(res, Err(AppError::Upstream { status: 500, .. })));
+        mock.assert_async().await;
+    }
+
+    fn test_email_config() -> EmailConfig {
+        EmailConfig {
+            smtp_host: "127.0.0.1".to_string(),
+            smtp_port: Some(1),
+            username: None,
+            password: None,
+            tls: SmtpTls::None,
+            from: "Alerts <alerts@example.com>".to_string(),
+            to: vec!["me@example.com".to_string()],
+            frequency: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_digest_queue_is_due() {
+        let day = Duration::from_secs(86400);
+        let mut queue = DigestQueue::default();
+        assert!(!queue.is_due(100_000, day));
+
+        queue.pending = test_alerts();
+        assert!(queue.is_due(100_000, day));
+        queue.last_sent = Some(50_000);
+        assert!(!queue.is_due(100_000, day));
+        assert!(queue.is_due(136_400, day));
+    }
+
+    #[test]
+    fn test_email_build_message() {
+        let channel = EmailChannel::new(test_email_config(), PathBuf::from("unused.json"));
+        let message = channel.build_message(&test_alerts()).unwrap();
+        let raw = String::from_utf8(message.formatted()).unwrap();
+        assert!(raw.contains("Subject: Rescue Groups: 1 new match"));
+        assert!(raw.contains("To: me@example.com"));
+        assert!(raw.contains("text/html"));
+
+        let mut config = test_email_config();
+        config.to = vec!["not an address".to_string()];
+        let channel = EmailChannel::new(config, PathBuf::from("unused.json"));
+        assert!(matches!(
+            channel.build_message(&test_alerts()),
+            Err(AppError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_email_queues_until_due() {
+        let dir = std::env::temp_dir().join("rescue-groups-mcp-email-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("email_digest.json");
+        save_json(
+            &path,
+            &DigestQueue {
+                pending: Vec::new(),
+                last_sent: Some(now_secs()),
+            },
+        )
+        .unwrap();
+
+        // A digest was just sent, so new alerts are only queued
+        let channel = EmailChannel::new(test_email_config(), path.clone());
+        channel.notify(&test_alerts()).await.unwrap();
+        channel.flush().await.unwrap();
+        let queue: DigestQueue = load_json(&path).unwrap();
+        assert_eq!(queue.pending.len(), 1);
+
+        // Once due, sending is attempted (and fails against the closed port),
+        // leaving the alerts queued for the next attempt
+        save_json(
+            &path,
+            &DigestQueue {
+                pending: queue.pending,
+                last_sent: None,
+            },
+        )
+        .unwrap();
+        assert!(channel.flush().await.is_err());
+        let queue: DigestQueue = load_json(&path).unwrap();
+        assert_eq!(queue.pending.len(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
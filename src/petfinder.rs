@@ -0,0 +1,712 @@
+use crate::cassette::exchange;
+use crate::cli::{OrgSearchArgs, SpeciesArgs, ToolArgs};
+use crate::client::{apply_search_defaults, sort_by_id};
+use crate::config::{PetfinderConfig, Settings};
+use crate::error::AppError;
+use crate::shared_cache;
+use crate::source::AnimalSource;
+use crate::trace;
+use crate::validate;
+use futures::future::BoxFuture;
+use moka::future::Cache;
+use serde_json::{json, Value};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Prefix added to Petfinder IDs so they can be routed back to this source
+/// (e.g. in `get_animal_details` or `compare_animals`).
+pub const ID_PREFIX: &str = "pf-";
+
+// Access tokens are valid for an hour; refresh a little early
+static TOKEN_CACHE: LazyLock<Cache<String, String>> = LazyLock::new(|| {
+    Cache::builder()
+        .max_capacity(4)
+        .time_to_live(Duration::from_secs(55 * 60))
+        .build()
+});
+
+/// Petfinder v2 API adapter. Authenticates with the OAuth client-credentials
+/// flow and normalizes responses into the RescueGroups shape.
+pub struct PetfinderSource {
+    config: PetfinderConfig,
+}
+
+impl PetfinderSource {
+    pub fn new(config: PetfinderConfig) -> Self {
+        Self { config }
+    }
+
+    async fn access_token(&self, settings: &Settings) -> Result<String, AppError> {
+        let key = format!("{}:{}", self.config.base_url, self.config.client_id);
+        if let Some(token) = TOKEN_CACHE.get(&key).await {
+            return Ok(token);
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(settings.timeout)
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to build client: {}", e)))?;
+        let response = client
+            .post(format!("{}/oauth2/token", self.config.base_url))
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.config.client_id.as_str()),
+                ("client_secret", self.config.client_secret.as_str()),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(AppError::ConfigError(format!(
+                "Petfinder authentication failed: {}",
+                response.status()
+            )));
+        }
+
+        let body: Value = response.json().await?;
+        let token = body["access_token"]
+            .as_str()
+            .ok_or_else(|| AppError::ApiError("Petfinder returned no access token".to_string()))?
+            .to_string();
+        TOKEN_CACHE.insert(key, token.clone()).await;
+        Ok(token)
+    }
+
+    async fn get(
+        &self,
+        settings: &Settings,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<Value, AppError> {
+        let url = format!("{}{}", self.config.base_url, path);
+        let cache_key = format!("GET:{}:{:?}", url, query);
+        if let Some(cached) = shared_cache::cached(settings, &cache_key).await {
+            return Ok(cached);
+        }
+
+        // The query is part of the URL so cassettes tell searches apart
+        let mut request_url = reqwest::Url::parse(&url)
+            .map_err(|e| AppError::Internal(format!("Invalid Petfinder URL: {}", e)))?;
+        if !query.is_empty() {
+            request_url.query_pairs_mut().extend_pairs(query);
+        }
+        if settings.dry_run {
+            let headers = [("Authorization", "Bearer")];
+            trace::planned(settings, "GET", request_url.as_str(), &headers, None);
+            return Err(AppError::DryRun);
+        }
+        let (status, data) = exchange(settings, "GET", request_url.as_str(), None, || async {
+            let token = self.access_token(settings).await?;
+            let client = reqwest::Client::builder()
+                .timeout(settings.request_timeout()?)
+                .build()
+                .map_err(|e| AppError::Internal(format!("Failed to build client: {}", e)))?;
+            client
+                .get(request_url.clone())
+                .bearer_auth(token)
+                .send()
+                .await
+                .map_err(|e| settings.deadline_error(e))
+        })
+        .await?;
+
+        if !status.is_success() {
+            if status == reqwest::StatusCode::NOT_FOUND {
+                return Err(AppError::NotFound);
+            }
+            return Err(AppError::Upstream {
+                status: status.as_u16(),
+                message: format!("Petfinder API Error: {}", status),
+            });
+        }
+
+        shared_cache::store(settings, cache_key, &data).await;
+        Ok(data)
+    }
+}
+
+/// Maps RescueGroups species names ("dogs", "smallandfurry") to Petfinder
+/// animal types ("dog", "small-furry").
+pub fn petfinder_type(species: &str) -> String {
+    match species.to_lowercase().as_str() {
+        "smallandfurry" | "small & furry" | "small-furry" => "small-furry".to_string(),
+        "scalesfinsandother" | "scales, fins & other" => "scales-fins-other".to_string(),
+        "barnyard" => "barnyard".to_string(),
+        other => other.strip_suffix('s').unwrap_or(other).to_string(),
+    }
+}
+
+fn yes_no(value: &Value) -> Value {
+    match value.as_bool() {
+        Some(true) => json!("Yes"),
+        Some(false) => json!("No"),
+        None => Value::Null,
+    }
+}
+
+fn prefixed(id: &Value) -> String {
+    match id {
+        Value::String(s) => format!("{}{}", ID_PREFIX, s),
+        other => format!("{}{}", ID_PREFIX, other),
+    }
+}
+
+pub fn normalize_animal(animal: &Value) -> Value {
+    let breeds = &animal["breeds"];
+    let mut breed = breeds["primary"].as_str().unwrap_or("Mix").to_string();
+    if let Some(secondary) = breeds["secondary"].as_str() {
+        breed = format!("{} / {}", breed, secondary);
+    } else if breeds["mixed"].as_bool() == Some(true) {
+        breed.push_str(" Mix");
+    }
+
+    let pictures: Vec<Value> = animal["photos"]
+        .as_array()
+        .map(|photos| {
+            photos
+                .iter()
+                .filter(|p| p["full"].is_string())
+                .enumerate()
+                .map(|(i, p)| {
+                    json!({
+                        "order": i + 1,
+                        "urlSecureFullsize": p["full"],
+                        "urlSecureThumbnail": p["small"],
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    json!({
+        "type": "animals",
+        "id": prefixed(&animal["id"]),
+        "attributes": {
+            "name": animal["name"],
+            "breedString": breed,
+            "sex": animal["gender"],
+            "ageGroup": animal["age"],
+            "sizeGroup": animal["size"],
+            "descriptionText": animal["description"],
+            "url": animal["url"],
+            "colorDetails": animal["colors"]["primary"],
+            "isGoodWithChildren": yes_no(&animal["environment"]["children"]),
+            "isGoodWithDogs": yes_no(&animal["environment"]["dogs"]),
+            "isGoodWithCats": yes_no(&animal["environment"]["cats"]),
+            "isHouseTrained": yes_no(&animal["attributes"]["house_trained"]),
+            "isSpecialNeeds": yes_no(&animal["attributes"]["special_needs"]),
+            "orgsAnimalsPictures": pictures,
+            "rescueId": animal["organization_animal_id"],
+            "createdDate": animal["published_at"],
+            "updatedDate": animal["status_changed_at"],
+            "distance": animal["distance"],
+            "postalcode": animal["contact"]["address"]["postcode"],
+            "source": "petfinder"
+        },
+        "relationships": {
+            "orgs": { "data": [{ "type": "orgs", "id": prefixed(&animal["organization_id"]) }] }
+        }
+    })
+}
+
+/// Petfinder listings embed the shelter contact; expose it the way
+/// RescueGroups does (an `orgs` entry in `included`).
+fn contact_as_org(animal: &Value) -> Value {
+    let contact = &animal["contact"];
+    json!({
+        "type": "orgs",
+        "id": prefixed(&animal["organization_id"]),
+        "attributes": {
+            "name": animal["organization_id"],
+            "email": contact["email"],
+            "phone": contact["phone"],
+            "city": contact["address"]["city"],
+            "state": contact["address"]["state"],
+        }
+    })
+}
+
+pub fn normalize_org(org: &Value) -> Value {
+    let address = &org["address"];
+    json!({
+        "type": "orgs",
+        "id": prefixed(&org["id"]),
+        "attributes": {
+            "name": org["name"],
+            "email": org["email"],
+            "phone": org["phone"],
+            "street": address["address1"],
+            "city": address["city"],
+            "state": address["state"],
+            "postalcode": address["postcode"],
+            "url": if org["website"].is_string() { &org["website"] } else { &org["url"] },
+            "about": org["mission_statement"],
+            "source": "petfinder"
+        }
+    })
+}
+
+fn search_query(settings: &Settings, args: &ToolArgs) -> Vec<(&'static str, String)> {
+    let species = args.species.as_deref().unwrap_or(&settings.default_species);
+    let postal_code = args
+        .postal_code
+        .as_deref()
+        .unwrap_or(&settings.default_postal_code);
+    // Petfinder caps the radius at 500 miles
+    let miles = args.miles.unwrap_or(settings.default_miles).min(500);
+
+    let mut query = vec![
+        ("type", petfinder_type(species)),
+        ("location", postal_code.to_string()),
+        ("distance", miles.to_string()),
+        ("status", "adoptable".to_string()),
+    ];
+
+    let lower = |v: &str| v.to_lowercase().replace('-', "");
+    if let Some(breeds) = &args.breeds {
+        query.push(("breed", breeds.clone()));
+    }
+    if let Some(sex) = &args.sex {
+        query.push(("gender", lower(sex)));
+    }
+    if let Some(age) = &args.age {
+        query.push(("age", lower(age)));
+    }
+    if let Some(size) = &args.size {
+        query.push(("size", lower(size)));
+    }
+    // Petfinder only filters on named colors, which map onto color groups
+    if let Some(color) = args.color_group.as_ref().or(args.color.as_ref()) {
+        query.push(("color", color.clone()));
+    }
+    for (key, value) in [
+        ("good_with_children", args.good_with_children),
+        ("good_with_dogs", args.good_with_dogs),
+        ("good_with_cats", args.good_with_cats),
+        ("house_trained", args.house_trained),
+        ("special_needs", args.special_needs),
+    ] {
+        if let Some(v) = value {
+            query.push((key, v.to_string()));
+        }
+    }
+    match args.sort_by.as_deref() {
+        Some("Newest") => query.push(("sort", "recent".to_string())),
+        Some("Distance") => query.push(("sort", "distance".to_string())),
+        Some("Random") if !settings.deterministic => query.push(("sort", "random".to_string())),
+        _ => {}
+    }
+    if let Some(page) = args.page {
+        query.push(("page", page.to_string()));
+    }
+    query
+}
+
+impl AnimalSource for PetfinderSource {
+    fn name(&self) -> &'static str {
+        "petfinder"
+    }
+
+    fn owns_id(&self, id: &str) -> bool {
+        id.starts_with(ID_PREFIX)
+    }
+
+    fn search<'a>(
+        &'a self,
+        settings: &'a Settings,
+        args: ToolArgs,
+    ) -> BoxFuture<'a, Result<Value, AppError>> {
+        Box::pin(async move {
+            let args = validate::search_args(apply_search_defaults(settings, args)?)?;
+            let data = self
+                .get(settings, "/animals", &search_query(settings, &args))
+                .await?;
+            let animals: Vec<Value> = data["animals"]
+                .as_array()
+                .map(|a| a.iter().map(normalize_animal).collect())
+                .unwrap_or_default();
+            let mut result = json!({ "data": animals });
+            // Deterministic output lists the matches by ID instead of at random
+            if settings.deterministic && args.sort_by.as_deref() == Some("Random") {
+                sort_by_id(&mut result);
+            }
+            if data["pagination"]["total_pages"].as_u64().unwrap_or(1) > 1 {
+                result["truncated"] = json!(true);
+                result["total"] = data["pagination"]["total_count"].clone();
+            }
+            Ok(result)
+        })
+    }
+
+    fn get_animal<'a>(
+        &'a self,
+        settings: &'a Settings,
+        animal_id: &'a str,
+    ) -> BoxFuture<'a, Result<Value, AppError>> {
+        Box::pin(async move {
+            let id = animal_id.strip_prefix(ID_PREFIX).unwrap_or(animal_id);
+            let data = self.get(settings, &format!("/animals/{}", id), &[]).await?;
+            let animal = &data["animal"];
+            if animal.is_null() {
+                return Err(AppError::NotFound);
+            }
+            Ok(json!({
+                "data": [normalize_animal(animal)],
+                "included": [contact_as_org(animal)]
+            }))
+        })
+    }
+
+    fn search_orgs<'a>(
+        &'a self,
+        settings: &'a Settings,
+        args: OrgSearchArgs,
+    ) -> BoxFuture<'a, Result<Value, AppError>> {
+        Box::pin(async move {
+            let postal_code = args
+                .postal_code
+                .clone()
+                .unwrap_or_else(|| settings.default_postal_code.clone());
+            let miles = args.miles.unwrap_or(settings.default_miles).min(500);
+            let mut query = vec![("location", postal_code), ("distance", miles.to_string())];
+            if let Some(q) = &args.query {
+                query.push(("query", q.clone()));
+            }
+            let data = self.get(settings, "/organizations", &query).await?;
+            let orgs: Vec<Value> = data["organizations"]
+                .as_array()
+                .map(|o| o.iter().map(normalize_org).collect())
+                .unwrap_or_default();
+            Ok(json!({ "data": orgs }))
+        })
+    }
+
+    fn list_breeds<'a>(
+        &'a self,
+        settings: &'a Settings,
+        args: SpeciesArgs,
+    ) -> BoxFuture<'a, Result<Value, AppError>> {
+        Box::pin(async move {
+            let path = format!("/types/{}/breeds", petfinder_type(&args.species));
+            let data = self.get(settings, &path, &[]).await?;
+            let breeds: Vec<Value> = data["breeds"]
+                .as_array()
+                .map(|b| {
+                    b.iter()
+                        .map(|breed| {
+                            json!({
+                                "type": "breeds",
+                                "id": breed["name"],
+                                "attributes": { "name": breed["name"] }
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            Ok(json!({ "data": breeds }))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SourceKind;
+    use crate::source::{get_animal, get_breeds, search_animals, search_orgs};
+
+    fn get_test_settings(rg_url: String, pf_url: String, sources: Vec<SourceKind>) -> Settings {
+        Settings {
+            default_postal_code: "90210".to_string(),
+            sources,
+            petfinder: Some(PetfinderConfig {
+                client_id: "client".to_string(),
+                client_secret: "secret".to_string(),
+                base_url: pf_url,
+            }),
+            ..Settings::for_test(rg_url, std::env::temp_dir().join("rescue-groups-mcp-test"))
+        }
+    }
+
+    async fn mock_token(server: &mut mockito::ServerGuard) -> mockito::Mock {
+        server
+            .mock("POST", "/oauth2/token")
+            .match_body(mockito::Matcher::UrlEncoded(
+                "grant_type".to_string(),
+                "client_credentials".to_string(),
+            ))
+            .with_status(200)
+            .with_body(r#"{"token_type": "Bearer", "expires_in": 3600, "access_token": "tok"}"#)
+            .create_async()
+            .await
+    }
+
+    #[test]
+    fn test_petfinder_type() {
+        assert_eq!(petfinder_type("dogs"), "dog");
+        assert_eq!(petfinder_type("Cats"), "cat");
+        assert_eq!(petfinder_type("smallandfurry"), "small-furry");
+        assert_eq!(petfinder_type("horse"), "horse");
+    }
+
+    #[test]
+    fn test_normalize_animal() {
+        let animal = json!({
+            "id": 123,
+            "name": "Biscuit",
+            "breeds": {"primary": "Corgi", "secondary": null, "mixed": true},
+            "gender": "Female",
+            "age": "Young",
+            "size": "Small",
+            "url": "https://petfinder.com/biscuit",
+            "environment": {"children": true, "dogs": false, "cats": null},
+            "attributes": {"house_trained": true, "special_needs": false},
+            "photos": [{"full": "https://photos/biscuit.jpg"}]
+        });
+        let normalized = normalize_animal(&animal);
+        assert_eq!(normalized["id"], "pf-123");
+        let attrs = &normalized["attributes"];
+        assert_eq!(attrs["breedString"], "Corgi Mix");
+        assert_eq!(attrs["isGoodWithChildren"], "Yes");
+        assert_eq!(attrs["isGoodWithDogs"], "No");
+        assert!(attrs["isGoodWithCats"].is_null());
+        assert_eq!(
+            attrs["orgsAnimalsPictures"][0]["urlSecureFullsize"],
+            "https://photos/biscuit.jpg"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_petfinder_search_and_details() {
+        let mut server = mockito::Server::new_async().await;
+        let _token = mock_token(&mut server).await;
+        let search = server
+            .mock("GET", "/animals")
+            .match_header("authorization", "Bearer tok")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("type".into(), "cat".into()),
+                mockito::Matcher::UrlEncoded("location".into(), "90210".into()),
+                mockito::Matcher::UrlEncoded("size".into(), "xlarge".into()),
+                mockito::Matcher::UrlEncoded("good_with_children".into(), "true".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"animals": [{"id": 1, "name": "Tom", "breeds": {"primary": "Tabby"}}]}"#)
+            .create_async()
+            .await;
+        let _details = server
+            .mock("GET", "/animals/1")
+            .with_status(200)
+            .with_body(
+                r#"{"animal": {"id": 1, "name": "Tom", "organization_id": "CA123", "contact": {"email": "a@b.c", "address": {"city": "LA", "state": "CA"}}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let settings = get_test_settings(
+            "http://unused".to_string(),
+            server.url(),
+            vec![SourceKind::Petfinder],
+        );
+        let args = ToolArgs {
+            species: Some("cats".to_string()),
+            size: Some("X-Large".to_string()),
+            good_with_children: Some(true),
+            ..Default::default()
+        };
+        let res = search_animals(&settings, args).await.unwrap();
+        assert_eq!(res["data"][0]["id"], "pf-1");
+        assert_eq!(res["data"][0]["attributes"]["breedString"], "Tabby");
+        search.assert_async().await;
+
+        let res = get_animal(
+            &settings,
+            crate::cli::AnimalIdArgs {
+                animal_id: "pf-1".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(res["data"][0]["attributes"]["name"], "Tom");
+        assert_eq!(res["included"][0]["attributes"]["email"], "a@b.c");
+    }
+
+    #[tokio::test]
+    async fn test_federated_search_merges_sources() {
+        let mut rg = mockito::Server::new_async().await;
+        let mut pf = mockito::Server::new_async().await;
+        let _token = mock_token(&mut pf).await;
+        let _rg_search = rg
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "10", "attributes": {"name": "Rex"}}, {"id": "11", "attributes": {"name": "Max"}}]}"#,
+            )
+            .create_async()
+            .await;
+        let _pf_search = pf
+            .mock("GET", "/animals")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{"animals": [{"id": 1, "name": "Tom"}]}"#)
+            .create_async()
+            .await;
+        let _pf_orgs = pf
+            .mock("GET", "/organizations")
+            .match_query(mockito::Matcher::Any)
+            .with_status(500)
+            .create_async()
+            .await;
+        let _rg_orgs = rg
+            .mock("POST", "/public/orgs/search")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "5", "attributes": {"name": "Shelter"}}]}"#)
+            .create_async()
+            .await;
+
+        let settings = get_test_settings(
+            rg.url(),
+            pf.url(),
+            vec![SourceKind::RescueGroups, SourceKind::Petfinder],
+        );
+        let res = search_animals(&settings, ToolArgs::default())
+            .await
+            .unwrap();
+        let ids: Vec<&str> = res["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["10", "pf-1", "11"]);
+
+        // A failing source is reported without failing the whole request
+        let args = OrgSearchArgs {
+            postal_code: None,
+            miles: None,
+            query: None,
+            species: None,
+        };
+        let res = search_orgs(&settings, args).await.unwrap();
+        assert_eq!(res["data"].as_array().unwrap().len(), 1);
+        assert!(res["errors"][0].as_str().unwrap().starts_with("petfinder"));
+    }
+
+    #[tokio::test]
+    async fn test_federated_search_merges_cross_listings() {
+        let mut rg = mockito::Server::new_async().await;
+        let mut pf = mockito::Server::new_async().await;
+        let _token = mock_token(&mut pf).await;
+        let _rg_search = rg
+            .mock("POST", "/public/animals/search/available/dogs/haspic")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [
+                    {"id": "10", "attributes": {"name": "Rex", "rescueId": "A-17", "url": "http://rg/10"},
+                     "relationships": {"orgs": {"data": [{"type": "orgs", "id": "5"}]}}},
+                    {"id": "11", "attributes": {"name": "Max", "breedString": "Beagle", "sex": "Male", "ageGroup": "Adult"}}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+        let _pf_search = pf
+            .mock("GET", "/animals")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(
+                r#"{"animals": [
+                    {"id": 1, "name": "REX", "organization_animal_id": "a17", "organization_id": "TX1"},
+                    {"id": 2, "name": "Max", "breeds": {"primary": "Beagle"}, "gender": "Male", "age": "Adult"},
+                    {"id": 3, "name": "Max", "breeds": {"primary": "Beagle"}, "gender": "Female", "age": "Adult"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let settings = get_test_settings(
+            rg.url(),
+            pf.url(),
+            vec![SourceKind::RescueGroups, SourceKind::Petfinder],
+        );
+        let res = search_animals(&settings, ToolArgs::default())
+            .await
+            .unwrap();
+        let animals = res["data"].as_array().unwrap();
+        let ids: Vec<&str> = animals.iter().map(|a| a["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, vec!["10", "11", "pf-3"]);
+
+        let listings = &animals[0]["attributes"]["listings"];
+        assert_eq!(listings[0]["org_id"], "5");
+        assert_eq!(listings[0]["source"], "rescuegroups");
+        assert_eq!(listings[1]["id"], "pf-1");
+        assert_eq!(listings[1]["org_id"], "pf-TX1");
+        assert_eq!(animals[1]["attributes"]["listings"][1]["id"], "pf-2");
+        assert!(animals[2]["attributes"]["listings"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_federated_search_sorts_by_distance() {
+        let mut rg = mockito::Server::new_async().await;
+        let mut pf = mockito::Server::new_async().await;
+        let _token = mock_token(&mut pf).await;
+        let _rg_search = rg
+            .mock("POST", "/public/animals/search/available/dogs/haspic?sort=distance")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "10", "attributes": {"name": "Rex", "distance": 4}}, {"id": "11", "attributes": {"name": "Max", "distance": 30}}]}"#,
+            )
+            .create_async()
+            .await;
+        let _pf_search = pf
+            .mock("GET", "/animals")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{"animals": [{"id": 1, "name": "Tom", "distance": 12.5}, {"id": 2, "name": "Kit"}]}"#)
+            .create_async()
+            .await;
+
+        let settings = get_test_settings(
+            rg.url(),
+            pf.url(),
+            vec![SourceKind::RescueGroups, SourceKind::Petfinder],
+        );
+        let args = ToolArgs {
+            sort_by: Some("Distance".to_string()),
+            ..Default::default()
+        };
+        let res = search_animals(&settings, args).await.unwrap();
+        let ids: Vec<&str> = res["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["10", "pf-1", "11", "pf-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_petfinder_breeds_and_auth_failure() {
+        let mut server = mockito::Server::new_async().await;
+        let _token = server
+            .mock("POST", "/oauth2/token")
+            .with_status(401)
+            .create_async()
+            .await;
+
+        let mut settings = get_test_settings(
+            "http://unused".to_string(),
+            server.url(),
+            vec![SourceKind::Petfinder],
+        );
+        // Tokens are cached per client, and mockito reuses server URLs
+        if let Some(petfinder) = settings.petfinder.as_mut() {
+            petfinder.client_id = "revoked".to_string();
+        }
+        let res = get_breeds(
+            &settings,
+            SpeciesArgs {
+                species: "dogs".to_string(),
+            },
+        )
+        .await;
+        assert!(matches!(res, Err(AppError::ConfigError(_))));
+    }
+}
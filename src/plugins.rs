@@ -0,0 +1,306 @@
+use crate::config::{PluginTool, Settings};
+use crate::error::AppError;
+use serde_json::{json, Value};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// `tools/list` entries for the configured plugins, sorted by name.
+pub fn get_plugin_tool_definitions(settings: &Settings) -> Vec<Value> {
+    let mut names: Vec<&String> = settings.plugins.keys().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let plugin = &settings.plugins[name];
+            json!({
+                "name": name,
+                "description": plugin
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| format!("Deployment-specific tool '{}'.", name)),
+                "inputSchema": plugin
+                    .input_schema
+                    .clone()
+                    // Without a schema, any arguments are passed on
+                    .unwrap_or_else(|| {
+                        json!({ "type": "object", "properties": {}, "additionalProperties": true })
+                    }),
+            })
+        })
+        .collect()
+}
+
+/// Runs the plugin registered as `name`, returning `None` when there is no
+/// such plugin.
+pub async fn call_plugin(
+    settings: &Settings,
+    name: &str,
+    arguments: &Value,
+) -> Result<Option<Value>, AppError> {
+    let Some(plugin) = settings.plugins.get(name) else {
+        return Ok(None);
+    };
+    check_required(plugin, arguments)?;
+
+    let timeout = plugin
+        .timeout_seconds
+        .map(Duration::from_secs)
+        .unwrap_or(settings.timeout);
+    let result = if let Some(command) = &plugin.command {
+        tokio::time::timeout(timeout, run_command(command, arguments)).await
+    } else if let Some(url) = &plugin.url {
+        tokio::time::timeout(timeout, forward(plugin, url, arguments)).await
+    } else {
+        return Err(AppError::ConfigError(format!(
+            "Plugin '{}' has neither command nor url",
+            name
+        )));
+    };
+    result
+        .map_err(|_| AppError::ApiError(format!("Plugin '{}' timed out", name)))?
+        .map(Some)
+}
+
+fn check_required(plugin: &PluginTool, arguments: &Value) -> Result<(), AppError> {
+    let required = plugin
+        .input_schema
+        .as_ref()
+        .and_then(|s| s.get("required"))
+        .and_then(Value::as_array);
+    for field in required.into_iter().flatten().filter_map(Value::as_str) {
+        if arguments.get(field).is_none_or(Value::is_null) {
+            return Err(AppError::invalid_argument(
+                field,
+                format!("Missing required argument '{}'", field),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Replaces `{name}` placeholders with the matching argument; unset
+/// arguments become empty strings. Only the template is scanned, so
+/// argument values are inserted as given, braces and all.
+fn expand(template: &str, arguments: &Value) -> String {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(len)
+                if after[..len]
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_') =>
+            {
+                match &arguments[&after[..len]] {
+                    Value::String(s) => expanded.push_str(s),
+                    Value::Null => {}
+                    other => expanded.push_str(&other.to_string()),
+                }
+                rest = &after[len + 1..];
+            }
+            _ => {
+                expanded.push('{');
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+fn text_result(text: String) -> Value {
+    json!({ "content": [{ "type": "text", "text": text }] })
+}
+
+async fn run_command(command: &str, arguments: &Value) -> Result<Value, AppError> {
+    let mut parts = command
+        .split_whitespace()
+        .map(|part| expand(part, arguments));
+    let program = parts
+        .next()
+        .ok_or_else(|| AppError::ConfigError("Plugin command is empty".to_string()))?;
+
+    let mut child = Command::new(&program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| AppError::ApiError(format!("Failed to run '{}': {}", program, e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A program that ignores its input may exit before reading it
+        let _ = stdin.write_all(arguments.to_string().as_bytes()).await;
+    }
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        return Err(AppError::ApiError(format!(
+            "'{}' failed ({}): {}",
+            program,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(text_result(
+        String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string(),
+    ))
+}
+
+/// POSTs the arguments to `url`. A JSON response that already is an MCP
+/// tool result (has `content`) is passed through; anything else is
+/// returned as text.
+async fn forward(plugin: &PluginTool, url: &str, arguments: &Value) -> Result<Value, AppError> {
+    let client = reqwest::Client::new();
+    let mut request = client.post(url).json(arguments);
+    for (name, value) in &plugin.headers {
+        request = request.header(name, value);
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        return Err(AppError::ApiError(format!(
+            "Plugin endpoint returned {}: {}",
+            status,
+            body.trim()
+        )));
+    }
+
+    match serde_json::from_str::<Value>(&body) {
+        Ok(value) if value.get("content").is_some() => Ok(value),
+        Ok(value) => Ok(text_result(serde_json::to_string_pretty(&value)?)),
+        Err(_) => Ok(text_result(body)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    fn get_test_settings(plugins: HashMap<String, PluginTool>) -> Settings {
+        Settings {
+            timeout: Duration::from_secs(5),
+            plugins,
+            ..Settings::for_test(
+                "http://unused",
+                std::env::temp_dir().join("rescue-groups-mcp-test"),
+            )
+        }
+    }
+
+    #[test]
+    fn test_expand() {
+        let args = json!({"animal_id": "123", "limit": 5});
+        assert_eq!(expand("--id={animal_id}", &args), "--id=123");
+        assert_eq!(expand("{limit}", &args), "5");
+        assert_eq!(expand("--org={org_id}", &args), "--org=");
+        assert_eq!(expand("{not a placeholder}", &args), "{not a placeholder}");
+        assert_eq!(expand("{ {limit}", &args), "{ 5");
+
+        // Values are inserted as given, not expanded again
+        let args = json!({"a": "{b}", "b": "x", "query": "{literal}"});
+        assert_eq!(expand("{a}-{b}", &args), "{b}-x");
+        assert_eq!(expand("q={query}", &args), "q={literal}");
+    }
+
+    #[test]
+    fn test_definitions() {
+        let mut plugins = HashMap::new();
+        plugins.insert(
+            "foster_lookup".to_string(),
+            PluginTool {
+                description: Some("Find a foster home".to_string()),
+                url: Some("http://unused".to_string()),
+                input_schema: Some(json!({"type": "object", "required": ["animal_id"]})),
+                ..Default::default()
+            },
+        );
+        let tools = get_plugin_tool_definitions(&get_test_settings(plugins));
+        assert_eq!(tools[0]["name"], "foster_lookup");
+        assert_eq!(tools[0]["inputSchema"]["required"][0], "animal_id");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_plugin() {
+        let mut plugins = HashMap::new();
+        plugins.insert(
+            "echo".to_string(),
+            PluginTool {
+                command: Some("echo foster {animal_id}".to_string()),
+                input_schema: Some(json!({"type": "object", "required": ["animal_id"]})),
+                ..Default::default()
+            },
+        );
+        plugins.insert(
+            "fail".to_string(),
+            PluginTool {
+                command: Some("false".to_string()),
+                ..Default::default()
+            },
+        );
+        let settings = get_test_settings(plugins);
+
+        let result = call_plugin(&settings, "echo", &json!({"animal_id": "42"}))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result["content"][0]["text"], "foster 42");
+
+        assert!(matches!(
+            call_plugin(&settings, "echo", &json!({})).await,
+            Err(AppError::ValidationError { parameter: Some(p), .. }) if p == "animal_id"
+        ));
+        assert!(call_plugin(&settings, "fail", &json!({})).await.is_err());
+        assert!(call_plugin(&settings, "missing", &json!({}))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_http_plugin() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/foster")
+            .match_header("authorization", "Bearer secret")
+            .match_body(mockito::Matcher::Json(json!({"animal_id": "42"})))
+            .with_status(200)
+            .with_body(r#"{"home": "Jane D."}"#)
+            .create_async()
+            .await;
+
+        let mut plugins = HashMap::new();
+        plugins.insert(
+            "foster".to_string(),
+            PluginTool {
+                url: Some(format!("{}/foster", server.url())),
+                headers: HashMap::from([(
+                    "Authorization".to_string(),
+                    "Bearer secret".to_string(),
+                )]),
+                ..Default::default()
+            },
+        );
+        let settings = get_test_settings(plugins);
+
+        let result = call_plugin(&settings, "foster", &json!({"animal_id": "42"}))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(result["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("Jane D."));
+    }
+}
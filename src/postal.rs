@@ -0,0 +1,425 @@
+use crate::config::Settings;
+use crate::error::AppError;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::OnceLock;
+use tracing::warn;
+
+/// US/Canada dataset compiled into the binary, in the GeoNames postal code
+/// dump format: the full GeoNames table when `task postal-db:fetch` has
+/// downloaded it before building, otherwise a sample of major cities (see
+/// `build.rs`). Canadian entries are forward sortation areas (the first
+/// three characters of a postal code).
+#[cfg(feature = "postal-db")]
+const BUNDLED: &str = include_str!(env!("POSTAL_DATA"));
+#[cfg(not(feature = "postal-db"))]
+const BUNDLED: &str = "";
+
+const EARTH_RADIUS_MILES: f64 = 3958.8;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PostalEntry {
+    pub country: String,
+    pub code: String,
+    pub place: String,
+    pub state: String,
+    /// State or province abbreviation, e.g. "TX"
+    pub state_code: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct PostalDb {
+    entries: Vec<PostalEntry>,
+    /// Index into `entries` by canonical code; the first row of a code wins
+    by_code: HashMap<String, usize>,
+}
+
+static DB: OnceLock<PostalDb> = OnceLock::new();
+
+/// Upper-cases a postal code and drops spaces and dashes, so "k1p 1j1",
+/// "K1P1J1" and "78704-1234" compare by their significant part.
+fn canonical(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// Whether `input` is shaped like a US ZIP (optionally ZIP+4) or Canadian
+/// postal code, as opposed to a place name.
+pub fn looks_like_postal_code(input: &str) -> bool {
+    let code = canonical(input);
+    let bytes = code.as_bytes();
+    let us = (code.len() == 5 || code.len() == 9) && bytes.iter().all(u8::is_ascii_digit);
+    let ca = code.len() == 6
+        && bytes.iter().enumerate().all(|(i, b)| {
+            if i % 2 == 0 {
+                b.is_ascii_alphabetic()
+            } else {
+                b.is_ascii_digit()
+            }
+        });
+    us || ca
+}
+
+/// Great-circle distance between two coordinates, in miles.
+pub fn haversine_miles(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_MILES * a.sqrt().asin()
+}
+
+impl PostalDb {
+    /// Parses tab-separated GeoNames rows (country, postal code, place,
+    /// state, state code, 4 unused admin columns, latitude, longitude).
+    /// Malformed rows are skipped.
+    pub fn parse(text: &str) -> Self {
+        let entries: Vec<PostalEntry> = text
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split('\t').collect();
+                if fields.len() < 11 {
+                    return None;
+                }
+                Some(PostalEntry {
+                    country: fields[0].to_string(),
+                    code: fields[1].to_string(),
+                    place: fields[2].to_string(),
+                    state: fields[3].to_string(),
+                    state_code: fields[4].to_string(),
+                    latitude: fields[9].parse().ok()?,
+                    longitude: fields[10].parse().ok()?,
+                })
+            })
+            .collect();
+        let mut by_code = HashMap::with_capacity(entries.len());
+        for (i, entry) in entries.iter().enumerate() {
+            by_code.entry(canonical(&entry.code)).or_insert(i);
+        }
+        PostalDb { entries, by_code }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Looks up a postal code. ZIP+4 codes match their 5-digit ZIP and
+    /// Canadian codes fall back to their forward sortation area.
+    pub fn lookup(&self, code: &str) -> Option<&PostalEntry> {
+        let code = canonical(code);
+        let candidates = [
+            code.clone(),
+            code.chars().take(5).collect(),
+            code.chars().take(3).collect(),
+        ];
+        candidates
+            .iter()
+            .find_map(|candidate| self.by_code.get(candidate))
+            .map(|&i| &self.entries[i])
+    }
+
+    /// Finds the postal codes of a place given as "City", "City, ST" or
+    /// "City, State".
+    pub fn find_place(&self, query: &str) -> Vec<&PostalEntry> {
+        let (city, state) = match query.rsplit_once(',') {
+            Some((city, state)) => (city.trim(), Some(state.trim())),
+            None => (query.trim(), None),
+        };
+        self.entries
+            .iter()
+            .filter(|e| e.place.eq_ignore_ascii_case(city))
+            .filter(|e| {
+                state.is_none_or(|s| {
+                    e.state_code.eq_ignore_ascii_case(s) || e.state.eq_ignore_ascii_case(s)
+                })
+            })
+            .collect()
+    }
+
+    /// Distance in miles between two known postal codes.
+    pub fn distance_miles(&self, from: &str, to: &str) -> Option<f64> {
+        let (a, b) = (self.lookup(from)?, self.lookup(to)?);
+        Some(haversine_miles(
+            a.latitude,
+            a.longitude,
+            b.latitude,
+            b.longitude,
+        ))
+    }
+}
+
+/// The postal database: the file configured as `postal_db` (e.g. a full
+/// GeoNames `US.txt` dump) or the bundled dataset. Loaded once.
+pub fn postal_db(settings: &Settings) -> &'static PostalDb {
+    DB.get_or_init(|| {
+        if let Some(path) = &settings.postal_db {
+            match fs::read_to_string(path) {
+                Ok(text) => return PostalDb::parse(&text),
+                Err(e) => warn!(
+                    "Failed to read postal_db '{}', using the bundled dataset: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+        PostalDb::parse(BUNDLED)
+    })
+}
+
+/// A US ZIP (ZIP+4 is cut to the ZIP) or Canadian postal code ("A1A 1A1")
+/// in the form the search API expects, or `None` if `input` isn't one.
+pub fn normalize_postal_code(input: &str) -> Option<String> {
+    let input = input.trim();
+    if !input
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-')
+        || !looks_like_postal_code(input)
+    {
+        return None;
+    }
+    let code = canonical(input);
+    Some(match code.len() {
+        6 => format!("{} {}", &code[..3], &code[3..]),
+        _ => code[..5].to_string(),
+    })
+}
+
+/// Turns a location into something the search API accepts: postal codes
+/// are normalized, known place names ("Austin, TX") become their first
+/// postal code, and other names are left for the API to judge. Input with
+/// digits that is no valid postal code is rejected up front, as the API
+/// only answers it with an opaque error.
+pub fn resolve_location(settings: &Settings, location: &str) -> Result<String, AppError> {
+    if let Some(code) = normalize_postal_code(location) {
+        return Ok(code);
+    }
+    if let Some(entry) = postal_db(settings).find_place(location).first() {
+        return Ok(entry.code.clone());
+    }
+    if location.trim().is_empty() || location.chars().any(|c| c.is_ascii_digit()) {
+        return Err(AppError::invalid_argument("postal_code", format!(
+            "'{}' is not a valid postal code. Use a US ZIP (90210 or 90210-1234), a Canadian postal code (K1P 1J1) or a place name such as \"Austin, TX\"",
+            location
+        )));
+    }
+    Ok(location.to_string())
+}
+
+/// Parses a coordinate the API may send as a number or a string.
+pub(crate) fn coordinate(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+/// Coordinates of a listing or org: explicit `lat`/`lon` attributes, or
+/// the centroid of its postal code.
+fn item_coordinates(db: &PostalDb, attrs: &Value) -> Option<(f64, f64)> {
+    if let (Some(lat), Some(lon)) = (coordinate(&attrs["lat"]), coordinate(&attrs["lon"])) {
+        return Some((lat, lon));
+    }
+    let entry = db.lookup(attrs["postalcode"].as_str()?)?;
+    Some((entry.latitude, entry.longitude))
+}
+
+/// Where an animal is: its own location attributes, or those of the
+/// location or org it's related to in `included`.
+fn animal_coordinates(db: &PostalDb, animal: &Value, included: &[Value]) -> Option<(f64, f64)> {
+    item_coordinates(db, &animal["attributes"]).or_else(|| {
+        ["locations", "orgs"].iter().find_map(|kind| {
+            let id = &animal["relationships"][*kind]["data"][0]["id"];
+            included
+                .iter()
+                .find(|item| item["type"] == *kind && item["id"] == *id)
+                .and_then(|item| item_coordinates(db, &item["attributes"]))
+        })
+    })
+}
+
+/// Fills in `attributes.distance` (miles from `origin`) for animals the
+/// API returned without one, when both ends can be located.
+pub fn annotate_distances(settings: &Settings, data: &mut Value, origin: &str) {
+    let db = postal_db(settings);
+    let Some(origin) = resolve_location(settings, origin)
+        .ok()
+        .and_then(|code| db.lookup(&code))
+    else {
+        return;
+    };
+    let included = data["included"].as_array().cloned().unwrap_or_default();
+    let Some(animals) = data["data"].as_array_mut() else {
+        return;
+    };
+    for animal in animals {
+        if coordinate(&animal["attributes"]["distance"]).is_some() {
+            continue;
+        }
+        if let Some((lat, lon)) = animal_coordinates(db, animal, &included) {
+            let miles = haversine_miles(origin.latitude, origin.longitude, lat, lon);
+            animal["attributes"]["distance"] = json!((miles * 10.0).round() / 10.0);
+        }
+    }
+}
+
+/// Sorts animals nearest first; those without a known distance go last.
+pub fn sort_by_distance(animals: &mut [Value]) {
+    animals.sort_by(|a, b| {
+        let distance = |v: &Value| coordinate(&v["attributes"]["distance"]).unwrap_or(f64::MAX);
+        distance(a).total_cmp(&distance(b))
+    });
+}
+
+/// Resolves a postal code or place name to its known locations.
+pub fn lookup_location(settings: &Settings, query: &str) -> Result<Value, AppError> {
+    let db = postal_db(settings);
+    let postal_code = looks_like_postal_code(query);
+    let matches: Vec<&PostalEntry> = if postal_code {
+        db.lookup(query).into_iter().collect()
+    } else {
+        db.find_place(query)
+    };
+    Ok(json!({
+        "query": query,
+        "is_postal_code": postal_code,
+        "matches": matches,
+        "dataset_size": db.len(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "US\t78704\tAustin\tTexas\tTX\t\t\t\t\t30.2430\t-97.7670\t4
+US\t78701\tAustin\tTexas\tTX\t\t\t\t\t30.2710\t-97.7420\t4
+US\t75201\tDallas\tTexas\tTX\t\t\t\t\t32.7870\t-96.7990\t4
+CA\tK1P\tOttawa\tOntario\tON\t\t\t\t\t45.4210\t-75.6970\t4
+malformed row";
+
+    #[test]
+    fn test_looks_like_postal_code() {
+        assert!(looks_like_postal_code("78704"));
+        assert!(looks_like_postal_code("78704-1234"));
+        assert!(looks_like_postal_code("k1p 1j1"));
+        assert!(!looks_like_postal_code("Austin, TX"));
+        assert!(!looks_like_postal_code("7870"));
+        assert!(!looks_like_postal_code("1K1 P1J"));
+    }
+
+    #[test]
+    fn test_normalize_postal_code() {
+        assert_eq!(normalize_postal_code(" 78704 ").as_deref(), Some("78704"));
+        assert_eq!(
+            normalize_postal_code("78704-1234").as_deref(),
+            Some("78704")
+        );
+        assert_eq!(normalize_postal_code("k1p1j1").as_deref(), Some("K1P 1J1"));
+        assert_eq!(normalize_postal_code("7870!4"), None);
+        assert_eq!(normalize_postal_code("Austin, TX"), None);
+    }
+
+    #[test]
+    fn test_resolve_location() {
+        let settings = crate::config::Settings::new("key");
+        assert_eq!(resolve_location(&settings, "K1P 1J1").unwrap(), "K1P 1J1");
+        // Unknown names are left for the API
+        assert_eq!(
+            resolve_location(&settings, "Smallville").unwrap(),
+            "Smallville"
+        );
+        assert!(matches!(
+            resolve_location(&settings, "9021"),
+            Err(AppError::ValidationError { message: msg, .. }) if msg.contains("90210-1234")
+        ));
+        assert!(resolve_location(&settings, " ").is_err());
+    }
+
+    #[test]
+    fn test_lookup_and_places() {
+        let db = PostalDb::parse(SAMPLE);
+        assert_eq!(db.len(), 4);
+        assert_eq!(db.lookup("78704-1234").unwrap().place, "Austin");
+        assert_eq!(db.lookup("K1P 1J1").unwrap().place, "Ottawa");
+        assert!(db.lookup("99999").is_none());
+        // Codes are indexed in canonical form, keeping the first row
+        let db = PostalDb::parse(&format!(
+            "{}\nCA\tk1p\tElsewhere\tOntario\tON\t\t\t\t\t1.0\t1.0\t4",
+            SAMPLE
+        ));
+        assert_eq!(db.lookup("k1p").unwrap().place, "Ottawa");
+
+        assert_eq!(db.find_place("austin, tx").len(), 2);
+        assert_eq!(db.find_place("Dallas, Texas")[0].code, "75201");
+        assert!(db.find_place("Austin, MN").is_empty());
+    }
+
+    #[test]
+    fn test_distance() {
+        let db = PostalDb::parse(SAMPLE);
+        // Austin to Dallas is roughly 180 miles as the crow flies
+        let miles = db.distance_miles("78704", "75201").unwrap();
+        assert!((170.0..195.0).contains(&miles), "{}", miles);
+        assert!(db.distance_miles("78704", "00000").is_none());
+        assert_eq!(haversine_miles(10.0, 20.0, 10.0, 20.0), 0.0);
+    }
+
+    #[test]
+    fn test_sort_by_distance() {
+        let settings = crate::config::Settings::new("key");
+        let mut data = json!({
+            "data": [
+                {"id": "far", "attributes": {"distance": "1500.5"}},
+                {"id": "unknown", "attributes": {"lat": 30.27, "lon": -97.74}},
+                {"id": "near", "attributes": {"distance": 3}}
+            ]
+        });
+        // An origin that can't be located leaves the results alone
+        annotate_distances(&settings, &mut data, "Nowhere, ZZ");
+        assert!(data["data"][1]["attributes"]["distance"].is_null());
+
+        let animals = data["data"].as_array_mut().unwrap();
+        sort_by_distance(animals);
+        let ids: Vec<&str> = animals.iter().map(|a| a["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, vec!["near", "far", "unknown"]);
+    }
+
+    #[cfg(feature = "postal-db")]
+    #[test]
+    fn test_annotate_distances_with_bundled_dataset() {
+        let settings = crate::config::Settings::new("key");
+        let mut data = json!({
+            "data": [
+                {"id": "1", "attributes": {"postalcode": "75201"}},
+                {"id": "2", "attributes": {}, "relationships": {"orgs": {"data": [{"type": "orgs", "id": "5"}]}}},
+                {"id": "3", "attributes": {"lat": "30.2710", "lon": "-97.7420"}},
+                {"id": "4", "attributes": {"distance": 7}}
+            ],
+            "included": [{"type": "orgs", "id": "5", "attributes": {"postalcode": "78701"}}]
+        });
+        annotate_distances(&settings, &mut data, "Austin, TX");
+        let dallas = data["data"][0]["attributes"]["distance"].as_f64().unwrap();
+        assert!((170.0..195.0).contains(&dallas), "{}", dallas);
+        assert_eq!(data["data"][1]["attributes"]["distance"], 0.0);
+        assert_eq!(data["data"][2]["attributes"]["distance"], 0.0);
+        // Distances reported by the API are kept
+        assert_eq!(data["data"][3]["attributes"]["distance"], 7);
+    }
+
+    #[cfg(feature = "postal-db")]
+    #[test]
+    fn test_bundled_dataset_parses() {
+        let db = PostalDb::parse(BUNDLED);
+        assert_eq!(db.len(), BUNDLED.lines().count());
+        assert_eq!(db.lookup("90210").unwrap().state_code, "CA");
+    }
+}
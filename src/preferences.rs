@@ -0,0 +1,315 @@
+use crate::config::Settings;
+use crate::continuation::session_key;
+use crate::error::AppError;
+use crate::matching::{check_criterion, Criterion};
+use crate::postal::coordinate;
+use crate::synonyms::suggest;
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Most points a single preference can weigh.
+pub const MAX_WEIGHT: u32 = 10;
+
+/// Ranks by distance from the searched location instead of an attribute.
+pub const NEARBY: &str = "nearby";
+
+/// Preferences results can be ranked by: name, label, attribute and the
+/// values that satisfy it.
+const PREFERENCES: &[(&str, &str, &str, &[&str])] = &[
+    ("baby", "Baby", "ageGroup", &["Baby"]),
+    ("young", "Young", "ageGroup", &["Young"]),
+    ("adult", "Adult", "ageGroup", &["Adult"]),
+    ("senior", "Senior", "ageGroup", &["Senior"]),
+    ("small", "Small", "sizeGroup", &["Small"]),
+    ("medium", "Medium-sized", "sizeGroup", &["Medium"]),
+    ("large", "Large", "sizeGroup", &["Large", "X-Large"]),
+    ("male", "Male", "sex", &["Male"]),
+    ("female", "Female", "sex", &["Female"]),
+    (
+        "good_with_children",
+        "Good with children",
+        "isGoodWithChildren",
+        &["Yes"],
+    ),
+    (
+        "good_with_dogs",
+        "Good with dogs",
+        "isGoodWithDogs",
+        &["Yes"],
+    ),
+    (
+        "good_with_cats",
+        "Good with cats",
+        "isGoodWithCats",
+        &["Yes"],
+    ),
+    ("house_trained", "House-trained", "isHouseTrained", &["Yes"]),
+    ("low_energy", "Low energy", "energyLevel", &["Low"]),
+    ("high_energy", "High energy", "energyLevel", &["High"]),
+];
+
+/// How long a session's preferences are kept without being used.
+const PREFERENCES_IDLE: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Stored preferences by session
+static SESSION_PREFERENCES: LazyLock<Cache<String, Preferences>> = LazyLock::new(|| {
+    Cache::builder()
+        .max_capacity(1_000)
+        .time_to_idle(PREFERENCES_IDLE)
+        .build()
+});
+
+/// What a user would like in an animal, for ranking search results.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Preferences {
+    /// Points per preference, from 1 to `MAX_WEIGHT`
+    #[serde(default)]
+    pub weights: BTreeMap<String, u32>,
+    /// Preferences an animal must meet to be listed at all
+    #[serde(default)]
+    pub required: Vec<String>,
+}
+
+/// Every preference name, `nearby` included.
+pub fn preference_names() -> Vec<&'static str> {
+    PREFERENCES
+        .iter()
+        .map(|(name, ..)| *name)
+        .chain([NEARBY])
+        .collect()
+}
+
+fn criterion(name: &str, weight: u32) -> Option<Criterion> {
+    PREFERENCES
+        .iter()
+        .find(|(n, ..)| *n == name)
+        .map(|(_, label, attribute, accepted)| Criterion::new(label, attribute, accepted, weight))
+}
+
+impl Preferences {
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty() && self.required.is_empty()
+    }
+
+    /// Checks every name is known and every weight in range.
+    pub fn validate(&self) -> Result<(), AppError> {
+        let known: Vec<String> = preference_names().iter().map(|n| n.to_string()).collect();
+        for name in self.weights.keys().chain(&self.required) {
+            if !known.contains(name) {
+                let hint = match suggest(name, &known).as_slice() {
+                    [] => format!("Known preferences: {}", known.join(", ")),
+                    close => format!("Did you mean: {}?", close.join(", ")),
+                };
+                return Err(AppError::invalid_argument(
+                    "preferences",
+                    format!("Unknown preference '{}'. {}", name, hint),
+                ));
+            }
+        }
+        if self.required.iter().any(|name| name == NEARBY) {
+            return Err(AppError::invalid_argument(
+                "preferences",
+                format!(
+                    "'{}' can't be required: limit the search radius with miles instead",
+                    NEARBY
+                ),
+            ));
+        }
+        if let Some((name, weight)) = self
+            .weights
+            .iter()
+            .find(|(_, &w)| !(1..=MAX_WEIGHT).contains(&w))
+        {
+            return Err(AppError::invalid_argument(
+                "preferences",
+                format!(
+                    "Weight {} for '{}' is out of range: use 1 to {}",
+                    weight, name, MAX_WEIGHT
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Drops the animals missing a required preference and orders the rest by
+/// how well they fit the weighted ones, best first. Each animal gets a
+/// 0-100 `score` and one reason per preference in its `meta`; `nearby`
+/// earns its points in proportion to how much of the `miles` radius is
+/// left. The API's order is kept among equal scores.
+pub fn rank(data: &mut Value, preferences: &Preferences, miles: u32) {
+    let animals = match data["data"].take() {
+        Value::Array(animals) => animals,
+        _ => Vec::new(),
+    };
+    let required: Vec<Criterion> = preferences
+        .required
+        .iter()
+        .filter_map(|name| criterion(name, 0))
+        .collect();
+    let found = animals.len();
+    let animals: Vec<Value> = animals
+        .into_iter()
+        .filter(|animal| required.iter().all(|c| check_criterion(animal, c).0))
+        .collect();
+
+    let total: u32 = preferences.weights.values().sum();
+    let mut ranked: Vec<(u32, Value)> = animals
+        .into_iter()
+        .map(|mut animal| {
+            let mut earned = 0.0;
+            let mut reasons = Vec::new();
+            for (name, &weight) in &preferences.weights {
+                let (points, reason) = match criterion(name, weight) {
+                    Some(c) => {
+                        let (met, reason) = check_criterion(&animal, &c);
+                        (if met { f64::from(weight) } else { 0.0 }, reason)
+                    }
+                    None => nearby(&animal, weight, miles),
+                };
+                earned += points;
+                reasons.push(reason);
+            }
+            for c in &required {
+                reasons.push(format!("{} (required)", check_criterion(&animal, c).1));
+            }
+            let score = if total == 0 {
+                100
+            } else {
+                (earned * 100.0 / f64::from(total)).round() as u32
+            };
+            animal["meta"] = json!({ "score": score, "reasons": reasons });
+            (score, animal)
+        })
+        .collect();
+    ranked.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    let ranked: Vec<Value> = ranked.into_iter().map(|(_, animal)| animal).collect();
+    data["excluded"] = json!(found - ranked.len());
+    data["data"] = json!(ranked);
+    data["preferences"] = json!(preferences);
+}
+
+/// Points for being close, with the reason shown for it.
+fn nearby(animal: &Value, weight: u32, miles: u32) -> (f64, String) {
+    match coordinate(&animal["attributes"]["distance"]) {
+        Some(distance) => {
+            let closeness = (1.0 - distance / f64::from(miles.max(1))).clamp(0.0, 1.0);
+            let mark = if closeness >= 0.5 { "✅" } else { "❌" };
+            (
+                f64::from(weight) * closeness,
+                format!("{} Nearby ({:.1} miles)", mark, distance),
+            )
+        }
+        None => (0.0, "❔ Nearby (distance unknown)".to_string()),
+    }
+}
+
+/// The score and reasons of each ranked animal, for structured output.
+pub fn ranking(data: &Value) -> Vec<Value> {
+    data["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|animal| {
+            json!({
+                "id": animal["id"],
+                "name": animal["attributes"]["name"],
+                "score": animal["meta"]["score"],
+                "reasons": animal["meta"]["reasons"],
+            })
+        })
+        .collect()
+}
+
+/// The preferences stored for this session, if any.
+pub async fn session_preferences(settings: &Settings) -> Option<Preferences> {
+    SESSION_PREFERENCES.get(&session_key(settings).ok()?).await
+}
+
+/// Stores preferences for this session's later searches; empty ones clear
+/// them.
+pub async fn set_session_preferences(
+    settings: &Settings,
+    preferences: Preferences,
+) -> Result<(), AppError> {
+    preferences.validate()?;
+    let key = session_key(settings)?;
+    if preferences.is_empty() {
+        SESSION_PREFERENCES.invalidate(&key).await;
+    } else {
+        SESSION_PREFERENCES.insert(key, preferences).await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animal(id: &str, attributes: Value) -> Value {
+        json!({ "id": id, "attributes": attributes })
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut data = json!({ "data": [
+            animal("1", json!({ "ageGroup": "Adult", "sizeGroup": "Small", "isGoodWithCats": "Yes", "distance": 40.0 })),
+            animal("2", json!({ "ageGroup": "Senior", "sizeGroup": "Large", "isGoodWithCats": "Yes", "distance": 5.0 })),
+            animal("3", json!({ "ageGroup": "Senior", "sizeGroup": "Small", "isGoodWithCats": "No" })),
+            animal("4", json!({ "ageGroup": "Senior", "sizeGroup": "Small", "isGoodWithCats": "Yes", "distance": "10" })),
+        ]});
+        let preferences = Preferences {
+            weights: BTreeMap::from([
+                ("senior".to_string(), 3),
+                ("small".to_string(), 2),
+                (NEARBY.to_string(), 5),
+            ]),
+            required: vec!["good_with_cats".to_string()],
+        };
+        rank(&mut data, &preferences, 50);
+
+        let ids: Vec<&str> = data["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["id"].as_str().unwrap())
+            .collect();
+        // Not good with cats is out; nearby seniors first
+        assert_eq!(ids, ["4", "2", "1"]);
+        assert_eq!(data["excluded"], 1);
+        // 3 + 2 + 5 * 0.8 of 10
+        assert_eq!(data["data"][0]["meta"]["score"], 90);
+        let reasons = data["data"][1]["meta"]["reasons"].as_array().unwrap();
+        assert!(reasons.contains(&json!("✅ Nearby (5.0 miles)")));
+        assert!(reasons.contains(&json!("❌ Small (Large)")));
+        assert!(reasons.contains(&json!("✅ Good with cats (Yes) (required)")));
+        assert_eq!(ranking(&data)[2]["id"], "1");
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut preferences = Preferences::default();
+        preferences.weights.insert("senoir".to_string(), 2);
+        assert!(matches!(
+            preferences.validate(),
+            Err(AppError::ValidationError { message: msg, .. }) if msg.contains("Did you mean: senior?")
+        ));
+
+        let preferences = Preferences {
+            weights: BTreeMap::from([("senior".to_string(), MAX_WEIGHT + 1)]),
+            required: Vec::new(),
+        };
+        assert!(preferences.validate().is_err());
+
+        let preferences = Preferences {
+            weights: BTreeMap::new(),
+            required: vec![NEARBY.to_string()],
+        };
+        assert!(preferences.validate().is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::client::{fetch_all_pages, fetch_upstream};
+use crate::config::{PrivateApiConfig, Settings};
+use crate::error::AppError;
+use crate::shared_cache;
+use crate::trace;
+use moka::future::Cache;
+use serde_json::{json, Value};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+// Account tokens are short-lived; refresh well before they expire
+static TOKEN_CACHE: LazyLock<Cache<String, String>> = LazyLock::new(|| {
+    Cache::builder()
+        .max_capacity(4)
+        .time_to_live(Duration::from_secs(55 * 60))
+        .build()
+});
+
+fn private_config(settings: &Settings) -> Result<&PrivateApiConfig, AppError> {
+    settings.private_api.as_ref().ok_or_else(|| {
+        AppError::ConfigError(
+            "The private API requires a [private_api] section with username and password"
+                .to_string(),
+        )
+    })
+}
+
+/// Exchanges the account credentials for a bearer token, cached per account.
+async fn account_token(settings: &Settings) -> Result<String, AppError> {
+    let config = private_config(settings)?;
+    let key = format!("{}:{}", settings.base_url, config.username);
+    if let Some(token) = TOKEN_CACHE.get(&key).await {
+        return Ok(token);
+    }
+
+    let url = format!("{}{}", settings.base_url, config.login_path);
+    if settings.dry_run {
+        let body = json!({ "username": config.username, "password": "[redacted]" });
+        let headers = [
+            ("Authorization", settings.api_key.as_str()),
+            ("Content-Type", "application/json"),
+        ];
+        trace::planned(settings, "POST", &url, &headers, Some(&body));
+        return Err(AppError::DryRun);
+    }
+
+    // The login takes its turn with the rate limit like any request, but
+    // skips tracing and cassettes, which would keep the password and token
+    shared_cache::until_ready(settings, &settings.limiter).await?;
+    let client = reqwest::Client::builder()
+        .timeout(settings.request_timeout()?)
+        .build()
+        .map_err(|e| AppError::Internal(format!("Failed to build client: {}", e)))?;
+    let response = client
+        .post(url)
+        .header("Authorization", &settings.api_key)
+        .json(&json!({
+            "username": config.username,
+            "password": config.password,
+        }))
+        .send()
+        .await
+        .map_err(|e| settings.deadline_error(e))?;
+
+    if !response.status().is_success() {
+        return Err(AppError::ConfigError(format!(
+            "RescueGroups account login failed: {}",
+            response.status()
+        )));
+    }
+
+    let body: Value = response.json().await?;
+    let token = ["token", "access_token"]
+        .iter()
+        .find_map(|field| body[field].as_str().or(body["data"][field].as_str()))
+        .ok_or_else(|| AppError::ApiError("Account login returned no token".to_string()))?
+        .to_string();
+    TOKEN_CACHE.insert(key, token.clone()).await;
+    Ok(token)
+}
+
+/// Every animal of the logged-in account's organization, including ones
+/// that are not (yet) public, with status and species names in `included`.
+pub async fn list_private_animals(settings: &Settings) -> Result<Value, AppError> {
+    let auth = format!("Bearer {}", account_token(settings).await?);
+    let url = format!("{}/animals?include=statuses,species", settings.base_url);
+    fetch_all_pages(settings, &url, Some(&auth)).await
+}
+
+/// One animal of the account's organization, whether public or not.
+pub async fn get_private_animal(settings: &Settings, animal_id: &str) -> Result<Value, AppError> {
+    let auth = format!("Bearer {}", account_token(settings).await?);
+    let url = format!(
+        "{}/animals/{}?include=pictures,statuses,species,breeds",
+        settings.base_url, animal_id
+    );
+    fetch_upstream(settings, &url, "GET", None, &auth).await
+}
+
+/// The organization record(s) the account belongs to.
+pub async fn get_private_org(settings: &Settings) -> Result<Value, AppError> {
+    let auth = format!("Bearer {}", account_token(settings).await?);
+    let url = format!("{}/orgs", settings.base_url);
+    fetch_upstream(settings, &url, "GET", None, &auth).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_settings(url: String, private_api: Option<PrivateApiConfig>) -> Settings {
+        Settings {
+            private_api,
+            ..Settings::for_test(url, std::env::temp_dir().join("rescue-groups-mcp-test"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requires_config() {
+        let settings = get_test_settings("http://unused".to_string(), None);
+        assert!(matches!(
+            list_private_animals(&settings).await,
+            Err(AppError::ConfigError(msg)) if msg.contains("[private_api]")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_private_animals() {
+        let mut server = mockito::Server::new_async().await;
+        let _login = server
+            .mock("POST", "/login")
+            .match_header("authorization", "test_key")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"username": "staff", "password": "secret"}),
+            ))
+            .with_status(200)
+            .with_body(r#"{"data": {"token": "acct-token"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _animals = server
+            .mock("GET", "/animals")
+            .match_query(mockito::Matcher::Any)
+            .match_header("authorization", "Bearer acct-token")
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "1", "attributes": {"name": "Intake Ivy", "isPublic": false}}], "meta": {"pages": 1}}"#,
+            )
+            .create_async()
+            .await;
+        let _animal = server
+            .mock("GET", "/animals/1")
+            .match_query(mockito::Matcher::Any)
+            .match_header("authorization", "Bearer acct-token")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "1", "attributes": {"name": "Intake Ivy"}}]}"#)
+            .create_async()
+            .await;
+
+        let settings = get_test_settings(
+            server.url(),
+            Some(PrivateApiConfig {
+                username: "staff".to_string(),
+                password: "secret".to_string(),
+                login_path: "/login".to_string(),
+            }),
+        );
+        let data = list_private_animals(&settings).await.unwrap();
+        assert_eq!(data["data"][0]["attributes"]["name"], "Intake Ivy");
+
+        // The token is reused for later requests
+        let data = get_private_animal(&settings, "1").await.unwrap();
+        assert_eq!(data["data"][0]["id"], "1");
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_does_not_log_in() {
+        let mut server = mockito::Server::new_async().await;
+        let login = server.mock("POST", "/login").expect(0).create_async().await;
+
+        let log = crate::trace::RequestLog::default();
+        let settings = Settings {
+            dry_run: true,
+            request_log: Some(log.clone()),
+            ..get_test_settings(
+                server.url(),
+                Some(PrivateApiConfig {
+                    username: "staff".to_string(),
+                    password: "secret".to_string(),
+                    login_path: "/login".to_string(),
+                }),
+            )
+        };
+        assert!(matches!(
+            list_private_animals(&settings).await,
+            Err(AppError::DryRun)
+        ));
+        login.assert_async().await;
+
+        let planned = log.lock().unwrap();
+        assert_eq!(planned[0]["url"], format!("{}/login", server.url()));
+        assert_eq!(planned[0]["body"]["password"], "[redacted]");
+    }
+}
@@ -0,0 +1,558 @@
+use crate::cli::{MetadataArgs, SpeciesArgs, ToolArgs};
+use crate::client::{list_breeds, list_metadata, list_species};
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::synonyms::SPECIES_SYNONYMS;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::warn;
+
+/// Known values the parser matches free text against, loaded from the
+/// (cached) metadata endpoints.
+#[derive(Debug, Default, Clone)]
+pub struct Vocabulary {
+    /// (singular, plural), lowercase
+    pub species: Vec<(String, String)>,
+    pub breeds: Vec<String>,
+    pub colors: Vec<String>,
+    pub patterns: Vec<String>,
+    pub qualities: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct QueryMatch {
+    pub term: String,
+    pub field: String,
+    pub value: Value,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct ParsedQuery {
+    pub args: ToolArgs,
+    pub matched: Vec<QueryMatch>,
+    pub notes: Vec<String>,
+    pub unrecognized: Vec<String>,
+}
+
+// Filler words that carry no search meaning
+const STOPWORDS: &[&str] = &[
+    "a",
+    "an",
+    "and",
+    "any",
+    "for",
+    "from",
+    "i",
+    "in",
+    "is",
+    "looking",
+    "me",
+    "my",
+    "near",
+    "of",
+    "or",
+    "pet",
+    "please",
+    "show",
+    "that",
+    "the",
+    "to",
+    "want",
+    "who",
+    "with",
+    "within",
+    "zip",
+    "around",
+    "find",
+    "would",
+    "like",
+    "some",
+    "one",
+    "home",
+    "adopt",
+    "adoptable",
+];
+
+const AGES: &[(&str, &str)] = &[
+    ("baby", "Baby"),
+    ("young", "Young"),
+    ("adult", "Adult"),
+    ("senior", "Senior"),
+    ("elderly", "Senior"),
+];
+
+const SIZES: &[(&str, &str)] = &[
+    ("extra large", "X-Large"),
+    ("x large", "X-Large"),
+    ("giant", "X-Large"),
+    ("small", "Small"),
+    ("tiny", "Small"),
+    ("little", "Small"),
+    ("medium", "Medium"),
+    ("large", "Large"),
+    ("big", "Large"),
+];
+
+const SEXES: &[(&str, &str)] = &[
+    ("male", "Male"),
+    ("boy", "Male"),
+    ("female", "Female"),
+    ("girl", "Female"),
+];
+
+const SORTS: &[(&str, &str)] = &[
+    ("newest", "Newest"),
+    ("recently listed", "Newest"),
+    ("closest", "Distance"),
+    ("nearest", "Distance"),
+    ("random", "Random"),
+];
+
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect()
+}
+
+struct Tokens {
+    words: Vec<String>,
+    consumed: Vec<bool>,
+}
+
+impl Tokens {
+    fn new(text: &str) -> Self {
+        let words: Vec<String> = normalize(text)
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        let consumed = vec![false; words.len()];
+        Tokens { words, consumed }
+    }
+
+    /// Finds an unconsumed occurrence of `phrase` and consumes it.
+    fn take(&mut self, phrase: &str) -> bool {
+        let phrase: Vec<String> = normalize(phrase)
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        if phrase.is_empty() || phrase.len() > self.words.len() {
+            return false;
+        }
+        for start in 0..=self.words.len() - phrase.len() {
+            let range = start..start + phrase.len();
+            if self.words[range.clone()] == phrase[..]
+                && !self.consumed[range.clone()].contains(&true)
+            {
+                self.consumed[range].iter_mut().for_each(|c| *c = true);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Consumes the first unconsumed phrase from `candidates` (longest
+    /// first) and returns it.
+    fn take_any<'a>(&mut self, candidates: &'a [String]) -> Option<&'a String> {
+        let mut sorted: Vec<&String> = candidates.iter().collect();
+        sorted.sort_by_key(|c| std::cmp::Reverse(c.split_whitespace().count()));
+        sorted.into_iter().find(|c| self.take(c))
+    }
+
+    fn leftovers(&self) -> Vec<String> {
+        self.words
+            .iter()
+            .zip(&self.consumed)
+            .filter(|(word, consumed)| !**consumed && !STOPWORDS.contains(&word.as_str()))
+            .map(|(word, _)| word.clone())
+            .collect()
+    }
+}
+
+fn record(parsed: &mut ParsedQuery, term: &str, field: &str, value: Value) {
+    parsed.matched.push(QueryMatch {
+        term: term.to_string(),
+        field: field.to_string(),
+        value,
+    });
+}
+
+/// Finds the species named in `query`, as the plural used in search URLs.
+pub fn detect_species(query: &str, vocabulary: &Vocabulary) -> Option<String> {
+    let mut tokens = Tokens::new(query);
+    for (word, species, _) in SPECIES_SYNONYMS {
+        if tokens.take(word) {
+            return Some(species.to_string());
+        }
+    }
+    vocabulary
+        .species
+        .iter()
+        .find(|(singular, plural)| tokens.take(plural) || tokens.take(singular))
+        .map(|(_, plural)| plural.clone())
+}
+
+/// Rule-based conversion of a free-text request into search arguments.
+pub fn parse_with_vocabulary(query: &str, vocabulary: &Vocabulary) -> ParsedQuery {
+    let mut tokens = Tokens::new(query);
+    let mut parsed = ParsedQuery::default();
+
+    // Location: "within 30 miles of 78704"
+    for i in 0..tokens.words.len() {
+        let word = tokens.words[i].clone();
+        if !word.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let unit = tokens.words.get(i + 1).map(String::as_str);
+        if matches!(unit, Some("miles") | Some("mile") | Some("mi")) {
+            if let Ok(miles) = word.parse::<u32>() {
+                tokens.consumed[i] = true;
+                tokens.consumed[i + 1] = true;
+                parsed.args.miles = Some(miles);
+                record(
+                    &mut parsed,
+                    &format!("{} miles", word),
+                    "miles",
+                    json!(miles),
+                );
+            }
+        } else if word.len() == 5 && parsed.args.postal_code.is_none() {
+            tokens.consumed[i] = true;
+            parsed.args.postal_code = Some(word.clone());
+            record(&mut parsed, &word, "postal_code", json!(word));
+        }
+    }
+
+    // Compatibility: "good with cats and dogs", "kid friendly"
+    let companions = [
+        (
+            "good_with_children",
+            &["kids", "children", "kid", "child", "toddlers"][..],
+        ),
+        ("good_with_dogs", &["dogs", "dog", "other dogs"][..]),
+        ("good_with_cats", &["cats", "cat", "other cats"][..]),
+    ];
+    let good_with = tokens
+        .words
+        .windows(2)
+        .position(|w| w[0] == "good" && w[1] == "with");
+    if let Some(start) = good_with {
+        tokens.consumed[start] = true;
+        tokens.consumed[start + 1] = true;
+        let mut i = start + 2;
+        while i < tokens.words.len() {
+            let word = tokens.words[i].as_str();
+            if word == "and" || word == "other" {
+                tokens.consumed[i] = true;
+                i += 1;
+                continue;
+            }
+            let Some((field, _)) = companions.iter().find(|(_, words)| words.contains(&word))
+            else {
+                break;
+            };
+            tokens.consumed[i] = true;
+            record(
+                &mut parsed,
+                &format!("good with {}", word),
+                field,
+                json!(true),
+            );
+            i += 1;
+        }
+    }
+    for (field, words) in companions {
+        for word in words {
+            if tokens.take(&format!("{} friendly", word)) {
+                record(
+                    &mut parsed,
+                    &format!("{} friendly", word),
+                    field,
+                    json!(true),
+                );
+            }
+        }
+    }
+    for m in parsed.matched.clone() {
+        match m.field.as_str() {
+            "good_with_children" => parsed.args.good_with_children = Some(true),
+            "good_with_dogs" => parsed.args.good_with_dogs = Some(true),
+            "good_with_cats" => parsed.args.good_with_cats = Some(true),
+            _ => {}
+        }
+    }
+
+    for phrase in [
+        "house trained",
+        "housetrained",
+        "housebroken",
+        "potty trained",
+    ] {
+        if tokens.take(phrase) {
+            parsed.args.house_trained = Some(true);
+            record(&mut parsed, phrase, "house_trained", json!(true));
+        }
+    }
+    if tokens.take("special needs") {
+        parsed.args.special_needs = Some(true);
+        record(&mut parsed, "special needs", "special_needs", json!(true));
+    }
+    for phrase in ["needs foster", "foster"] {
+        if tokens.take(phrase) {
+            parsed.args.needs_foster = Some(true);
+            record(&mut parsed, phrase, "needs_foster", json!(true));
+        }
+    }
+
+    // Species, including "puppy"/"kitten" which also imply an age
+    for (word, species, age) in SPECIES_SYNONYMS {
+        if parsed.args.species.is_none() && tokens.take(word) {
+            parsed.args.species = Some(species.to_string());
+            record(&mut parsed, word, "species", json!(species));
+            if let Some(age) = age {
+                parsed.args.age = Some(age.to_string());
+                record(&mut parsed, word, "age", json!(age));
+            }
+        }
+    }
+    if parsed.args.species.is_none() {
+        for (singular, plural) in &vocabulary.species {
+            if tokens.take(plural) || tokens.take(singular) {
+                parsed.args.species = Some(plural.clone());
+                record(&mut parsed, singular, "species", json!(plural));
+                break;
+            }
+        }
+    }
+
+    // Breeds are matched before sizes and colors, since many breed names
+    // contain them ("Miniature Poodle", "Black Mouth Cur").
+    let species_names: Vec<&String> = vocabulary
+        .species
+        .iter()
+        .flat_map(|(s, p)| [s, p])
+        .collect();
+    let breeds: Vec<String> = vocabulary
+        .breeds
+        .iter()
+        .filter(|b| !species_names.contains(&&b.to_lowercase()))
+        .cloned()
+        .collect();
+    if let Some(breed) = tokens.take_any(&breeds) {
+        parsed.args.breeds = Some(breed.clone());
+        record(&mut parsed, &breed.to_lowercase(), "breeds", json!(breed));
+        if let Some(other) = tokens.take_any(&breeds) {
+            parsed.notes.push(format!(
+                "Only one breed can be searched at a time; ignored '{}'.",
+                other
+            ));
+        }
+    }
+
+    let keyword_fields: [(&str, &[(&str, &str)]); 4] = [
+        ("age", AGES),
+        ("size", SIZES),
+        ("sex", SEXES),
+        ("sort_by", SORTS),
+    ];
+    for (field, keywords) in keyword_fields {
+        for (word, value) in keywords {
+            if tokens.take(word) {
+                let slot = match field {
+                    "age" => &mut parsed.args.age,
+                    "size" => &mut parsed.args.size,
+                    "sex" => &mut parsed.args.sex,
+                    _ => &mut parsed.args.sort_by,
+                };
+                if slot.is_none() {
+                    *slot = Some(value.to_string());
+                    record(&mut parsed, word, field, json!(value));
+                }
+            }
+        }
+    }
+
+    if let Some(color) = tokens.take_any(&vocabulary.colors) {
+        parsed.args.color = Some(color.clone());
+        record(&mut parsed, &color.to_lowercase(), "color", json!(color));
+    }
+    if let Some(pattern) = tokens.take_any(&vocabulary.patterns) {
+        parsed.args.pattern = Some(pattern.clone());
+        record(
+            &mut parsed,
+            &pattern.to_lowercase(),
+            "pattern",
+            json!(pattern),
+        );
+    }
+
+    while let Some(quality) = tokens.take_any(&vocabulary.qualities) {
+        parsed.notes.push(format!(
+            "'{}' is an animal quality, which searches can't filter on; check each animal's description.",
+            quality
+        ));
+    }
+
+    parsed.unrecognized = tokens.leftovers();
+    parsed
+}
+
+fn names(data: Result<Value, AppError>, what: &str) -> Vec<String> {
+    match data {
+        Ok(data) => data["data"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|i| i["attributes"]["name"].as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        Err(e) => {
+            warn!("Failed to load {} vocabulary: {}", what, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Loads the vocabularies for the species named in `query` (or the default
+/// species) and parses it. Metadata responses are cached like any other
+/// API call, so repeated parsing is cheap.
+pub async fn parse_search_query(settings: &Settings, query: &str) -> Result<Value, AppError> {
+    let species_data = list_species(settings).await?;
+    let mut vocabulary = Vocabulary {
+        species: species_data["data"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|s| {
+                        let attrs = &s["attributes"];
+                        Some((
+                            attrs["singular"].as_str()?.to_lowercase(),
+                            attrs["plural"].as_str()?.to_lowercase(),
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let species =
+        detect_species(query, &vocabulary).unwrap_or_else(|| settings.default_species.clone());
+    let metadata = |metadata_type: &str| MetadataArgs {
+        metadata_type: metadata_type.to_string(),
+        species: Some(species.clone()),
+    };
+    vocabulary.breeds = names(
+        list_breeds(
+            settings,
+            SpeciesArgs {
+                species: species.clone(),
+            },
+        )
+        .await,
+        "breeds",
+    );
+    vocabulary.colors = names(list_metadata(settings, metadata("colors")).await, "colors");
+    vocabulary.patterns = names(
+        list_metadata(settings, metadata("patterns")).await,
+        "patterns",
+    );
+    vocabulary.qualities = names(
+        list_metadata(
+            settings,
+            MetadataArgs {
+                metadata_type: "qualities".to_string(),
+                species: None,
+            },
+        )
+        .await,
+        "qualities",
+    );
+
+    let parsed = parse_with_vocabulary(query, &vocabulary);
+    Ok(json!({
+        "query": query,
+        "args": parsed.args,
+        "matched": parsed.matched,
+        "notes": parsed.notes,
+        "unrecognized": parsed.unrecognized,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocabulary() -> Vocabulary {
+        Vocabulary {
+            species: vec![
+                ("dog".to_string(), "dogs".to_string()),
+                ("cat".to_string(), "cats".to_string()),
+            ],
+            breeds: vec![
+                "Poodle".to_string(),
+                "Miniature Poodle".to_string(),
+                "Labrador Retriever".to_string(),
+            ],
+            colors: vec!["Black".to_string(), "White".to_string()],
+            patterns: vec!["Brindle".to_string()],
+            qualities: vec!["Hypoallergenic".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_parse_example_query() {
+        let parsed = parse_with_vocabulary(
+            "young small hypoallergenic dog good with cats within 30 miles of 78704",
+            &vocabulary(),
+        );
+        assert_eq!(parsed.args.species.as_deref(), Some("dogs"));
+        assert_eq!(parsed.args.age.as_deref(), Some("Young"));
+        assert_eq!(parsed.args.size.as_deref(), Some("Small"));
+        assert_eq!(parsed.args.good_with_cats, Some(true));
+        assert_eq!(parsed.args.miles, Some(30));
+        assert_eq!(parsed.args.postal_code.as_deref(), Some("78704"));
+        assert!(parsed.notes[0].contains("Hypoallergenic"));
+        assert!(parsed.unrecognized.is_empty());
+    }
+
+    #[test]
+    fn test_parse_breeds_and_compatibility() {
+        let parsed = parse_with_vocabulary(
+            "Black miniature poodle, good with kids and dogs, house-trained female",
+            &vocabulary(),
+        );
+        // The longest breed wins over the "Poodle" it contains
+        assert_eq!(parsed.args.breeds.as_deref(), Some("Miniature Poodle"));
+        assert_eq!(parsed.args.color.as_deref(), Some("Black"));
+        assert_eq!(parsed.args.good_with_children, Some(true));
+        assert_eq!(parsed.args.good_with_dogs, Some(true));
+        assert_eq!(parsed.args.house_trained, Some(true));
+        assert_eq!(parsed.args.sex.as_deref(), Some("Female"));
+    }
+
+    #[test]
+    fn test_parse_puppy_and_unrecognized() {
+        let parsed = parse_with_vocabulary("newest fluffy puppies", &vocabulary());
+        assert_eq!(parsed.args.species.as_deref(), Some("dogs"));
+        assert_eq!(parsed.args.age.as_deref(), Some("Baby"));
+        assert_eq!(parsed.args.sort_by.as_deref(), Some("Newest"));
+        assert_eq!(parsed.unrecognized, vec!["fluffy"]);
+    }
+
+    #[test]
+    fn test_detect_species() {
+        assert_eq!(
+            detect_species("a calm cat please", &vocabulary()).as_deref(),
+            Some("cats")
+        );
+        assert_eq!(
+            detect_species("kitten", &vocabulary()).as_deref(),
+            Some("cats")
+        );
+        assert_eq!(detect_species("something cute", &vocabulary()), None);
+    }
+}
@@ -0,0 +1,305 @@
+use crate::error::AppError;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+/// Requests waiting for the rate limit when `rate_limit_queue` isn't set.
+pub const DEFAULT_QUEUE_LIMIT: usize = 50;
+
+/// How urgently a request needs a rate limit slot.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Priority {
+    /// A tool call someone is waiting on
+    #[default]
+    Interactive,
+    /// Alert polling, digests and other jobs nobody is watching
+    Background,
+}
+
+/// Bounded line of requests waiting for the rate limit. Sessions take turns,
+/// one request each, so a client firing off a burst can't hold up the others,
+/// and background requests only go when no interactive one is waiting. Once
+/// `capacity` requests are waiting, new ones are turned away instead of
+/// waiting indefinitely.
+#[derive(Debug)]
+pub struct RequestQueue {
+    capacity: usize,
+    state: Mutex<QueueState>,
+    turn: Notify,
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    next_ticket: u64,
+    interactive: Line,
+    background: Line,
+    queued: usize,
+}
+
+/// Waiting requests of one priority, by session.
+#[derive(Debug, Default)]
+struct Line {
+    /// Sessions with waiting requests, the one being served first
+    sessions: VecDeque<(String, VecDeque<u64>)>,
+}
+
+impl Line {
+    fn front(&self) -> Option<u64> {
+        self.sessions
+            .front()
+            .and_then(|(_, tickets)| tickets.front())
+            .copied()
+    }
+
+    fn push(&mut self, session: &str, ticket: u64) {
+        match self.sessions.iter_mut().find(|(s, _)| s == session) {
+            Some((_, tickets)) => tickets.push_back(ticket),
+            None => self
+                .sessions
+                .push_back((session.to_string(), VecDeque::from([ticket]))),
+        }
+    }
+
+    /// Drops `ticket`, sending its session to the back of the line if it was
+    /// just served. False if it isn't in this line.
+    fn remove(&mut self, ticket: u64) -> bool {
+        let Some(position) = self
+            .sessions
+            .iter()
+            .position(|(_, tickets)| tickets.contains(&ticket))
+        else {
+            return false;
+        };
+        let served = self.front() == Some(ticket);
+        let (session, mut tickets) = self.sessions.remove(position).unwrap();
+        tickets.retain(|&t| t != ticket);
+        if tickets.is_empty() {
+            return true;
+        }
+        if served {
+            self.sessions.push_back((session, tickets));
+        } else {
+            self.sessions.insert(position, (session, tickets));
+        }
+        true
+    }
+}
+
+impl QueueState {
+    fn is_next(&self, ticket: u64) -> bool {
+        self.interactive.front().or(self.background.front()) == Some(ticket)
+    }
+
+    fn remove(&mut self, ticket: u64) {
+        if self.interactive.remove(ticket) || self.background.remove(ticket) {
+            self.queued -= 1;
+        }
+    }
+}
+
+impl Default for RequestQueue {
+    fn default() -> Self {
+        RequestQueue::new(DEFAULT_QUEUE_LIMIT)
+    }
+}
+
+/// A place in the queue, given up when the request is through or dropped.
+struct Ticket<'a> {
+    queue: &'a RequestQueue,
+    id: u64,
+}
+
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().remove(self.id);
+        self.queue.turn.notify_waiters();
+    }
+}
+
+impl RequestQueue {
+    /// A queue holding at most `capacity` waiting requests.
+    pub fn new(capacity: usize) -> Self {
+        RequestQueue {
+            capacity,
+            state: Mutex::new(QueueState::default()),
+            turn: Notify::new(),
+        }
+    }
+
+    /// Requests currently waiting, including the one being let through.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().queued
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether new requests are being turned away.
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.capacity
+    }
+
+    /// Waits for `session`'s turn, then for `ready` (the rate limiter). A
+    /// background request waiting for `ready` gives up its turn when an
+    /// interactive one arrives, and calls `ready` again on its next turn.
+    /// Fails right away when the queue is full.
+    pub async fn run<T, F: Future<Output = T>>(
+        &self,
+        session: &str,
+        priority: Priority,
+        ready: impl Fn() -> F,
+    ) -> Result<T, AppError> {
+        let ticket = {
+            let mut state = self.state.lock().unwrap();
+            if state.queued >= self.capacity {
+                return Err(AppError::RateLimited(format!(
+                    "Rate limited, {} requests queued; try again shortly",
+                    state.queued
+                )));
+            }
+            let id = state.next_ticket;
+            state.next_ticket += 1;
+            state.queued += 1;
+            match priority {
+                Priority::Interactive => state.interactive.push(session, id),
+                Priority::Background => state.background.push(session, id),
+            }
+            Ticket { queue: self, id }
+        };
+        if priority == Priority::Interactive {
+            self.turn.notify_waiters();
+        }
+
+        loop {
+            // Registered before checking, so a turn ending in between isn't missed
+            let turn = self.turn.notified();
+            if !self.state.lock().unwrap().is_next(ticket.id) {
+                turn.await;
+                continue;
+            }
+            if priority == Priority::Interactive {
+                return Ok(ready().await);
+            }
+            tokio::select! {
+                value = ready() => return Ok(value),
+                _ = turn => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::sync::Semaphore;
+
+    #[tokio::test]
+    async fn test_sessions_take_turns() {
+        let queue = Arc::new(RequestQueue::new(10));
+        // Holds everyone up until all requests are queued
+        let gate = Arc::new(Semaphore::new(0));
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let mut tasks = Vec::new();
+        for (i, session) in ["a", "a", "a", "b"].into_iter().enumerate() {
+            let (queue, gate, order) = (queue.clone(), gate.clone(), order.clone());
+            tasks.push(tokio::spawn(async move {
+                queue
+                    .run(session, Priority::Interactive, || async {
+                        gate.acquire().await.unwrap().forget();
+                        order.lock().unwrap().push(format!("{}{}", session, i));
+                    })
+                    .await
+            }));
+            tokio::task::yield_now().await;
+        }
+        while queue.len() < 4 {
+            tokio::task::yield_now().await;
+        }
+        gate.add_permits(4);
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        // "b" doesn't wait behind all of "a"'s burst
+        assert_eq!(*order.lock().unwrap(), ["a0", "b3", "a1", "a2"]);
+        assert!(queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_interactive_requests_go_first() {
+        let queue = Arc::new(RequestQueue::new(10));
+        let gate = Arc::new(Semaphore::new(0));
+        let background = {
+            let (queue, gate) = (queue.clone(), gate.clone());
+            tokio::spawn(async move {
+                queue
+                    .run("alerts", Priority::Background, || async {
+                        gate.acquire().await.unwrap().forget()
+                    })
+                    .await
+            })
+        };
+        while queue.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        // Takes the turn the background request was waiting with
+        queue
+            .run("a", Priority::Interactive, || async {})
+            .await
+            .unwrap();
+        assert_eq!(queue.len(), 1);
+
+        gate.add_permits(1);
+        background.await.unwrap().unwrap();
+        assert!(queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_rejects() {
+        let queue = Arc::new(RequestQueue::new(2));
+        let gate = Arc::new(Semaphore::new(0));
+        let mut tasks = Vec::new();
+        for _ in 0..2 {
+            let (queue, gate) = (queue.clone(), gate.clone());
+            tasks.push(tokio::spawn(async move {
+                queue
+                    .run("a", Priority::Interactive, || async {
+                        gate.acquire().await.unwrap().forget()
+                    })
+                    .await
+            }));
+        }
+        while queue.len() < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        let err = queue
+            .run("b", Priority::Interactive, || async {})
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, AppError::RateLimited(msg) if msg.contains("Rate limited, 2 requests queued"))
+        );
+
+        gate.add_permits(2);
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        assert!(queue
+            .run("b", Priority::Interactive, || async { 1 })
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_request_leaves_queue() {
+        let queue = RequestQueue::new(1);
+        let waiting = queue.run("a", Priority::Interactive, std::future::pending::<()>);
+        let _ = tokio::time::timeout(std::time::Duration::from_millis(10), waiting).await;
+        assert!(queue.is_empty());
+    }
+}
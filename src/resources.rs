@@ -0,0 +1,127 @@
+use crate::error::AppError;
+use crate::validate;
+use serde_json::{json, Value};
+
+/// Scheme of the URIs resources are read from.
+pub const SCHEME: &str = "rescuegroups://";
+
+/// URI templates clients can fill in with IDs from the conversation.
+pub fn resource_templates() -> Vec<Value> {
+    vec![
+        json!({
+            "uriTemplate": "rescuegroups://animal/{animal_id}",
+            "name": "Animal",
+            "description": "An adoptable animal's profile, with its organization and location.",
+            "mimeType": "text/markdown"
+        }),
+        json!({
+            "uriTemplate": "rescuegroups://org/{org_id}",
+            "name": "Organization",
+            "description": "A rescue organization's details and contact information.",
+            "mimeType": "text/markdown"
+        }),
+        json!({
+            "uriTemplate": "rescuegroups://org/{org_id}/animals?species={species}",
+            "name": "Organization animals",
+            "description": "The animals an organization has available for adoption; species is optional.",
+            "mimeType": "text/markdown"
+        }),
+    ]
+}
+
+/// The tool and arguments that read `uri`.
+pub fn resolve_resource(uri: &str) -> Result<(&'static str, Value), AppError> {
+    let unknown = || {
+        AppError::invalid_argument(
+            "uri",
+            format!(
+                "Unknown resource '{}'. Use one of: {}",
+                uri,
+                resource_templates()
+                    .iter()
+                    .filter_map(|t| t["uriTemplate"].as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        )
+    };
+    let rest = uri.strip_prefix(SCHEME).ok_or_else(unknown)?;
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+
+    match segments.as_slice() {
+        ["animal", animal_id] => {
+            validate::animal_id(animal_id)?;
+            Ok(("get_animal_details", json!({ "animal_id": animal_id })))
+        }
+        ["org", org_id] => {
+            validate::numeric_id("org_id", org_id)?;
+            Ok(("get_organization_details", json!({ "org_id": org_id })))
+        }
+        ["org", org_id, "animals"] => {
+            validate::numeric_id("org_id", org_id)?;
+            let mut arguments = json!({ "org_id": org_id });
+            for pair in query.split('&').filter(|p| !p.is_empty()) {
+                match pair.split_once('=') {
+                    Some(("species", species)) if !species.is_empty() => {
+                        arguments["species"] = json!(species);
+                    }
+                    // An unfilled `{species}` means any species
+                    Some(("species", _)) => {}
+                    _ => {
+                        return Err(AppError::invalid_argument(
+                            "uri",
+                            format!(
+                            "Unknown parameter '{}' in resource '{}': only species is supported",
+                            pair, uri
+                        ),
+                        ))
+                    }
+                }
+            }
+            Ok(("list_org_animals", arguments))
+        }
+        _ => Err(unknown()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_resource() {
+        assert_eq!(
+            resolve_resource("rescuegroups://animal/10123456").unwrap(),
+            ("get_animal_details", json!({ "animal_id": "10123456" }))
+        );
+        assert_eq!(
+            resolve_resource("rescuegroups://org/866").unwrap(),
+            ("get_organization_details", json!({ "org_id": "866" }))
+        );
+        assert_eq!(
+            resolve_resource("rescuegroups://org/866/animals?species=cats").unwrap(),
+            (
+                "list_org_animals",
+                json!({ "org_id": "866", "species": "cats" })
+            )
+        );
+        assert_eq!(
+            resolve_resource("rescuegroups://org/866/animals").unwrap(),
+            ("list_org_animals", json!({ "org_id": "866" }))
+        );
+
+        assert!(matches!(
+            resolve_resource("rescuegroups://animal/fluffy"),
+            Err(AppError::ValidationError { message: msg, .. }) if msg.contains("malformed")
+        ));
+        assert!(matches!(
+            resolve_resource("rescuegroups://org/866/animals?sort=random"),
+            Err(AppError::ValidationError { message: msg, .. }) if msg.contains("'sort=random'")
+        ));
+        assert!(matches!(
+            resolve_resource("https://example.com/animal/1"),
+            Err(AppError::ValidationError { message: msg, .. }) if msg.contains("rescuegroups://animal/{animal_id}")
+        ));
+    }
+}
@@ -0,0 +1,331 @@
+use crate::cli::SemanticSearchArgs;
+use crate::config::{EmbeddingsConfig, Settings};
+use crate::error::AppError;
+use crate::source::search_animals;
+use futures::future::BoxFuture;
+use moka::future::Cache;
+use serde_json::{json, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+const HASHING_DIMENSIONS: usize = 512;
+const DEFAULT_LIMIT: usize = 5;
+
+// Embeddings of animal descriptions, keyed by embedder and animal ID, so
+// repeated searches over the same listings only embed new animals.
+static EMBEDDING_INDEX: LazyLock<Cache<String, Arc<Vec<f32>>>> = LazyLock::new(|| {
+    Cache::builder()
+        .max_capacity(5_000)
+        .time_to_live(Duration::from_secs(24 * 60 * 60))
+        .build()
+});
+
+/// Turns text into vectors whose cosine similarity reflects relatedness.
+pub trait Embedder: Send + Sync {
+    /// Identifies the model, so cached vectors are never mixed across models.
+    fn id(&self) -> String;
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Vec<f32>>, AppError>>;
+}
+
+/// Dependency-free fallback: a bag of stemmed words hashed into a fixed
+/// number of dimensions. It only matches shared vocabulary, so configure an
+/// embeddings API for true semantic matching.
+pub struct HashingEmbedder;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "he",
+    "her", "his", "i", "in", "is", "it", "its", "me", "my", "of", "on", "or", "she", "so", "that",
+    "the", "their", "they", "this", "to", "was", "we", "who", "will", "with", "you", "your",
+];
+
+fn stem(word: &str) -> &str {
+    for suffix in ["ing", "ed", "es", "s"] {
+        if word.len() > suffix.len() + 2 {
+            if let Some(stripped) = word.strip_suffix(suffix) {
+                return stripped;
+            }
+        }
+    }
+    word
+}
+
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 1 && !STOPWORDS.contains(w))
+        .map(|w| stem(w).to_string())
+        .collect()
+}
+
+fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+impl HashingEmbedder {
+    pub fn embed_one(text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; HASHING_DIMENSIONS];
+        for token in tokenize(text) {
+            let mut hasher = DefaultHasher::new();
+            token.hash(&mut hasher);
+            vector[(hasher.finish() as usize) % HASHING_DIMENSIONS] += 1.0;
+        }
+        normalize(vector)
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn id(&self) -> String {
+        "hashing".to_string()
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Vec<f32>>, AppError>> {
+        Box::pin(async move { Ok(texts.iter().map(|t| Self::embed_one(t)).collect()) })
+    }
+}
+
+/// Calls an OpenAI-compatible `/embeddings` endpoint.
+pub struct ApiEmbedder {
+    url: String,
+    api_key: Option<String>,
+    model: String,
+    timeout: Duration,
+}
+
+impl Embedder for ApiEmbedder {
+    fn id(&self) -> String {
+        format!("{}#{}", self.url, self.model)
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Vec<f32>>, AppError>> {
+        Box::pin(async move {
+            let client = reqwest::Client::builder()
+                .timeout(self.timeout)
+                .build()
+                .map_err(|e| AppError::Internal(format!("Failed to build client: {}", e)))?;
+            let mut request = client
+                .post(&self.url)
+                .json(&json!({ "model": self.model, "input": texts }));
+            if let Some(key) = &self.api_key {
+                request = request.bearer_auth(key);
+            }
+
+            let response = request.send().await?;
+            if !response.status().is_success() {
+                return Err(AppError::Upstream {
+                    status: response.status().as_u16(),
+                    message: format!("Embeddings API Error: {}", response.status()),
+                });
+            }
+
+            let body: Value = response.json().await?;
+            let vectors: Vec<Vec<f32>> = body["data"]
+                .as_array()
+                .map(|items| {
+                    items
+                        .iter()
+                        .map(|item| {
+                            let vector = item["embedding"]
+                                .as_array()
+                                .map(|v| {
+                                    v.iter()
+                                        .filter_map(|x| x.as_f64())
+                                        .map(|x| x as f32)
+                                        .collect()
+                                })
+                                .unwrap_or_default();
+                            normalize(vector)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            if vectors.len() != texts.len() {
+                return Err(AppError::ApiError(format!(
+                    "Embeddings API returned {} vectors for {} inputs",
+                    vectors.len(),
+                    texts.len()
+                )));
+            }
+            Ok(vectors)
+        })
+    }
+}
+
+pub fn build_embedder(config: &EmbeddingsConfig, timeout: Duration) -> Box<dyn Embedder> {
+    match &config.url {
+        Some(url) => Box::new(ApiEmbedder {
+            url: url.clone(),
+            api_key: config.api_key.clone(),
+            model: config
+                .model
+                .clone()
+                .unwrap_or_else(|| "text-embedding-3-small".to_string()),
+            timeout,
+        }),
+        None => Box::new(HashingEmbedder),
+    }
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    // Vectors are normalized on creation
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// The text an animal is indexed by: name, breed and description.
+pub fn animal_text(animal: &Value) -> String {
+    let attrs = &animal["attributes"];
+    [
+        attrs["name"].as_str(),
+        attrs["breedString"].as_str(),
+        attrs["ageGroup"].as_str(),
+        attrs["sizeGroup"].as_str(),
+        attrs["descriptionText"].as_str(),
+    ]
+    .iter()
+    .flatten()
+    .cloned()
+    .collect::<Vec<&str>>()
+    .join(". ")
+}
+
+/// Ranks animals by similarity to `query`, embedding only the animals not
+/// already in the index.
+pub async fn rank_animals(
+    embedder: &dyn Embedder,
+    query: &str,
+    animals: Vec<Value>,
+) -> Result<Vec<(f32, Value)>, AppError> {
+    let embedder_id = embedder.id();
+    let key = |animal: &Value| format!("{}:{}", embedder_id, animal["id"]);
+
+    let mut missing = Vec::new();
+    for animal in &animals {
+        if !EMBEDDING_INDEX.contains_key(&key(animal)) {
+            missing.push(animal);
+        }
+    }
+
+    let mut texts: Vec<String> = missing.iter().map(|a| animal_text(a)).collect();
+    texts.push(query.to_string());
+    let mut vectors = embedder.embed(&texts).await?;
+    let query_vector = vectors.pop().unwrap_or_default();
+    for (animal, vector) in missing.iter().zip(vectors) {
+        EMBEDDING_INDEX.insert(key(animal), Arc::new(vector)).await;
+    }
+
+    let mut ranked = Vec::new();
+    for animal in animals {
+        let score = match EMBEDDING_INDEX.get(&key(&animal)).await {
+            Some(vector) => cosine_similarity(&query_vector, &vector),
+            None => 0.0,
+        };
+        ranked.push((score, animal));
+    }
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    Ok(ranked)
+}
+
+/// Fetches candidates with the regular search filters, then orders them by
+/// how well their descriptions match a free-text query.
+pub async fn semantic_search(
+    settings: &Settings,
+    args: SemanticSearchArgs,
+) -> Result<Value, AppError> {
+    let data = search_animals(settings, args.search).await?;
+    let animals = data["data"].as_array().cloned().unwrap_or_default();
+
+    let embedder = build_embedder(&settings.embeddings, settings.timeout);
+    let ranked = rank_animals(embedder.as_ref(), &args.query, animals).await?;
+
+    let results: Vec<Value> = ranked
+        .into_iter()
+        .take(args.limit.unwrap_or(DEFAULT_LIMIT))
+        .map(|(score, mut animal)| {
+            animal["meta"] = json!({ "score": score });
+            animal
+        })
+        .collect();
+
+    Ok(json!({ "data": results, "query": args.query }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animal(id: &str, name: &str, description: &str) -> Value {
+        json!({
+            "id": id,
+            "attributes": { "name": name, "descriptionText": description }
+        })
+    }
+
+    #[test]
+    fn test_tokenize_and_stem() {
+        assert_eq!(
+            tokenize("The dog is sleeping on couches!"),
+            vec!["dog", "sleep", "couch"]
+        );
+    }
+
+    #[test]
+    fn test_hashing_embedder_similarity() {
+        let calm = HashingEmbedder::embed_one("calm quiet dog, loves napping on the couch");
+        let query = HashingEmbedder::embed_one("calm couch dog");
+        let active = HashingEmbedder::embed_one("energetic runner who needs hiking");
+        assert!(cosine_similarity(&query, &calm) > cosine_similarity(&query, &active));
+        assert!((cosine_similarity(&calm, &calm) - 1.0).abs() < 1e-5);
+    }
+
+    #[tokio::test]
+    async fn test_rank_animals() {
+        let animals = vec![
+            animal("sem-1", "Zoom", "High energy, loves running and agility"),
+            animal(
+                "sem-2",
+                "Snooze",
+                "Calm couch potato, fine alone during the workday",
+            ),
+        ];
+        let ranked = rank_animals(&HashingEmbedder, "calm couch potato", animals)
+            .await
+            .unwrap();
+        assert_eq!(ranked[0].1["attributes"]["name"], "Snooze");
+        assert!(ranked[0].0 > ranked[1].0);
+    }
+
+    #[tokio::test]
+    async fn test_api_embedder() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/embeddings")
+            .match_header("authorization", "Bearer key")
+            .with_status(200)
+            .with_body(r#"{"data": [{"embedding": [3.0, 4.0]}, {"embedding": [1.0, 0.0]}]}"#)
+            .create_async()
+            .await;
+
+        let config = EmbeddingsConfig {
+            url: Some(format!("{}/embeddings", server.url())),
+            api_key: Some("key".to_string()),
+            model: None,
+        };
+        let embedder = build_embedder(&config, Duration::from_secs(1));
+        assert!(embedder.id().ends_with("#text-embedding-3-small"));
+        let vectors = embedder
+            .embed(&["a".to_string(), "b".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(vectors[0], vec![0.6, 0.8]);
+
+        // Mismatched vector counts are rejected
+        let res = embedder.embed(&["a".to_string()]).await;
+        assert!(matches!(res, Err(AppError::ApiError(_))));
+    }
+}
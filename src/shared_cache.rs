@@ -0,0 +1,284 @@
+use crate::config::Settings;
+use crate::error::AppError;
+use governor::{
+    clock::DefaultClock,
+    state::{InMemoryState, NotKeyed},
+    RateLimiter,
+};
+use serde_json::Value;
+use std::time::Duration;
+
+/// How long shared entries live, matching the local response cache.
+#[cfg_attr(not(feature = "redis"), allow(dead_code))]
+const TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Response cache and fixed-window rate limit kept in Redis or Valkey, so
+/// replicas behind a load balancer share one view of the upstream quota.
+/// Any error talking to the server is logged and the replica falls back to
+/// its local cache and limiter.
+pub struct SharedCache {
+    #[cfg(feature = "redis")]
+    client: redis::Client,
+    #[cfg(feature = "redis")]
+    connection: tokio::sync::OnceCell<redis::aio::MultiplexedConnection>,
+    #[cfg_attr(not(feature = "redis"), allow(dead_code))]
+    prefix: String,
+    #[cfg_attr(not(feature = "redis"), allow(dead_code))]
+    max_requests: u32,
+    #[cfg_attr(not(feature = "redis"), allow(dead_code))]
+    window: Duration,
+}
+
+impl std::fmt::Debug for SharedCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedCache")
+            .field("prefix", &self.prefix)
+            .field("max_requests", &self.max_requests)
+            .field("window", &self.window)
+            .finish()
+    }
+}
+
+impl SharedCache {
+    /// Validates `url`; the connection itself is opened on first use.
+    #[cfg(feature = "redis")]
+    pub fn connect(
+        url: &str,
+        prefix: &str,
+        max_requests: u32,
+        window: Duration,
+    ) -> Result<Self, AppError> {
+        let client = redis::Client::open(url)
+            .map_err(|e| AppError::ConfigError(format!("Invalid [cache] redis_url: {}", e)))?;
+        Ok(SharedCache {
+            client,
+            connection: tokio::sync::OnceCell::new(),
+            prefix: prefix.to_string(),
+            max_requests,
+            window: window.max(Duration::from_secs(1)),
+        })
+    }
+
+    #[cfg(not(feature = "redis"))]
+    pub fn connect(
+        _url: &str,
+        _prefix: &str,
+        _max_requests: u32,
+        _window: Duration,
+    ) -> Result<Self, AppError> {
+        Err(AppError::ConfigError(
+            "[cache] redis_url requires a build with the `redis` feature".to_string(),
+        ))
+    }
+
+    #[cfg_attr(not(feature = "redis"), allow(dead_code))]
+    fn response_key(&self, key: &str) -> String {
+        use sha2::{Digest, Sha256};
+        let digest = hex::encode(Sha256::digest(key.as_bytes()));
+        format!("{}:response:{}", self.prefix, digest)
+    }
+
+    #[cfg(feature = "redis")]
+    async fn connection(&self) -> Result<redis::aio::MultiplexedConnection, redis::RedisError> {
+        self.connection
+            .get_or_try_init(|| self.client.get_multiplexed_async_connection())
+            .await
+            .cloned()
+    }
+
+    #[cfg(feature = "redis")]
+    async fn get(&self, key: &str) -> Option<Value> {
+        use redis::AsyncCommands;
+        let result: Result<Option<String>, redis::RedisError> =
+            async { self.connection().await?.get(self.response_key(key)).await }.await;
+        match result {
+            Ok(cached) => cached.and_then(|s| serde_json::from_str(&s).ok()),
+            Err(e) => {
+                tracing::warn!("Shared cache read failed: {}", e);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "redis"))]
+    async fn get(&self, _key: &str) -> Option<Value> {
+        None
+    }
+
+    #[cfg(feature = "redis")]
+    async fn insert(&self, key: &str, value: &Value) {
+        use redis::AsyncCommands;
+        let result: Result<(), redis::RedisError> = async {
+            self.connection()
+                .await?
+                .set_ex(self.response_key(key), value.to_string(), TTL.as_secs())
+                .await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("Shared cache write failed: {}", e);
+        }
+    }
+
+    #[cfg(not(feature = "redis"))]
+    async fn insert(&self, _key: &str, _value: &Value) {}
+
+    #[cfg(feature = "redis")]
+    async fn remove(&self, key: &str) {
+        use redis::AsyncCommands;
+        let result: Result<(), redis::RedisError> =
+            async { self.connection().await?.del(self.response_key(key)).await }.await;
+        if let Err(e) = result {
+            tracing::warn!("Shared cache delete failed: {}", e);
+        }
+    }
+
+    #[cfg(not(feature = "redis"))]
+    async fn remove(&self, _key: &str) {}
+
+    /// Waits until the shared request count of the current window is within
+    /// `rate_limit_requests`, counting this request.
+    #[cfg(feature = "redis")]
+    async fn until_ready(&self) {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let window_ms = self.window.as_millis() as u64;
+        loop {
+            let now_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default();
+            let slot = now_ms / window_ms;
+            let key = format!("{}:ratelimit:{}", self.prefix, slot);
+
+            let count: Result<u32, redis::RedisError> = async {
+                let mut connection = self.connection().await?;
+                let (count,): (u32,) = redis::pipe()
+                    .atomic()
+                    .incr(&key, 1)
+                    .expire(&key, (self.window.as_secs() * 2) as i64)
+                    .ignore()
+                    .query_async(&mut connection)
+                    .await?;
+                Ok(count)
+            }
+            .await;
+
+            match count {
+                Ok(count) if count > self.max_requests => {
+                    let remaining = (slot + 1) * window_ms - now_ms;
+                    tokio::time::sleep(Duration::from_millis(remaining)).await;
+                }
+                Ok(_) => return,
+                Err(e) => {
+                    tracing::warn!("Shared rate limit unavailable, using local limiter: {}", e);
+                    return;
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "redis"))]
+    async fn until_ready(&self) {}
+}
+
+/// Looks `key` up in the local cache, then in the shared one. Shared hits
+/// are copied into the local cache.
+pub async fn cached(settings: &Settings, key: &str) -> Option<Value> {
+    // A dry run shows every request, even ones the cache would answer
+    if settings.dry_run {
+        return None;
+    }
+    if let Some(value) = settings.cache.get(key).await {
+        return Some(value);
+    }
+    let value = settings.shared_cache.as_ref()?.get(key).await?;
+    settings.cache.insert(key.to_string(), value.clone()).await;
+    Some(value)
+}
+
+/// Stores a response in the local and, if configured, the shared cache.
+pub async fn store(settings: &Settings, key: String, value: &Value) {
+    if let Some(shared) = &settings.shared_cache {
+        shared.insert(&key, value).await;
+    }
+    settings.cache.insert(key, value.clone()).await;
+}
+
+/// Drops a response from the local and, if configured, the shared cache.
+pub async fn invalidate(settings: &Settings, key: &str) {
+    if let Some(shared) = &settings.shared_cache {
+        shared.remove(key).await;
+    }
+    settings.cache.invalidate(key).await;
+}
+
+/// Waits its turn in the request queue for `limiter` and, if configured,
+/// the limit shared across replicas. Fails when the queue is full.
+pub async fn until_ready(
+    settings: &Settings,
+    limiter: &RateLimiter<NotKeyed, InMemoryState, DefaultClock>,
+) -> Result<(), AppError> {
+    let session = settings.session.as_deref().unwrap_or_default();
+    settings
+        .queue
+        .run(session, settings.priority, || limiter.until_ready())
+        .await?;
+    until_shared_ready(settings).await;
+    Ok(())
+}
+
+/// Waits only for the limit shared across replicas, if there is one.
+pub async fn until_shared_ready(settings: &Settings) {
+    if let Some(shared) = &settings.shared_cache {
+        shared.until_ready().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "redis"))]
+    #[test]
+    fn test_connect_requires_feature() {
+        let err = SharedCache::connect("redis://127.0.0.1", "x", 60, Duration::from_secs(60))
+            .unwrap_err();
+        assert!(matches!(err, AppError::ConfigError(msg) if msg.contains("`redis` feature")));
+    }
+
+    #[cfg(feature = "redis")]
+    #[tokio::test]
+    async fn test_unreachable_server_falls_back() {
+        // Nothing listens on the discard port, so every call degrades to a miss
+        let shared =
+            SharedCache::connect("redis://127.0.0.1:9", "test", 1, Duration::from_secs(60))
+                .unwrap();
+        assert!(shared.get("GET:/animals").await.is_none());
+        shared.insert("GET:/animals", &serde_json::json!({})).await;
+        shared.until_ready().await;
+    }
+
+    #[cfg(feature = "redis")]
+    #[test]
+    fn test_invalid_url() {
+        let err =
+            SharedCache::connect("not a url", "test", 1, Duration::from_secs(60)).unwrap_err();
+        assert!(matches!(err, AppError::ConfigError(_)));
+    }
+
+    #[test]
+    fn test_response_key_is_prefixed_hash() {
+        let shared = SharedCache {
+            #[cfg(feature = "redis")]
+            client: redis::Client::open("redis://127.0.0.1").unwrap(),
+            #[cfg(feature = "redis")]
+            connection: tokio::sync::OnceCell::new(),
+            prefix: "prod".to_string(),
+            max_requests: 1,
+            window: Duration::from_secs(1),
+        };
+        let key = shared.response_key("GET:https://api.rescuegroups.org/v5/public/orgs/1:");
+        assert!(key.starts_with("prod:response:"));
+        assert_eq!(key.len(), "prod:response:".len() + 64);
+    }
+}
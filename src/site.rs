@@ -0,0 +1,293 @@
+use crate::cli::OrgIdArgs;
+use crate::client::{get_organization_details, list_all_org_animals};
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::fmt::escape_html;
+use crate::models::{parse_list, parse_single, Animal, Organization};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+const STYLESHEET: &str = "body{font-family:system-ui,sans-serif;max-width:960px;margin:0 auto;padding:1rem;color:#222}
+a{color:#2a6f97}
+.grid{display:grid;grid-template-columns:repeat(auto-fill,minmax(200px,1fr));gap:1rem;list-style:none;padding:0}
+.card{border:1px solid #ddd;border-radius:8px;overflow:hidden}
+.card img{width:100%;height:200px;object-fit:cover}
+.card p{margin:.5rem}
+.photos img{max-width:100%;margin-bottom:.5rem}
+table{border-collapse:collapse}
+td,th{text-align:left;padding:.25rem 1rem .25rem 0}
+footer{margin-top:2rem;font-size:.8rem;color:#666}
+";
+
+/// File name of an animal's details page. IDs come from the API, so only
+/// safe characters are kept.
+fn animal_page(animal: &Animal) -> String {
+    let id: String = animal
+        .id
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+    format!("{}.html", id)
+}
+
+fn page(title: &str, stylesheet: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<link rel=\"stylesheet\" href=\"{}\">\n</head>\n<body>\n{}\n<footer>Generated by rescue-groups-mcp</footer>\n</body>\n</html>\n",
+        escape_html(title),
+        stylesheet,
+        body
+    )
+}
+
+fn org_contact(org: &Organization) -> String {
+    let attrs = &org.attributes;
+    let mut lines = Vec::new();
+    let location: Vec<&str> = [attrs.city.as_deref(), attrs.state.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect();
+    if !location.is_empty() {
+        lines.push(escape_html(&location.join(", ")));
+    }
+    if let Some(email) = &attrs.email {
+        lines.push(format!(
+            "<a href=\"mailto:{0}\">{0}</a>",
+            escape_html(email)
+        ));
+    }
+    if let Some(phone) = &attrs.phone {
+        lines.push(escape_html(phone));
+    }
+    if let Some(url) = &attrs.url {
+        lines.push(format!("<a href=\"{0}\">{0}</a>", escape_html(url)));
+    }
+    format!("<p>{}</p>", lines.join("<br>\n"))
+}
+
+pub fn render_index(org: &Organization, animals: &[Animal]) -> String {
+    let org_name = org
+        .attributes
+        .name
+        .as_deref()
+        .unwrap_or("Adoptable Animals");
+    let cards: Vec<String> = animals
+        .iter()
+        .map(|animal| {
+            let attrs = &animal.attributes;
+            let picture = attrs
+                .orgs_animals_pictures
+                .first()
+                .and_then(|p| p.url_secure_fullsize.as_deref())
+                .map(|p| format!("<img src=\"{}\" alt=\"\" loading=\"lazy\">", escape_html(p)))
+                .unwrap_or_default();
+            format!(
+                "<li class=\"card\"><a href=\"animals/{}\">{}<p><strong>{}</strong></p></a><p>{}</p></li>",
+                animal_page(animal),
+                picture,
+                escape_html(attrs.name.as_deref().unwrap_or("Unknown")),
+                escape_html(attrs.breed_string.as_deref().unwrap_or("Mix"))
+            )
+        })
+        .collect();
+
+    let about = org
+        .attributes
+        .about
+        .as_deref()
+        .map(|a| format!("<p>{}</p>\n", escape_html(a)))
+        .unwrap_or_default();
+
+    let body = format!(
+        "<h1>{}</h1>\n{}{}\n<h2>Adoptable Animals ({})</h2>\n<ul class=\"grid\">\n{}\n</ul>",
+        escape_html(org_name),
+        about,
+        org_contact(org),
+        animals.len(),
+        cards.join("\n")
+    );
+    page(org_name, "style.css", &body)
+}
+
+pub fn render_animal(org: &Organization, animal: &Animal) -> String {
+    let attrs = &animal.attributes;
+    let name = attrs.name.as_deref().unwrap_or("Unknown");
+
+    let photos: String = attrs
+        .orgs_animals_pictures
+        .iter()
+        .filter_map(|p| p.url_secure_fullsize.as_deref())
+        .map(|p| {
+            format!(
+                "<img src=\"{}\" alt=\"{}\">\n",
+                escape_html(p),
+                escape_html(name)
+            )
+        })
+        .collect();
+
+    let rows: String = [
+        ("Breed", &attrs.breed_string),
+        ("Sex", &attrs.sex),
+        ("Age", &attrs.age_group),
+        ("Size", &attrs.size_group),
+        ("Color", &attrs.color_details),
+        ("Good with children", &attrs.is_good_with_children),
+        ("Good with dogs", &attrs.is_good_with_dogs),
+        ("Good with cats", &attrs.is_good_with_cats),
+        ("House-trained", &attrs.is_house_trained),
+        ("Special needs", &attrs.is_special_needs),
+    ]
+    .iter()
+    .filter_map(|(label, value)| {
+        value
+            .as_deref()
+            .map(|v| format!("<tr><th>{}</th><td>{}</td></tr>\n", label, escape_html(v)))
+    })
+    .collect();
+
+    let adopt_link = attrs
+        .url
+        .as_deref()
+        .map(|u| {
+            format!(
+                "<p><a href=\"{}\">Apply to adopt {}</a></p>\n",
+                escape_html(u),
+                escape_html(name)
+            )
+        })
+        .unwrap_or_default();
+
+    let body = format!(
+        "<p><a href=\"../index.html\">&larr; All animals</a></p>\n<h1>{}</h1>\n<div class=\"photos\">\n{}</div>\n<table>\n{}</table>\n<p>{}</p>\n{}<h2>Contact {}</h2>\n{}",
+        escape_html(name),
+        photos,
+        rows,
+        escape_html(attrs.description_text.as_deref().unwrap_or("No description available.")),
+        adopt_link,
+        escape_html(org.attributes.name.as_deref().unwrap_or("the rescue")),
+        org_contact(org)
+    );
+    page(name, "../style.css", &body)
+}
+
+/// Renders an organization's adoptable animals to a static HTML site in
+/// `out_dir`: an index, one page per animal and a stylesheet. Pages of
+/// animals no longer listed are removed, so the export can run from cron.
+pub async fn export_site(
+    settings: &Settings,
+    org_id: &str,
+    out_dir: &Path,
+) -> Result<Value, AppError> {
+    let org: Organization = parse_single(
+        &get_organization_details(
+            settings,
+            OrgIdArgs {
+                org_id: org_id.to_string(),
+            },
+        )
+        .await?,
+    )?;
+    let animals: Vec<Animal> = parse_list(&list_all_org_animals(settings, org_id).await?)?;
+
+    let animals_dir = out_dir.join("animals");
+    if animals_dir.exists() {
+        fs::remove_dir_all(&animals_dir)?;
+    }
+    fs::create_dir_all(&animals_dir)?;
+
+    fs::write(out_dir.join("style.css"), STYLESHEET)?;
+    fs::write(out_dir.join("index.html"), render_index(&org, &animals))?;
+    for animal in &animals {
+        fs::write(
+            animals_dir.join(animal_page(animal)),
+            render_animal(&org, animal),
+        )?;
+    }
+
+    Ok(json!({
+        "org_id": org_id,
+        "org_name": org.attributes.name,
+        "path": out_dir.display().to_string(),
+        "animals": animals.len(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_settings(url: String) -> Settings {
+        Settings::for_test(url, std::env::temp_dir().join("rescue-groups-mcp-test"))
+    }
+
+    #[test]
+    fn test_render_escapes_and_links() {
+        let org: Organization = serde_json::from_value(json!({
+            "id": "9",
+            "attributes": {"name": "Paws & Claws", "email": "hi@paws.org"}
+        }))
+        .unwrap();
+        let animal: Animal = serde_json::from_value(json!({
+            "id": "12/../x",
+            "attributes": {
+                "name": "<Rex>",
+                "isGoodWithDogs": "No",
+                "orgsAnimalsPictures": [{"urlSecureFullsize": "http://rex.jpg"}]
+            }
+        }))
+        .unwrap();
+
+        let index = render_index(&org, std::slice::from_ref(&animal));
+        assert!(index.contains("<h1>Paws &amp; Claws</h1>"));
+        assert!(index.contains("href=\"animals/12x.html\""));
+        assert!(index.contains("&lt;Rex&gt;"));
+        assert!(index.contains("Adoptable Animals (1)"));
+
+        let details = render_animal(&org, &animal);
+        assert!(details.contains("<img src=\"http://rex.jpg\""));
+        assert!(details.contains("<tr><th>Good with dogs</th><td>No</td></tr>"));
+        assert!(details.contains("mailto:hi@paws.org"));
+        assert!(details.contains("href=\"../style.css\""));
+    }
+
+    #[tokio::test]
+    async fn test_export_site() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+        let out_dir = std::env::temp_dir().join("rescue-groups-mcp-site");
+        let _ = fs::remove_dir_all(&out_dir);
+
+        // A page left over from a previous export of an adopted animal
+        fs::create_dir_all(out_dir.join("animals")).unwrap();
+        fs::write(out_dir.join("animals/adopted.html"), "").unwrap();
+
+        let _org = server
+            .mock("GET", "/public/orgs/5")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "5", "attributes": {"name": "Shelter"}}]}"#)
+            .create_async()
+            .await;
+        let _animals = server
+            .mock(
+                "GET",
+                "/public/orgs/5/animals/search/available?limit=250&page=1",
+            )
+            .with_status(200)
+            .with_body(
+                r#"{"data": [{"id": "1", "attributes": {"name": "Ace"}}, {"id": "2", "attributes": {"name": "Bo"}}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let summary = export_site(&settings, "5", &out_dir).await.unwrap();
+        assert_eq!(summary["animals"], 2);
+        assert!(out_dir.join("index.html").exists());
+        assert!(out_dir.join("style.css").exists());
+        assert!(out_dir.join("animals/1.html").exists());
+        assert!(out_dir.join("animals/2.html").exists());
+        assert!(!out_dir.join("animals/adopted.html").exists());
+
+        fs::remove_dir_all(&out_dir).unwrap();
+    }
+}
@@ -0,0 +1,424 @@
+use crate::cli::{AnimalIdArgs, OrgSearchArgs, SpeciesArgs, ToolArgs};
+use crate::client;
+use crate::config::{Settings, SourceKind};
+use crate::error::AppError;
+use crate::petfinder::PetfinderSource;
+use crate::postal::{annotate_distances, sort_by_distance};
+use crate::validate;
+use futures::future::{join_all, BoxFuture};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+
+/// A backend that provides animal listings. Every implementation returns
+/// responses in the RescueGroups JSON:API shape (`{"data": [...]}` with
+/// `attributes` per item) so the formatters work regardless of the source.
+pub trait AnimalSource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Whether an animal or organization ID was issued by this source.
+    fn owns_id(&self, id: &str) -> bool;
+
+    fn search<'a>(
+        &'a self,
+        settings: &'a Settings,
+        args: ToolArgs,
+    ) -> BoxFuture<'a, Result<Value, AppError>>;
+
+    fn get_animal<'a>(
+        &'a self,
+        settings: &'a Settings,
+        animal_id: &'a str,
+    ) -> BoxFuture<'a, Result<Value, AppError>>;
+
+    fn search_orgs<'a>(
+        &'a self,
+        settings: &'a Settings,
+        args: OrgSearchArgs,
+    ) -> BoxFuture<'a, Result<Value, AppError>>;
+
+    fn list_breeds<'a>(
+        &'a self,
+        settings: &'a Settings,
+        args: SpeciesArgs,
+    ) -> BoxFuture<'a, Result<Value, AppError>>;
+}
+
+pub struct RescueGroupsSource;
+
+impl AnimalSource for RescueGroupsSource {
+    fn name(&self) -> &'static str {
+        "rescuegroups"
+    }
+
+    fn owns_id(&self, id: &str) -> bool {
+        !id.starts_with(crate::petfinder::ID_PREFIX)
+    }
+
+    fn search<'a>(
+        &'a self,
+        settings: &'a Settings,
+        args: ToolArgs,
+    ) -> BoxFuture<'a, Result<Value, AppError>> {
+        Box::pin(client::fetch_pets(settings, args))
+    }
+
+    fn get_animal<'a>(
+        &'a self,
+        settings: &'a Settings,
+        animal_id: &'a str,
+    ) -> BoxFuture<'a, Result<Value, AppError>> {
+        Box::pin(client::get_animal_details(
+            settings,
+            AnimalIdArgs {
+                animal_id: animal_id.to_string(),
+            },
+        ))
+    }
+
+    fn search_orgs<'a>(
+        &'a self,
+        settings: &'a Settings,
+        args: OrgSearchArgs,
+    ) -> BoxFuture<'a, Result<Value, AppError>> {
+        Box::pin(client::search_organizations(settings, args))
+    }
+
+    fn list_breeds<'a>(
+        &'a self,
+        settings: &'a Settings,
+        args: SpeciesArgs,
+    ) -> BoxFuture<'a, Result<Value, AppError>> {
+        Box::pin(client::list_breeds(settings, args))
+    }
+}
+
+/// The sources selected by the `sources` config key, in configured order.
+pub fn configured_sources(settings: &Settings) -> Vec<Box<dyn AnimalSource>> {
+    settings
+        .sources
+        .iter()
+        .filter_map(|kind| -> Option<Box<dyn AnimalSource>> {
+            match kind {
+                SourceKind::RescueGroups => Some(Box::new(RescueGroupsSource)),
+                SourceKind::Petfinder => settings
+                    .petfinder
+                    .clone()
+                    .map(|config| Box::new(PetfinderSource::new(config)) as Box<dyn AnimalSource>),
+            }
+        })
+        .collect()
+}
+
+/// Merges responses from several sources by interleaving their `data`
+/// arrays, so the first page of results shows listings from every source.
+/// Failing sources are reported in `errors` unless all of them failed.
+fn merge_results(results: Vec<(&'static str, Result<Value, AppError>)>) -> Result<Value, AppError> {
+    let mut lists = Vec::new();
+    let mut errors = Vec::new();
+    let mut first_error = None;
+    let mut truncated = false;
+    let mut total = Some(0);
+
+    for (name, result) in results {
+        match result {
+            Ok(value) => {
+                let list = value["data"].as_array().cloned().unwrap_or_default();
+                truncated |= value["truncated"] == true;
+                // Unknown if any truncated source didn't say how many it has
+                total = match value["total"].as_u64() {
+                    Some(count) => total.map(|t| t + count),
+                    None if value["truncated"] == true => None,
+                    None => total.map(|t| t + list.len() as u64),
+                };
+                lists.push(list);
+            }
+            Err(e) => {
+                warn!("Source '{}' failed: {}", name, e);
+                errors.push(format!("{}: {}", name, e));
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    if lists.is_empty() {
+        return Err(first_error.unwrap_or(AppError::NotFound));
+    }
+
+    let longest = lists.iter().map(|l| l.len()).max().unwrap_or(0);
+    let mut merged = Vec::new();
+    for i in 0..longest {
+        for list in &lists {
+            if let Some(item) = list.get(i) {
+                merged.push(item.clone());
+            }
+        }
+    }
+
+    let mut result = json!({ "data": merged, "errors": errors });
+    if truncated {
+        result["truncated"] = json!(true);
+        result["total"] = json!(total);
+    }
+    Ok(result)
+}
+
+async fn federate<'a, F>(sources: &'a [Box<dyn AnimalSource>], call: F) -> Result<Value, AppError>
+where
+    F: Fn(&'a dyn AnimalSource) -> BoxFuture<'a, Result<Value, AppError>>,
+{
+    if let [source] = sources {
+        return call(source.as_ref()).await;
+    }
+
+    let results = join_all(sources.iter().map(|s| call(s.as_ref()))).await;
+    merge_results(sources.iter().map(|s| s.name()).zip(results).collect())
+}
+
+fn normalized(value: &Value) -> Option<String> {
+    let text: String = value
+        .as_str()?
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Keys under which two listings count as the same animal: the name with
+/// the org's reference number, or name, breed, sex and age together.
+fn listing_keys(animal: &Value) -> Vec<String> {
+    let attrs = &animal["attributes"];
+    let Some(name) = normalized(&attrs["name"]) else {
+        return Vec::new();
+    };
+    let mut keys = Vec::new();
+    if let Some(reference) = normalized(&attrs["rescueId"]) {
+        keys.push(format!("ref:{}:{}", name, reference));
+    }
+    let profile: Option<Vec<String>> = ["breedString", "sex", "ageGroup"]
+        .iter()
+        .map(|field| normalized(&attrs[*field]))
+        .collect();
+    if let Some(profile) = profile {
+        keys.push(format!("profile:{}:{}", name, profile.join(":")));
+    }
+    keys
+}
+
+fn listing(animal: &Value) -> Value {
+    json!({
+        "id": animal["id"],
+        "source": animal["attributes"]["source"].as_str().unwrap_or("rescuegroups"),
+        "org_id": animal["relationships"]["orgs"]["data"][0]["id"],
+        "url": animal["attributes"]["url"],
+    })
+}
+
+/// Merges animals cross-listed by several orgs or backends into the first
+/// listing, which gains a `listings` attribute naming every org that has
+/// the animal, so each can be contacted.
+pub fn merge_cross_listings(animals: Vec<Value>) -> Vec<Value> {
+    let mut merged: Vec<Value> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for animal in animals {
+        let keys = listing_keys(&animal);
+        if let Some(&position) = keys.iter().find_map(|k| index.get(k)) {
+            let first = &mut merged[position]["attributes"];
+            if !first["listings"].is_array() {
+                first["listings"] = json!([]);
+            }
+            if let Some(listings) = first["listings"].as_array_mut() {
+                listings.push(listing(&animal));
+            }
+            for key in keys {
+                index.entry(key).or_insert(position);
+            }
+            continue;
+        }
+        for key in keys {
+            index.entry(key).or_insert(merged.len());
+        }
+        merged.push(animal);
+    }
+
+    // The first listing is included too, once it's known to have company
+    for animal in &mut merged {
+        if let Some(others) = animal["attributes"]["listings"].as_array().cloned() {
+            let mut listings = vec![listing(animal)];
+            listings.extend(others);
+            animal["attributes"]["listings"] = json!(listings);
+        }
+    }
+    merged
+}
+
+/// Searches every configured source for adoptable animals. When several
+/// sources are searched, animals listed on more than one are merged. With
+/// `[widening]` enabled, a search finding nothing is retried farther out,
+/// with a `note` saying so.
+pub async fn search_animals(settings: &Settings, args: ToolArgs) -> Result<Value, AppError> {
+    let mut result = search_within(settings, args.clone()).await?;
+    let widening = &settings.widening;
+    if !widening.enabled || !is_empty(&result) {
+        return Ok(result);
+    }
+
+    let asked = args.miles.unwrap_or(settings.default_miles);
+    let mut miles = asked;
+    loop {
+        let wider = miles
+            .saturating_mul(widening.factor)
+            .min(widening.max_miles);
+        if wider <= miles {
+            result["note"] = json!(format!(
+                "No matches within {} miles, or within {} miles either.",
+                asked, miles
+            ));
+            return Ok(result);
+        }
+        miles = wider;
+        let retry = search_within(
+            settings,
+            ToolArgs {
+                miles: Some(miles),
+                ..args.clone()
+            },
+        )
+        .await?;
+        if !is_empty(&retry) {
+            result = retry;
+            result["note"] = json!(format!(
+                "No matches within {} miles; showing results within {} miles.",
+                asked, miles
+            ));
+            result["widened_miles"] = json!(miles);
+            return Ok(result);
+        }
+    }
+}
+
+fn is_empty(result: &Value) -> bool {
+    result["data"].as_array().is_none_or(Vec::is_empty)
+}
+
+async fn search_within(settings: &Settings, args: ToolArgs) -> Result<Value, AppError> {
+    let sources = configured_sources(settings);
+    let origin = args
+        .postal_code
+        .clone()
+        .unwrap_or_else(|| settings.default_postal_code.clone());
+    let mut result = federate(&sources, |source| {
+        let (args, origin) = (args.clone(), origin.clone());
+        Box::pin(async move {
+            // Distances are filled in per source, while `included` is still there
+            let mut data = source.search(settings, args).await?;
+            annotate_distances(settings, &mut data, &origin);
+            Ok(data)
+        })
+    })
+    .await?;
+    if sources.len() > 1 {
+        if let Some(animals) = result["data"].as_array_mut() {
+            *animals = merge_cross_listings(std::mem::take(animals));
+            // Each source sorted its own page; restore nearest first overall
+            if args.sort_by.as_deref() == Some("Distance") {
+                sort_by_distance(animals);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Fetches an animal from the source that issued its ID.
+pub async fn get_animal(settings: &Settings, args: AnimalIdArgs) -> Result<Value, AppError> {
+    validate::animal_id(&args.animal_id)?;
+    let source = configured_sources(settings)
+        .into_iter()
+        .find(|s| s.owns_id(&args.animal_id))
+        .ok_or(AppError::NotFound)?;
+    source.get_animal(settings, &args.animal_id).await
+}
+
+/// Contact details for an animal. Petfinder listings carry their contact
+/// inline, so their details response already has the `included` org.
+pub async fn get_contact(settings: &Settings, args: AnimalIdArgs) -> Result<Value, AppError> {
+    validate::animal_id(&args.animal_id)?;
+    if RescueGroupsSource.owns_id(&args.animal_id) {
+        client::get_contact_info(settings, args).await
+    } else {
+        get_animal(settings, args).await
+    }
+}
+
+/// Searches every configured source for organizations. With a `species`,
+/// only orgs currently listing that species nearby are kept, each with its
+/// `speciesListings` count, since org records don't say what they take in.
+pub async fn search_orgs(settings: &Settings, args: OrgSearchArgs) -> Result<Value, AppError> {
+    let sources = configured_sources(settings);
+    let orgs = federate(&sources, |source| {
+        source.search_orgs(settings, args.clone())
+    });
+    let Some(species) = args.species.clone() else {
+        return orgs.await;
+    };
+
+    let listings = search_within(
+        settings,
+        ToolArgs {
+            postal_code: args.postal_code.clone(),
+            miles: args.miles,
+            species: Some(species.clone()),
+            ..Default::default()
+        },
+    );
+    let (orgs, listings) = tokio::join!(orgs, listings);
+    let (mut orgs, listings) = (orgs?, listings?);
+
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    for animal in listings["data"].as_array().into_iter().flatten() {
+        if let Some(org_id) = animal["relationships"]["orgs"]["data"][0]["id"].as_str() {
+            *counts.entry(org_id.to_string()).or_default() += 1;
+        }
+    }
+    if let Some(data) = orgs["data"].as_array_mut() {
+        data.retain_mut(|org| {
+            let count = org["id"].as_str().and_then(|id| counts.get(id)).copied();
+            if let Some(count) = count {
+                org["attributes"]["speciesListings"] = json!(count);
+            }
+            count.is_some()
+        });
+    }
+    // The unfiltered total no longer describes what is shown
+    if let Some(result) = orgs.as_object_mut() {
+        result.remove("total");
+    }
+    orgs["species"] = json!(species);
+    Ok(orgs)
+}
+
+/// Lists breeds from every configured source; names known to several
+/// sources are only listed once.
+pub async fn get_breeds(settings: &Settings, args: SpeciesArgs) -> Result<Value, AppError> {
+    let sources = configured_sources(settings);
+    let mut result = federate(&sources, |source| {
+        source.list_breeds(settings, args.clone())
+    })
+    .await?;
+    if sources.len() > 1 {
+        if let Some(breeds) = result["data"].as_array_mut() {
+            let mut seen = HashSet::new();
+            breeds.retain(|b| {
+                seen.insert(
+                    b["attributes"]["name"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_lowercase(),
+                )
+            });
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,127 @@
+use crate::error::AppError;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+// Serializes read-modify-write cycles on the data files between the alert
+// scheduler and concurrent tool calls.
+static LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Default location for locally persisted state (saved searches, favorites,
+/// snapshots), e.g. `~/.local/share/rescue-groups-mcp` on Linux.
+pub fn default_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .map(|d| d.join("rescue-groups-mcp"))
+        .unwrap_or_else(|| PathBuf::from(".rescue-groups-mcp"))
+}
+
+/// Reads a JSON document, returning the default value if the file does not
+/// exist yet.
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T, AppError> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let content = fs::read_to_string(path)?;
+    if content.trim().is_empty() {
+        return Ok(T::default());
+    }
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Writes a JSON document via a temporary file and rename, so a crash
+/// mid-write never leaves a truncated store behind.
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(value)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Loads the JSON document at `path`, applies `f` to it and saves the result,
+/// holding the store lock throughout so concurrent updates don't overwrite
+/// each other. Nothing is saved if `f` fails.
+pub async fn update<T, R>(
+    path: &Path,
+    f: impl FnOnce(&mut T) -> Result<R, AppError>,
+) -> Result<R, AppError>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    let _guard = LOCK.lock().await;
+    let mut value = load_json(path)?;
+    let result = f(&mut value)?;
+    save_json(path, &value)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_load_missing_returns_default() {
+        let path = std::env::temp_dir().join("rescue-groups-mcp-store-missing.json");
+        let _ = fs::remove_file(&path);
+        let value: HashMap<String, String> = load_json(&path).unwrap();
+        assert!(value.is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = std::env::temp_dir().join("rescue-groups-mcp-store-test");
+        let path = dir.join("nested").join("store.json");
+        let mut value = HashMap::new();
+        value.insert("key".to_string(), "value".to_string());
+
+        save_json(&path, &value).unwrap();
+        let loaded: HashMap<String, String> = load_json(&path).unwrap();
+        assert_eq!(loaded, value);
+        assert!(!path.with_extension("json.tmp").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_invalid_json() {
+        let path = std::env::temp_dir().join("rescue-groups-mcp-store-invalid.json");
+        fs::write(&path, "{not json").unwrap();
+        let res: Result<HashMap<String, String>, AppError> = load_json(&path);
+        assert!(matches!(res, Err(AppError::Serialization(_))));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update() {
+        let path = std::env::temp_dir().join("rescue-groups-mcp-store-update.json");
+        let _ = fs::remove_file(&path);
+        let updates = (0..10).map(|i| {
+            let path = path.clone();
+            tokio::spawn(async move {
+                update(&path, |map: &mut HashMap<String, u32>| {
+                    map.insert(format!("key{}", i), i);
+                    Ok(())
+                })
+                .await
+            })
+        });
+        for handle in updates.collect::<Vec<_>>() {
+            handle.await.unwrap().unwrap();
+        }
+        let loaded: HashMap<String, u32> = load_json(&path).unwrap();
+        assert_eq!(loaded.len(), 10);
+
+        let res = update(&path, |map: &mut HashMap<String, u32>| {
+            map.clear();
+            Err::<(), _>(AppError::NotFound)
+        })
+        .await;
+        assert!(matches!(res, Err(AppError::NotFound)));
+        let loaded: HashMap<String, u32> = load_json(&path).unwrap();
+        assert_eq!(loaded.len(), 10);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,144 @@
+/// Everyday words for species, as (word, species, implied age group).
+/// Species are the plurals used in search URLs.
+pub const SPECIES_SYNONYMS: &[(&str, &str, Option<&str>)] = &[
+    ("puppy", "dogs", Some("Baby")),
+    ("puppies", "dogs", Some("Baby")),
+    ("pup", "dogs", Some("Baby")),
+    ("pups", "dogs", Some("Baby")),
+    ("kitten", "cats", Some("Baby")),
+    ("kittens", "cats", Some("Baby")),
+    ("doggo", "dogs", None),
+    ("doggos", "dogs", None),
+    ("doggy", "dogs", None),
+    ("doggie", "dogs", None),
+    ("pupper", "dogs", None),
+    ("hound", "dogs", None),
+    ("kitty", "cats", None),
+    ("kitties", "cats", None),
+    ("bunny", "rabbits", None),
+    ("bunnies", "rabbits", None),
+    ("pony", "horses", None),
+    ("ponies", "horses", None),
+    // Kinds of animal that are listed under a broader species
+    ("parrot", "birds", None),
+    ("parrots", "birds", None),
+    ("parakeet", "birds", None),
+    ("parakeets", "birds", None),
+    ("budgie", "birds", None),
+    ("budgies", "birds", None),
+    ("cockatiel", "birds", None),
+    ("cockatiels", "birds", None),
+    ("lizard", "reptiles", None),
+    ("lizards", "reptiles", None),
+    ("snake", "reptiles", None),
+    ("snakes", "reptiles", None),
+];
+
+/// The species (and age group, for words like "puppy") an everyday word
+/// stands for.
+pub fn species_synonym(term: &str) -> Option<(&'static str, Option<&'static str>)> {
+    let term = term.trim().to_lowercase();
+    SPECIES_SYNONYMS
+        .iter()
+        .find(|(word, _, _)| *word == term)
+        .map(|(_, species, age)| (*species, *age))
+}
+
+/// Levenshtein distance between two strings, by characters.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            current.push(
+                (previous[j] + cost)
+                    .min(previous[j + 1] + 1)
+                    .min(current[j] + 1),
+            );
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The `candidates` close to `term`, closest first, with their edit
+/// distance. A candidate qualifies within half its length in edits, or when
+/// one contains the other. Spacing and punctuation are ignored, so
+/// "golden doodel" is two edits from "Goldendoodle".
+pub fn ranked<'a>(term: &str, candidates: &'a [String]) -> Vec<(usize, &'a String)> {
+    let term = term.trim().to_lowercase();
+    let compact = |s: &str| -> String { s.chars().filter(|c| c.is_alphanumeric()).collect() };
+    let mut scored: Vec<(usize, &String)> = candidates
+        .iter()
+        .filter_map(|candidate| {
+            let lower = candidate.to_lowercase();
+            let distance =
+                edit_distance(&term, &lower).min(edit_distance(&compact(&term), &compact(&lower)));
+            let close = distance <= (lower.chars().count() / 2).max(1);
+            let contained = term.len() >= 3 && (lower.contains(&term) || term.contains(&lower));
+            (close || contained).then_some((distance, candidate))
+        })
+        .collect();
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored
+}
+
+/// Up to three `candidates` close to `term`, closest first.
+pub fn suggest(term: &str, candidates: &[String]) -> Vec<String> {
+    ranked(term, candidates)
+        .into_iter()
+        .take(3)
+        .map(|(_, candidate)| candidate.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_species_synonym() {
+        assert_eq!(species_synonym("Puppy"), Some(("dogs", Some("Baby"))));
+        assert_eq!(species_synonym("bunny"), Some(("rabbits", None)));
+        assert_eq!(species_synonym("doggo"), Some(("dogs", None)));
+        assert_eq!(species_synonym("Parrots"), Some(("birds", None)));
+        assert_eq!(species_synonym("dogs"), None);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("dgos", "dogs"), 2);
+        assert_eq!(edit_distance("cat", "cats"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_suggest() {
+        let species = vec![
+            "dogs".to_string(),
+            "cats".to_string(),
+            "rabbits".to_string(),
+            "horses".to_string(),
+        ];
+        assert_eq!(suggest("dgos", &species), vec!["dogs"]);
+        assert_eq!(suggest("rabit", &species), vec!["rabbits"]);
+        assert!(suggest("zebra", &species).is_empty());
+    }
+
+    #[test]
+    fn test_ranked() {
+        let breeds = vec![
+            "Golden Retriever".to_string(),
+            "Goldendoodle".to_string(),
+            "Labradoodle".to_string(),
+            "Poodle".to_string(),
+        ];
+        let matches = ranked("golden doodel", &breeds);
+        assert_eq!(matches[0], (2, &breeds[1]));
+        assert!(matches.iter().all(|(_, b)| *b != "Poodle"));
+        assert_eq!(ranked("doodle", &breeds).len(), 3);
+    }
+}
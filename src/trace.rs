@@ -0,0 +1,118 @@
+use crate::config::Settings;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+/// Requests made during one tool call, collected for its `debug: true`
+/// output.
+pub type RequestLog = Arc<Mutex<Vec<Value>>>;
+
+/// Notes an upstream request: logged with `--trace-api`, and added to the
+/// call's request log if it has one. `status` is `None` for responses served
+/// from the cache. Credentials travel in headers and are never included.
+pub fn upstream(
+    settings: &Settings,
+    method: &str,
+    url: &str,
+    body: Option<&Value>,
+    status: Option<StatusCode>,
+    response: &Value,
+) {
+    if !settings.trace_api && settings.request_log.is_none() {
+        return;
+    }
+    let outcome = match status {
+        Some(status) => status.to_string(),
+        None => "cached".to_string(),
+    };
+    let summary = summarize(response);
+
+    if settings.trace_api {
+        debug!(
+            target: "rescue_groups_mcp::api",
+            "{} {} (Authorization: [redacted]) body={} -> {} {}",
+            method,
+            url,
+            body.map_or_else(|| "none".to_string(), |b| b.to_string()),
+            outcome,
+            summary
+        );
+    }
+    if let Some(log) = &settings.request_log {
+        let mut entry = json!({
+            "method": method,
+            "url": url,
+            "response": format!("{} {}", outcome, summary),
+        });
+        if let Some(body) = body {
+            entry["body"] = body.clone();
+        }
+        log.lock().unwrap().push(entry);
+    }
+}
+
+/// Notes a request that a dry run stopped from being sent, with its
+/// headers. The `Authorization` value is redacted.
+pub fn planned(
+    settings: &Settings,
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&Value>,
+) {
+    let headers: serde_json::Map<String, Value> = headers
+        .iter()
+        .map(|(name, value)| {
+            let value = match *name {
+                "Authorization" => "[redacted]",
+                _ => value,
+            };
+            (name.to_string(), json!(value))
+        })
+        .collect();
+    let mut entry = json!({ "method": method, "url": url, "headers": headers });
+    if let Some(body) = body {
+        entry["body"] = body.clone();
+    }
+    if let Some(log) = &settings.request_log {
+        log.lock().unwrap().push(entry);
+    }
+}
+
+/// A one-line description of a response: its item count, or its errors.
+fn summarize(response: &Value) -> String {
+    if let Some(errors) = response["errors"].as_array() {
+        let details: Vec<&str> = errors
+            .iter()
+            .filter_map(|e| e["detail"].as_str().or_else(|| e["title"].as_str()))
+            .collect();
+        return format!("errors: {}", details.join("; "));
+    }
+    match &response["data"] {
+        Value::Array(items) => match response["meta"]["count"].as_u64() {
+            Some(count) => format!("{} items of {}", items.len(), count),
+            None => format!("{} items", items.len()),
+        },
+        Value::Object(_) => "1 item".to_string(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize() {
+        assert_eq!(
+            summarize(&json!({"data": [{}, {}], "meta": {"count": 40}})),
+            "2 items of 40"
+        );
+        assert_eq!(summarize(&json!({"data": {"id": "1"}})), "1 item");
+        assert_eq!(
+            summarize(&json!({"errors": [{"detail": "Invalid filter"}]})),
+            "errors: Invalid filter"
+        );
+    }
+}
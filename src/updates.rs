@@ -0,0 +1,283 @@
+use crate::alerts::now_secs;
+use crate::cli::AnimalIdArgs;
+use crate::client::fan_out;
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::favorites::list_favorites;
+use crate::fmt::extract_single_item;
+use crate::source::get_animal;
+use crate::store;
+use crate::validate;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Status of an animal whose listing can be fetched but doesn't say more.
+const AVAILABLE: &str = "Available";
+/// Status of an animal whose listing is gone, usually because it was adopted.
+const UNAVAILABLE: &str = "No longer available";
+
+/// Details compared between checks: label and attribute.
+const TRACKED_FIELDS: [(&str, &str); 7] = [
+    ("Name", "name"),
+    ("Breed", "breedString"),
+    ("Age", "ageGroup"),
+    ("Size", "sizeGroup"),
+    ("Adoption Fee", "adoptionFeeString"),
+    ("Description", "descriptionText"),
+    ("Last Updated", "updatedDate"),
+];
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct UpdatesFile {
+    #[serde(default)]
+    pub animals: HashMap<String, AnimalSnapshot>,
+}
+
+/// An animal as it was at its last check.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AnimalSnapshot {
+    pub name: String,
+    pub status: String,
+    /// Tracked details by label, photo count included
+    #[serde(default)]
+    pub fields: BTreeMap<String, Value>,
+    /// Unix timestamp of the check
+    pub checked_at: u64,
+}
+
+fn updates_path(settings: &Settings) -> PathBuf {
+    settings.data_dir.join("updates.json")
+}
+
+/// The status name in an animal's `included` statuses, if the API sent one.
+fn status_name(data: &Value, animal: &Value) -> Option<String> {
+    let id = &animal["relationships"]["statuses"]["data"][0]["id"];
+    data["included"]
+        .as_array()?
+        .iter()
+        .find(|item| item["type"] == "statuses" && item["id"] == *id)
+        .and_then(|item| item["attributes"]["name"].as_str())
+        .map(str::to_string)
+}
+
+fn snapshot(data: &Value, animal: &Value) -> AnimalSnapshot {
+    let attrs = &animal["attributes"];
+    let mut fields: BTreeMap<String, Value> = TRACKED_FIELDS
+        .iter()
+        .filter(|(_, attribute)| !attrs[attribute].is_null())
+        .map(|(label, attribute)| (label.to_string(), attrs[attribute].clone()))
+        .collect();
+    let photos = attrs["orgsAnimalsPictures"].as_array().map_or(0, Vec::len);
+    fields.insert("Photos".to_string(), json!(photos));
+    AnimalSnapshot {
+        name: attrs["name"].as_str().unwrap_or("Unknown").to_string(),
+        status: status_name(data, animal).unwrap_or_else(|| AVAILABLE.to_string()),
+        fields,
+        checked_at: now_secs(),
+    }
+}
+
+/// How an animal differs from its snapshot: `new` on its first check, then
+/// `unavailable`, `available_again`, `status_changed`, `updated` (with the
+/// details that changed) or `unchanged`.
+fn compare(id: &str, previous: Option<&AnimalSnapshot>, current: &AnimalSnapshot) -> Value {
+    let mut report = json!({
+        "id": id,
+        "name": current.name,
+        "status": current.status,
+        "since": previous.map(|p| p.checked_at),
+    });
+    let Some(previous) = previous else {
+        report["change"] = json!("new");
+        return report;
+    };
+    let change = match (previous.status.as_str(), current.status.as_str()) {
+        (before, after) if before == after => {
+            let changes: Vec<Value> = previous
+                .fields
+                .keys()
+                .chain(current.fields.keys())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .filter(|label| previous.fields.get(*label) != current.fields.get(*label))
+                .map(|label| {
+                    json!({
+                        "field": label,
+                        "before": previous.fields.get(label),
+                        "after": current.fields.get(label),
+                    })
+                })
+                .collect();
+            if changes.is_empty() {
+                "unchanged"
+            } else {
+                report["changes"] = json!(changes);
+                "updated"
+            }
+        }
+        (_, UNAVAILABLE) => "unavailable",
+        (UNAVAILABLE, _) => "available_again",
+        (before, _) => {
+            report["previous_status"] = json!(before);
+            "status_changed"
+        }
+    };
+    report["change"] = json!(change);
+    report
+}
+
+/// Checks `animal_ids`, plus the favorites when `favorites` is set or no
+/// IDs are given, against the snapshots taken at their last check, and
+/// stores new snapshots. Animals whose listing is gone are reported as no
+/// longer available; their last known details are kept.
+pub async fn check_updates(
+    settings: &Settings,
+    animal_ids: Vec<String>,
+    favorites: bool,
+) -> Result<Value, AppError> {
+    let mut ids = animal_ids;
+    if favorites || ids.is_empty() {
+        ids.extend(list_favorites(settings).await?.into_iter().map(|f| f.id));
+    }
+    let mut seen = HashSet::new();
+    ids.retain(|id| seen.insert(id.clone()));
+    if ids.is_empty() {
+        return Err(AppError::validation(
+            "No animals to check: pass animal_ids or save favorites first".to_string(),
+        ));
+    }
+    for id in &ids {
+        validate::animal_id(id)?;
+    }
+
+    let fetched = fan_out(
+        settings,
+        ids.iter().map(|id| {
+            get_animal(
+                settings,
+                AnimalIdArgs {
+                    animal_id: id.clone(),
+                },
+            )
+        }),
+    )
+    .await;
+
+    store::update(&updates_path(settings), |file: &mut UpdatesFile| {
+        let mut report = Vec::new();
+        for (id, result) in ids.iter().zip(fetched) {
+            let previous = file.animals.get(id);
+            let current = match result.as_ref().map(|data| {
+                data.get("data")
+                    .and_then(extract_single_item)
+                    .map(|animal| snapshot(data, animal))
+            }) {
+                Ok(Some(current)) => current,
+                Ok(None) | Err(AppError::NotFound) => AnimalSnapshot {
+                    status: UNAVAILABLE.to_string(),
+                    checked_at: now_secs(),
+                    ..previous.cloned().unwrap_or_else(|| AnimalSnapshot {
+                        name: "Unknown".to_string(),
+                        status: String::new(),
+                        fields: BTreeMap::new(),
+                        checked_at: 0,
+                    })
+                },
+                Err(e) => {
+                    // Keep the old snapshot so the next check compares against it
+                    report.push(json!({ "id": id, "change": "error", "error": e.to_string() }));
+                    continue;
+                }
+            };
+            report.push(compare(id, previous, &current));
+            file.animals.insert(id.clone(), current);
+        }
+        Ok(json!({ "data": report }))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use moka::future::Cache;
+
+    use std::sync::Arc;
+
+    fn get_test_settings(url: String) -> Settings {
+        let data_dir = std::env::temp_dir().join("rescue-groups-mcp-updates");
+        let _ = std::fs::remove_dir_all(&data_dir);
+        Settings {
+            // No caching, so each check sees the current listing
+            cache: Arc::new(Cache::new(0)),
+            ..Settings::for_test(url, data_dir)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_updates() {
+        let mut server = mockito::Server::new_async().await;
+        let settings = get_test_settings(server.url());
+
+        let animal = |fee: &str| {
+            format!(
+                r#"{{"data": [{{"id": "1", "attributes": {{"name": "Rex", "adoptionFeeString": "{}"}}}}]}}"#,
+                fee
+            )
+        };
+        let rex = server
+            .mock("GET", "/public/animals/1")
+            .with_status(200)
+            .with_body(animal("$100"))
+            .create_async()
+            .await;
+        let _gone = server
+            .mock("GET", "/public/animals/2")
+            .with_status(200)
+            .with_body(r#"{"data": [{"id": "2", "attributes": {"name": "Bo"}}]}"#)
+            .create_async()
+            .await;
+
+        let ids = vec!["1".to_string(), "2".to_string(), "1".to_string()];
+        let first = check_updates(&settings, ids.clone(), false).await.unwrap();
+        assert_eq!(first["data"].as_array().unwrap().len(), 2);
+        assert_eq!(first["data"][0]["change"], "new");
+
+        rex.remove_async().await;
+        let _rex = server
+            .mock("GET", "/public/animals/1")
+            .with_status(200)
+            .with_body(animal("$50"))
+            .create_async()
+            .await;
+        _gone.remove_async().await;
+        let _gone = server
+            .mock("GET", "/public/animals/2")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let second = check_updates(&settings, ids.clone(), false).await.unwrap();
+        assert_eq!(second["data"][0]["change"], "updated");
+        assert_eq!(
+            second["data"][0]["changes"],
+            json!([{ "field": "Adoption Fee", "before": "$100", "after": "$50" }])
+        );
+        assert_eq!(second["data"][1]["change"], "unavailable");
+        assert_eq!(second["data"][1]["name"], "Bo");
+
+        let third = check_updates(&settings, ids, false).await.unwrap();
+        assert_eq!(third["data"][0]["change"], "unchanged");
+        assert_eq!(third["data"][1]["change"], "unchanged");
+        assert_eq!(third["data"][1]["status"], UNAVAILABLE);
+
+        assert!(matches!(
+            check_updates(&settings, Vec::new(), true).await,
+            Err(AppError::ValidationError { .. })
+        ));
+        std::fs::remove_dir_all(&settings.data_dir).unwrap();
+    }
+}
@@ -0,0 +1,231 @@
+use crate::cli::ToolArgs;
+use crate::error::AppError;
+use crate::petfinder::ID_PREFIX;
+
+pub const SEXES: &[&str] = &["Male", "Female"];
+pub const AGES: &[&str] = &["Baby", "Young", "Adult", "Senior"];
+pub const SIZES: &[&str] = &["Small", "Medium", "Large", "X-Large"];
+pub const SORT_ORDERS: &[&str] = &["Newest", "Distance", "Random"];
+
+/// Most animals `compare_animals` takes at once.
+pub const MAX_COMPARE: usize = 5;
+
+/// Most postal codes `compare_locations` searches at once.
+pub const MAX_LOCATIONS: usize = 5;
+
+/// Longest look-back `list_new_arrivals` accepts, in days.
+pub const MAX_ARRIVAL_DAYS: u32 = 90;
+
+/// Search radius range. Upstream answers absurd radii with odd results
+/// rather than an error.
+pub const MIN_MILES: u32 = 1;
+pub const MAX_MILES: u32 = 1000;
+
+/// Most ranked results `semantic_search_pets` and `match_me_a_pet` return.
+pub const MAX_LIMIT: usize = 50;
+
+/// The canonical spelling of `value` among `allowed`, ignoring case.
+pub fn one_of(field: &str, value: &str, allowed: &[&str]) -> Result<String, AppError> {
+    allowed
+        .iter()
+        .find(|a| a.eq_ignore_ascii_case(value.trim()))
+        .map(|a| a.to_string())
+        .ok_or_else(|| {
+            AppError::invalid_argument(
+                field,
+                format!(
+                    "'{}' is not a valid {}. Use one of: {}",
+                    value,
+                    field,
+                    allowed.join(", ")
+                ),
+            )
+        })
+}
+
+/// Checks a search radius is within `MIN_MILES`..=`MAX_MILES`.
+pub fn miles(miles: Option<u32>) -> Result<(), AppError> {
+    match miles {
+        Some(m) if !(MIN_MILES..=MAX_MILES).contains(&m) => Err(AppError::invalid_argument(
+            "miles",
+            format!(
+                "miles {} is out of range: use {} to {}",
+                m, MIN_MILES, MAX_MILES
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks the enumerated search filters, normalizing their case
+/// ("senior" → "Senior"), and the search radius.
+pub fn search_args(mut args: ToolArgs) -> Result<ToolArgs, AppError> {
+    miles(args.miles)?;
+    for (field, value, allowed) in [
+        ("sex", &mut args.sex, SEXES),
+        ("age", &mut args.age, AGES),
+        ("size", &mut args.size, SIZES),
+        ("sort_by", &mut args.sort_by, SORT_ORDERS),
+    ] {
+        if let Some(v) = value.as_deref() {
+            *value = Some(one_of(field, v, allowed)?);
+        }
+    }
+    Ok(args)
+}
+
+/// Checks that `id` is a numeric ID, as RescueGroups issues them.
+pub fn numeric_id(field: &str, id: &str) -> Result<(), AppError> {
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+        Ok(())
+    } else {
+        Err(AppError::invalid_argument(
+            field,
+            format!(
+                "{} '{}' is malformed: expected a number such as 10123456",
+                field, id
+            ),
+        ))
+    }
+}
+
+/// Checks an animal ID: numeric for RescueGroups, `pf-` and a number for
+/// Petfinder.
+pub fn animal_id(id: &str) -> Result<(), AppError> {
+    match id.strip_prefix(ID_PREFIX) {
+        Some(number) if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) => Ok(()),
+        Some(_) => Err(AppError::invalid_argument(
+            "animal_id",
+            format!(
+                "animal_id '{}' is malformed: Petfinder IDs look like {}12345678",
+                id, ID_PREFIX
+            ),
+        )),
+        None => numeric_id("animal_id", id),
+    }
+}
+
+/// Checks the IDs given to `compare_animals`: one to five distinct IDs,
+/// each well-formed.
+pub fn compare_ids(ids: &[String]) -> Result<(), AppError> {
+    let mut distinct = ids.to_vec();
+    distinct.sort();
+    distinct.dedup();
+    if distinct.is_empty() {
+        return Err(AppError::invalid_argument(
+            "animal_ids",
+            format!(
+                "animal_ids is empty: pass 1 to {} animal IDs to compare",
+                MAX_COMPARE
+            ),
+        ));
+    }
+    if distinct.len() > MAX_COMPARE {
+        return Err(AppError::invalid_argument(
+            "animal_ids",
+            format!(
+                "animal_ids has {} different IDs, but at most {} can be compared at once; drop {}",
+                distinct.len(),
+                MAX_COMPARE,
+                distinct.len() - MAX_COMPARE
+            ),
+        ));
+    }
+    distinct.iter().try_for_each(|id| animal_id(id))
+}
+
+/// Checks the postal codes given to `compare_locations`: two to five
+/// distinct ones. Returns them without repeats, in the order given.
+pub fn location_codes(codes: &[String]) -> Result<Vec<String>, AppError> {
+    let mut distinct: Vec<String> = Vec::new();
+    for code in codes.iter().map(|c| c.trim()).filter(|c| !c.is_empty()) {
+        if !distinct.iter().any(|d| d.eq_ignore_ascii_case(code)) {
+            distinct.push(code.to_string());
+        }
+    }
+    if distinct.len() < 2 || distinct.len() > MAX_LOCATIONS {
+        return Err(AppError::invalid_argument(
+            "postal_codes",
+            format!(
+                "postal_codes has {} different locations: pass 2 to {} to compare",
+                distinct.len(),
+                MAX_LOCATIONS
+            ),
+        ));
+    }
+    Ok(distinct)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_args() {
+        let args = search_args(ToolArgs {
+            age: Some("senior".to_string()),
+            size: Some("x-large".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(args.age.as_deref(), Some("Senior"));
+        assert_eq!(args.size.as_deref(), Some("X-Large"));
+
+        let err = search_args(ToolArgs {
+            sort_by: Some("Oldest".to_string()),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation Error: 'Oldest' is not a valid sort_by. Use one of: Newest, Distance, Random"
+        );
+
+        let err = search_args(ToolArgs {
+            miles: Some(5000),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation Error: miles 5000 is out of range: use 1 to 1000"
+        );
+        assert!(miles(Some(0)).is_err());
+        assert!(miles(Some(1000)).is_ok());
+        assert!(miles(None).is_ok());
+    }
+
+    #[test]
+    fn test_ids() {
+        assert!(animal_id("10123456").is_ok());
+        assert!(animal_id("pf-42").is_ok());
+        assert!(matches!(
+            animal_id("abc"),
+            Err(AppError::ValidationError { .. })
+        ));
+        assert!(animal_id("pf-").is_err());
+        assert!(animal_id("").is_err());
+
+        assert!(compare_ids(&[]).is_err());
+        assert!(compare_ids(&["1".to_string(), "x".to_string()]).is_err());
+        let six: Vec<String> = (1..=6).map(|i| i.to_string()).collect();
+        assert!(matches!(
+            compare_ids(&six),
+            Err(AppError::ValidationError { message: msg, .. }) if msg.contains("at most 5")
+        ));
+        // Duplicates count once
+        assert!(compare_ids(&vec!["1".to_string(); 6]).is_ok());
+        assert!(compare_ids(&["1".to_string(), "2".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn test_location_codes() {
+        let codes = |c: &[&str]| c.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            location_codes(&codes(&["78704", " 78704", "90210"])).unwrap(),
+            codes(&["78704", "90210"])
+        );
+        assert!(location_codes(&codes(&["78704", "78704"])).is_err());
+        assert!(location_codes(&codes(&["1", "2", "3", "4", "5", "6"])).is_err());
+    }
+}